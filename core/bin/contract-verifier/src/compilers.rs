@@ -0,0 +1,430 @@
+//! Registry of compilers available to the contract verifier.
+//!
+//! Compilers are stored in the registry root (`$ZKSYNC_HOME/etc` by default) using the following layout:
+//! `{compiler}-bin/{version}/{compiler}`, e.g. `zksolc-bin/v1.3.14/zksolc`. Each version directory
+//! may contain a `{compiler}.sha256` file with the hex-encoded SHA-256 digest of the binary, which is checked
+//! before the binary is used.
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use anyhow::Context as _;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractVerifierError;
+
+/// Kind of the compiler managed by [`CompilerRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompilerKind {
+    ZkSolc,
+    Solc,
+    ZkVyper,
+    Vyper,
+}
+
+impl CompilerKind {
+    pub const ALL: [Self; 4] = [Self::ZkSolc, Self::Solc, Self::ZkVyper, Self::Vyper];
+
+    pub fn binary_name(self) -> &'static str {
+        match self {
+            Self::ZkSolc => "zksolc",
+            Self::Solc => "solc",
+            Self::ZkVyper => "zkvyper",
+            Self::Vyper => "vyper",
+        }
+    }
+
+    fn dir_name(self) -> String {
+        format!("{}-bin", self.binary_name())
+    }
+
+    fn checksum_file_name(self) -> String {
+        format!("{}.sha256", self.binary_name())
+    }
+}
+
+impl fmt::Display for CompilerKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.binary_name())
+    }
+}
+
+/// Result of checksum validation for a compiler binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChecksumStatus {
+    Valid,
+    Missing,
+}
+
+/// File metadata used to avoid recomputing checksums for binaries that didn't change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BinaryFingerprint {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl BinaryFingerprint {
+    fn new(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        Ok(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// Registry of compiler binaries stored on the local file system.
+#[derive(Debug)]
+pub struct CompilerRegistry {
+    root: PathBuf,
+    require_checksums: bool,
+    validated_binaries: Mutex<HashMap<PathBuf, BinaryFingerprint>>,
+}
+
+impl CompilerRegistry {
+    pub fn new(root: impl Into<PathBuf>, require_checksums: bool) -> Self {
+        Self {
+            root: root.into(),
+            require_checksums,
+            validated_binaries: Mutex::default(),
+        }
+    }
+
+    /// Creates a registry with the root at `$ZKSYNC_HOME/etc`.
+    pub fn from_env(require_checksums: bool) -> Self {
+        let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
+        Self::new(Path::new(&zksync_home).join("etc"), require_checksums)
+    }
+
+    fn version_dir(&self, kind: CompilerKind, version: &str) -> PathBuf {
+        self.root.join(kind.dir_name()).join(version)
+    }
+
+    /// Lists versions of the specified compiler that pass validation.
+    pub fn versions(&self, kind: CompilerKind) -> anyhow::Result<Vec<String>> {
+        let kind_dir = self.root.join(kind.dir_name());
+        if !kind_dir.exists() {
+            return Ok(vec![]);
+        }
+        let entries = fs::read_dir(&kind_dir)
+            .with_context(|| format!("failed reading `{}`", kind_dir.display()))?;
+
+        let mut versions = vec![];
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let Ok(version) = entry.file_name().into_string() else {
+                continue;
+            };
+            match self.resolve(kind, &version) {
+                Ok(_) => versions.push(version),
+                Err(err) => tracing::warn!("Skipping {kind} {version}: {err}"),
+            }
+        }
+        versions.sort_unstable();
+        Ok(versions)
+    }
+
+    /// Returns the path to a validated compiler binary.
+    pub fn resolve(
+        &self,
+        kind: CompilerKind,
+        version: &str,
+    ) -> Result<PathBuf, ContractVerifierError> {
+        let unknown_version =
+            || ContractVerifierError::UnknownCompilerVersion(kind.to_string(), version.to_owned());
+        // Guard against path traversal via user-provided versions.
+        let is_valid_version = !version.is_empty()
+            && version
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '+' | '_'))
+            && version != "."
+            && version != "..";
+        if !is_valid_version {
+            return Err(unknown_version());
+        }
+
+        let version_dir = self.version_dir(kind, version);
+        let binary_path = version_dir.join(kind.binary_name());
+        if !binary_path.is_file() {
+            return Err(unknown_version());
+        }
+        self.validate_binary(kind, &version_dir, &binary_path)
+            .map_err(|err| {
+                ContractVerifierError::UntrustedCompiler(
+                    kind.to_string(),
+                    version.to_owned(),
+                    err.to_string(),
+                )
+            })?;
+        Ok(binary_path)
+    }
+
+    fn validate_binary(
+        &self,
+        kind: CompilerKind,
+        version_dir: &Path,
+        binary_path: &Path,
+    ) -> anyhow::Result<()> {
+        let fingerprint = BinaryFingerprint::new(binary_path)?;
+        let validated_binaries = self.validated_binaries.lock().unwrap();
+        if validated_binaries.get(binary_path) == Some(&fingerprint) {
+            return Ok(());
+        }
+        drop(validated_binaries);
+
+        let status = validate_checksum(kind, version_dir, binary_path)?;
+        if status == ChecksumStatus::Missing {
+            anyhow::ensure!(!self.require_checksums, "checksum file is missing");
+            tracing::warn!(
+                "Compiler binary `{}` has no checksum file; using it without validation",
+                binary_path.display()
+            );
+        }
+        self.validated_binaries
+            .lock()
+            .unwrap()
+            .insert(binary_path.to_owned(), fingerprint);
+        Ok(())
+    }
+
+    /// Imports compilers from a local `.tar` or `.tar.gz` archive that follows the registry layout
+    /// (e.g., contains `zksolc-bin/v1.3.14/zksolc`). All compilers are validated before any of them
+    /// is imported, so a failed import doesn't change the registry. Already present versions are replaced.
+    /// Returns the list of imported compilers.
+    pub fn import_archive(
+        &self,
+        archive_path: &Path,
+    ) -> anyhow::Result<Vec<(CompilerKind, String)>> {
+        let file = fs::File::open(archive_path)
+            .with_context(|| format!("failed opening `{}`", archive_path.display()))?;
+        let file = BufReader::new(file);
+        let is_gzipped = archive_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| {
+                name.ends_with(".tar.gz") || name.ends_with(".tgz")
+            });
+
+        fs::create_dir_all(&self.root)?;
+        // Unpack to the registry root so that compilers can be moved into place atomically.
+        let staging_dir =
+            tempfile::tempdir_in(&self.root).context("failed creating staging dir")?;
+        let unpack_result = if is_gzipped {
+            tar::Archive::new(flate2::read::GzDecoder::new(file)).unpack(staging_dir.path())
+        } else {
+            tar::Archive::new(file).unpack(staging_dir.path())
+        };
+        unpack_result.context("failed unpacking archive")?;
+
+        let mut staged = vec![];
+        for kind in CompilerKind::ALL {
+            let staged_kind_dir = staging_dir.path().join(kind.dir_name());
+            if !staged_kind_dir.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(&staged_kind_dir)? {
+                let entry = entry?;
+                let version = entry
+                    .file_name()
+                    .into_string()
+                    .map_err(|name| anyhow::anyhow!("invalid version: {name:?}"))?;
+                let staged_dir = entry.path();
+                let binary_path = staged_dir.join(kind.binary_name());
+                if !binary_path.is_file() {
+                    tracing::warn!("Skipping {kind} {version}: binary is missing in the archive");
+                    continue;
+                }
+
+                let status = validate_checksum(kind, &staged_dir, &binary_path)
+                    .with_context(|| format!("failed validating {kind} {version}"))?;
+                anyhow::ensure!(
+                    status == ChecksumStatus::Valid || !self.require_checksums,
+                    "{kind} {version} has no checksum file"
+                );
+                make_executable(&binary_path)?;
+                staged.push((kind, version, staged_dir));
+            }
+        }
+
+        let mut imported = Vec::with_capacity(staged.len());
+        for (kind, version, staged_dir) in staged {
+            let target_dir = self.version_dir(kind, &version);
+            fs::create_dir_all(self.root.join(kind.dir_name()))?;
+            if target_dir.exists() {
+                // Move the replaced version out of the way rather than removing it, so that the version
+                // is never partially present. The replaced version is removed together with the staging dir.
+                let replaced_dir = staging_dir
+                    .path()
+                    .join(format!("replaced-{}-{version}", kind.dir_name()));
+                fs::rename(&target_dir, &replaced_dir)
+                    .with_context(|| format!("failed moving replaced {kind} {version}"))?;
+            }
+            fs::rename(&staged_dir, &target_dir)
+                .with_context(|| format!("failed moving {kind} {version} into place"))?;
+            tracing::info!("Imported {kind} {version}");
+            imported.push((kind, version));
+        }
+        Ok(imported)
+    }
+}
+
+/// Validates the binary against the checksum file in `version_dir`, if the file is present.
+fn validate_checksum(
+    kind: CompilerKind,
+    version_dir: &Path,
+    binary_path: &Path,
+) -> anyhow::Result<ChecksumStatus> {
+    let checksum_path = version_dir.join(kind.checksum_file_name());
+    if !checksum_path.exists() {
+        return Ok(ChecksumStatus::Missing);
+    }
+    let expected = fs::read_to_string(&checksum_path)
+        .with_context(|| format!("failed reading `{}`", checksum_path.display()))?;
+    // Support the `sha256sum` output format, in which the digest is followed by the file name.
+    let expected = expected.split_whitespace().next().unwrap_or_default();
+    let actual = sha256_digest(binary_path)?;
+    anyhow::ensure!(
+        expected.eq_ignore_ascii_case(&actual),
+        "checksum mismatch: expected {expected}, got {actual}"
+    );
+    Ok(ChecksumStatus::Valid)
+}
+
+fn sha256_digest(path: &Path) -> anyhow::Result<String> {
+    let mut file =
+        fs::File::open(path).with_context(|| format!("failed opening `{}`", path.display()))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINARY: &[u8] = b"#!/bin/sh\necho compiler";
+
+    fn write_compiler(root: &Path, kind: CompilerKind, version: &str, checksum: Option<&str>) {
+        let version_dir = root.join(kind.dir_name()).join(version);
+        fs::create_dir_all(&version_dir).unwrap();
+        fs::write(version_dir.join(kind.binary_name()), BINARY).unwrap();
+        if let Some(checksum) = checksum {
+            fs::write(version_dir.join(kind.checksum_file_name()), checksum).unwrap();
+        }
+    }
+
+    fn binary_checksum() -> String {
+        hex::encode(Sha256::digest(BINARY))
+    }
+
+    #[test]
+    fn resolving_compilers() {
+        let root = tempfile::tempdir().unwrap();
+        let checksum = format!("{}  zksolc\n", binary_checksum());
+        write_compiler(
+            root.path(),
+            CompilerKind::ZkSolc,
+            "v1.3.14",
+            Some(&checksum),
+        );
+        write_compiler(root.path(), CompilerKind::ZkSolc, "v1.3.13", Some("00"));
+        write_compiler(root.path(), CompilerKind::ZkSolc, "v1.3.12", None);
+
+        let registry = CompilerRegistry::new(root.path(), false);
+        let path = registry.resolve(CompilerKind::ZkSolc, "v1.3.14").unwrap();
+        assert!(path.ends_with("zksolc-bin/v1.3.14/zksolc"));
+        registry.resolve(CompilerKind::ZkSolc, "v1.3.12").unwrap();
+
+        let err = registry
+            .resolve(CompilerKind::ZkSolc, "v1.3.13")
+            .unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::UntrustedCompiler(..)),
+            "{err:?}"
+        );
+        let err = registry
+            .resolve(CompilerKind::ZkSolc, "../zksolc-bin/v1.3.14")
+            .unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::UnknownCompilerVersion(..)),
+            "{err:?}"
+        );
+        let versions = registry.versions(CompilerKind::ZkSolc).unwrap();
+        assert_eq!(versions, ["v1.3.12", "v1.3.14"]);
+        assert!(registry.versions(CompilerKind::Solc).unwrap().is_empty());
+
+        let strict_registry = CompilerRegistry::new(root.path(), true);
+        let versions = strict_registry.versions(CompilerKind::ZkSolc).unwrap();
+        assert_eq!(versions, ["v1.3.14"]);
+    }
+
+    #[test]
+    fn importing_compilers_from_archive() {
+        let source = tempfile::tempdir().unwrap();
+        write_compiler(
+            source.path(),
+            CompilerKind::Solc,
+            "0.8.17",
+            Some(&binary_checksum()),
+        );
+        write_compiler(source.path(), CompilerKind::Vyper, "0.3.10", None);
+
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_path = archive_dir.path().join("compilers.tar.gz");
+        let archive_file = fs::File::create(&archive_path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(archive_file, flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_dir_all(".", source.path()).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let root = tempfile::tempdir().unwrap();
+        let registry = CompilerRegistry::new(root.path(), false);
+        let mut imported = registry.import_archive(&archive_path).unwrap();
+        imported.sort_by_key(|(kind, _)| kind.binary_name());
+        assert_eq!(
+            imported,
+            [
+                (CompilerKind::Solc, "0.8.17".to_owned()),
+                (CompilerKind::Vyper, "0.3.10".to_owned()),
+            ]
+        );
+        assert_eq!(registry.versions(CompilerKind::Solc).unwrap(), ["0.8.17"]);
+        assert_eq!(registry.versions(CompilerKind::Vyper).unwrap(), ["0.3.10"]);
+
+        let strict_root = tempfile::tempdir().unwrap();
+        let strict_registry = CompilerRegistry::new(strict_root.path(), true);
+        let err = strict_registry.import_archive(&archive_path).unwrap_err();
+        assert!(err.to_string().contains("no checksum"), "{err}");
+        // `solc` has a valid checksum, but must not be imported since the import as a whole has failed.
+        assert!(strict_registry
+            .versions(CompilerKind::Solc)
+            .unwrap()
+            .is_empty());
+
+        // Re-importing replaces existing versions.
+        let imported = registry.import_archive(&archive_path).unwrap();
+        assert_eq!(imported.len(), 2);
+        assert_eq!(registry.versions(CompilerKind::Solc).unwrap(), ["0.8.17"]);
+    }
+}
@@ -1,3 +1,5 @@
+use zksync_types::contract_verification_api::CompilationError;
+
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ContractVerifierError {
     #[error("Internal error")]
@@ -8,12 +10,16 @@ pub enum ContractVerifierError {
     IncorrectConstructorArguments,
     #[error("Compilation takes too much time")]
     CompilationTimeout,
+    #[error("{0} exceeded the {1} limit")]
+    ResourceLimitExceeded(String, &'static str),
     #[error("{0} error: {1}")]
     CompilerError(String, String),
     #[error("Compilation error")]
-    CompilationError(serde_json::Value),
+    CompilationError(Vec<CompilationError>),
     #[error("Unknown {0} version: {1}")]
     UnknownCompilerVersion(String, String),
+    #[error("{0} {1} is not trusted: {2}")]
+    UntrustedCompiler(String, String, String),
     #[error("Contract with {0} name is missing in sources")]
     MissingContract(String),
     #[error("There is no {0} source file")]
@@ -23,3 +29,29 @@ pub enum ContractVerifierError {
     #[error("Failed to deserialize standard JSON input")]
     FailedToDeserializeInput,
 }
+
+impl ContractVerifierError {
+    /// Returns structured compilation errors to be persisted with the verification request status.
+    /// Besides errors reported by the compiler, this includes failures of the compiler process.
+    pub fn compilation_errors(&self) -> Vec<CompilationError> {
+        match self {
+            Self::CompilationError(errors) => errors.clone(),
+            Self::CompilationTimeout => vec![CompilationError::new(
+                "verifier",
+                "Timeout",
+                self.to_string(),
+            )],
+            Self::ResourceLimitExceeded(compiler, _) => vec![CompilationError::new(
+                compiler.as_str(),
+                "ResourceLimitExceeded",
+                self.to_string(),
+            )],
+            Self::CompilerError(compiler, stderr) => vec![CompilationError::new(
+                compiler.as_str(),
+                "CompilerFailure",
+                stderr.as_str(),
+            )],
+            _ => vec![],
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, path::PathBuf, sync::Arc};
 
 use anyhow::Context as _;
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
@@ -10,105 +10,43 @@ use zksync_env_config::FromEnv;
 use zksync_queued_job_processor::JobProcessor;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
-use crate::verifier::ContractVerifier;
+use crate::{
+    compilers::{CompilerKind, CompilerRegistry},
+    verifier::ContractVerifier,
+};
 
+pub mod compilers;
 pub mod error;
+pub mod metadata;
+pub mod sandbox;
 pub mod verifier;
 pub mod zksolc_utils;
 pub mod zkvyper_utils;
 
-async fn update_compiler_versions(connection_pool: &ConnectionPool) {
+async fn update_compiler_versions(
+    connection_pool: &ConnectionPool,
+    compilers: &CompilerRegistry,
+) -> anyhow::Result<()> {
     let mut storage = connection_pool.access_storage().await.unwrap();
     let mut transaction = storage.start_transaction().await.unwrap();
 
-    let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-
-    let zksolc_path = format!("{}/etc/zksolc-bin/", zksync_home);
-    let zksolc_versions: Vec<String> = std::fs::read_dir(zksolc_path)
-        .unwrap()
-        .filter_map(|file| {
-            let file = file.unwrap();
-            let Ok(file_type) = file.file_type() else {
-                return None;
-            };
-            if file_type.is_dir() {
-                file.file_name().into_string().ok()
-            } else {
-                None
-            }
-        })
-        .collect();
-    transaction
-        .contract_verification_dal()
-        .set_zksolc_versions(zksolc_versions)
-        .await
-        .unwrap();
-
-    let solc_path = format!("{}/etc/solc-bin/", zksync_home);
-    let solc_versions: Vec<String> = std::fs::read_dir(solc_path)
-        .unwrap()
-        .filter_map(|file| {
-            let file = file.unwrap();
-            let Ok(file_type) = file.file_type() else {
-                return None;
-            };
-            if file_type.is_dir() {
-                file.file_name().into_string().ok()
-            } else {
-                None
-            }
-        })
-        .collect();
-    transaction
-        .contract_verification_dal()
-        .set_solc_versions(solc_versions)
-        .await
-        .unwrap();
-
-    let zkvyper_path = format!("{}/etc/zkvyper-bin/", zksync_home);
-    let zkvyper_versions: Vec<String> = std::fs::read_dir(zkvyper_path)
-        .unwrap()
-        .filter_map(|file| {
-            let file = file.unwrap();
-            let Ok(file_type) = file.file_type() else {
-                return None;
-            };
-            if file_type.is_dir() {
-                file.file_name().into_string().ok()
-            } else {
-                None
-            }
-        })
-        .collect();
-    transaction
-        .contract_verification_dal()
-        .set_zkvyper_versions(zkvyper_versions)
-        .await
-        .unwrap();
-
-    let vyper_path = format!("{}/etc/vyper-bin/", zksync_home);
-    let vyper_versions: Vec<String> = std::fs::read_dir(vyper_path)
-        .unwrap()
-        .filter_map(|file| {
-            let file = file.unwrap();
-            let Ok(file_type) = file.file_type() else {
-                return None;
-            };
-            if file_type.is_dir() {
-                file.file_name().into_string().ok()
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    transaction
-        .contract_verification_dal()
-        .set_vyper_versions(vyper_versions)
-        .await
-        .unwrap();
+    for kind in CompilerKind::ALL {
+        let versions = compilers
+            .versions(kind)
+            .with_context(|| format!("failed listing {kind} versions"))?;
+        tracing::info!("Available {kind} versions: {versions:?}");
+        let mut dal = transaction.contract_verification_dal();
+        match kind {
+            CompilerKind::ZkSolc => dal.set_zksolc_versions(versions).await,
+            CompilerKind::Solc => dal.set_solc_versions(versions).await,
+            CompilerKind::ZkVyper => dal.set_zkvyper_versions(versions).await,
+            CompilerKind::Vyper => dal.set_vyper_versions(versions).await,
+        }
+        .with_context(|| format!("failed saving {kind} versions"))?;
+    }
 
     transaction.commit().await.unwrap();
+    Ok(())
 }
 
 use structopt::StructOpt;
@@ -119,6 +57,10 @@ struct Opt {
     /// Number of jobs to process. If None, runs indefinitely.
     #[structopt(long)]
     jobs_number: Option<usize>,
+    /// Imports compilers from the specified `.tar` / `.tar.gz` archive, updates the list of
+    /// supported compiler versions and exits.
+    #[structopt(long)]
+    import_compilers: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -174,9 +116,23 @@ async fn main() -> anyhow::Result<()> {
         .expect("Error setting Ctrl+C handler");
     }
 
-    update_compiler_versions(&pool).await;
+    let compilers = Arc::new(CompilerRegistry::from_env(
+        verifier_config.require_compiler_checksums,
+    ));
+    if let Some(archive_path) = &opt.import_compilers {
+        let imported = compilers.import_archive(archive_path).with_context(|| {
+            format!(
+                "failed importing compilers from `{}`",
+                archive_path.display()
+            )
+        })?;
+        tracing::info!("Imported {} compiler(s)", imported.len());
+        update_compiler_versions(&pool, &compilers).await?;
+        return Ok(());
+    }
+    update_compiler_versions(&pool, &compilers).await?;
 
-    let contract_verifier = ContractVerifier::new(verifier_config, pool);
+    let contract_verifier = ContractVerifier::new(verifier_config, pool, compilers);
     let tasks = vec![
         // todo PLA-335: Leftovers after the prover DB split.
         // The prover connection pool is not used by the contract verifier, but we need to pass it
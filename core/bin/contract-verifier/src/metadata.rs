@@ -0,0 +1,176 @@
+//! Metadata-agnostic bytecode comparison.
+
+use ciborium::value::Value;
+use zksync_types::contract_verification_api::MatchLevel;
+
+/// Maximum number of zero bytes after the metadata length. EraVM bytecode is padded
+/// to an odd number of 32-byte words, so padding never exceeds 63 bytes.
+const MAX_PADDING_LEN: usize = 63;
+/// Metadata keys emitted by Solidity-compatible compilers; at least one of them must be present
+/// for a CBOR map to be recognized as metadata.
+const METADATA_KEYS: &[&str] = &["ipfs", "bzzr0", "bzzr1", "solc"];
+
+/// Compares the compiled bytecode with the deployed one. Returns `None` if the bytecodes don't match
+/// even after stripping metadata.
+pub fn compare_bytecodes(compiled: &[u8], deployed: &[u8]) -> Option<MatchLevel> {
+    if compiled == deployed {
+        return Some(MatchLevel::Full);
+    }
+
+    let compiled_code = strip_cbor_metadata(compiled)?;
+    let deployed_code = strip_cbor_metadata(deployed)?;
+    (compiled_code == deployed_code).then_some(MatchLevel::Partial)
+}
+
+/// Strips Solidity-style CBOR metadata from the end of the bytecode. The metadata is followed
+/// by its 2-byte big-endian length, and, for EraVM, by zero padding to the word boundary.
+/// Returns `None` if the bytecode doesn't end with CBOR metadata.
+fn strip_cbor_metadata(bytecode: &[u8]) -> Option<&[u8]> {
+    let trailing_zeros = bytecode.iter().rev().take_while(|&&byte| byte == 0).count();
+    // The metadata length may end with zero bytes itself, so all padding lengths are tried
+    // starting from the shortest one.
+    (0..=trailing_zeros.min(MAX_PADDING_LEN)).find_map(|padding_len| {
+        strip_unpadded_cbor_metadata(&bytecode[..bytecode.len() - padding_len])
+    })
+}
+
+fn strip_unpadded_cbor_metadata(bytecode: &[u8]) -> Option<&[u8]> {
+    let (code_and_metadata, len_bytes) = bytecode.split_at(bytecode.len().checked_sub(2)?);
+    let metadata_len = usize::from(u16::from_be_bytes([len_bytes[0], len_bytes[1]]));
+    let code_len = code_and_metadata.len().checked_sub(metadata_len)?;
+    let (code, metadata) = code_and_metadata.split_at(code_len);
+    is_cbor_metadata(metadata).then_some(code)
+}
+
+/// Checks that `metadata` consists of exactly one CBOR map with text keys containing
+/// at least one of [`METADATA_KEYS`].
+fn is_cbor_metadata(mut metadata: &[u8]) -> bool {
+    let Ok(Value::Map(entries)) = ciborium::de::from_reader(&mut metadata) else {
+        return false;
+    };
+    if !metadata.is_empty() {
+        return false; // The declared length exceeds the actual metadata size.
+    }
+
+    let mut has_known_key = false;
+    for (key, _) in &entries {
+        let Value::Text(key) = key else {
+            return false;
+        };
+        has_known_key |= METADATA_KEYS.contains(&key.as_str());
+    }
+    has_known_key
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+
+    fn metadata(ipfs_hash: Vec<u8>) -> Vec<u8> {
+        let metadata = Value::Map(vec![
+            (Value::Text("ipfs".to_owned()), Value::Bytes(ipfs_hash)),
+            (Value::Text("solc".to_owned()), Value::Bytes(vec![0, 8, 19])),
+        ]);
+        let mut bytes = vec![];
+        ciborium::ser::into_writer(&metadata, &mut bytes).unwrap();
+        bytes
+    }
+
+    /// Builds a word-aligned bytecode with the specified CBOR metadata.
+    fn bytecode_with_metadata(code: &[u8], metadata: &[u8]) -> Vec<u8> {
+        let mut bytecode = code.to_vec();
+        bytecode.extend_from_slice(metadata);
+        let metadata_len = u16::try_from(metadata.len()).unwrap();
+        bytecode.extend_from_slice(&metadata_len.to_be_bytes());
+        let padded_len = (bytecode.len() + 31) / 32 * 32;
+        bytecode.resize(padded_len, 0);
+        bytecode
+    }
+
+    #[test]
+    fn stripping_cbor_metadata() {
+        let code = [1_u8; 64];
+        let bytecode = bytecode_with_metadata(&code, &metadata(vec![0xff; 34]));
+        assert_eq!(strip_cbor_metadata(&bytecode), Some(&code[..]));
+
+        let bytecode = [1_u8; 96];
+        assert_eq!(strip_cbor_metadata(&bytecode), None);
+        assert_eq!(strip_cbor_metadata(&[0; 32]), None);
+    }
+
+    #[test]
+    fn stripping_metadata_with_zero_bytes() {
+        let code = [1_u8; 64];
+        // Zero bytes inside the metadata are not mistaken for padding.
+        let bytecode = bytecode_with_metadata(&code, &metadata(vec![0; 34]));
+        assert_eq!(strip_cbor_metadata(&bytecode), Some(&code[..]));
+
+        // Metadata length is 256 bytes, i.e., its last byte is zero.
+        let long_metadata = metadata(vec![0xff; 239]);
+        assert_eq!(long_metadata.len(), 256);
+        let bytecode = bytecode_with_metadata(&code, &long_metadata);
+        assert_eq!(strip_cbor_metadata(&bytecode), Some(&code[..]));
+    }
+
+    #[test]
+    fn rejecting_malformed_metadata() {
+        let code = [1_u8; 64];
+        let mut metadata = metadata(vec![0xff; 34]);
+        // The declared length covers a code byte in addition to the metadata.
+        let mut bytecode = code.to_vec();
+        bytecode.extend_from_slice(&metadata);
+        bytecode.extend_from_slice(&u16::try_from(metadata.len() + 1).unwrap().to_be_bytes());
+        assert_eq!(strip_unpadded_cbor_metadata(&bytecode), None);
+
+        // The declared length exceeds the size of the CBOR map.
+        metadata.push(0);
+        let bytecode = bytecode_with_metadata(&code, &metadata);
+        assert_eq!(strip_cbor_metadata(&bytecode), None);
+
+        // CBOR map without metadata keys.
+        let map = Value::Map(vec![(
+            Value::Text("foo".to_owned()),
+            Value::Bytes(vec![0xff; 32]),
+        )]);
+        let mut metadata = vec![];
+        ciborium::ser::into_writer(&map, &mut metadata).unwrap();
+        let bytecode = bytecode_with_metadata(&code, &metadata);
+        assert_eq!(strip_cbor_metadata(&bytecode), None);
+    }
+
+    #[test]
+    fn comparing_bytecodes_with_cbor_metadata() {
+        let code = [1_u8; 64];
+        let compiled = bytecode_with_metadata(&code, &metadata(vec![0xff; 34]));
+        assert_eq!(
+            compare_bytecodes(&compiled, &compiled),
+            Some(MatchLevel::Full)
+        );
+
+        let deployed = bytecode_with_metadata(&code, &metadata(vec![0xee; 34]));
+        assert_eq!(
+            compare_bytecodes(&compiled, &deployed),
+            Some(MatchLevel::Partial)
+        );
+        // Metadata length may differ, e.g. if the compiler is configured to omit the IPFS hash.
+        let deployed = bytecode_with_metadata(&code, &metadata(vec![]));
+        assert_eq!(
+            compare_bytecodes(&compiled, &deployed),
+            Some(MatchLevel::Partial)
+        );
+
+        let deployed = bytecode_with_metadata(&[2; 64], &metadata(vec![0xff; 34]));
+        assert_eq!(compare_bytecodes(&compiled, &deployed), None);
+    }
+
+    #[test]
+    fn keccak256_metadata_is_not_stripped() {
+        let mut compiled = vec![1_u8; 64];
+        compiled.extend_from_slice(&[0xff; 32]);
+        let mut deployed = vec![1_u8; 64];
+        deployed.extend_from_slice(&[0xee; 32]);
+        assert_eq!(compare_bytecodes(&compiled, &deployed), None);
+    }
+}
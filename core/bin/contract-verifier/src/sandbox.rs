@@ -0,0 +1,368 @@
+//! Sandboxed execution of compiler processes.
+
+use std::{
+    process::{Output, Stdio},
+    time::Duration,
+};
+
+use futures::future;
+use tokio::{io::AsyncWriteExt, process::Command, time};
+use zksync_config::ContractVerifierConfig;
+
+use crate::error::ContractVerifierError;
+
+/// Resource limits for a compiler process.
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceLimits {
+    /// Wall-clock time limit. The process is killed once it is exceeded.
+    pub timeout: Duration,
+    /// CPU time limit enforced by the OS.
+    pub cpu_time: Duration,
+    /// Virtual memory limit enforced by the OS.
+    pub memory_bytes: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn new(config: &ContractVerifierConfig) -> Self {
+        Self {
+            timeout: config.compilation_timeout(),
+            cpu_time: config.compilation_cpu_limit(),
+            memory_bytes: config.compilation_memory_limit_bytes(),
+        }
+    }
+}
+
+/// Runs a compiler `command` in a sandbox, i.e. with a cleared environment (except for `PATH`), in a temporary
+/// working directory and with the specified resource limits. `stdin` is piped to the process if provided.
+///
+/// The compiler runs in a separate process group, which is killed once the compiler exits or is timed out,
+/// so that helper processes spawned by the compiler (e.g., `solc` spawned by `zksolc`) don't outlive it.
+pub async fn run_sandboxed(
+    compiler_name: &str,
+    mut command: Command,
+    stdin: Option<Vec<u8>>,
+    limits: &ResourceLimits,
+) -> Result<Output, ContractVerifierError> {
+    let working_dir = tempfile::tempdir().map_err(|_err| ContractVerifierError::InternalError)?;
+    command.env_clear();
+    // Compilers may invoke helper binaries (e.g., `vyper` wrappers), so `PATH` is preserved.
+    if let Some(path) = std::env::var_os("PATH") {
+        command.env("PATH", path);
+    }
+    command
+        .env("TMPDIR", working_dir.path())
+        .current_dir(working_dir.path())
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    set_resource_limits(&mut command, limits);
+
+    let cpu_time_before = children_cpu_time();
+    let mut child = command.spawn().map_err(|err| {
+        tracing::error!("Failed spawning {compiler_name}: {err}");
+        ContractVerifierError::InternalError
+    })?;
+    let _process_group = child.id().map(ProcessGroupGuard);
+    let child_stdin = stdin.and_then(|stdin| Some((child.stdin.take()?, stdin)));
+    let write_stdin = async move {
+        if let Some((mut child_stdin, stdin)) = child_stdin {
+            child_stdin.write_all(&stdin).await?;
+            // `child_stdin` is dropped here, so that the compiler knows that the input is complete.
+        }
+        std::io::Result::Ok(())
+    };
+
+    // Stdin is written concurrently with reading the output, so that a compiler that doesn't read
+    // its input (or produces output before reading all of it) cannot block the verifier.
+    // If the timeout is exceeded, the child process is killed on drop.
+    let (write_result, output) = time::timeout(
+        limits.timeout,
+        future::join(write_stdin, child.wait_with_output()),
+    )
+    .await
+    .map_err(|_| ContractVerifierError::CompilationTimeout)?;
+    let output = output.map_err(|_err| ContractVerifierError::InternalError)?;
+    let used_cpu_time = children_cpu_time().saturating_sub(cpu_time_before);
+    check_resource_limits(compiler_name, &output, limits, used_cpu_time)?;
+    if let Err(err) = write_result {
+        // The compiler may legitimately exit without reading all its input.
+        tracing::debug!("Failed writing stdin of {compiler_name}: {err}");
+    }
+    Ok(output)
+}
+
+/// Kills the process group with the specified ID on drop.
+#[derive(Debug)]
+struct ProcessGroupGuard(u32);
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        {
+            let pgid = libc::pid_t::try_from(self.0).expect("process ID overflow");
+            // SAFETY: `kill` has no memory safety preconditions. The group may already be gone,
+            // in which case the call fails with `ESRCH`, which is fine.
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+        }
+    }
+}
+
+/// Returns the total CPU time used by terminated and waited-for child processes of this process.
+#[cfg(unix)]
+fn children_cpu_time() -> Duration {
+    fn to_duration(time: libc::timeval) -> Duration {
+        Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+    }
+
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    // SAFETY: `getrusage` only writes to the provided struct, which is zero-initialized.
+    let usage = unsafe {
+        libc::getrusage(libc::RUSAGE_CHILDREN, usage.as_mut_ptr());
+        usage.assume_init()
+    };
+    to_duration(usage.ru_utime) + to_duration(usage.ru_stime)
+}
+
+#[cfg(not(unix))]
+fn children_cpu_time() -> Duration {
+    Duration::ZERO
+}
+
+#[cfg(unix)]
+fn set_resource_limits(command: &mut Command, limits: &ResourceLimits) {
+    fn rlimit(soft: u64, hard: u64) -> libc::rlimit {
+        libc::rlimit {
+            rlim_cur: soft as libc::rlim_t,
+            rlim_max: hard as libc::rlim_t,
+        }
+    }
+
+    fn check(return_code: libc::c_int) -> std::io::Result<()> {
+        if return_code == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    }
+
+    // The process receives `SIGXCPU` once the soft limit is exceeded, and `SIGKILL` after the hard one.
+    let cpu_secs = limits.cpu_time.as_secs().max(1);
+    let memory_bytes = limits.memory_bytes;
+    // SAFETY: the closure only calls `setpgid` and `setrlimit`, which are async-signal-safe, and doesn't allocate.
+    unsafe {
+        command.pre_exec(move || {
+            // Start a new process group, so that the compiler can be killed together with its child processes.
+            check(libc::setpgid(0, 0))?;
+            check(libc::setrlimit(
+                libc::RLIMIT_CPU,
+                &rlimit(cpu_secs, cpu_secs + 1),
+            ))?;
+            if let Some(memory_bytes) = memory_bytes {
+                check(libc::setrlimit(
+                    libc::RLIMIT_AS,
+                    &rlimit(memory_bytes, memory_bytes),
+                ))?;
+            }
+            check(libc::setrlimit(libc::RLIMIT_CORE, &rlimit(0, 0)))
+        });
+    }
+}
+
+/// Checks whether the compiler process was terminated because of exceeding resource limits.
+///
+/// `used_cpu_time` is the CPU time used by the process; it's required to distinguish the `SIGKILL` sent by the OS
+/// after exceeding the hard CPU time limit from other reasons of the process being killed.
+fn check_resource_limits(
+    compiler_name: &str,
+    output: &Output,
+    limits: &ResourceLimits,
+    used_cpu_time: Duration,
+) -> Result<(), ContractVerifierError> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt as _;
+
+        // Mirrors rounding in `set_resource_limits()`.
+        let cpu_time_limit = Duration::from_secs(limits.cpu_time.as_secs().max(1));
+        let cpu_time_exceeded = match output.status.signal() {
+            Some(libc::SIGXCPU) => true,
+            Some(libc::SIGKILL) => used_cpu_time >= cpu_time_limit,
+            _ => false,
+        };
+        if cpu_time_exceeded {
+            return Err(ContractVerifierError::ResourceLimitExceeded(
+                compiler_name.to_owned(),
+                "CPU time",
+            ));
+        }
+    }
+
+    // Memory exhaustion manifests differently depending on the compiler implementation
+    // (Rust for `zksolc` / `zkvyper`, C++ for `solc`, Python for `vyper`).
+    const OOM_MARKERS: &[&str] = &["memory allocation of", "std::bad_alloc", "MemoryError"];
+    if limits.memory_bytes.is_some() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if OOM_MARKERS.iter().any(|&marker| stderr.contains(marker)) {
+            return Err(ContractVerifierError::ResourceLimitExceeded(
+                compiler_name.to_owned(),
+                "memory",
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn limits(timeout: Duration) -> ResourceLimits {
+        ResourceLimits {
+            timeout,
+            cpu_time: timeout,
+            memory_bytes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn running_sandboxed_process() {
+        let mut command = Command::new("/bin/sh");
+        command.arg("-c").arg("cat; echo $SECRET_VAR; pwd");
+        // Variables set for the command must be cleared as well as the inherited ones.
+        command.env("SECRET_VAR", "secret");
+
+        let output = run_sandboxed(
+            "sh",
+            command,
+            Some(b"input\n".to_vec()),
+            &limits(Duration::from_secs(10)),
+        )
+        .await
+        .unwrap();
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<_> = stdout.lines().collect();
+        assert_eq!(lines[..2], ["input", ""]);
+        assert_ne!(lines[2], std::env::current_dir().unwrap().to_str().unwrap());
+    }
+
+    #[tokio::test]
+    async fn sandboxed_process_timeout() {
+        let mut command = Command::new("/bin/sh");
+        command.arg("-c").arg("sleep 10");
+
+        let err = run_sandboxed("sh", command, None, &limits(Duration::from_millis(100)))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::CompilationTimeout),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn sandboxed_process_cpu_limit() {
+        let mut command = Command::new("/bin/sh");
+        command.arg("-c").arg("while true; do :; done");
+        let limits = ResourceLimits {
+            timeout: Duration::from_secs(30),
+            cpu_time: Duration::from_secs(1),
+            memory_bytes: None,
+        };
+
+        let err = run_sandboxed("sh", command, None, &limits)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ContractVerifierError::ResourceLimitExceeded(_, "CPU time")
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn sandboxed_process_not_reading_stdin() {
+        let mut command = Command::new("/bin/sh");
+        command.arg("-c").arg("sleep 10");
+        // Larger than the pipe buffer, so writing it blocks until the process reads it.
+        let stdin = vec![b'0'; 1 << 20];
+
+        let err = run_sandboxed(
+            "sh",
+            command,
+            Some(stdin),
+            &limits(Duration::from_millis(100)),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::CompilationTimeout),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn killed_sandboxed_process_is_not_reported_as_exceeding_limits() {
+        let mut command = Command::new("/bin/sh");
+        command.arg("-c").arg("kill -KILL $$");
+
+        let output = run_sandboxed("sh", command, None, &limits(Duration::from_secs(10)))
+            .await
+            .unwrap();
+        assert!(!output.status.success());
+    }
+
+    #[tokio::test]
+    async fn sandboxed_process_group_is_killed_on_timeout() {
+        let pid_dir = tempfile::tempdir().unwrap();
+        let pid_path = pid_dir.path().join("pid");
+        let mut command = Command::new("/bin/sh");
+        command
+            .arg("-c")
+            .arg(format!("sleep 30 & echo $! > {}; wait", pid_path.display()));
+
+        let err = run_sandboxed("sh", command, None, &limits(Duration::from_millis(500)))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::CompilationTimeout),
+            "{err:?}"
+        );
+
+        let pid: libc::pid_t = std::fs::read_to_string(&pid_path)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        let stat_path = format!("/proc/{pid}/stat");
+        // The killed process may be reaped with a delay; until then, it's a zombie.
+        let is_running = || {
+            std::fs::read_to_string(&stat_path).map_or(false, |stat| {
+                let state = stat.rsplit(')').next().unwrap_or_default().trim_start();
+                !state.starts_with('Z')
+            })
+        };
+        for _ in 0..50 {
+            if !is_running() {
+                return;
+            }
+            time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Grandchild process {pid} survived the timeout");
+    }
+}
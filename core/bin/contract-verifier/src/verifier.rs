@@ -1,7 +1,7 @@
 use std::{
     collections::HashMap,
-    env,
     path::Path,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -10,21 +10,23 @@ use chrono::Utc;
 use ethabi::{Contract, Token};
 use lazy_static::lazy_static;
 use regex::Regex;
-use tokio::time;
 use zksync_config::ContractVerifierConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_env_config::FromEnv;
 use zksync_queued_job_processor::{async_trait, JobProcessor};
 use zksync_types::{
     contract_verification_api::{
-        CompilationArtifacts, CompilerType, DeployContractCalldata, SourceCodeData,
-        VerificationInfo, VerificationRequest,
+        CompilationArtifacts, CompilationError, CompilerType, DeployContractCalldata,
+        SourceCodeData, VerificationInfo, VerificationRequest,
     },
     Address,
 };
 
 use crate::{
+    compilers::{CompilerKind, CompilerRegistry},
     error::ContractVerifierError,
+    metadata::compare_bytecodes,
+    sandbox::ResourceLimits,
     zksolc_utils::{Optimizer, Settings, Source, StandardJson, ZkSolc, ZkSolcInput, ZkSolcOutput},
     zkvyper_utils::{ZkVyper, ZkVyperInput},
 };
@@ -43,13 +45,19 @@ enum ConstructorArgs {
 pub struct ContractVerifier {
     config: ContractVerifierConfig,
     connection_pool: ConnectionPool,
+    compilers: Arc<CompilerRegistry>,
 }
 
 impl ContractVerifier {
-    pub fn new(config: ContractVerifierConfig, connection_pool: ConnectionPool) -> Self {
+    pub fn new(
+        config: ContractVerifierConfig,
+        connection_pool: ConnectionPool,
+        compilers: Arc<CompilerRegistry>,
+    ) -> Self {
         Self {
             config,
             connection_pool,
+            compilers,
         }
     }
 
@@ -57,8 +65,9 @@ impl ContractVerifier {
         storage: &mut StorageProcessor<'_>,
         mut request: VerificationRequest,
         config: ContractVerifierConfig,
+        compilers: &CompilerRegistry,
     ) -> Result<VerificationInfo, ContractVerifierError> {
-        let artifacts = Self::compile(request.clone(), config).await?;
+        let artifacts = Self::compile(request.clone(), config, compilers).await?;
 
         // Bytecode should be present because it is checked when accepting request.
         let (deployed_bytecode, creation_tx_calldata) = storage
@@ -74,9 +83,8 @@ impl ContractVerifier {
             request.req.contract_address,
         );
 
-        if artifacts.bytecode != deployed_bytecode {
-            return Err(ContractVerifierError::BytecodeMismatch);
-        }
+        let match_level = compare_bytecodes(&artifacts.bytecode, &deployed_bytecode)
+            .ok_or(ContractVerifierError::BytecodeMismatch)?;
 
         match constructor_args {
             ConstructorArgs::Check(args) => {
@@ -93,12 +101,14 @@ impl ContractVerifier {
             request,
             artifacts,
             verified_at: Utc::now(),
+            match_level,
         })
     }
 
     async fn compile_zksolc(
         request: VerificationRequest,
         config: ContractVerifierConfig,
+        compilers: &CompilerRegistry,
     ) -> Result<CompilationArtifacts, ContractVerifierError> {
         // Users may provide either just contract name or
         // source file name and contract name joined with ":".
@@ -113,52 +123,28 @@ impl ContractVerifier {
             };
         let input = Self::build_zksolc_input(request.clone(), file_name.clone())?;
 
-        let zksync_home = env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-        let zksolc_path = Path::new(&zksync_home)
-            .join("etc")
-            .join("zksolc-bin")
-            .join(request.req.compiler_versions.zk_compiler_version())
-            .join("zksolc");
-        if !zksolc_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "zksolc".to_string(),
-                request.req.compiler_versions.zk_compiler_version(),
-            ));
-        }
-
-        let solc_path = Path::new(&zksync_home)
-            .join("etc")
-            .join("solc-bin")
-            .join(request.req.compiler_versions.compiler_version())
-            .join("solc");
-        if !solc_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "solc".to_string(),
-                request.req.compiler_versions.compiler_version(),
-            ));
-        }
-
-        let zksolc = ZkSolc::new(zksolc_path, solc_path);
-
-        let output = time::timeout(config.compilation_timeout(), zksolc.async_compile(input))
-            .await
-            .map_err(|_| ContractVerifierError::CompilationTimeout)??;
+        let zksolc_path = compilers.resolve(
+            CompilerKind::ZkSolc,
+            &request.req.compiler_versions.zk_compiler_version(),
+        )?;
+        let solc_path = compilers.resolve(
+            CompilerKind::Solc,
+            &request.req.compiler_versions.compiler_version(),
+        )?;
+        let zksolc = ZkSolc::new(zksolc_path, solc_path, ResourceLimits::new(&config));
+        let output = zksolc.async_compile(input).await?;
 
         match output {
             ZkSolcOutput::StandardJson(output) => {
                 if let Some(errors) = output.get("errors") {
-                    let errors = errors.as_array().unwrap().clone();
-                    if errors
-                        .iter()
-                        .any(|err| err["severity"].as_str().unwrap() == "error")
-                    {
-                        let error_messages = errors
-                            .into_iter()
-                            .map(|err| err["formattedMessage"].clone())
-                            .collect();
-                        return Err(ContractVerifierError::CompilationError(
-                            serde_json::Value::Array(error_messages),
-                        ));
+                    let errors: Vec<CompilationError> = serde_json::from_value(errors.clone())
+                        .map_err(|err| {
+                            tracing::error!("zksolc returned malformed errors: {err}");
+                            ContractVerifierError::InternalError
+                        })?;
+                    // Warnings are persisted together with errors, since they may help to find the cause.
+                    if errors.iter().any(CompilationError::is_error) {
+                        return Err(ContractVerifierError::CompilationError(errors));
                     }
                 }
 
@@ -201,6 +187,7 @@ impl ContractVerifier {
     async fn compile_zkvyper(
         request: VerificationRequest,
         config: ContractVerifierConfig,
+        compilers: &CompilerRegistry,
     ) -> Result<CompilationArtifacts, ContractVerifierError> {
         // Users may provide either just contract name or
         // source file name and contract name joined with ":".
@@ -212,36 +199,16 @@ impl ContractVerifier {
             };
         let input = Self::build_zkvyper_input(request.clone())?;
 
-        let zksync_home = env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-        let zkvyper_path = Path::new(&zksync_home)
-            .join("etc")
-            .join("zkvyper-bin")
-            .join(request.req.compiler_versions.zk_compiler_version())
-            .join("zkvyper");
-        if !zkvyper_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "zkvyper".to_string(),
-                request.req.compiler_versions.zk_compiler_version(),
-            ));
-        }
-
-        let vyper_path = Path::new(&zksync_home)
-            .join("etc")
-            .join("vyper-bin")
-            .join(request.req.compiler_versions.compiler_version())
-            .join("vyper");
-        if !vyper_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "vyper".to_string(),
-                request.req.compiler_versions.compiler_version(),
-            ));
-        }
-
-        let zkvyper = ZkVyper::new(zkvyper_path, vyper_path);
-
-        let output = time::timeout(config.compilation_timeout(), zkvyper.async_compile(input))
-            .await
-            .map_err(|_| ContractVerifierError::CompilationTimeout)??;
+        let zkvyper_path = compilers.resolve(
+            CompilerKind::ZkVyper,
+            &request.req.compiler_versions.zk_compiler_version(),
+        )?;
+        let vyper_path = compilers.resolve(
+            CompilerKind::Vyper,
+            &request.req.compiler_versions.compiler_version(),
+        )?;
+        let zkvyper = ZkVyper::new(zkvyper_path, vyper_path, ResourceLimits::new(&config));
+        let output = zkvyper.async_compile(input).await?;
 
         let file_name = format!("{contract_name}.vy");
         let object = output
@@ -268,10 +235,11 @@ impl ContractVerifier {
     async fn compile(
         request: VerificationRequest,
         config: ContractVerifierConfig,
+        compilers: &CompilerRegistry,
     ) -> Result<CompilationArtifacts, ContractVerifierError> {
         match request.req.source_code_data.compiler_type() {
-            CompilerType::Solc => Self::compile_zksolc(request, config).await,
-            CompilerType::Vyper => Self::compile_zkvyper(request, config).await,
+            CompilerType::Solc => Self::compile_zksolc(request, config, compilers).await,
+            CompilerType::Vyper => Self::compile_zkvyper(request, config, compilers).await,
         }
     }
 
@@ -431,12 +399,8 @@ impl ContractVerifier {
             }
             Err(error) => {
                 let error_message = error.to_string();
-                let compilation_errors = match error {
-                    ContractVerifierError::CompilationError(compilation_errors) => {
-                        compilation_errors
-                    }
-                    _ => serde_json::Value::Array(Vec::new()),
-                };
+                let compilation_errors = serde_json::to_value(error.compilation_errors())
+                    .expect("failed serializing compilation errors");
                 storage
                     .contract_verification_dal()
                     .save_verification_error(request_id, error_message, compilation_errors, None)
@@ -497,6 +461,7 @@ impl JobProcessor for ContractVerifier {
         started_at: Instant,
     ) -> tokio::task::JoinHandle<anyhow::Result<()>> {
         let connection_pool = self.connection_pool.clone();
+        let compilers = self.compilers.clone();
         tokio::task::spawn(async move {
             tracing::info!("Started to process request with id = {}", job.id);
 
@@ -505,7 +470,7 @@ impl JobProcessor for ContractVerifier {
             let mut connection = connection_pool.access_storage().await.unwrap();
 
             let job_id = job.id;
-            let verification_result = Self::verify(&mut connection, job, config).await;
+            let verification_result = Self::verify(&mut connection, job, config, &compilers).await;
             Self::process_result(&mut connection, job_id, verification_result).await;
 
             metrics::histogram!(
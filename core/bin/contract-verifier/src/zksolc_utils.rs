@@ -1,8 +1,11 @@
-use std::{collections::HashMap, io::Write, path::PathBuf, process::Stdio};
+use std::{collections::HashMap, io::Write, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
-use crate::error::ContractVerifierError;
+use crate::{
+    error::ContractVerifierError,
+    sandbox::{run_sandboxed, ResourceLimits},
+};
 
 #[derive(Debug)]
 pub enum ZkSolcInput {
@@ -101,13 +104,19 @@ impl Optimizer {
 pub struct ZkSolc {
     zksolc_path: PathBuf,
     solc_path: PathBuf,
+    limits: ResourceLimits,
 }
 
 impl ZkSolc {
-    pub fn new(zksolc_path: impl Into<PathBuf>, solc_path: impl Into<PathBuf>) -> Self {
+    pub fn new(
+        zksolc_path: impl Into<PathBuf>,
+        solc_path: impl Into<PathBuf>,
+        limits: ResourceLimits,
+    ) -> Self {
         ZkSolc {
             zksolc_path: zksolc_path.into(),
             solc_path: solc_path.into(),
+            limits,
         }
     }
 
@@ -115,40 +124,18 @@ impl ZkSolc {
         &self,
         input: ZkSolcInput,
     ) -> Result<ZkSolcOutput, ContractVerifierError> {
-        use tokio::io::AsyncWriteExt;
         let mut command = tokio::process::Command::new(&self.zksolc_path);
         if let ZkSolcInput::StandardJson(input) = &input {
             if input.settings.is_system {
                 command.arg("--system-mode");
             }
         }
-        command
-            .arg("--solc")
-            .arg(self.solc_path.to_str().unwrap())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+        command.arg("--solc").arg(self.solc_path.to_str().unwrap());
         match input {
             ZkSolcInput::StandardJson(input) => {
-                let mut child = command
-                    .arg("--standard-json")
-                    .stdin(Stdio::piped())
-                    .spawn()
-                    .map_err(|_err| ContractVerifierError::InternalError)?;
-                let stdin = child.stdin.as_mut().unwrap();
+                command.arg("--standard-json");
                 let content = serde_json::to_vec(&input).unwrap();
-                stdin
-                    .write_all(&content)
-                    .await
-                    .map_err(|_err| ContractVerifierError::InternalError)?;
-                stdin
-                    .flush()
-                    .await
-                    .map_err(|_err| ContractVerifierError::InternalError)?;
-
-                let output = child
-                    .wait_with_output()
-                    .await
-                    .map_err(|_err| ContractVerifierError::InternalError)?;
+                let output = run_sandboxed("zksolc", command, Some(content), &self.limits).await?;
                 if output.status.success() {
                     Ok(ZkSolcOutput::StandardJson(
                         serde_json::from_slice(&output.stdout)
@@ -170,18 +157,13 @@ impl ZkSolc {
                     .map_err(|_err| ContractVerifierError::InternalError)?;
                 file.write_all(content.as_bytes())
                     .map_err(|_err| ContractVerifierError::InternalError)?;
-                let child = command
+                command
                     .arg(file.path().to_str().unwrap())
                     .arg("--optimization")
                     .arg("3")
                     .arg("--yul")
-                    .arg("--bin")
-                    .spawn()
-                    .map_err(|_err| ContractVerifierError::InternalError)?;
-                let output = child
-                    .wait_with_output()
-                    .await
-                    .map_err(|_err| ContractVerifierError::InternalError)?;
+                    .arg("--bin");
+                let output = run_sandboxed("zksolc", command, None, &self.limits).await?;
                 if output.status.success() {
                     Ok(ZkSolcOutput::YulSingleFile(
                         String::from_utf8(output.stdout).expect("Couldn't parse string"),
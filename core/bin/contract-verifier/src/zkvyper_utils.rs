@@ -1,6 +1,9 @@
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, process::Stdio};
+use std::{collections::HashMap, fs::File, io::Write, path::PathBuf};
 
-use crate::error::ContractVerifierError;
+use crate::{
+    error::ContractVerifierError,
+    sandbox::{run_sandboxed, ResourceLimits},
+};
 
 #[derive(Debug)]
 pub struct ZkVyperInput {
@@ -11,13 +14,19 @@ pub struct ZkVyperInput {
 pub struct ZkVyper {
     zkvyper_path: PathBuf,
     vyper_path: PathBuf,
+    limits: ResourceLimits,
 }
 
 impl ZkVyper {
-    pub fn new(zkvyper_path: impl Into<PathBuf>, vyper_path: impl Into<PathBuf>) -> Self {
+    pub fn new(
+        zkvyper_path: impl Into<PathBuf>,
+        vyper_path: impl Into<PathBuf>,
+        limits: ResourceLimits,
+    ) -> Self {
         ZkVyper {
             zkvyper_path: zkvyper_path.into(),
             vyper_path: vyper_path.into(),
+            limits,
         }
     }
 
@@ -33,9 +42,7 @@ impl ZkVyper {
             .arg("--vyper")
             .arg(self.vyper_path.to_str().unwrap())
             .arg("-f")
-            .arg("combined_json")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .arg("combined_json");
 
         let temp_dir = tempfile::tempdir().map_err(|_err| ContractVerifierError::InternalError)?;
         for (mut name, content) in input.sources {
@@ -54,13 +61,7 @@ impl ZkVyper {
             command.arg(path.into_os_string());
         }
 
-        let child = command
-            .spawn()
-            .map_err(|_err| ContractVerifierError::InternalError)?;
-        let output = child
-            .wait_with_output()
-            .await
-            .map_err(|_err| ContractVerifierError::InternalError)?;
+        let output = run_sandboxed("zkvyper", command, None, &self.limits).await?;
         if output.status.success() {
             Ok(serde_json::from_slice(&output.stdout).expect("Compiler output must be valid JSON"))
         } else {
@@ -183,6 +183,9 @@ pub struct OptionalENConfig {
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     #[serde(default = "OptionalENConfig::default_merkle_tree_stalled_writes_timeout_sec")]
     merkle_tree_stalled_writes_timeout_sec: u64,
+    /// Number of past L1 batches retained in the Merkle tree besides the latest one. If not specified,
+    /// the tree is not pruned.
+    pub merkle_tree_pruning_retained_l1_batches: Option<u64>,
 
     // Other config settings
     /// Port on which the Prometheus exporter server is listening.
@@ -9,7 +9,7 @@ use zksync_basic_types::{Address, L2ChainId};
 use zksync_core::{
     api_server::{
         execution_sandbox::VmConcurrencyLimiter,
-        healthcheck::HealthCheckHandle,
+        healthcheck::{HealthCheckHandle, SlowQueriesVisibility},
         tx_sender::{ApiContracts, TxSenderBuilder},
         web3::{ApiBuilder, Namespace},
     },
@@ -162,6 +162,7 @@ async fn init_tasks(
         block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
         memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
         stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+        pruning_retained_l1_batches: config.optional.merkle_tree_pruning_retained_l1_batches,
     })
     .await;
     healthchecks.push(Box::new(metadata_calculator.tree_health_check()));
@@ -275,6 +276,7 @@ async fn init_tasks(
     let healthcheck_handle = HealthCheckHandle::spawn_server(
         ([0, 0, 0, 0], config.required.healthcheck_port).into(),
         healthchecks,
+        SlowQueriesVisibility::Hidden,
     );
 
     if let Some(port) = config.optional.prometheus_port {
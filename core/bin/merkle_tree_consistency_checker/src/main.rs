@@ -1,12 +1,23 @@
-use std::{path::Path, time::Instant};
+use std::{
+    fs, ops,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use anyhow::Context as _;
 use clap::Parser;
-use zksync_config::DBConfig;
+use serde::Serialize;
+use zksync_config::{DBConfig, PostgresConfig};
+use zksync_dal::ConnectionPool;
 use zksync_env_config::FromEnv;
-use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_merkle_tree::{
+    domain::ZkSyncTree,
+    recovery::{partition_key_range, PARTITION_COUNT},
+    ConsistencyCheckOptions, ConsistencyReport, Key, TreeEntry,
+};
 use zksync_storage::RocksDB;
-use zksync_types::L1BatchNumber;
+use zksync_types::{L1BatchNumber, H256};
+use zksync_utils::u256_to_h256;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -20,34 +31,251 @@ struct Cli {
     /// applied to it last. If not specified, the latest tree version is checked.
     #[arg(long = "l1-batch")]
     l1_batch: Option<u32>,
+    /// Restricts the check to the specified key partition (0..=15), i.e. to hashed keys starting
+    /// with the specified hex digit. If not specified, the entire tree is checked.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..PARTITION_COUNT as i64))]
+    partition: Option<u8>,
+    /// Path to write a JSON report with all found inconsistencies to.
+    #[arg(long)]
+    report: Option<PathBuf>,
+    /// Rebuilds tree partitions affected by inconsistencies using storage logs from Postgres.
+    /// Only the latest tree version can be repaired. The tree must not be used by other components
+    /// (e.g., the metadata calculator) at the same time.
+    #[arg(long)]
+    repair: bool,
 }
 
 impl Cli {
-    fn run(self, config: &DBConfig) {
+    async fn run(self, config: &DBConfig) -> anyhow::Result<()> {
         let db_path = &config.merkle_tree.path;
         tracing::info!("Verifying consistency of Merkle tree at {db_path}");
         let start = Instant::now();
         let db = RocksDB::new(Path::new(db_path));
-        let tree = ZkSyncTree::new_lightweight(db);
-
-        let l1_batch_number = if let Some(number) = self.l1_batch {
-            L1BatchNumber(number)
-        } else {
-            let next_number = tree.next_l1_batch_number();
-            if next_number == L1BatchNumber(0) {
-                tracing::info!("Merkle tree is empty, skipping");
-                return;
-            }
-            next_number - 1
-        };
+        let mut tree = ZkSyncTree::new_lightweight(db);
 
+        let next_number = tree.next_l1_batch_number();
+        if next_number == L1BatchNumber(0) {
+            tracing::info!("Merkle tree is empty, skipping");
+            return Ok(());
+        }
+        let l1_batch_number = self.l1_batch.map_or(next_number - 1, L1BatchNumber);
         tracing::info!("L1 batch number to check: {l1_batch_number}");
-        tree.verify_consistency(l1_batch_number);
-        tracing::info!("Merkle tree verified in {:?}", start.elapsed());
+
+        let options = ConsistencyCheckOptions {
+            key_range: self
+                .partition
+                .map(|partition| partition_key_range(partition.into())),
+            ..ConsistencyCheckOptions::default()
+        };
+        let report = tree
+            .check_consistency(l1_batch_number, &options)
+            .with_context(|| {
+                format!("failed checking Merkle tree at L1 batch #{l1_batch_number}")
+            })?;
+        log_report(&report);
+        tracing::info!("Merkle tree checked in {:?}", start.elapsed());
+        if let Some(report_path) = &self.report {
+            let json_report = JsonReport::new(l1_batch_number, &report);
+            let json_report = serde_json::to_string_pretty(&json_report)?;
+            fs::write(report_path, json_report)
+                .with_context(|| format!("failed writing report to `{}`", report_path.display()))?;
+            tracing::info!("Written report to `{}`", report_path.display());
+        }
+
+        if report.is_consistent() {
+            return Ok(());
+        }
+        anyhow::ensure!(
+            self.repair,
+            "Merkle tree at L1 batch #{l1_batch_number} has {} inconsistencies",
+            report.errors.len()
+        );
+        anyhow::ensure!(
+            l1_batch_number == next_number - 1,
+            "Only the latest tree version (L1 batch #{}) can be repaired",
+            next_number - 1
+        );
+
+        let partitions = report.affected_partitions();
+        repair_tree(&mut tree, l1_batch_number, &partitions).await?;
+        let report = tree
+            .check_consistency(l1_batch_number, &options)
+            .context("failed checking Merkle tree after repair")?;
+        log_report(&report);
+        anyhow::ensure!(
+            report.is_consistent(),
+            "Merkle tree at L1 batch #{l1_batch_number} has {} inconsistencies after repair",
+            report.errors.len()
+        );
+        Ok(())
+    }
+}
+
+fn log_report(report: &ConsistencyReport) {
+    tracing::info!(
+        "Checked {} nodes ({} leaves), found {} inconsistencies",
+        report.checked_nodes,
+        report.checked_leaves,
+        report.errors.len()
+    );
+    for err in &report.errors {
+        tracing::warn!("{err}");
+    }
+    if !report.is_consistent() {
+        tracing::warn!(
+            "Affected key partitions: {:?}",
+            report.affected_partitions()
+        );
+    }
+}
+
+/// Approximate number of storage logs loaded from Postgres in a single query during repair.
+const REPAIR_CHUNK_SIZE: u64 = 200_000;
+
+/// Splits the specified key partition into `chunk_count` contiguous key ranges. Like with
+/// `get_storage_logs_chunk()`, the end of each range is exclusive.
+fn partition_chunk_ranges(
+    partition: usize,
+    chunk_count: u64,
+) -> impl Iterator<Item = ops::RangeInclusive<H256>> {
+    let key_range = partition_key_range(partition);
+    let (partition_start, partition_end) = (*key_range.start(), *key_range.end());
+    let stride = (partition_end - partition_start) / chunk_count + 1;
+    (0..chunk_count).map(move |i| {
+        let start = partition_start + stride * i;
+        let end = start.saturating_add(stride).min(partition_end);
+        u256_to_h256(start)..=u256_to_h256(end)
+    })
+}
+
+/// Rebuilds the specified key partitions of the tree one by one using storage logs from Postgres.
+async fn repair_tree(
+    tree: &mut ZkSyncTree,
+    l1_batch_number: L1BatchNumber,
+    partitions: &[usize],
+) -> anyhow::Result<()> {
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let mut storage = pool.access_storage().await?;
+    let (_, last_miniblock) = storage
+        .blocks_dal()
+        .get_miniblock_range_of_l1_batch(l1_batch_number)
+        .await?
+        .with_context(|| format!("L1 batch #{l1_batch_number} is not present in Postgres"))?;
+    let expected_root_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(l1_batch_number)
+        .await?
+        .with_context(|| format!("root hash for L1 batch #{l1_batch_number} is not in Postgres"))?;
+    let storage_log_count = storage
+        .snapshots_creator_dal()
+        .get_storage_logs_count(l1_batch_number)
+        .await?;
+    // Hashed keys are distributed uniformly, so each chunk contains approximately
+    // `REPAIR_CHUNK_SIZE` storage logs.
+    let partition_chunk_size = REPAIR_CHUNK_SIZE * PARTITION_COUNT as u64;
+    let chunks_per_partition =
+        (storage_log_count + partition_chunk_size - 1) / partition_chunk_size;
+    let chunks_per_partition = chunks_per_partition.max(1);
+
+    if partitions.is_empty() {
+        // Inconsistencies not attributed to any keys (e.g., a leaf count mismatch) are repaired
+        // by recomputing the tree root.
+        tracing::info!("Recomputing Merkle tree root");
+        tree.rebuild_partitions(&[], &[]);
+    }
+    for &partition in partitions {
+        let mut entries = vec![];
+        // The greatest possible key in a partition is excluded from the last chunk, but it cannot
+        // practically be hit by a hash.
+        for key_range in partition_chunk_ranges(partition, chunks_per_partition) {
+            let storage_logs = storage
+                .snapshots_creator_dal()
+                .get_storage_logs_chunk(last_miniblock, key_range)
+                .await?;
+            entries.extend(
+                storage_logs
+                    .into_iter()
+                    .map(|log| TreeEntry::new(log.key, log.enumeration_index, log.value)),
+            );
+        }
+        tracing::info!(
+            "Rebuilding partition {partition:x} with {} entries",
+            entries.len()
+        );
+        tree.rebuild_partitions(&[partition], &entries);
+    }
+
+    let root_hash = tree.root_hash();
+    anyhow::ensure!(
+        root_hash == expected_root_hash,
+        "Root hash mismatch after repair: tree has {root_hash:?}, Postgres has {expected_root_hash:?}"
+    );
+    tracing::info!("Repaired Merkle tree partitions {partitions:?}");
+    Ok(())
+}
+
+/// JSON-serializable version of a [`ConsistencyReport`].
+#[derive(Debug, Serialize)]
+struct JsonReport {
+    l1_batch_number: u32,
+    key_range: Option<JsonKeyRange>,
+    checked_nodes: u64,
+    checked_leaves: u64,
+    errors: Vec<JsonError>,
+    /// Repair hints: key ranges and key partitions affected by inconsistencies.
+    affected_key_ranges: Vec<JsonKeyRange>,
+    affected_partitions: Vec<usize>,
+}
+
+impl JsonReport {
+    fn new(l1_batch_number: L1BatchNumber, report: &ConsistencyReport) -> Self {
+        let errors = report.errors.iter().map(|err| JsonError {
+            message: err.to_string(),
+            affected_key_range: err.affected_key_range().map(JsonKeyRange::from),
+        });
+        Self {
+            l1_batch_number: l1_batch_number.0,
+            key_range: report.key_range.clone().map(JsonKeyRange::from),
+            checked_nodes: report.checked_nodes,
+            checked_leaves: report.checked_leaves,
+            errors: errors.collect(),
+            affected_key_ranges: report
+                .affected_key_ranges()
+                .into_iter()
+                .map(JsonKeyRange::from)
+                .collect(),
+            affected_partitions: report.affected_partitions(),
+        }
     }
 }
 
-fn main() -> anyhow::Result<()> {
+#[derive(Debug, Serialize)]
+struct JsonError {
+    message: String,
+    affected_key_range: Option<JsonKeyRange>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonKeyRange {
+    start: H256,
+    end: H256,
+}
+
+impl From<ops::RangeInclusive<Key>> for JsonKeyRange {
+    fn from(range: ops::RangeInclusive<Key>) -> Self {
+        Self {
+            start: u256_to_h256(*range.start()),
+            end: u256_to_h256(*range.end()),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
     #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
     let log_format = vlog::log_format_from_env();
     #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
@@ -65,6 +293,5 @@ fn main() -> anyhow::Result<()> {
     let _guard = builder.build();
 
     let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
-    Cli::parse().run(&db_config);
-    Ok(())
+    Cli::parse().run(&db_config).await
 }
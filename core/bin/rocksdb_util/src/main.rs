@@ -1,11 +1,17 @@
+use std::path::Path;
+
 use anyhow::Context as _;
 use clap::{Parser, Subcommand};
-use zksync_config::DBConfig;
+use zksync_config::{DBConfig, ObjectStoreConfig, PostgresConfig};
+use zksync_core::metadata_calculator::{create_tree_checkpoint, restore_tree_checkpoint};
+use zksync_dal::ConnectionPool;
 use zksync_env_config::FromEnv;
+use zksync_object_store::ObjectStoreFactory;
 use zksync_storage::rocksdb::{
     backup::{BackupEngine, BackupEngineOptions, RestoreOptions},
     Env, Error, Options, DB,
 };
+use zksync_types::L1BatchNumber;
 
 #[derive(Debug, Parser)]
 #[command(author = "Matter Labs", version, about = "RocksDB management utility", long_about = None)]
@@ -22,6 +28,22 @@ enum Command {
     /// Restores RocksDB from backup.
     #[command(name = "restore-from-backup")]
     Restore,
+    /// Creates a Merkle tree checkpoint for the specified L1 batch and uploads it to the object store.
+    /// The tree must not be used by other components (e.g., the metadata calculator) at the same time.
+    #[command(name = "tree-checkpoint")]
+    TreeCheckpoint {
+        /// L1 batch to create the checkpoint for.
+        #[arg(long)]
+        l1_batch: u32,
+    },
+    /// Restores the Merkle tree from a checkpoint stored in the object store. The Merkle tree
+    /// directory must not exist.
+    #[command(name = "restore-tree-checkpoint")]
+    RestoreTreeCheckpoint {
+        /// L1 batch of the checkpoint to restore.
+        #[arg(long)]
+        l1_batch: u32,
+    },
 }
 
 fn create_backup(config: &DBConfig) -> Result<(), Error> {
@@ -44,13 +66,58 @@ fn restore_from_latest_backup(config: &DBConfig) -> Result<(), Error> {
     engine.restore_from_latest_backup(db_dir, db_dir, &RestoreOptions::default())
 }
 
-fn main() -> anyhow::Result<()> {
+async fn create_checkpoint(
+    config: &DBConfig,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<()> {
+    let object_store_config =
+        ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+    let object_store = ObjectStoreFactory::new(object_store_config)
+        .create_store()
+        .await;
+    let db_path = Path::new(&config.merkle_tree.path);
+    let manifest = create_tree_checkpoint(db_path, l1_batch_number, &*object_store).await?;
+    println!("Created Merkle tree checkpoint: {manifest:#?}");
+    Ok(())
+}
+
+async fn restore_checkpoint(
+    config: &DBConfig,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<()> {
+    let object_store_config =
+        ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+    let object_store = ObjectStoreFactory::new(object_store_config)
+        .create_store()
+        .await;
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let mut storage = pool.access_storage().await?;
+    let db_path = Path::new(&config.merkle_tree.path);
+    restore_tree_checkpoint(&mut storage, &*object_store, l1_batch_number, db_path).await
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
     let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
     match Cli::parse().command {
         Command::Backup => create_backup(&db_config).context("create_backup"),
         Command::Restore => {
             restore_from_latest_backup(&db_config).context("restore_from_latest_backup")
         }
+        Command::TreeCheckpoint { l1_batch } => {
+            create_checkpoint(&db_config, L1BatchNumber(l1_batch))
+                .await
+                .context("create_checkpoint")
+        }
+        Command::RestoreTreeCheckpoint { l1_batch } => {
+            restore_checkpoint(&db_config, L1BatchNumber(l1_batch))
+                .await
+                .context("restore_checkpoint")
+        }
     }
 }
 
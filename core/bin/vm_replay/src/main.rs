@@ -0,0 +1,133 @@
+use anyhow::Context as _;
+use clap::{Parser, ValueEnum};
+use zksync_config::{configs::chain::NetworkConfig, PostgresConfig};
+use zksync_core::vm_replay::{L1BatchDiff, VmReplayer};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_types::{L1BatchNumber, VmVersion};
+
+/// VM version to replay L1 batches with.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum VmVersionArg {
+    M5WithoutRefunds,
+    M5WithRefunds,
+    M6Initial,
+    M6BugWithCompressionFixed,
+    Vm1_3_2,
+    VmVirtualBlocks,
+    VmVirtualBlocksRefundsEnhancement,
+    VmBoojumIntegration,
+}
+
+impl From<VmVersionArg> for VmVersion {
+    fn from(arg: VmVersionArg) -> Self {
+        match arg {
+            VmVersionArg::M5WithoutRefunds => Self::M5WithoutRefunds,
+            VmVersionArg::M5WithRefunds => Self::M5WithRefunds,
+            VmVersionArg::M6Initial => Self::M6Initial,
+            VmVersionArg::M6BugWithCompressionFixed => Self::M6BugWithCompressionFixed,
+            VmVersionArg::Vm1_3_2 => Self::Vm1_3_2,
+            VmVersionArg::VmVirtualBlocks => Self::VmVirtualBlocks,
+            VmVersionArg::VmVirtualBlocksRefundsEnhancement => {
+                Self::VmVirtualBlocksRefundsEnhancement
+            }
+            VmVersionArg::VmBoojumIntegration => Self::VmBoojumIntegration,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "VM replay utility", long_about = None)]
+struct Cli {
+    /// First L1 batch to replay.
+    #[arg(long)]
+    from: u32,
+    /// Last L1 batch to replay (inclusive). If not specified, only the `from` batch is replayed.
+    #[arg(long)]
+    to: Option<u32>,
+    /// Additionally replays each batch with the specified VM version and compares outputs
+    /// of both runs instead of comparing with Postgres.
+    #[arg(long, value_enum)]
+    compare_with: Option<VmVersionArg>,
+    /// Outputs diffs as JSON objects, so that they are machine-readable.
+    #[arg(long)]
+    json: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
+    let log_format = vlog::log_format_from_env();
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let cli = Cli::parse();
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let replayer = VmReplayer::new(pool, network_config.zksync_network_id);
+
+    let last_l1_batch = cli.to.unwrap_or(cli.from);
+    let mut batches_with_mismatches = vec![];
+    for number in cli.from..=last_l1_batch {
+        let l1_batch_number = L1BatchNumber(number);
+        let outputs = replayer
+            .replay(l1_batch_number, None)
+            .await
+            .with_context(|| format!("failed replaying L1 batch #{l1_batch_number}"))?;
+        let diff = if let Some(vm_version) = cli.compare_with {
+            let other_outputs = replayer
+                .replay(l1_batch_number, Some(vm_version.into()))
+                .await
+                .with_context(|| {
+                    format!("failed replaying L1 batch #{l1_batch_number} with {vm_version:?}")
+                })?;
+            L1BatchDiff::new(&outputs, &other_outputs)
+        } else {
+            let persisted_outputs = replayer
+                .load_persisted_outputs(l1_batch_number)
+                .await
+                .with_context(|| {
+                    format!("failed loading outputs of L1 batch #{l1_batch_number}")
+                })?;
+            L1BatchDiff::new(&persisted_outputs, &outputs)
+        };
+
+        if cli.json {
+            let output = serde_json::json!({
+                "l1_batch_number": l1_batch_number,
+                "diff": diff,
+            });
+            println!("{output}");
+        } else if diff.is_empty() {
+            println!("L1 batch #{l1_batch_number}: no mismatches");
+        } else {
+            println!(
+                "L1 batch #{l1_batch_number}: {} mismatch(es)",
+                diff.mismatches.len()
+            );
+            for mismatch in &diff.mismatches {
+                println!("  {mismatch}");
+            }
+        }
+        if !diff.skipped.is_empty() {
+            tracing::warn!(
+                "Outputs {:?} were not compared for L1 batch #{l1_batch_number} since they are missing",
+                diff.skipped
+            );
+        }
+        if !diff.is_empty() {
+            batches_with_mismatches.push(l1_batch_number);
+        }
+    }
+
+    anyhow::ensure!(
+        batches_with_mismatches.is_empty(),
+        "mismatches found for L1 batches {batches_with_mismatches:?}"
+    );
+    Ok(())
+}
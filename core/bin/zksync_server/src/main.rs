@@ -11,8 +11,9 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig,
-        ProofDataHandlerConfig, ProverGroupConfig, WitnessGeneratorConfig,
+        BasicWitnessInputProducerConfig, FriProofCompressorConfig, FriProverConfig,
+        FriWitnessGeneratorConfig, PrometheusConfig, ProofDataHandlerConfig, ProverGroupConfig,
+        PruningConfig, WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     FetcherConfig, GasAdjusterConfig, ObjectStoreConfig, PostgresConfig, ProverConfigs,
@@ -111,6 +112,8 @@ async fn main() -> anyhow::Result<()> {
         prometheus_config: PrometheusConfig::from_env().ok(),
         proof_data_handler_config: ProofDataHandlerConfig::from_env().ok(),
         prover_group_config: ProverGroupConfig::from_env().ok(),
+        pruning_config: PruningConfig::from_env().ok(),
+        basic_witness_input_producer_config: BasicWitnessInputProducerConfig::from_env().ok(),
         witness_generator_config: WitnessGeneratorConfig::from_env().ok(),
         api_config: ApiConfig::from_env().ok(),
         contracts_config: ContractsConfig::from_env().ok(),
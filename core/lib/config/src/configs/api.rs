@@ -89,6 +89,10 @@ pub struct Web3JsonRpcConfig {
     pub websocket_requests_per_minute_limit: Option<u32>,
     /// Tree API url, currently used to proxy `getProof` calls to the tree
     pub tree_api_url: Option<String>,
+    /// Path to the RocksDB instance used as the warm VM state for sandboxed execution against the latest
+    /// and pending miniblocks. The state is synced from Postgres, similarly to the state keeper cache.
+    /// If not set, the warm VM state is disabled, and all VM storage reads are served by Postgres.
+    pub warm_vm_state_db_path: Option<String>,
 }
 
 impl Web3JsonRpcConfig {
@@ -126,6 +130,7 @@ impl Web3JsonRpcConfig {
             max_response_body_size_mb: Default::default(),
             websocket_requests_per_minute_limit: Default::default(),
             tree_api_url: None,
+            warm_vm_state_db_path: None,
         }
     }
 
@@ -212,12 +217,23 @@ impl Web3JsonRpcConfig {
     pub fn tree_api_url(&self) -> Option<String> {
         self.tree_api_url.clone()
     }
+
+    pub fn warm_vm_state_db_path(&self) -> Option<&str> {
+        self.warm_vm_state_db_path.as_deref()
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct HealthCheckConfig {
     /// Port to which the REST server is listening.
     pub port: u16,
+    /// Whether to serve DB queries that are currently executing for too long on `/debug/slow_queries`.
+    #[serde(default)]
+    pub expose_slow_queries: bool,
+    /// Whether to include bound query arguments into `/debug/slow_queries` output. Arguments may contain
+    /// sensitive data, so they are omitted by default. Has no effect unless `expose_slow_queries` is set.
+    #[serde(default)]
+    pub expose_slow_query_args: bool,
 }
 
 impl HealthCheckConfig {
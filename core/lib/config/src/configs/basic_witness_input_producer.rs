@@ -0,0 +1,16 @@
+use serde::Deserialize;
+
+/// Configuration for the basic witness input producer.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct BasicWitnessInputProducerConfig {
+    /// Size of the in-memory cache of produced witness inputs in megabytes. The cache allows retried jobs
+    /// to skip re-executing the L1 batch. If not specified, the cache is disabled.
+    #[serde(default)]
+    pub witness_input_cache_size_mb: Option<u64>,
+}
+
+impl BasicWitnessInputProducerConfig {
+    pub fn witness_input_cache_capacity(&self) -> u64 {
+        self.witness_input_cache_size_mb.unwrap_or(0) * super::BYTES_IN_MEGABYTE as u64
+    }
+}
@@ -10,6 +10,14 @@ pub struct ContractVerifierConfig {
     pub polling_interval: Option<u64>,
     /// Port to which the Prometheus exporter server is listening.
     pub prometheus_port: u16,
+    /// Max CPU time of a compiler process (in s). If not set, the compilation timeout is used.
+    pub compilation_cpu_limit: Option<u64>,
+    /// Max virtual memory of a compiler process (in MB). If not set, memory is not limited.
+    pub compilation_memory_limit_mb: Option<u64>,
+    /// Whether to reject compilers without a checksum file. Compilers with a mismatching checksum
+    /// are always rejected.
+    #[serde(default)]
+    pub require_compiler_checksums: bool,
 }
 
 impl ContractVerifierConfig {
@@ -17,6 +25,18 @@ impl ContractVerifierConfig {
         Duration::from_secs(self.compilation_timeout)
     }
 
+    pub fn compilation_cpu_limit(&self) -> Duration {
+        Duration::from_secs(
+            self.compilation_cpu_limit
+                .unwrap_or(self.compilation_timeout),
+        )
+    }
+
+    pub fn compilation_memory_limit_bytes(&self) -> Option<u64> {
+        self.compilation_memory_limit_mb
+            .map(|mb| mb * 1_024 * 1_024)
+    }
+
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval.unwrap_or(1000))
     }
@@ -47,6 +47,11 @@ pub struct MerkleTreeConfig {
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// Number of past L1 batches (= tree versions) retained in the Merkle tree besides the latest one.
+    /// Older versions are pruned and cannot be queried via the tree API. If not specified,
+    /// pruning is disabled and all tree versions are retained.
+    #[serde(default)]
+    pub pruning_retained_l1_batches: Option<u64>,
 }
 
 impl Default for MerkleTreeConfig {
@@ -60,6 +65,7 @@ impl Default for MerkleTreeConfig {
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            pruning_retained_l1_batches: None,
         }
     }
 }
@@ -155,6 +161,12 @@ pub struct PostgresConfig {
     pub master_url: Option<String>,
     /// URL for the replica database.
     pub replica_url: Option<String>,
+    /// URLs for additional read replicas. If specified, connections for the API servers are routed
+    /// to these replicas based on their replication lag, with the master database used as a fallback.
+    pub read_replica_urls: Vec<String>,
+    /// Maximum size of the master database pool used as a fallback if `read_replica_urls` are specified.
+    /// If not specified, a small default size is used.
+    pub read_replica_fallback_pool_size: Option<u32>,
     /// URL for the prover database.
     pub prover_url: Option<String>,
     /// Maximum size of the connection pool.
@@ -22,6 +22,9 @@ pub struct FriProverConfig {
     pub witness_vector_generator_thread_count: Option<usize>,
     pub queue_capacity: usize,
     pub witness_vector_receiver_port: u16,
+    /// Duration of leases for picked jobs. If set, provers periodically extend leases while jobs are processed,
+    /// and jobs with expired leases are re-queued without waiting for the processing timeout.
+    pub job_lease_duration_in_secs: Option<u32>,
 
     // whether to write to public GCS bucket for https://github.com/matter-labs/era-boojum-validator-cli
     pub shall_save_to_public_bucket: bool,
@@ -31,4 +34,9 @@ impl FriProverConfig {
     pub fn proof_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.generation_timeout_in_secs as u64)
     }
+
+    pub fn job_lease_duration(&self) -> Option<Duration> {
+        self.job_lease_duration_in_secs
+            .map(|secs| Duration::from_secs(secs.into()))
+    }
 }
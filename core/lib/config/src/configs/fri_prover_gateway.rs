@@ -6,6 +6,13 @@ use serde::Deserialize;
 pub struct FriProverGatewayConfig {
     pub api_url: String,
     pub api_poll_duration_secs: u16,
+    /// API key of the prover cluster used to authenticate in the proof data handler API.
+    pub api_key: Option<String>,
+    /// Port of the HTTP API for prover operators (batch priorities). The API is only bound
+    /// to localhost. If not set, the API is not started.
+    pub operator_api_port: Option<u16>,
+    /// Port of the read-only proving dashboard API. If not set, the API is not started.
+    pub dashboard_port: Option<u16>,
 
     /// Configurations for prometheus
     pub prometheus_listener_port: u16,
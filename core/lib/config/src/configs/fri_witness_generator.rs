@@ -24,6 +24,10 @@ pub struct FriWitnessGeneratorConfig {
 
     // whether to write to public GCS bucket for https://github.com/matter-labs/era-boojum-validator-cli
     pub shall_save_to_public_bucket: bool,
+    /// Capacity of the in-memory cache of basic circuits witness generation inputs, in megabytes.
+    /// The cache allows to skip reloading inputs when a job is retried or a batch is re-proven
+    /// by the same witness generator. If not set, caching is disabled.
+    pub input_cache_size_mb: Option<u64>,
 }
 impl FriWitnessGeneratorConfig {
     pub fn witness_generation_timeout(&self) -> Duration {
@@ -33,4 +37,8 @@ impl FriWitnessGeneratorConfig {
     pub fn last_l1_batch_to_process(&self) -> u32 {
         self.last_l1_batch_to_process.unwrap_or(u32::MAX)
     }
+
+    pub fn input_cache_capacity(&self) -> u64 {
+        self.input_cache_size_mb.unwrap_or(0) * 1_024 * 1_024
+    }
 }
@@ -2,6 +2,7 @@
 pub use self::{
     alerts::AlertsConfig,
     api::ApiConfig,
+    basic_witness_input_producer::BasicWitnessInputProducerConfig,
     chain::ChainConfig,
     circuit_synthesizer::CircuitSynthesizerConfig,
     contract_verifier::ContractVerifierConfig,
@@ -20,6 +21,7 @@ pub use self::{
     proof_data_handler::ProofDataHandlerConfig,
     prover::{ProverConfig, ProverConfigs},
     prover_group::ProverGroupConfig,
+    pruning::PruningConfig,
     snapshots_creator::SnapshotsCreatorConfig,
     utils::PrometheusConfig,
     witness_generator::WitnessGeneratorConfig,
@@ -27,6 +29,7 @@ pub use self::{
 
 pub mod alerts;
 pub mod api;
+pub mod basic_witness_input_producer;
 pub mod chain;
 pub mod circuit_synthesizer;
 pub mod contract_verifier;
@@ -47,6 +50,7 @@ pub mod object_store;
 pub mod proof_data_handler;
 pub mod prover;
 pub mod prover_group;
+pub mod pruning;
 pub mod snapshots_creator;
 pub mod utils;
 pub mod witness_generator;
@@ -14,9 +14,36 @@ pub struct ProofDataHandlerConfig {
     pub proof_generation_timeout_in_secs: u16,
     pub protocol_version_loading_mode: ProtocolVersionLoadingMode,
     pub fri_protocol_version_id: u16,
+    /// API keys of prover clusters allowed to use the API, each in the `<cluster_name>:<api_key>` format.
+    /// If neither API keys nor client identities are configured, the API is unauthenticated, and all requests
+    /// are attributed to a single `default` cluster.
+    #[serde(default)]
+    pub prover_cluster_api_keys: Vec<String>,
+    /// Name of the HTTP header containing the verified client certificate identity. Should be set
+    /// by an mTLS-terminating proxy in front of the API, which must strip this header from incoming requests.
+    pub client_identity_header: Option<String>,
+    /// Explicit opt-in for authenticating prover clusters by the client identity header instead of
+    /// an API key. Must be set if `client_identity_header` is set.
+    #[serde(default)]
+    pub trust_client_identity_header: bool,
+    /// Client certificate identities of prover clusters, each in the `<cluster_name>:<identity>` format.
+    #[serde(default)]
+    pub prover_cluster_identities: Vec<String>,
+    /// Maximum number of prover clusters that can concurrently prove the same L1 batch. If not set,
+    /// each batch is proven by a single cluster at a time.
+    pub max_provers_per_batch: Option<usize>,
+    /// Path to the JSON-serialized verification key of the SNARK wrapper used to verify submitted proofs.
+    /// The key must match the scheduler verification key hash of the current protocol version.
+    /// If not set, submitted proofs are rejected.
+    pub snark_wrapper_vk_path: Option<String>,
 }
+
 impl ProofDataHandlerConfig {
     pub fn proof_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.proof_generation_timeout_in_secs as u64)
     }
+
+    pub fn max_provers_per_batch(&self) -> usize {
+        self.max_provers_per_batch.unwrap_or(1)
+    }
 }
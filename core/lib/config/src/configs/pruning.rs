@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the Postgres pruning component.
+///
+/// Retention is specified per class of data in L1 batches, counting back from the last L1 batch that
+/// is executed on L1 and processed by the Merkle tree. If retention for a class is not specified,
+/// the corresponding data is not pruned.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PruningConfig {
+    /// Interval between pruning iterations in milliseconds.
+    #[serde(default = "PruningConfig::default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Maximum number of L1 batches pruned in a single database transaction. Keeping this value small
+    /// bounds the duration of locks held by the pruner.
+    #[serde(default = "PruningConfig::default_chunk_size")]
+    pub chunk_size: u32,
+    /// Retention for the state history, i.e., storage logs overwritten by later writes. The latest value
+    /// for each storage slot is never pruned.
+    #[serde(default)]
+    pub storage_logs_retention_l1_batches: Option<u32>,
+    /// Retention for events and L2-to-L1 logs.
+    #[serde(default)]
+    pub events_retention_l1_batches: Option<u32>,
+    /// Retention for executed transactions and their call traces.
+    #[serde(default)]
+    pub transactions_retention_l1_batches: Option<u32>,
+    /// Retention for successfully completed FRI prover jobs and witness inputs.
+    #[serde(default)]
+    pub prover_jobs_retention_l1_batches: Option<u32>,
+}
+
+impl PruningConfig {
+    const fn default_poll_interval_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_chunk_size() -> u32 {
+        10
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+}
@@ -0,0 +1,4 @@
+-- Bytecode hash of the verified contract, used to propagate verification to other contracts with the same bytecode.
+-- Only populated for verifications saved after this migration.
+ALTER TABLE contracts_verification_info ADD COLUMN IF NOT EXISTS bytecode_hash BYTEA;
+CREATE INDEX IF NOT EXISTS contracts_verification_info_bytecode_hash_idx ON contracts_verification_info (bytecode_hash);
@@ -0,0 +1 @@
+ALTER TABLE prover_jobs_fri DROP COLUMN IF EXISTS lease_expires_at;
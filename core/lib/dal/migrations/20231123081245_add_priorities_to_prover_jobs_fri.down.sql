@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_prover_jobs_fri_circuit_id_round_status;
+DROP INDEX IF EXISTS idx_prover_jobs_fri_queued_priority_order;
+
+DROP TABLE IF EXISTS fri_prover_batch_priorities;
+
+ALTER TABLE prover_jobs_fri DROP COLUMN IF EXISTS priority;
@@ -0,0 +1,15 @@
+ALTER TABLE prover_jobs_fri ADD COLUMN IF NOT EXISTS priority SMALLINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS fri_prover_batch_priorities
+(
+    l1_batch_number BIGINT PRIMARY KEY,
+    priority        SMALLINT  NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_queued_priority_order
+    ON prover_jobs_fri (priority DESC, aggregation_round DESC, l1_batch_number ASC, id ASC)
+    WHERE status = 'queued';
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_circuit_id_round_status
+    ON prover_jobs_fri (circuit_id, aggregation_round, status);
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS proof_generation_assignments
+(
+    l1_batch_number  BIGINT    NOT NULL REFERENCES proof_generation_details (l1_batch_number) ON DELETE CASCADE,
+    prover_cluster   TEXT      NOT NULL,
+    status           TEXT      NOT NULL,
+    error            TEXT,
+    assigned_at      TIMESTAMP NOT NULL,
+    lease_expires_at TIMESTAMP NOT NULL,
+    updated_at       TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, prover_cluster)
+);
+
+CREATE INDEX IF NOT EXISTS idx_proof_generation_assignments_active
+    ON proof_generation_assignments (l1_batch_number, lease_expires_at)
+    WHERE status = 'assigned';
+
+-- Batches picked before the assignments were introduced are attributed to the default prover cluster
+-- (the one used if authentication is disabled) with a lease equal to the default proof generation timeout.
+INSERT INTO proof_generation_assignments
+    (l1_batch_number, prover_cluster, status, assigned_at, lease_expires_at, updated_at)
+SELECT l1_batch_number, 'default', 'assigned', prover_taken_at, prover_taken_at + INTERVAL '5 hours', now()
+FROM proof_generation_details
+WHERE status = 'picked_by_prover' AND prover_taken_at IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS pruning_log
+(
+    pruned_data           TEXT      NOT NULL PRIMARY KEY,
+    last_pruned_l1_batch  BIGINT    NOT NULL,
+    last_pruned_miniblock BIGINT    NOT NULL,
+    created_at            TIMESTAMP NOT NULL,
+    updated_at            TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+-- Backfilled hashes are indistinguishable from the ones saved by the server, so they are left in place.
+SELECT 1;
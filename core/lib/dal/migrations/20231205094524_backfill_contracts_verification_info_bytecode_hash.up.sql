@@ -0,0 +1,9 @@
+-- Backfills hashes of deployed bytecodes for contracts verified before hashes were stored. The account code storage
+-- key for a contract is its address left-padded to 32 bytes.
+UPDATE contracts_verification_info
+SET bytecode_hash = factory_deps.bytecode_hash
+FROM storage
+JOIN factory_deps ON factory_deps.bytecode_hash = storage.value
+WHERE contracts_verification_info.bytecode_hash IS NULL
+    AND storage.address = '\x0000000000000000000000000000000000008002'::bytea
+    AND storage.key = '\x000000000000000000000000'::bytea || contracts_verification_info.address;
@@ -0,0 +1,432 @@
+//! In-memory implementation of backend traits.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use async_trait::async_trait;
+use zksync_types::{
+    api,
+    block::{L1BatchHeader, MiniblockHeader},
+    L1BatchNumber, L2ChainId, MiniblockNumber, StorageKey, StorageLog, StorageLogKind, Transaction,
+    H256,
+};
+
+use super::{BlocksReader, EventsReader, StorageReader, TransactionsReader};
+
+/// In-memory storage backend intended for unit tests of code generic over the backend traits.
+///
+/// Data is inserted in the same order as it's persisted by the state keeper: miniblocks with their
+/// storage logs, factory deps, transactions and events first, and then the L1 batch sealing
+/// all miniblocks inserted after the previous batch. Unlike Postgres, the storage never errors.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryBackend {
+    miniblocks: BTreeMap<MiniblockNumber, MiniblockHeader>,
+    l1_batches: BTreeMap<L1BatchNumber, (L1BatchHeader, MiniblockNumber, MiniblockNumber)>,
+    /// Values of storage slots keyed by the hashed key, and then by the miniblock they were written in.
+    storage_values: HashMap<H256, BTreeMap<MiniblockNumber, H256>>,
+    /// Hashed keys written in the miniblocks not sealed in an L1 batch yet.
+    pending_written_keys: HashSet<H256>,
+    initial_writes: HashMap<H256, L1BatchNumber>,
+    factory_deps: HashMap<H256, (MiniblockNumber, Vec<u8>)>,
+    transactions: HashMap<H256, api::Transaction>,
+    receipts: HashMap<H256, api::TransactionReceipt>,
+    raw_transactions: BTreeMap<MiniblockNumber, Vec<Transaction>>,
+    events: BTreeMap<MiniblockNumber, Vec<api::Log>>,
+}
+
+impl InMemoryBackend {
+    /// Inserts a miniblock together with storage logs produced in it. Read logs are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the miniblock number doesn't immediately follow the last inserted miniblock.
+    pub fn insert_miniblock(&mut self, header: MiniblockHeader, storage_logs: &[StorageLog]) {
+        let number = header.number;
+        let expected_number = self
+            .miniblocks
+            .keys()
+            .next_back()
+            .map_or(MiniblockNumber(0), |&last| last + 1);
+        assert_eq!(
+            number, expected_number,
+            "miniblocks must be inserted in order"
+        );
+
+        for log in storage_logs {
+            if log.kind == StorageLogKind::Write {
+                let hashed_key = log.key.hashed_key();
+                let values = self.storage_values.entry(hashed_key).or_default();
+                values.insert(number, log.value);
+                self.pending_written_keys.insert(hashed_key);
+            }
+        }
+        self.miniblocks.insert(number, header);
+    }
+
+    /// Seals an L1 batch containing all miniblocks inserted after the previous batch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no such miniblocks, or if the batch number doesn't immediately follow
+    /// the last sealed batch.
+    pub fn insert_l1_batch(&mut self, header: L1BatchHeader) {
+        let (expected_number, first_miniblock) = match self.l1_batches.iter().next_back() {
+            Some((&number, (_, _, last_miniblock))) => (number + 1, *last_miniblock + 1),
+            None => (L1BatchNumber(0), MiniblockNumber(0)),
+        };
+        assert_eq!(
+            header.number, expected_number,
+            "L1 batches must be inserted in order"
+        );
+        let last_miniblock = *self
+            .miniblocks
+            .keys()
+            .next_back()
+            .filter(|&&last| last >= first_miniblock)
+            .expect("L1 batch must contain at least one miniblock");
+
+        for hashed_key in self.pending_written_keys.drain() {
+            self.initial_writes
+                .entry(hashed_key)
+                .or_insert(header.number);
+        }
+        self.l1_batches
+            .insert(header.number, (header, first_miniblock, last_miniblock));
+    }
+
+    pub fn insert_factory_deps(
+        &mut self,
+        miniblock: MiniblockNumber,
+        factory_deps: HashMap<H256, Vec<u8>>,
+    ) {
+        for (hash, bytecode) in factory_deps {
+            self.factory_deps
+                .entry(hash)
+                .or_insert((miniblock, bytecode));
+        }
+    }
+
+    /// Inserts an executed transaction in both server and API representations.
+    pub fn insert_transaction(
+        &mut self,
+        miniblock: MiniblockNumber,
+        transaction: Transaction,
+        api_transaction: api::Transaction,
+        receipt: api::TransactionReceipt,
+    ) {
+        let hash = transaction.hash();
+        self.raw_transactions
+            .entry(miniblock)
+            .or_default()
+            .push(transaction);
+        self.transactions.insert(hash, api_transaction);
+        self.receipts.insert(hash, receipt);
+    }
+
+    /// Inserts events emitted in the specified miniblock in the order of emission.
+    pub fn insert_events(&mut self, miniblock: MiniblockNumber, events: Vec<api::Log>) {
+        self.events.entry(miniblock).or_default().extend(events);
+    }
+
+    fn log_matches(filter: &api::GetLogsFilter, log: &api::Log) -> bool {
+        if !filter.addresses.is_empty() && !filter.addresses.contains(&log.address) {
+            return false;
+        }
+        filter.topics.iter().all(|(topic_index, topics)| {
+            let topic = (*topic_index as usize)
+                .checked_sub(1)
+                .and_then(|idx| log.topics.get(idx));
+            topic.map_or(false, |topic| topics.contains(topic))
+        })
+    }
+}
+
+#[async_trait]
+impl BlocksReader for InMemoryBackend {
+    async fn get_sealed_miniblock_number(&mut self) -> sqlx::Result<MiniblockNumber> {
+        let number = self.miniblocks.keys().next_back();
+        Ok(*number.expect("DAL invocation before genesis"))
+    }
+
+    async fn get_sealed_l1_batch_number(&mut self) -> sqlx::Result<L1BatchNumber> {
+        let number = self.l1_batches.keys().next_back();
+        Ok(*number.expect("DAL invocation before genesis"))
+    }
+
+    async fn get_miniblock_header(
+        &mut self,
+        number: MiniblockNumber,
+    ) -> sqlx::Result<Option<MiniblockHeader>> {
+        Ok(self.miniblocks.get(&number).cloned())
+    }
+
+    async fn get_miniblock_hash(&mut self, number: MiniblockNumber) -> sqlx::Result<Option<H256>> {
+        Ok(self.miniblocks.get(&number).map(|header| header.hash))
+    }
+
+    async fn get_l1_batch_header(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<L1BatchHeader>> {
+        Ok(self
+            .l1_batches
+            .get(&number)
+            .map(|(header, ..)| header.clone()))
+    }
+
+    async fn get_miniblock_range_of_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<(MiniblockNumber, MiniblockNumber)>> {
+        let range = self.l1_batches.get(&l1_batch_number);
+        Ok(range.map(|&(_, first, last)| (first, last)))
+    }
+}
+
+#[async_trait]
+impl TransactionsReader for InMemoryBackend {
+    async fn get_transaction_by_hash(
+        &mut self,
+        hash: H256,
+        _chain_id: L2ChainId,
+    ) -> sqlx::Result<Option<api::Transaction>> {
+        Ok(self.transactions.get(&hash).cloned())
+    }
+
+    async fn get_transaction_receipt(
+        &mut self,
+        hash: H256,
+    ) -> sqlx::Result<Option<api::TransactionReceipt>> {
+        Ok(self.receipts.get(&hash).cloned())
+    }
+
+    async fn get_raw_miniblock_transactions(
+        &mut self,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Vec<Transaction>> {
+        let transactions = self.raw_transactions.get(&miniblock);
+        Ok(transactions.cloned().unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl StorageReader for InMemoryBackend {
+    async fn get_historical_value_unchecked(
+        &mut self,
+        key: &StorageKey,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<H256> {
+        let Some(values) = self.storage_values.get(&key.hashed_key()) else {
+            return Ok(H256::zero());
+        };
+        let value = values.range(..=miniblock).next_back();
+        Ok(value.map_or_else(H256::zero, |(_, value)| *value))
+    }
+
+    async fn get_l1_batch_number_for_initial_write(
+        &mut self,
+        key: &StorageKey,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        Ok(self.initial_writes.get(&key.hashed_key()).copied())
+    }
+
+    async fn get_factory_dep_unchecked(
+        &mut self,
+        hash: H256,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        let dep = self.factory_deps.get(&hash);
+        let dep = dep.filter(|(deployed_at, _)| *deployed_at <= miniblock);
+        Ok(dep.map(|(_, bytecode)| bytecode.clone()))
+    }
+}
+
+#[async_trait]
+impl EventsReader for InMemoryBackend {
+    async fn get_logs(
+        &mut self,
+        filter: api::GetLogsFilter,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::Log>> {
+        if filter.from_block > filter.to_block {
+            return Ok(vec![]);
+        }
+        let logs = self
+            .events
+            .range(filter.from_block..=filter.to_block)
+            .flat_map(|(_, logs)| logs)
+            .filter(|log| Self::log_matches(&filter, log))
+            .take(limit)
+            .cloned();
+        Ok(logs.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{AccountTreeId, Address, ProtocolVersionId, U64};
+
+    use super::*;
+
+    fn create_miniblock_header(number: u32) -> MiniblockHeader {
+        MiniblockHeader {
+            number: MiniblockNumber(number),
+            timestamp: number.into(),
+            hash: H256::from_low_u64_be(number.into()),
+            l1_tx_count: 0,
+            l2_tx_count: 0,
+            base_fee_per_gas: 100,
+            l1_gas_price: 100,
+            l2_fair_gas_price: 100,
+            base_system_contracts_hashes: Default::default(),
+            protocol_version: Some(ProtocolVersionId::latest()),
+            virtual_blocks: 1,
+        }
+    }
+
+    fn create_l1_batch_header(number: u32) -> L1BatchHeader {
+        L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            Address::default(),
+            Default::default(),
+            ProtocolVersionId::latest(),
+        )
+    }
+
+    fn create_log(miniblock: u32, address: Address, topics: Vec<H256>) -> api::Log {
+        api::Log {
+            address,
+            topics,
+            data: Default::default(),
+            block_hash: None,
+            block_number: Some(U64::from(miniblock)),
+            l1_batch_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: Some(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn in_memory_blocks_and_storage() {
+        let mut storage = InMemoryBackend::default();
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        storage.insert_miniblock(create_miniblock_header(0), &[]);
+        storage.insert_l1_batch(create_l1_batch_header(0));
+        storage.insert_miniblock(
+            create_miniblock_header(1),
+            &[StorageLog::new_write_log(key, H256::repeat_byte(1))],
+        );
+        storage.insert_factory_deps(
+            MiniblockNumber(1),
+            HashMap::from([(H256::repeat_byte(0xff), vec![0; 32])]),
+        );
+        storage.insert_miniblock(
+            create_miniblock_header(2),
+            &[StorageLog::new_write_log(key, H256::repeat_byte(2))],
+        );
+
+        assert_eq!(
+            storage.get_sealed_miniblock_number().await.unwrap(),
+            MiniblockNumber(2)
+        );
+        assert_eq!(
+            storage.get_sealed_l1_batch_number().await.unwrap(),
+            L1BatchNumber(0)
+        );
+        assert_eq!(
+            storage
+                .get_l1_batch_number_for_initial_write(&key)
+                .await
+                .unwrap(),
+            None
+        );
+
+        storage.insert_l1_batch(create_l1_batch_header(1));
+        assert_eq!(
+            storage
+                .get_miniblock_range_of_l1_batch(L1BatchNumber(1))
+                .await
+                .unwrap(),
+            Some((MiniblockNumber(1), MiniblockNumber(2)))
+        );
+        assert_eq!(
+            storage
+                .get_l1_batch_number_for_initial_write(&key)
+                .await
+                .unwrap(),
+            Some(L1BatchNumber(1))
+        );
+
+        for (miniblock, expected_value) in [
+            (0, H256::zero()),
+            (1, H256::repeat_byte(1)),
+            (2, H256::repeat_byte(2)),
+            (100, H256::repeat_byte(2)),
+        ] {
+            let value = storage
+                .get_historical_value_unchecked(&key, MiniblockNumber(miniblock))
+                .await
+                .unwrap();
+            assert_eq!(value, expected_value, "miniblock #{miniblock}");
+        }
+
+        let dep = storage
+            .get_factory_dep_unchecked(H256::repeat_byte(0xff), MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(dep, None);
+        let dep = storage
+            .get_factory_dep_unchecked(H256::repeat_byte(0xff), MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(dep, Some(vec![0; 32]));
+    }
+
+    #[tokio::test]
+    async fn in_memory_logs_filtering() {
+        let mut storage = InMemoryBackend::default();
+        let topic = H256::repeat_byte(1);
+        storage.insert_events(
+            MiniblockNumber(1),
+            vec![
+                create_log(1, Address::repeat_byte(1), vec![topic]),
+                create_log(1, Address::repeat_byte(2), vec![topic]),
+            ],
+        );
+        storage.insert_events(
+            MiniblockNumber(2),
+            vec![create_log(2, Address::repeat_byte(1), vec![H256::zero()])],
+        );
+
+        let filter = api::GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(2),
+            addresses: vec![Address::repeat_byte(1)],
+            topics: vec![],
+        };
+        let logs = storage.get_logs(filter.clone(), 10).await.unwrap();
+        assert_eq!(logs.len(), 2);
+        let logs = storage.get_logs(filter.clone(), 1).await.unwrap();
+        assert_eq!(logs[0].block_number, Some(1.into()));
+
+        let filter = api::GetLogsFilter {
+            topics: vec![(1, vec![topic])],
+            ..filter
+        };
+        let logs = storage.get_logs(filter.clone(), 10).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].address, Address::repeat_byte(1));
+
+        let filter = api::GetLogsFilter {
+            addresses: vec![],
+            to_block: MiniblockNumber(1),
+            ..filter
+        };
+        let logs = storage.get_logs(filter, 10).await.unwrap();
+        assert_eq!(logs.len(), 2);
+    }
+}
@@ -0,0 +1,128 @@
+//! Storage backend traits abstracting hot-path reads used by the state keeper and the Web3 API.
+//!
+//! The traits are implemented for [`StorageProcessor`](crate::StorageProcessor), which delegates
+//! to the corresponding DAL methods, and for [`InMemoryBackend`], which allows testing code generic
+//! over the traits without a Postgres instance. Method names and semantics mirror the DAL methods
+//! they abstract.
+//!
+//! # Scope
+//!
+//! Only the following read paths are generic over the traits:
+//!
+//! - Loading the pending miniblock header on state keeper startup
+//!   (`state_keeper::io::common::load_pending_miniblock_header` in `zksync_core`).
+//! - Reading L2 block info from the system context in the API sandbox
+//!   (`api_server::execution_sandbox::apply::read_l2_block_info` in `zksync_core`).
+//!
+//! Running whole components on top of [`InMemoryBackend`] is out of scope. The state keeper I/O,
+//! the batch executor and the Web3 API namespaces take a [`ConnectionPool`](crate::ConnectionPool),
+//! write through DAL methods that have no trait counterparts, and the batch executor initializes
+//! its RocksDB cache from Postgres. Their test suites (`state_keeper/io/tests`,
+//! `state_keeper/batch_executor/tests` and `api_server/web3/tests`) therefore still require
+//! `ConnectionPool::test_pool()`.
+
+use async_trait::async_trait;
+use zksync_types::{
+    api,
+    block::{L1BatchHeader, MiniblockHeader},
+    L1BatchNumber, L2ChainId, MiniblockNumber, StorageKey, Transaction, H256,
+};
+
+pub use self::in_memory::InMemoryBackend;
+
+mod in_memory;
+mod postgres;
+
+/// Read access to miniblocks and L1 batches.
+#[async_trait]
+pub trait BlocksReader: Send {
+    /// Returns the number of the last sealed miniblock.
+    async fn get_sealed_miniblock_number(&mut self) -> sqlx::Result<MiniblockNumber>;
+
+    /// Returns the number of the last sealed L1 batch.
+    async fn get_sealed_l1_batch_number(&mut self) -> sqlx::Result<L1BatchNumber>;
+
+    async fn get_miniblock_header(
+        &mut self,
+        number: MiniblockNumber,
+    ) -> sqlx::Result<Option<MiniblockHeader>>;
+
+    /// Returns the hash of the specified miniblock, or `None` if the miniblock is not sealed.
+    async fn get_miniblock_hash(&mut self, number: MiniblockNumber) -> sqlx::Result<Option<H256>>;
+
+    async fn get_l1_batch_header(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<L1BatchHeader>>;
+
+    /// Returns the first and the last miniblock in the specified L1 batch, or `None` if the batch
+    /// is not sealed.
+    async fn get_miniblock_range_of_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<(MiniblockNumber, MiniblockNumber)>>;
+}
+
+/// Read access to executed transactions.
+#[async_trait]
+pub trait TransactionsReader: Send {
+    async fn get_transaction_by_hash(
+        &mut self,
+        hash: H256,
+        chain_id: L2ChainId,
+    ) -> sqlx::Result<Option<api::Transaction>>;
+
+    async fn get_transaction_receipt(
+        &mut self,
+        hash: H256,
+    ) -> sqlx::Result<Option<api::TransactionReceipt>>;
+
+    /// Returns the server transactions (not API ones) from a certain miniblock.
+    /// Returns an empty list if the miniblock doesn't exist.
+    async fn get_raw_miniblock_transactions(
+        &mut self,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Vec<Transaction>>;
+}
+
+/// Read access to the historical VM state.
+#[async_trait]
+pub trait StorageReader: Send {
+    /// Returns the value of the storage slot as of the end of the specified miniblock. Does not check
+    /// whether the miniblock exists; returns the current value if the miniblock is in the future.
+    async fn get_historical_value_unchecked(
+        &mut self,
+        key: &StorageKey,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<H256>;
+
+    /// Returns the L1 batch in which the storage slot was written to for the first time.
+    async fn get_l1_batch_number_for_initial_write(
+        &mut self,
+        key: &StorageKey,
+    ) -> sqlx::Result<Option<L1BatchNumber>>;
+
+    /// Returns the bytecode with the specified hash if it was deployed no later than
+    /// the specified miniblock.
+    async fn get_factory_dep_unchecked(
+        &mut self,
+        hash: H256,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Option<Vec<u8>>>;
+}
+
+/// Read access to events emitted by transactions.
+#[async_trait]
+pub trait EventsReader: Send {
+    /// Returns logs matching the filter, ordered by the miniblock number and the index in the miniblock.
+    async fn get_logs(
+        &mut self,
+        filter: api::GetLogsFilter,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::Log>>;
+}
+
+/// Combination of all read traits.
+pub trait ReadBackend: BlocksReader + TransactionsReader + StorageReader + EventsReader {}
+
+impl<T> ReadBackend for T where T: BlocksReader + TransactionsReader + StorageReader + EventsReader {}
@@ -0,0 +1,123 @@
+//! Postgres implementation of backend traits delegating to DAL methods.
+
+use async_trait::async_trait;
+use zksync_types::{
+    api,
+    block::{L1BatchHeader, MiniblockHeader},
+    L1BatchNumber, L2ChainId, MiniblockNumber, StorageKey, Transaction, H256,
+};
+
+use super::{BlocksReader, EventsReader, StorageReader, TransactionsReader};
+use crate::StorageProcessor;
+
+#[async_trait]
+impl<'a> BlocksReader for StorageProcessor<'a> {
+    async fn get_sealed_miniblock_number(&mut self) -> sqlx::Result<MiniblockNumber> {
+        self.blocks_web3_dal().get_sealed_miniblock_number().await
+    }
+
+    async fn get_sealed_l1_batch_number(&mut self) -> sqlx::Result<L1BatchNumber> {
+        self.blocks_web3_dal().get_sealed_l1_batch_number().await
+    }
+
+    async fn get_miniblock_header(
+        &mut self,
+        number: MiniblockNumber,
+    ) -> sqlx::Result<Option<MiniblockHeader>> {
+        self.blocks_dal().get_miniblock_header(number).await
+    }
+
+    async fn get_miniblock_hash(&mut self, number: MiniblockNumber) -> sqlx::Result<Option<H256>> {
+        self.blocks_web3_dal().get_miniblock_hash(number).await
+    }
+
+    async fn get_l1_batch_header(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<L1BatchHeader>> {
+        self.blocks_dal().get_l1_batch_header(number).await
+    }
+
+    async fn get_miniblock_range_of_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<(MiniblockNumber, MiniblockNumber)>> {
+        self.blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await
+    }
+}
+
+#[async_trait]
+impl<'a> TransactionsReader for StorageProcessor<'a> {
+    async fn get_transaction_by_hash(
+        &mut self,
+        hash: H256,
+        chain_id: L2ChainId,
+    ) -> sqlx::Result<Option<api::Transaction>> {
+        self.transactions_web3_dal()
+            .get_transaction(api::TransactionId::Hash(hash), chain_id)
+            .await
+    }
+
+    async fn get_transaction_receipt(
+        &mut self,
+        hash: H256,
+    ) -> sqlx::Result<Option<api::TransactionReceipt>> {
+        self.transactions_web3_dal()
+            .get_transaction_receipt(hash)
+            .await
+    }
+
+    async fn get_raw_miniblock_transactions(
+        &mut self,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Vec<Transaction>> {
+        self.transactions_web3_dal()
+            .get_raw_miniblock_transactions(miniblock)
+            .await
+    }
+}
+
+#[async_trait]
+impl<'a> StorageReader for StorageProcessor<'a> {
+    async fn get_historical_value_unchecked(
+        &mut self,
+        key: &StorageKey,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<H256> {
+        self.storage_web3_dal()
+            .get_historical_value_unchecked(key, miniblock)
+            .await
+    }
+
+    async fn get_l1_batch_number_for_initial_write(
+        &mut self,
+        key: &StorageKey,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        self.storage_web3_dal()
+            .get_l1_batch_number_for_initial_write(key)
+            .await
+    }
+
+    async fn get_factory_dep_unchecked(
+        &mut self,
+        hash: H256,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        self.storage_web3_dal()
+            .get_factory_dep_unchecked(hash, miniblock)
+            .await
+    }
+}
+
+#[async_trait]
+impl<'a> EventsReader for StorageProcessor<'a> {
+    async fn get_logs(
+        &mut self,
+        filter: api::GetLogsFilter,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::Log>> {
+        self.events_web3_dal().get_logs(filter, limit).await
+    }
+}
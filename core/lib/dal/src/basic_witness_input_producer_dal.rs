@@ -59,8 +59,7 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             BasicWitnessInputProducerJobStatus::Queued as BasicWitnessInputProducerJobStatus,
         )
         .instrument("create_basic_witness_input_producer_job")
-        .report_latency()
-        .execute(self.storage.conn())
+        .execute(self.storage)
         .await?;
 
         Ok(())
@@ -94,8 +93,7 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             JOB_MAX_ATTEMPT,
         )
         .instrument("get_next_basic_witness_input_producer_job")
-        .report_latency()
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?
         .map(|job| L1BatchNumber(job.l1_batch_number as u32));
 
@@ -137,8 +135,7 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             object_path,
         )
         .instrument("mark_job_as_successful")
-        .report_latency()
-        .execute(self.storage.conn())
+        .execute(self.storage)
         .await?;
 
         Ok(())
@@ -165,8 +162,7 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             BasicWitnessInputProducerJobStatus::Successful as BasicWitnessInputProducerJobStatus,
         )
         .instrument("mark_job_as_failed")
-        .report_latency()
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?
         .map(|job| job.attempts as u32);
 
@@ -41,8 +41,7 @@ impl BlocksDal<'_, '_> {
             "SELECT MAX(number) as \"number\" FROM l1_batches WHERE is_finished = TRUE"
         )
         .instrument("get_sealed_block_number")
-        .report_latency()
-        .fetch_one(self.storage.conn())
+        .fetch_one(self.storage)
         .await?
         .number
         .context("DAL invocation before genesis")?;
@@ -53,8 +52,7 @@ impl BlocksDal<'_, '_> {
     pub async fn get_sealed_miniblock_number(&mut self) -> sqlx::Result<MiniblockNumber> {
         let number: i64 = sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks")
             .instrument("get_sealed_miniblock_number")
-            .report_latency()
-            .fetch_one(self.storage.conn())
+            .fetch_one(self.storage)
             .await?
             .number
             .unwrap_or(0);
@@ -67,8 +65,7 @@ impl BlocksDal<'_, '_> {
         let number: i64 =
             sqlx::query!("SELECT MAX(number) as \"number\" FROM l1_batches WHERE hash IS NOT NULL")
                 .instrument("get_last_block_number_with_metadata")
-                .report_latency()
-                .fetch_one(self.storage.conn())
+                .fetch_one(self.storage)
                 .await?
                 .number
                 .context("DAL invocation before genesis")?;
@@ -95,7 +92,7 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("get_l1_batches_for_eth_tx_id")
         .with_arg("eth_tx_id", &eth_tx_id)
-        .fetch_all(self.storage.conn())
+        .fetch_all(self.storage)
         .await?;
 
         Ok(l1_batches.into_iter().map(Into::into).collect())
@@ -124,7 +121,7 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("get_storage_l1_batch")
         .with_arg("number", &number)
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await
     }
 
@@ -146,7 +143,7 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("get_l1_batch_header")
         .with_arg("number", &number)
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?
         .map(Into::into))
     }
@@ -161,9 +158,8 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_initial_bootloader_heap")
-        .report_latency()
         .with_arg("number", &number)
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -183,9 +179,8 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_storage_refunds")
-        .report_latency()
         .with_arg("number", &number)
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -207,9 +202,8 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_events_queue")
-        .report_latency()
         .with_arg("number", &number)
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -538,8 +532,7 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_blocks_metadata")
         .with_arg("number", &number)
-        .report_latency()
-        .execute(transaction.conn())
+        .execute(&mut transaction)
         .await?;
 
         if metadata.events_queue_commitment.is_some() || is_pre_boojum {
@@ -556,8 +549,7 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("save_batch_commitments")
             .with_arg("number", &number)
-            .report_latency()
-            .execute(transaction.conn())
+            .execute(&mut transaction)
             .await?;
 
             sqlx::query!(
@@ -570,8 +562,7 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("save_batch_aux_commitment")
             .with_arg("number", &number)
-            .report_latency()
-            .execute(transaction.conn())
+            .execute(&mut transaction)
             .await?;
         }
 
@@ -601,8 +592,7 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("get_matching_blocks_metadata")
             .with_arg("number", &number)
-            .report_latency()
-            .fetch_one(transaction.conn())
+            .fetch_one(&mut transaction)
             .await?
             .count;
 
@@ -641,7 +631,7 @@ impl BlocksDal<'_, '_> {
             LIMIT 1",
         )
         .instrument("get_last_committed_to_eth_l1_batch")
-        .fetch_one(self.storage.conn())
+        .fetch_one(self.storage)
         .await?;
         // genesis block is first generated without commitment, we should wait for the tree to set it.
         if block.commitment.is_none() {
@@ -754,7 +744,7 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("get_ready_for_dummy_proof_l1_batches")
         .with_arg("limit", &limit)
-        .fetch_all(self.storage.conn())
+        .fetch_all(self.storage)
         .await?;
 
         self.map_l1_batches(raw_batches)
@@ -829,7 +819,7 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("get_skipped_for_proof_l1_batches")
         .with_arg("limit", &limit)
-        .fetch_all(self.storage.conn())
+        .fetch_all(self.storage)
         .await?;
 
         self.map_l1_batches(raw_batches)
@@ -863,7 +853,7 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("get_ready_for_execute_l1_batches/no_max_timestamp")
             .with_arg("limit", &limit)
-            .fetch_all(self.storage.conn())
+            .fetch_all(self.storage)
             .await?,
 
             Some(max_l1_batch_timestamp_millis) => {
@@ -945,7 +935,7 @@ impl BlocksDal<'_, '_> {
             .instrument("get_ready_for_execute_l1_batches")
             .with_arg("numbers", &(expected_started_point..=max_ready_to_send_block))
             .with_arg("limit", &limit)
-            .fetch_all(self.storage.conn())
+            .fetch_all(self.storage)
             .await?
         } else {
             vec![]
@@ -990,7 +980,7 @@ impl BlocksDal<'_, '_> {
             .with_arg("bootloader_hash", &bootloader_hash)
             .with_arg("default_aa_hash", &default_aa_hash)
             .with_arg("protocol_version_id", &protocol_version_id)
-            .fetch_all(self.storage.conn())
+            .fetch_all(self.storage)
             .await?;
 
         self.map_l1_batches(raw_batches)
@@ -1037,7 +1027,7 @@ impl BlocksDal<'_, '_> {
         .with_arg("bootloader_hash", &bootloader_hash)
         .with_arg("default_aa_hash", &default_aa_hash)
         .with_arg("protocol_version_id", &protocol_version_id)
-        .fetch_all(self.storage.conn())
+        .fetch_all(self.storage)
         .await?;
 
         self.map_l1_batches(raw_batches)
@@ -1092,7 +1082,7 @@ impl BlocksDal<'_, '_> {
             LIMIT 1"
         )
         .instrument("get_newest_l1_batch_header")
-        .fetch_one(self.storage.conn())
+        .fetch_one(self.storage)
         .await?;
 
         Ok(last_l1_batch.into())
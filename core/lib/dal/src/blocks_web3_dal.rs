@@ -35,8 +35,7 @@ impl BlocksWeb3Dal<'_, '_> {
     pub async fn get_sealed_miniblock_number(&mut self) -> sqlx::Result<MiniblockNumber> {
         let number = sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks")
             .instrument("get_sealed_block_number")
-            .report_latency()
-            .fetch_one(self.storage.conn())
+            .fetch_one(self.storage)
             .await?
             .number
             .expect("DAL invocation before genesis");
@@ -46,8 +45,7 @@ impl BlocksWeb3Dal<'_, '_> {
     pub async fn get_sealed_l1_batch_number(&mut self) -> sqlx::Result<L1BatchNumber> {
         let number = sqlx::query!("SELECT MAX(number) as \"number\" FROM l1_batches")
             .instrument("get_sealed_block_number")
-            .report_latency()
-            .fetch_one(self.storage.conn())
+            .fetch_one(self.storage)
             .await?
             .number
             .expect("DAL invocation before genesis");
@@ -449,8 +447,7 @@ impl BlocksWeb3Dal<'_, '_> {
             )
             .instrument("get_block_details")
             .with_arg("block_number", &block_number)
-            .report_latency()
-            .fetch_optional(self.storage.conn())
+            .fetch_optional(self.storage)
             .await?;
 
             Ok(storage_block_details.map(|storage_block_details| {
@@ -492,8 +489,7 @@ impl BlocksWeb3Dal<'_, '_> {
             )
             .instrument("get_l1_batch_details")
             .with_arg("l1_batch_number", &l1_batch_number)
-            .report_latency()
-            .fetch_optional(self.storage.conn())
+            .fetch_optional(self.storage)
             .await?;
 
             Ok(l1_batch_details.map(api::L1BatchDetails::from))
@@ -532,8 +528,7 @@ impl BlocksWeb3Dal<'_, '_> {
             "migration_start_l1_batch_number",
             &migration_start_l1_batch_number,
         )
-        .report_latency()
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?;
 
         let result = record.map(|row| row.number as u32);
@@ -574,8 +569,7 @@ impl BlocksWeb3Dal<'_, '_> {
             "migration_start_l1_batch_number",
             &migration_start_l1_batch_number,
         )
-        .report_latency()
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?;
 
         let result = record.map(|row| row.number as u32);
@@ -1,4 +1,11 @@
-use std::{env, fmt, time::Duration};
+use std::{
+    env, fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use sqlx::{
@@ -6,7 +13,10 @@ use sqlx::{
     postgres::{PgConnectOptions, PgPool, PgPoolOptions, Postgres},
 };
 
-use crate::{metrics::CONNECTION_METRICS, StorageProcessor};
+use crate::{
+    metrics::{ConnectionTarget, CONNECTION_METRICS},
+    StorageProcessor,
+};
 
 pub mod holder;
 
@@ -23,6 +33,9 @@ pub struct ConnectionPoolBuilder<'a> {
     database_url: &'a str,
     max_size: u32,
     statement_timeout: Option<Duration>,
+    read_replica_urls: Vec<&'a str>,
+    max_replication_lag: Duration,
+    fallback_max_size: Option<u32>,
 }
 
 impl<'a> fmt::Debug for ConnectionPoolBuilder<'a> {
@@ -31,6 +44,9 @@ impl<'a> fmt::Debug for ConnectionPoolBuilder<'a> {
         f.debug_struct("ConnectionPoolBuilder")
             .field("max_size", &self.max_size)
             .field("statement_timeout", &self.statement_timeout)
+            .field("read_replicas", &self.read_replica_urls.len())
+            .field("max_replication_lag", &self.max_replication_lag)
+            .field("fallback_max_size", &self.fallback_max_size)
             .finish()
     }
 }
@@ -45,28 +61,223 @@ impl<'a> ConnectionPoolBuilder<'a> {
         self
     }
 
+    /// Sets read replicas for the pool. If replicas are set, connections acquired from the pool are routed
+    /// to a replica with the replication lag not exceeding `max_replication_lag`, falling back to the primary
+    /// database (i.e., the one specified when creating the builder) if all replicas lag or are unavailable.
+    ///
+    /// Since a connection may be routed to a replica, pools with replicas should only be used
+    /// for read-only workloads, such as `*_web3_dal` queries from the API server.
+    pub fn set_read_replicas(
+        &mut self,
+        urls: impl IntoIterator<Item = &'a str>,
+        max_replication_lag: Duration,
+    ) -> &mut Self {
+        self.read_replica_urls = urls.into_iter().collect();
+        self.max_replication_lag = max_replication_lag;
+        self
+    }
+
+    /// Sets the maximum size of the primary database pool if the pool has read replicas
+    /// (see [`Self::set_read_replicas()`]). Since the primary database is only used as a fallback in this case,
+    /// its pool should usually be much smaller than replica pools. Ignored if read replicas are not set.
+    ///
+    /// If not specified, the primary database pool has the same size as replica pools.
+    pub fn set_fallback_max_size(&mut self, max_size: u32) -> &mut Self {
+        self.fallback_max_size = Some(max_size);
+        self
+    }
+
     /// Builds a connection pool from this builder.
     pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
-        let options = PgPoolOptions::new().max_connections(self.max_size);
-        let mut connect_options: PgConnectOptions = self
-            .database_url
+        let max_connections = if self.read_replica_urls.is_empty() {
+            self.max_size
+        } else {
+            self.fallback_max_size.unwrap_or(self.max_size)
+        };
+        let pool = self
+            .build_pg_pool(self.database_url, max_connections)
+            .await
+            .context("Failed connecting to database")?;
+        tracing::info!(
+            "Created pool with {max_connections} max connections \
+             and {statement_timeout:?} statement timeout",
+            statement_timeout = self.statement_timeout
+        );
+
+        let replicas = if self.read_replica_urls.is_empty() {
+            None
+        } else {
+            let mut replicas = Vec::with_capacity(self.read_replica_urls.len());
+            for (index, &url) in self.read_replica_urls.iter().enumerate() {
+                // Replica pools connect lazily, so that an unavailable replica doesn't prevent the pool
+                // from being built; such a replica will be marked as unhealthy on the first acquisition.
+                let (options, connect_options) = self
+                    .pg_pool_options(url, self.max_size)
+                    .with_context(|| format!("Failed configuring read replica #{index}"))?;
+                let pool = options.connect_lazy_with(connect_options);
+                replicas.push(ReadReplica::new(index, pool));
+            }
+            tracing::info!(
+                "Added {} read replicas with {:?} max replication lag to the pool",
+                replicas.len(),
+                self.max_replication_lag
+            );
+            Some(Arc::new(ReadReplicas {
+                replicas,
+                max_lag: self.max_replication_lag,
+                next_index: AtomicUsize::new(0),
+            }))
+        };
+        Ok(ConnectionPool {
+            inner: pool,
+            replicas,
+        })
+    }
+
+    fn pg_pool_options(
+        &self,
+        database_url: &str,
+        max_size: u32,
+    ) -> anyhow::Result<(PgPoolOptions, PgConnectOptions)> {
+        let options = PgPoolOptions::new().max_connections(max_size);
+        let mut connect_options: PgConnectOptions = database_url
             .parse()
             .context("Failed parsing database URL")?;
         if let Some(timeout) = self.statement_timeout {
             let timeout_string = format!("{}s", timeout.as_secs());
             connect_options = connect_options.options([("statement_timeout", timeout_string)]);
         }
-        let pool = options
+        Ok((options, connect_options))
+    }
+
+    async fn build_pg_pool(&self, database_url: &str, max_size: u32) -> anyhow::Result<PgPool> {
+        let (options, connect_options) = self.pg_pool_options(database_url, max_size)?;
+        options
             .connect_with(connect_options)
             .await
-            .context("Failed connecting to database")?;
-        tracing::info!(
-            "Created pool with {max_connections} max connections \
-             and {statement_timeout:?} statement timeout",
-            max_connections = self.max_size,
-            statement_timeout = self.statement_timeout
-        );
-        Ok(ConnectionPool(pool))
+            .map_err(Into::into)
+    }
+}
+
+/// Read replica of the database together with its cached replication lag and health status.
+#[derive(Debug)]
+struct ReadReplica {
+    index: usize,
+    pool: PgPool,
+    /// Last measured replication lag together with the measurement timestamp.
+    lag: Mutex<Option<(Instant, Duration)>>,
+    /// If set, the replica is considered unhealthy (and is skipped when routing connections) until this moment.
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl ReadReplica {
+    /// Interval between replication lag checks for a replica.
+    const LAG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+    /// Timeout for acquiring a replica connection. Should be short, since the primary database
+    /// can be used as a fallback.
+    const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(1);
+    /// Interval during which a replica is skipped after failing to provide a connection.
+    const UNHEALTHY_INTERVAL: Duration = Duration::from_secs(10);
+
+    fn new(index: usize, pool: PgPool) -> Self {
+        Self {
+            index,
+            pool,
+            lag: Mutex::new(None),
+            unhealthy_until: Mutex::new(None),
+        }
+    }
+
+    fn cached_lag(&self) -> Option<Duration> {
+        let lag = self.lag.lock().expect("replica lag is poisoned");
+        let (measured_at, lag) = (*lag)?;
+        (measured_at.elapsed() < Self::LAG_CHECK_INTERVAL).then_some(lag)
+    }
+
+    fn is_healthy(&self) -> bool {
+        let unhealthy_until = self
+            .unhealthy_until
+            .lock()
+            .expect("replica health is poisoned");
+        unhealthy_until.map_or(true, |until| Instant::now() >= until)
+    }
+
+    fn mark_unhealthy(&self) {
+        let until = Instant::now() + Self::UNHEALTHY_INTERVAL;
+        *self
+            .unhealthy_until
+            .lock()
+            .expect("replica health is poisoned") = Some(until);
+    }
+
+    /// Acquires a connection to this replica if it's healthy and its replication lag doesn't exceed `max_lag`.
+    /// If a connection cannot be acquired, the replica is marked as unhealthy for [`Self::UNHEALTHY_INTERVAL`].
+    async fn acquire_if_synced(
+        &self,
+        max_lag: Duration,
+    ) -> sqlx::Result<Option<StorageProcessor<'static>>> {
+        if !self.is_healthy() {
+            return Ok(None);
+        }
+        let conn = tokio::time::timeout(Self::ACQUIRE_TIMEOUT, self.pool.acquire())
+            .await
+            .unwrap_or(Err(sqlx::Error::PoolTimedOut))
+            .map_err(|err| {
+                self.mark_unhealthy();
+                err
+            })?;
+        let mut storage = StorageProcessor::from_pool(conn);
+        let lag = if let Some(lag) = self.cached_lag() {
+            lag
+        } else {
+            // Uses the same check as the replication lag circuit breaker.
+            let lag_sec = storage.system_dal().get_replication_lag_sec().await;
+            let lag = Duration::from_secs(lag_sec.into());
+            *self.lag.lock().expect("replica lag is poisoned") = Some((Instant::now(), lag));
+            CONNECTION_METRICS.replication_lag[&self.index.to_string()].set(lag_sec.into());
+            lag
+        };
+
+        if lag > max_lag {
+            tracing::debug!(
+                "Read replica #{} lags by {lag:?} (max allowed lag: {max_lag:?})",
+                self.index
+            );
+            return Ok(None);
+        }
+        Ok(Some(storage))
+    }
+}
+
+/// Read replicas used by a [`ConnectionPool`].
+#[derive(Debug)]
+struct ReadReplicas {
+    replicas: Vec<ReadReplica>,
+    max_lag: Duration,
+    next_index: AtomicUsize,
+}
+
+impl ReadReplicas {
+    /// Acquires a connection to a non-lagging replica. Replicas are tried in the round-robin order.
+    async fn acquire(&self) -> Option<StorageProcessor<'static>> {
+        let start_index = self.next_index.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.replicas.len() {
+            let replica = &self.replicas[(start_index + offset) % self.replicas.len()];
+            match replica.acquire_if_synced(self.max_lag).await {
+                Ok(Some(storage)) => return Some(storage),
+                Ok(None) => { /* The replica lags; try the next one */ }
+                Err(err) => {
+                    ConnectionPool::report_connection_error(&err);
+                    tracing::warn!(
+                        "Failed to get connection to read replica #{}, marking it as unhealthy \
+                         for {:?}: {err}",
+                        replica.index,
+                        ReadReplica::UNHEALTHY_INTERVAL
+                    );
+                }
+            }
+        }
+        None
     }
 }
 
@@ -114,12 +325,26 @@ pub(super) async fn create_test_db() -> anyhow::Result<url::Url> {
     Ok(db_url)
 }
 
+/// Pool of connections to the database.
+///
+/// The pool may be configured with read replicas (see [`ConnectionPoolBuilder::set_read_replicas()`]),
+/// in which case connections are routed to a replica with acceptable replication lag if possible.
 #[derive(Clone)]
-pub struct ConnectionPool(pub(crate) PgPool);
+pub struct ConnectionPool {
+    /// Pool for the primary database.
+    pub(crate) inner: PgPool,
+    replicas: Option<Arc<ReadReplicas>>,
+}
 
 impl fmt::Debug for ConnectionPool {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_tuple("ConnectionPool").finish()
+        let replica_count = self
+            .replicas
+            .as_ref()
+            .map_or(0, |replicas| replicas.replicas.len());
+        f.debug_struct("ConnectionPool")
+            .field("read_replicas", &replica_count)
+            .finish()
     }
 }
 
@@ -143,6 +368,9 @@ impl ConnectionPool {
             database_url,
             max_size: max_pool_size,
             statement_timeout: None,
+            read_replica_urls: Vec::new(),
+            max_replication_lag: Duration::ZERO,
+            fallback_max_size: None,
         }
     }
 
@@ -165,7 +393,8 @@ impl ConnectionPool {
     }
 
     /// A version of `access_storage` that would also expose the duration of the connection
-    /// acquisition tagged to the `requester` name.
+    /// acquisition tagged to the `requester` name. If the pool has read replicas, the number
+    /// of connections routed to replicas and to the primary database is reported per requester as well.
     ///
     /// WARN: This method should not be used if it will result in too many time series (e.g.
     /// from witness generators or provers), otherwise Prometheus won't be able to handle it.
@@ -181,15 +410,29 @@ impl ConnectionPool {
         requester: Option<&'static str>,
     ) -> anyhow::Result<StorageProcessor<'_>> {
         let acquire_latency = CONNECTION_METRICS.acquire.start();
-        let conn = self
-            .acquire_connection_retried()
-            .await
-            .context("acquire_connection_retried()")?;
+        let replica_storage = match &self.replicas {
+            Some(replicas) => replicas.acquire().await,
+            None => None,
+        };
+        let (mut storage, target) = if let Some(storage) = replica_storage {
+            (storage, ConnectionTarget::Replica)
+        } else {
+            let conn = self
+                .acquire_connection_retried()
+                .await
+                .context("acquire_connection_retried()")?;
+            (StorageProcessor::from_pool(conn), ConnectionTarget::Primary)
+        };
+        storage.tag = requester;
         let elapsed = acquire_latency.observe();
+        storage.acquire_latency = Some(elapsed);
         if let Some(requester) = requester {
             CONNECTION_METRICS.acquire_tagged[&requester].observe(elapsed);
+            if self.replicas.is_some() {
+                CONNECTION_METRICS.routed[&(requester, target)].inc();
+            }
         }
-        Ok(StorageProcessor::from_pool(conn))
+        Ok(storage)
     }
 
     async fn acquire_connection_retried(&self) -> anyhow::Result<PoolConnection<Postgres>> {
@@ -198,10 +441,12 @@ impl ConnectionPool {
 
         let mut retry_count = 0;
         while retry_count < DB_CONNECTION_RETRIES {
-            CONNECTION_METRICS.pool_size.observe(self.0.size() as usize);
-            CONNECTION_METRICS.pool_idle.observe(self.0.num_idle());
+            CONNECTION_METRICS
+                .pool_size
+                .observe(self.inner.size() as usize);
+            CONNECTION_METRICS.pool_idle.observe(self.inner.num_idle());
 
-            let connection = self.0.acquire().await;
+            let connection = self.inner.acquire().await;
             let connection_err = match connection {
                 Ok(connection) => return Ok(connection),
                 Err(err) => {
@@ -218,7 +463,7 @@ impl ConnectionPool {
         }
 
         // Attempting to get the pooled connection for the last time
-        match self.0.acquire().await {
+        match self.inner.acquire().await {
             Ok(conn) => Ok(conn),
             Err(err) => {
                 Self::report_connection_error(&err);
@@ -262,4 +507,72 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[tokio::test]
+    async fn routing_to_read_replicas() {
+        let primary_url = create_test_db()
+            .await
+            .expect("Unable to prepare test database")
+            .to_string();
+        let replica_url = create_test_db()
+            .await
+            .expect("Unable to prepare test database")
+            .to_string();
+
+        let pool = ConnectionPool::builder(&primary_url, 2)
+            .set_read_replicas([replica_url.as_str()], Duration::from_secs(10))
+            .build()
+            .await
+            .unwrap();
+        let mut storage = pool.access_storage_tagged("test").await.unwrap();
+        let db_name: String = sqlx::query_scalar("SELECT current_database()")
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        assert!(replica_url.ends_with(&db_name), "{replica_url} {db_name}");
+        drop(storage);
+
+        let replicas = pool.replicas.as_ref().unwrap();
+        assert_eq!(replicas.replicas[0].cached_lag(), Some(Duration::ZERO));
+        // Emulate a lagging replica; connections should be routed to the primary database.
+        *replicas.replicas[0].lag.lock().unwrap() = Some((Instant::now(), Duration::from_secs(60)));
+        let mut storage = pool.access_storage_tagged("test").await.unwrap();
+        let db_name: String = sqlx::query_scalar("SELECT current_database()")
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        assert!(primary_url.ends_with(&db_name), "{primary_url} {db_name}");
+    }
+
+    #[tokio::test]
+    async fn skipping_unavailable_read_replica() {
+        let primary_url = create_test_db()
+            .await
+            .expect("Unable to prepare test database")
+            .to_string();
+        let mut replica_url = url::Url::parse(&primary_url).unwrap();
+        replica_url.set_path("/test-missing-replica");
+
+        let pool = ConnectionPool::builder(&primary_url, 2)
+            .set_read_replicas([replica_url.as_str()], Duration::from_secs(10))
+            .set_fallback_max_size(1)
+            .build()
+            .await
+            .unwrap();
+
+        let replica = &pool.replicas.as_ref().unwrap().replicas[0];
+        let mut storage = pool.access_storage_tagged("test").await.unwrap();
+        let db_name: String = sqlx::query_scalar("SELECT current_database()")
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        assert!(primary_url.ends_with(&db_name), "{primary_url} {db_name}");
+        assert!(!replica.is_healthy());
+        drop(storage);
+
+        // The replica should be skipped without trying to connect to it.
+        let started_at = Instant::now();
+        pool.access_storage_tagged("test").await.unwrap();
+        assert!(started_at.elapsed() < ReadReplica::ACQUIRE_TIMEOUT);
+    }
 }
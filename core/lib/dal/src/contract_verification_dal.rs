@@ -7,8 +7,8 @@ use anyhow::Context as _;
 use sqlx::postgres::types::PgInterval;
 use zksync_types::{
     contract_verification_api::{
-        DeployContractCalldata, VerificationIncomingRequest, VerificationInfo, VerificationRequest,
-        VerificationRequestStatus,
+        CompilationError, DeployContractCalldata, MatchLevel, VerificationIncomingRequest,
+        VerificationInfo, VerificationRequest, VerificationRequestStatus,
     },
     get_code_key, Address, CONTRACT_DEPLOYER_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH,
 };
@@ -20,6 +20,15 @@ pub struct ContractVerificationDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
 }
 
+/// Parses the `match_level` field of the stored verification info. Info saved before match levels
+/// were introduced doesn't have this field and corresponds to a full match.
+fn parse_match_level(match_level: Option<&str>) -> MatchLevel {
+    match match_level {
+        Some("partial") => MatchLevel::Partial,
+        _ => MatchLevel::Full,
+    }
+}
+
 #[derive(Debug)]
 enum Compiler {
     ZkSolc,
@@ -145,17 +154,30 @@ impl ContractVerificationDal<'_, '_> {
         .await?;
 
         let address = verification_info.request.req.contract_address;
+        let code_key = get_code_key(&address).hashed_key();
         // Serialization should always succeed.
         let verification_info_json = serde_json::to_value(verification_info)
             .expect("Failed to serialize verification info into serde_json");
+        // The hash of the deployed bytecode is stored to propagate verification to contracts with the same bytecode.
+        // It's resolved in the same way as the bytecode used for verification.
+        // A partial match never overwrites a full one; info saved before match levels were introduced
+        // has no `match_level` and corresponds to a full match.
         sqlx::query!(
             "INSERT INTO contracts_verification_info \
-            (address, verification_info) \
-            VALUES ($1, $2) \
+            (address, verification_info, bytecode_hash) \
+            VALUES ($1, $2, ( \
+                SELECT factory_deps.bytecode_hash FROM storage \
+                JOIN factory_deps ON factory_deps.bytecode_hash = storage.value \
+                WHERE storage.hashed_key = $3 AND storage.value != $4 \
+            )) \
             ON CONFLICT (address) \
-            DO UPDATE SET verification_info = $2",
+            DO UPDATE SET verification_info = $2, bytecode_hash = EXCLUDED.bytecode_hash \
+            WHERE EXCLUDED.verification_info->>'match_level' = 'full' \
+                OR contracts_verification_info.verification_info->>'match_level' = 'partial'",
             address.as_bytes(),
-            &verification_info_json
+            &verification_info_json,
+            code_key.as_bytes(),
+            FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
         )
         .execute(transaction.conn())
         .await?;
@@ -190,7 +212,11 @@ impl ContractVerificationDal<'_, '_> {
         id: usize,
     ) -> anyhow::Result<Option<VerificationRequestStatus>> {
         let Some(row) = sqlx::query!(
-            "SELECT status, error, compilation_errors FROM contract_verification_requests \
+            "SELECT status, error, compilation_errors, \
+                contracts_verification_info.verification_info->>'match_level' AS match_level \
+            FROM contract_verification_requests \
+            LEFT JOIN contracts_verification_info \
+                ON contracts_verification_info.address = contract_verification_requests.contract_address \
             WHERE id = $1",
             id as i64,
         )
@@ -203,9 +229,21 @@ impl ContractVerificationDal<'_, '_> {
         let mut compilation_errors = vec![];
         if let Some(errors) = row.compilation_errors {
             for value in errors.as_array().context("expected an array")? {
-                compilation_errors.push(value.as_str().context("expected string")?.to_string());
+                // Errors persisted before errors were structured are plain strings.
+                let error = match value {
+                    serde_json::Value::String(message) => {
+                        CompilationError::from_message(message.clone())
+                    }
+                    _ => serde_json::from_value(value.clone())
+                        .context("invalid compilation error")?,
+                };
+                compilation_errors.push(error);
             }
         }
+        // The match level is reported for the currently stored verification info, which may come
+        // from a later request upgrading a partial match to a full one.
+        let match_level =
+            (row.status == "successful").then(|| parse_match_level(row.match_level.as_deref()));
         Ok(Some(VerificationRequestStatus {
             status: row.status,
             error: row.error,
@@ -214,6 +252,7 @@ impl ContractVerificationDal<'_, '_> {
             } else {
                 Some(compilation_errors)
             },
+            match_level,
         }))
     }
 
@@ -256,18 +295,21 @@ impl ContractVerificationDal<'_, '_> {
         Ok(Some((row.bytecode, calldata)))
     }
 
-    /// Returns true if the contract has a stored contracts_verification_info.
-    pub async fn is_contract_verified(&mut self, address: Address) -> sqlx::Result<bool> {
-        let count = sqlx::query!(
-            "SELECT COUNT(*) as \"count!\" \
+    /// Returns the match level of the stored contracts_verification_info, or `None`
+    /// if the contract is not verified.
+    pub async fn get_contract_match_level(
+        &mut self,
+        address: Address,
+    ) -> sqlx::Result<Option<MatchLevel>> {
+        let row = sqlx::query!(
+            "SELECT verification_info->>'match_level' AS match_level \
             FROM contracts_verification_info \
             WHERE address = $1",
             address.as_bytes()
         )
-        .fetch_one(self.storage.conn())
-        .await?
-        .count;
-        Ok(count > 0)
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.map(|row| parse_match_level(row.match_level.as_deref())))
     }
 
     async fn get_compiler_versions(&mut self, compiler: Compiler) -> sqlx::Result<Vec<String>> {
@@ -387,4 +429,35 @@ impl ContractVerificationDal<'_, '_> {
         };
         Ok(Some(serde_json::from_value(info).context("invalid info")?))
     }
+
+    /// Returns verification info for a contract explicitly verified at another address, which has
+    /// the same bytecode as the contract at `address`. This allows to propagate verification
+    /// to contracts deployed by factories and to identical proxies.
+    pub async fn get_contract_verification_info_by_bytecode(
+        &mut self,
+        address: Address,
+    ) -> anyhow::Result<Option<VerificationInfo>> {
+        let code_key = get_code_key(&address).hashed_key();
+        let Some(row) = sqlx::query!(
+            "SELECT verification_info FROM contracts_verification_info \
+            WHERE bytecode_hash = ( \
+                SELECT factory_deps.bytecode_hash FROM storage \
+                JOIN factory_deps ON factory_deps.bytecode_hash = storage.value \
+                WHERE storage.hashed_key = $1 AND storage.value != $2 \
+            ) \
+            ORDER BY address \
+            LIMIT 1",
+            code_key.as_bytes(),
+            FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        else {
+            return Ok(None);
+        };
+        let Some(info) = row.verification_info else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_value(info).context("invalid info")?))
+    }
 }
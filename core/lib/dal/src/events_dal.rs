@@ -7,7 +7,9 @@ use zksync_types::{
     MiniblockNumber, VmEvent, H256,
 };
 
-use crate::{models::storage_event::StorageL2ToL1Log, SqlxError, StorageProcessor};
+use crate::{
+    metrics::MethodLatency, models::storage_event::StorageL2ToL1Log, SqlxError, StorageProcessor,
+};
 
 /// Wrapper around an optional event topic allowing to hex-format it for `COPY` instructions.
 #[derive(Debug)]
@@ -35,9 +37,10 @@ impl EventsDal<'_, '_> {
         block_number: MiniblockNumber,
         all_block_events: &[(IncludedTxLocation, Vec<&VmEvent>)],
     ) {
+        let latency = MethodLatency::new("save_events");
         let mut copy = self
             .storage
-            .conn()
+            .raw_conn()
             .copy_in_raw(
                 "COPY events(
                     miniblock_number, tx_hash, tx_index_in_block, address,
@@ -88,6 +91,7 @@ impl EventsDal<'_, '_> {
         copy.send(buffer.as_bytes()).await.unwrap();
         // note: all the time spent in this function is spent in `copy.finish()`
         copy.finish().await.unwrap();
+        drop(latency);
     }
 
     /// Removes events with a block number strictly greater than the specified `block_number`.
@@ -108,9 +112,10 @@ impl EventsDal<'_, '_> {
         block_number: MiniblockNumber,
         all_block_l2_to_l1_logs: &[(IncludedTxLocation, Vec<&UserL2ToL1Log>)],
     ) {
+        let latency = MethodLatency::new("save_user_l2_to_l1_logs");
         let mut copy = self
             .storage
-            .conn()
+            .raw_conn()
             .copy_in_raw(
                 "COPY l2_to_l1_logs(
                     miniblock_number, log_index_in_miniblock, log_index_in_tx, tx_hash,
@@ -161,6 +166,7 @@ impl EventsDal<'_, '_> {
         }
         copy.send(buffer.as_bytes()).await.unwrap();
         copy.finish().await.unwrap();
+        drop(latency);
     }
 
     /// Removes all L2-to-L1 logs with a miniblock number strictly greater than the specified `block_number`.
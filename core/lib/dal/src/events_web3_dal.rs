@@ -48,10 +48,9 @@ impl EventsWeb3Dal<'_, '_> {
             query = query.bind(offset as i32);
             let log = query
                 .instrument("get_log_block_number")
-                .report_latency()
                 .with_arg("filter", filter)
                 .with_arg("offset", &offset)
-                .fetch_optional(self.storage.conn())
+                .fetch_optional(self.storage)
                 .await?;
 
             Ok(log.map(|row| MiniblockNumber(row.get::<i64, _>("miniblock_number") as u32)))
@@ -101,10 +100,9 @@ impl EventsWeb3Dal<'_, '_> {
 
             let db_logs: Vec<StorageWeb3Log> = query
                 .instrument("get_logs")
-                .report_latency()
                 .with_arg("filter", &filter)
                 .with_arg("limit", &limit)
-                .fetch_all(self.storage.conn())
+                .fetch_all(self.storage)
                 .await?;
             let logs = db_logs.into_iter().map(Into::into).collect();
             Ok(logs)
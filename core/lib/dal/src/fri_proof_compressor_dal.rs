@@ -1,14 +1,18 @@
 use std::{collections::HashMap, str::FromStr, time::Duration};
 
-use sqlx::Row;
+use sqlx::{
+    types::chrono::{DateTime, Utc},
+    Row,
+};
 use strum::{Display, EnumString};
 use zksync_types::{
     proofs::{JobCountStatistics, StuckJobs},
+    prover_dashboard::FriJobInfo,
     L1BatchNumber,
 };
 
 use crate::{
-    time_utils::{duration_to_naive_time, pg_interval_from_duration},
+    time_utils::{duration_to_naive_time, naive_time_to_millis, pg_interval_from_duration},
     StorageProcessor,
 };
 
@@ -224,6 +228,39 @@ impl FriProofCompressorDal<'_, '_> {
         result
     }
 
+    pub async fn get_proof_compression_job_for_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<FriJobInfo>> {
+        let row = sqlx::query!(
+            "
+            SELECT l1_batch_number, status, attempts, error, picked_by,
+                created_at, processing_started_at, updated_at, time_taken
+            FROM proof_compression_jobs_fri
+            WHERE l1_batch_number = $1
+            ",
+            l1_batch_number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| FriJobInfo {
+            id: row.l1_batch_number as u64,
+            circuit_id: None,
+            depth: None,
+            status: row.status,
+            attempts: row.attempts as u32,
+            error: row.error,
+            picked_by: row.picked_by,
+            created_at: DateTime::from_naive_utc_and_offset(row.created_at, Utc),
+            processing_started_at: row
+                .processing_started_at
+                .map(|time| DateTime::from_naive_utc_and_offset(time, Utc)),
+            updated_at: DateTime::from_naive_utc_and_offset(row.updated_at, Utc),
+            time_taken_ms: row.time_taken.map(naive_time_to_millis),
+        }))
+    }
+
     pub async fn requeue_stuck_jobs(
         &mut self,
         processing_timeout: Duration,
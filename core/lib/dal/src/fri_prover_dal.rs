@@ -1,16 +1,21 @@
 use std::{collections::HashMap, convert::TryFrom, time::Duration};
 
+use sqlx::types::chrono::{DateTime, Utc};
 use zksync_types::{
     basic_fri_types::CircuitIdRoundTuple,
-    proofs::{AggregationRound, FriProverJobMetadata, JobCountStatistics, StuckJobs},
+    proofs::{
+        AggregationRound, FriBatchPriority, FriProverJobMetadata, FriProverJobPriority,
+        JobCountStatistics, StuckJobs,
+    },
     protocol_version::FriProtocolVersionId,
+    prover_dashboard::FriJobInfo,
     L1BatchNumber,
 };
 
 use crate::{
     instrument::InstrumentExt,
     metrics::MethodLatency,
-    time_utils::{duration_to_naive_time, pg_interval_from_duration},
+    time_utils::{duration_to_naive_time, naive_time_to_millis, pg_interval_from_duration},
     StorageProcessor,
 };
 
@@ -64,7 +69,7 @@ impl FriProverDal<'_, '_> {
                     FROM prover_jobs_fri
                     WHERE status = 'queued'
                     AND protocol_version = ANY($1)
-                    ORDER BY aggregation_round DESC, l1_batch_number ASC, id ASC
+                    ORDER BY priority DESC, aggregation_round DESC, l1_batch_number ASC, id ASC
                     LIMIT 1
                     FOR UPDATE
                     SKIP LOCKED
@@ -112,6 +117,17 @@ impl FriProverDal<'_, '_> {
                     processing_started_at = now(), updated_at = now(), 
                     picked_by = $4
                 WHERE id = (
+                    WITH circuit_load AS (
+                        SELECT circuit_id, aggregation_round,
+                            COUNT(*) FILTER (WHERE status IN ('in_progress', 'in_gpu_proof')) AS active,
+                            COUNT(*) FILTER (WHERE status = 'queued') AS queued
+                        FROM prover_jobs_fri
+                        WHERE status IN ('queued', 'in_progress', 'in_gpu_proof')
+                        AND (circuit_id, aggregation_round) IN (
+                            SELECT * FROM unnest($1::smallint[], $2::smallint[])
+                        )
+                        GROUP BY circuit_id, aggregation_round
+                    )
                     SELECT pj.id
                     FROM ( SELECT * FROM unnest($1::smallint[], $2::smallint[]) ) AS tuple (circuit_id, round)
                     JOIN LATERAL
@@ -120,10 +136,15 @@ impl FriProverDal<'_, '_> {
                         WHERE pj.status = 'queued'
                         AND pj.protocol_version = ANY($3)
                         AND pj.circuit_id = tuple.circuit_id AND pj.aggregation_round = tuple.round
-                        ORDER BY pj.l1_batch_number ASC, pj.id ASC
+                        ORDER BY pj.priority DESC, pj.l1_batch_number ASC, pj.id ASC
                         LIMIT 1
                     ) AS pj ON true
-                    ORDER BY pj.l1_batch_number ASC, pj.aggregation_round DESC, pj.id ASC
+                    JOIN circuit_load
+                        ON circuit_load.circuit_id = pj.circuit_id
+                        AND circuit_load.aggregation_round = pj.aggregation_round
+                    ORDER BY pj.priority DESC,
+                    circuit_load.active::real / circuit_load.queued ASC,
+                    pj.l1_batch_number ASC, pj.aggregation_round DESC, pj.id ASC
                     LIMIT 1
                     FOR UPDATE
                     SKIP LOCKED
@@ -200,9 +221,8 @@ impl FriProverDal<'_, '_> {
             id as i64,
         )
             .instrument("save_fri_proof")
-            .report_latency()
             .with_arg("id", &id)
-            .fetch_optional(self.storage.conn())
+            .fetch_optional(self.storage)
             .await
             .unwrap()
             .map(|row| FriProverJobMetadata {
@@ -227,12 +247,13 @@ impl FriProverDal<'_, '_> {
             sqlx::query!(
                 "
                 UPDATE prover_jobs_fri
-                SET status = 'queued', updated_at = now(), processing_started_at = now()
+                SET status = 'queued', updated_at = now(), processing_started_at = now(), lease_expires_at = NULL
                 WHERE id in (
                     SELECT id
                     FROM prover_jobs_fri
                     WHERE (status = 'in_progress' AND  processing_started_at <= now() - $1::interval AND attempts < $2)
                     OR (status = 'in_gpu_proof' AND  processing_started_at <= now() - $1::interval AND attempts < $2)
+                    OR (status IN ('in_progress', 'in_gpu_proof') AND lease_expires_at < now() AND attempts < $2)
                     OR (status = 'failed' AND attempts < $2)
                     FOR UPDATE SKIP LOCKED
                 )
@@ -250,6 +271,53 @@ impl FriProverDal<'_, '_> {
         }
     }
 
+    /// Acquires or extends the lease for an in-progress job picked by `picked_by`. Returns `false`
+    /// if the job is no longer processed by `picked_by` (e.g., it was re-queued after the lease expired).
+    pub async fn extend_lease(
+        &mut self,
+        id: u32,
+        lease_duration: Duration,
+        picked_by: &str,
+    ) -> sqlx::Result<bool> {
+        let lease_duration = pg_interval_from_duration(lease_duration);
+        let row = sqlx::query!(
+            "
+            UPDATE prover_jobs_fri
+            SET lease_expires_at = now() + $2::interval, updated_at = now()
+            WHERE id = $1
+            AND status IN ('in_progress', 'in_gpu_proof')
+            AND picked_by = $3
+            RETURNING id
+            ",
+            id as i64,
+            &lease_duration,
+            picked_by,
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.is_some())
+    }
+
+    /// Re-queues an in-progress job picked by `picked_by`, e.g. when the prover is shutting down.
+    /// The attempt is not counted since the job didn't fail.
+    pub async fn release_job(&mut self, id: u32, picked_by: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            "
+            UPDATE prover_jobs_fri
+            SET status = 'queued', attempts = GREATEST(attempts - 1, 0),
+                lease_expires_at = NULL, updated_at = now()
+            WHERE id = $1
+            AND status IN ('in_progress', 'in_gpu_proof')
+            AND picked_by = $2
+            ",
+            id as i64,
+            picked_by,
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn insert_prover_job(
         &mut self,
@@ -264,8 +332,8 @@ impl FriProverDal<'_, '_> {
     ) {
         sqlx::query!(
                     "
-                    INSERT INTO prover_jobs_fri (l1_batch_number, circuit_id, circuit_blob_url, aggregation_round, sequence_number, depth, is_node_final_proof, protocol_version, status, created_at, updated_at)
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'queued', now(), now())
+                    INSERT INTO prover_jobs_fri (l1_batch_number, circuit_id, circuit_blob_url, aggregation_round, sequence_number, depth, is_node_final_proof, protocol_version, priority, status, created_at, updated_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE((SELECT priority FROM fri_prover_batch_priorities WHERE l1_batch_number = $1), 0), 'queued', now(), now())
                     ON CONFLICT(l1_batch_number, aggregation_round, circuit_id, depth, sequence_number)
                     DO UPDATE SET updated_at=now()
                     ",
@@ -283,6 +351,140 @@ impl FriProverDal<'_, '_> {
             .unwrap();
     }
 
+    /// Sets the priority of all FRI prover jobs for the specified L1 batch, including jobs that will be created
+    /// for the batch later. Returns the number of updated existing jobs.
+    pub async fn set_batch_priority(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        priority: FriProverJobPriority,
+    ) -> sqlx::Result<u64> {
+        let mut transaction = self.storage.start_transaction().await?;
+        if priority == FriProverJobPriority::Normal {
+            sqlx::query!(
+                "DELETE FROM fri_prover_batch_priorities WHERE l1_batch_number = $1",
+                l1_batch_number.0 as i64,
+            )
+            .execute(transaction.conn())
+            .await?;
+        } else {
+            sqlx::query!(
+                "
+                INSERT INTO fri_prover_batch_priorities (l1_batch_number, priority, created_at, updated_at)
+                VALUES ($1, $2, now(), now())
+                ON CONFLICT (l1_batch_number) DO UPDATE SET priority = $2, updated_at = now()
+                ",
+                l1_batch_number.0 as i64,
+                priority as i16,
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+
+        let updated_jobs = sqlx::query!(
+            "
+            UPDATE prover_jobs_fri
+            SET priority = $2, updated_at = now()
+            WHERE l1_batch_number = $1 AND status <> 'successful' AND priority <> $2
+            ",
+            l1_batch_number.0 as i64,
+            priority as i16,
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        transaction.commit().await?;
+        Ok(updated_jobs)
+    }
+
+    /// Returns all L1 batches with a non-default priority.
+    pub async fn get_batch_priorities(&mut self) -> sqlx::Result<Vec<FriBatchPriority>> {
+        let rows = sqlx::query!(
+            "
+            SELECT l1_batch_number, priority, updated_at
+            FROM fri_prover_batch_priorities
+            ORDER BY l1_batch_number
+            "
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let priority = FriProverJobPriority::try_from(row.priority).ok()?;
+                Some(FriBatchPriority {
+                    l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                    priority,
+                    updated_at: DateTime::from_naive_utc_and_offset(row.updated_at, Utc),
+                })
+            })
+            .collect())
+    }
+
+    /// Returns all prover jobs for an L1 batch together with their aggregation rounds.
+    pub async fn get_prover_jobs_for_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<(AggregationRound, FriJobInfo)>> {
+        let rows = sqlx::query!(
+            "
+            SELECT id, circuit_id, aggregation_round, depth, status, attempts, error, picked_by,
+                created_at, processing_started_at, updated_at, time_taken
+            FROM prover_jobs_fri
+            WHERE l1_batch_number = $1
+            ORDER BY aggregation_round, circuit_id, depth, sequence_number
+            ",
+            l1_batch_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let job = FriJobInfo {
+                    id: row.id as u64,
+                    circuit_id: Some(row.circuit_id as u8),
+                    depth: Some(row.depth as u16),
+                    status: row.status,
+                    attempts: row.attempts as u32,
+                    error: row.error,
+                    picked_by: row.picked_by,
+                    created_at: DateTime::from_naive_utc_and_offset(row.created_at, Utc),
+                    processing_started_at: row
+                        .processing_started_at
+                        .map(|time| DateTime::from_naive_utc_and_offset(time, Utc)),
+                    updated_at: DateTime::from_naive_utc_and_offset(row.updated_at, Utc),
+                    time_taken_ms: row.time_taken.map(naive_time_to_millis),
+                };
+                (AggregationRound::from(row.aggregation_round as u8), job)
+            })
+            .collect())
+    }
+
+    /// Returns the number of queued jobs for each priority class.
+    pub async fn get_queued_jobs_count_by_priority(
+        &mut self,
+    ) -> HashMap<FriProverJobPriority, usize> {
+        sqlx::query!(
+            r#"
+            SELECT priority, COUNT(*) as "count!"
+            FROM prover_jobs_fri
+            WHERE status = 'queued'
+            GROUP BY priority
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|row| {
+            let priority = FriProverJobPriority::try_from(row.priority).ok()?;
+            Some((priority, row.count as usize))
+        })
+        .collect()
+    }
+
     pub async fn get_prover_jobs_stats(&mut self) -> HashMap<(u8, u8), JobCountStatistics> {
         {
             sqlx::query!(
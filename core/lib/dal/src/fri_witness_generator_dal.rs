@@ -1,18 +1,22 @@
 use std::{collections::HashMap, convert::TryFrom, time::Duration};
 
-use sqlx::Row;
+use sqlx::{
+    types::chrono::{DateTime, NaiveDateTime, NaiveTime, Utc},
+    Row,
+};
 use zksync_types::{
     proofs::{
         AggregationRound, JobCountStatistics, LeafAggregationJobMetadata,
         NodeAggregationJobMetadata, StuckJobs,
     },
     protocol_version::FriProtocolVersionId,
+    prover_dashboard::FriJobInfo,
     L1BatchNumber,
 };
 
 use crate::{
     metrics::MethodLatency,
-    time_utils::{duration_to_naive_time, pg_interval_from_duration},
+    time_utils::{duration_to_naive_time, naive_time_to_millis, pg_interval_from_duration},
     StorageProcessor,
 };
 
@@ -824,6 +828,57 @@ impl FriWitnessGeneratorDal<'_, '_> {
         }
     }
 
+    /// Returns witness generation jobs of the specified round for an L1 batch.
+    pub async fn get_witness_jobs_for_batch(
+        &mut self,
+        aggregation_round: AggregationRound,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<FriJobInfo>> {
+        let table_name = Self::input_table_name_for(aggregation_round);
+        let (id_column, circuit_id_column, depth_column) = match aggregation_round {
+            AggregationRound::BasicCircuits | AggregationRound::Scheduler => {
+                ("l1_batch_number", "NULL::SMALLINT", "NULL::INT")
+            }
+            AggregationRound::LeafAggregation => ("id", "circuit_id", "NULL::INT"),
+            AggregationRound::NodeAggregation => ("id", "circuit_id", "depth"),
+        };
+        let sql = format!(
+            r#"
+                SELECT {id_column} AS "id", {circuit_id_column} AS "circuit_id",
+                    {depth_column} AS "depth", status, attempts, error, picked_by,
+                    created_at, processing_started_at, updated_at, time_taken
+                FROM {table_name}
+                WHERE l1_batch_number = $1
+                ORDER BY "circuit_id", "depth", "id"
+                "#
+        );
+        let rows = sqlx::query(&sql)
+            .bind(l1_batch_number.0 as i64)
+            .fetch_all(self.storage.conn())
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| FriJobInfo {
+                id: row.get::<i64, _>("id") as u64,
+                circuit_id: row.get::<Option<i16>, _>("circuit_id").map(|id| id as u8),
+                depth: row.get::<Option<i32>, _>("depth").map(|depth| depth as u16),
+                status: row.get("status"),
+                attempts: row.get::<i16, _>("attempts") as u32,
+                error: row.get("error"),
+                picked_by: row.get("picked_by"),
+                created_at: DateTime::from_naive_utc_and_offset(row.get("created_at"), Utc),
+                processing_started_at: row
+                    .get::<Option<NaiveDateTime>, _>("processing_started_at")
+                    .map(|time| DateTime::from_naive_utc_and_offset(time, Utc)),
+                updated_at: DateTime::from_naive_utc_and_offset(row.get("updated_at"), Utc),
+                time_taken_ms: row
+                    .get::<Option<NaiveTime>, _>("time_taken")
+                    .map(naive_time_to_millis),
+            })
+            .collect())
+    }
+
     fn input_table_name_for(aggregation_round: AggregationRound) -> &'static str {
         match aggregation_round {
             AggregationRound::BasicCircuits => "witness_inputs_fri",
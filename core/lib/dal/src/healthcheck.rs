@@ -41,7 +41,7 @@ impl CheckHealth for ConnectionPoolHealthCheck {
         // This check is rather feeble, plan to make reliable here:
         // https://linear.app/matterlabs/issue/PLA-255/revamp-db-connection-health-check
         self.connection_pool.access_storage().await.unwrap();
-        let details = ConnectionPoolHealthDetails::new(&self.connection_pool.0).await;
+        let details = ConnectionPoolHealthDetails::new(&self.connection_pool.inner).await;
         Health::from(HealthStatus::Ready).with_details(details)
     }
 }
@@ -1,19 +1,119 @@
 //! DAL query instrumentation.
 
-use std::{fmt, future::Future, panic::Location};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    future::Future,
+    panic::Location,
+    sync::Mutex,
+};
 
+use futures::{
+    future::BoxFuture,
+    stream::{self, BoxStream},
+    FutureExt, StreamExt, TryFutureExt, TryStreamExt,
+};
+use serde::Serialize;
 use sqlx::{
-    postgres::{PgConnection, PgQueryResult, PgRow},
+    database::HasStatement,
+    postgres::{PgQueryResult, PgRow, PgTypeInfo},
     query::{Map, Query, QueryAs},
-    FromRow, IntoArguments, Postgres,
+    Describe, Either, Execute, Executor, FromRow, IntoArguments, PgConnection, Postgres,
 };
 use tokio::time::{Duration, Instant};
 
-use crate::metrics::REQUEST_METRICS;
+use crate::{
+    metrics::{CONNECTION_METRICS, REQUEST_METRICS},
+    StorageProcessor,
+};
 
 type ThreadSafeDebug<'a> = dyn fmt::Debug + Send + Sync + 'a;
 
 const SLOW_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+/// Maximum length of a logged query argument; longer arguments are truncated.
+const MAX_ARG_LEN: usize = 128;
+/// Tag used for slow queries executed on connections acquired without a tag.
+const UNTAGGED: &str = "untagged";
+
+/// Information about a slow query that is currently being executed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveSlowQuery {
+    /// Query name provided to `instrument()`.
+    pub name: &'static str,
+    /// Location of the query in the DAL code.
+    pub location: String,
+    /// Summary of the query arguments. Only included if requested in [`active_slow_queries()`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<String>,
+    /// Time elapsed since the query has started, in milliseconds.
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug)]
+struct ActiveSlowQueryEntry {
+    tag: Option<&'static str>,
+    name: &'static str,
+    location: &'static Location<'static>,
+    args: String,
+    started_at: Instant,
+}
+
+#[derive(Debug)]
+struct ActiveSlowQueries {
+    next_id: u64,
+    queries: BTreeMap<u64, ActiveSlowQueryEntry>,
+}
+
+static ACTIVE_SLOW_QUERIES: Mutex<ActiveSlowQueries> = Mutex::new(ActiveSlowQueries {
+    next_id: 0,
+    queries: BTreeMap::new(),
+});
+
+/// Returns slow queries currently being executed grouped by the tag of the connection
+/// (i.e., the requester provided to [`ConnectionPool::access_storage_tagged()`](crate::ConnectionPool::access_storage_tagged())).
+/// Bound query arguments may contain sensitive data, so they are only returned if `include_args` is set.
+pub fn active_slow_queries(include_args: bool) -> HashMap<&'static str, Vec<ActiveSlowQuery>> {
+    let active_queries = ACTIVE_SLOW_QUERIES
+        .lock()
+        .expect("active slow queries are poisoned");
+    let mut queries_by_tag = HashMap::<_, Vec<_>>::new();
+    for entry in active_queries.queries.values() {
+        let query = ActiveSlowQuery {
+            name: entry.name,
+            location: format!("{}:{}", entry.location.file(), entry.location.line()),
+            args: include_args.then(|| entry.args.clone()),
+            elapsed_ms: entry.started_at.elapsed().as_millis() as u64,
+        };
+        let tag = entry.tag.unwrap_or(UNTAGGED);
+        queries_by_tag.entry(tag).or_default().push(query);
+    }
+    queries_by_tag
+}
+
+/// Registration of a slow query in [`ACTIVE_SLOW_QUERIES`]. The query is unregistered on drop,
+/// including the case when the query future is cancelled.
+#[derive(Debug)]
+struct ActiveSlowQueryGuard(u64);
+
+impl ActiveSlowQueryGuard {
+    fn new(entry: ActiveSlowQueryEntry) -> Self {
+        let mut active_queries = ACTIVE_SLOW_QUERIES
+            .lock()
+            .expect("active slow queries are poisoned");
+        let id = active_queries.next_id;
+        active_queries.next_id += 1;
+        active_queries.queries.insert(id, entry);
+        Self(id)
+    }
+}
+
+impl Drop for ActiveSlowQueryGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active_queries) = ACTIVE_SLOW_QUERIES.lock() {
+            active_queries.queries.remove(&self.0);
+        }
+    }
+}
 
 /// Logged arguments for an SQL query.
 #[derive(Debug, Default)]
@@ -28,7 +128,16 @@ impl fmt::Display for QueryArgs<'_> {
         } else {
             formatter.write_str("(")?;
             for (i, (name, value)) in self.inner.iter().enumerate() {
-                write!(formatter, "{name}={value:?}")?;
+                let value = format!("{value:?}");
+                if value.len() > MAX_ARG_LEN {
+                    let mut end = MAX_ARG_LEN;
+                    while !value.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    write!(formatter, "{name}={}...", &value[..end])?;
+                } else {
+                    write!(formatter, "{name}={value}")?;
+                }
                 if i + 1 < self.inner.len() {
                     formatter.write_str(", ")?;
                 }
@@ -90,7 +199,6 @@ struct InstrumentedData<'a> {
     name: &'static str,
     location: &'static Location<'static>,
     args: QueryArgs<'a>,
-    report_latency: bool,
 }
 
 impl<'a> InstrumentedData<'a> {
@@ -99,19 +207,19 @@ impl<'a> InstrumentedData<'a> {
             name,
             location,
             args: QueryArgs::default(),
-            report_latency: false,
         }
     }
 
     async fn fetch<R>(
         self,
+        tag: Option<&'static str>,
         query_future: impl Future<Output = Result<R, sqlx::Error>>,
+        count_rows: fn(&R) -> usize,
     ) -> Result<R, sqlx::Error> {
         let Self {
             name,
             location,
             args,
-            report_latency,
         } = self;
         let started_at = Instant::now();
         tokio::pin!(query_future);
@@ -129,28 +237,39 @@ impl<'a> InstrumentedData<'a> {
                 );
                 REQUEST_METRICS.request_slow[&name].inc();
                 is_slow = true;
+                let _guard = ActiveSlowQueryGuard::new(ActiveSlowQueryEntry {
+                    tag,
+                    name,
+                    location,
+                    args: args.to_string(),
+                    started_at,
+                });
                 query_future.await
             }
         };
 
         let elapsed = started_at.elapsed();
-        if report_latency {
-            REQUEST_METRICS.request[&name].observe(elapsed);
-        }
+        REQUEST_METRICS.request[&name].observe(elapsed);
 
-        if let Err(err) = &output {
-            tracing::warn!(
-                "Query {name}{args} called at {file}:{line} has resulted in error: {err}",
-                file = location.file(),
-                line = location.line()
-            );
-            REQUEST_METRICS.request_error[&name].inc();
-        } else if is_slow {
-            tracing::info!(
-                "Slow query {name}{args} called at {file}:{line} has finished after {elapsed:?}",
-                file = location.file(),
-                line = location.line()
-            );
+        match &output {
+            Err(err) => {
+                tracing::warn!(
+                    "Query {name}{args} called at {file}:{line} has resulted in error: {err}",
+                    file = location.file(),
+                    line = location.line()
+                );
+                REQUEST_METRICS.request_error[&name].inc();
+            }
+            Ok(output) => {
+                REQUEST_METRICS.request_rows[&name].observe(count_rows(output));
+                if is_slow {
+                    tracing::info!(
+                        "Slow query {name}{args} called at {file}:{line} has finished after {elapsed:?}",
+                        file = location.file(),
+                        line = location.line()
+                    );
+                }
+            }
         }
         output
     }
@@ -161,12 +280,18 @@ impl<'a> InstrumentedData<'a> {
 ///
 /// The following instrumentation logic is included:
 ///
+/// - Query latency and the number of returned / affected rows are reported using metrics
+///   (`sql_request` and `sql_request_rows`, respectively).
 /// - If the query executes for too long, it is logged with a `WARN` level. The logged info includes
-///   the query name, its args provided via [Self::with_arg()`] and the caller location.
+///   the query name, its args provided via [Self::with_arg()`] (truncated if they are too long)
+///   and the caller location. While the query is executing, it is also returned
+///   by [`active_slow_queries()`].
 /// - If the query returns an error, it is logged with a `WARN` level. The logged info is everything
 ///   included in the case of a slow query, plus the error info.
 /// - Slow and erroneous queries are also reported using metrics (`dal.request.slow` and `dal.request.error`,
 ///   respectively). The query name is included as a metric label; args are not included for obvious reasons.
+/// - If the query is the first one executed on a connection, the time spent acquiring the connection
+///   from the pool is reported using the `sql_connection_acquire_by_method` metric.
 #[derive(Debug)]
 pub(crate) struct Instrumented<'a, Q> {
     query: Q,
@@ -174,12 +299,6 @@ pub(crate) struct Instrumented<'a, Q> {
 }
 
 impl<'a, Q> Instrumented<'a, Q> {
-    /// Indicates that latency should be reported for all calls.
-    pub fn report_latency(mut self) -> Self {
-        self.data.report_latency = true;
-        self
-    }
-
     /// Adds a traced query argument. The argument will be logged (using `Debug`) if the query executes too slow
     /// or finishes with an error.
     pub fn with_arg(mut self, name: &'static str, value: &'a ThreadSafeDebug) -> Self {
@@ -193,16 +312,25 @@ where
     A: 'q + IntoArguments<'q, Postgres>,
 {
     /// Executes an SQL statement using this query.
-    pub async fn execute(self, conn: &mut PgConnection) -> Result<PgQueryResult, sqlx::Error> {
-        self.data.fetch(self.query.execute(conn)).await
+    pub async fn execute(
+        self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<PgQueryResult, sqlx::Error> {
+        let (tag, conn) = prepare_connection(storage, self.data.name);
+        let query_future = self.query.execute(conn);
+        self.data
+            .fetch(tag, query_future, |result| result.rows_affected() as usize)
+            .await
     }
 
     /// Fetches an optional row using this query.
     pub async fn fetch_optional(
         self,
-        conn: &mut PgConnection,
+        storage: &mut StorageProcessor<'_>,
     ) -> Result<Option<PgRow>, sqlx::Error> {
-        self.data.fetch(self.query.fetch_optional(conn)).await
+        let (tag, conn) = prepare_connection(storage, self.data.name);
+        let query_future = self.query.fetch_optional(conn);
+        self.data.fetch(tag, query_future, count_optional).await
     }
 }
 
@@ -212,8 +340,13 @@ where
     O: Send + Unpin + for<'r> FromRow<'r, PgRow>,
 {
     /// Fetches all rows using this query and collects them into a `Vec`.
-    pub async fn fetch_all(self, conn: &mut PgConnection) -> Result<Vec<O>, sqlx::Error> {
-        self.data.fetch(self.query.fetch_all(conn)).await
+    pub async fn fetch_all(
+        self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<Vec<O>, sqlx::Error> {
+        let (tag, conn) = prepare_connection(storage, self.data.name);
+        let query_future = self.query.fetch_all(conn);
+        self.data.fetch(tag, query_future, Vec::len).await
     }
 }
 
@@ -224,18 +357,168 @@ where
     A: 'q + Send + IntoArguments<'q, Postgres>,
 {
     /// Fetches an optional row using this query.
-    pub async fn fetch_optional(self, conn: &mut PgConnection) -> Result<Option<O>, sqlx::Error> {
-        self.data.fetch(self.query.fetch_optional(conn)).await
+    pub async fn fetch_optional(
+        self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<Option<O>, sqlx::Error> {
+        let (tag, conn) = prepare_connection(storage, self.data.name);
+        let query_future = self.query.fetch_optional(conn);
+        self.data.fetch(tag, query_future, count_optional).await
     }
 
     /// Fetches a single row using this query.
-    pub async fn fetch_one(self, conn: &mut PgConnection) -> Result<O, sqlx::Error> {
-        self.data.fetch(self.query.fetch_one(conn)).await
+    pub async fn fetch_one(self, storage: &mut StorageProcessor<'_>) -> Result<O, sqlx::Error> {
+        let (tag, conn) = prepare_connection(storage, self.data.name);
+        let query_future = self.query.fetch_one(conn);
+        self.data.fetch(tag, query_future, |_| 1).await
     }
 
     /// Fetches all rows using this query and collects them into a `Vec`.
-    pub async fn fetch_all(self, conn: &mut PgConnection) -> Result<Vec<O>, sqlx::Error> {
-        self.data.fetch(self.query.fetch_all(conn)).await
+    pub async fn fetch_all(
+        self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<Vec<O>, sqlx::Error> {
+        let (tag, conn) = prepare_connection(storage, self.data.name);
+        let query_future = self.query.fetch_all(conn);
+        self.data.fetch(tag, query_future, Vec::len).await
+    }
+}
+
+fn count_optional<T>(row: &Option<T>) -> usize {
+    usize::from(row.is_some())
+}
+
+fn count_many(items: &[Either<PgQueryResult, PgRow>]) -> usize {
+    let mut row_count = 0;
+    let mut affected_row_count = 0;
+    for item in items {
+        match item {
+            Either::Left(result) => affected_row_count += result.rows_affected() as usize,
+            Either::Right(_) => row_count += 1,
+        }
+    }
+    // Statements returning rows report zero affected rows, and vice versa.
+    row_count.max(affected_row_count)
+}
+
+/// Reports the connection acquisition latency for the query with the specified name if it's the first query
+/// executed on the connection. Returns the connection tag and the raw connection.
+fn prepare_connection<'c>(
+    storage: &'c mut StorageProcessor<'_>,
+    name: &'static str,
+) -> (Option<&'static str>, &'c mut PgConnection) {
+    if let Some(latency) = storage.acquire_latency.take() {
+        CONNECTION_METRICS.acquire_by_method[&name].observe(latency);
+    }
+    (storage.tag, storage.raw_conn())
+}
+
+/// Interned names of DAL code locations executing queries via [`InstrumentedConnection`].
+static CALL_SITE_NAMES: Mutex<BTreeMap<(&'static str, u32), &'static str>> =
+    Mutex::new(BTreeMap::new());
+
+/// Returns a name for queries executed at the specified location, e.g. `blocks_dal.rs:123`.
+/// Names are leaked, which is fine since the number of call sites is bounded.
+fn call_site_name(location: &'static Location<'static>) -> &'static str {
+    let mut names = CALL_SITE_NAMES
+        .lock()
+        .expect("call site names are poisoned");
+    let key = (location.file(), location.line());
+    names.entry(key).or_insert_with(|| {
+        let file_name = location
+            .file()
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default();
+        Box::leak(format!("{file_name}:{}", location.line()).into_boxed_str())
+    })
+}
+
+/// Connection wrapper returned by `StorageProcessor::conn()`. Implements [`Executor`], so it can be used
+/// as a drop-in replacement for `&mut PgConnection` in `sqlx` queries.
+///
+/// All queries executed on the wrapper are instrumented in the same way as [`Instrumented`] queries
+/// (latency, number of rows, slow / erroneous queries logging and tracking). The query name is derived
+/// from the DAL code location that has obtained the connection, and query args are not available.
+/// Use [`InstrumentExt::instrument()`] to assign a more descriptive name and to trace args.
+#[derive(Debug)]
+pub(crate) struct InstrumentedConnection<'c> {
+    conn: &'c mut PgConnection,
+    tag: Option<&'static str>,
+    data: InstrumentedData<'static>,
+}
+
+impl<'c> InstrumentedConnection<'c> {
+    pub(crate) fn new(
+        storage: &'c mut StorageProcessor<'_>,
+        location: &'static Location<'static>,
+    ) -> Self {
+        let name = call_site_name(location);
+        let (tag, conn) = prepare_connection(storage, name);
+        Self {
+            conn,
+            tag,
+            data: InstrumentedData::new(name, location),
+        }
+    }
+}
+
+impl<'c> Executor<'c> for InstrumentedConnection<'c> {
+    type Database = Postgres;
+
+    // Other query methods (`execute`, `fetch_all`, `fetch_one` etc.) are implemented via `fetch_many()`
+    // or `fetch_optional()`, so overriding these two methods is sufficient.
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        self,
+        query: E,
+    ) -> BoxStream<'e, Result<Either<PgQueryResult, PgRow>, sqlx::Error>>
+    where
+        'c: 'e,
+        E: Execute<'q, Postgres>,
+    {
+        // Results are buffered so that the query latency and the number of rows can be measured.
+        // DAL methods collect query results anyway.
+        let query_future = self.conn.fetch_many(query).try_collect::<Vec<_>>();
+        self.data
+            .fetch(self.tag, query_future, |items| count_many(items))
+            .map_ok(|items| stream::iter(items.into_iter().map(Ok::<_, sqlx::Error>)))
+            .try_flatten_stream()
+            .boxed()
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E: 'q>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, Result<Option<PgRow>, sqlx::Error>>
+    where
+        'c: 'e,
+        E: Execute<'q, Postgres>,
+    {
+        let query_future = self.conn.fetch_optional(query);
+        self.data
+            .fetch(self.tag, query_future, count_optional)
+            .boxed()
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [PgTypeInfo],
+    ) -> BoxFuture<'e, Result<<Postgres as HasStatement<'q>>::Statement, sqlx::Error>>
+    where
+        'c: 'e,
+    {
+        self.conn.prepare_with(sql, parameters)
+    }
+
+    fn describe<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+    ) -> BoxFuture<'e, Result<Describe<Postgres>, sqlx::Error>>
+    where
+        'c: 'e,
+    {
+        self.conn.describe(sql)
     }
 }
 
@@ -257,7 +540,7 @@ mod tests {
             .instrument("erroneous")
             .with_arg("miniblock", &MiniblockNumber(1))
             .with_arg("hash", &H256::zero())
-            .fetch_optional(conn.conn())
+            .fetch_optional(&mut conn)
             .await
             .unwrap_err();
     }
@@ -267,14 +550,76 @@ mod tests {
         let pool = ConnectionPool::test_pool().await;
         // Add `vlog::init()` here to debug this test
 
-        let mut conn = pool.access_storage().await.unwrap();
-        sqlx::query("SELECT pg_sleep(1.5)")
-            .map(drop)
-            .instrument("slow")
-            .with_arg("miniblock", &MiniblockNumber(1))
-            .with_arg("hash", &H256::zero())
-            .fetch_optional(conn.conn())
-            .await
-            .unwrap();
+        let mut conn = pool.access_storage_tagged("slow_test").await.unwrap();
+        let query_task = async {
+            sqlx::query("SELECT pg_sleep(1.5)")
+                .map(drop)
+                .instrument("slow")
+                .with_arg("miniblock", &MiniblockNumber(1))
+                .with_arg("hash", &H256::zero())
+                .fetch_optional(&mut conn)
+                .await
+                .unwrap();
+        };
+        let check_task = async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let active_queries = active_slow_queries(true);
+            let queries = &active_queries["slow_test"];
+            assert_eq!(queries.len(), 1, "{queries:?}");
+            assert_eq!(queries[0].name, "slow");
+            let args = queries[0].args.as_ref().unwrap();
+            assert!(args.contains("miniblock=1"), "{queries:?}");
+            assert!(queries[0].elapsed_ms >= 100, "{queries:?}");
+
+            let queries = &active_slow_queries(false)["slow_test"];
+            assert_eq!(queries[0].args, None);
+        };
+        tokio::join!(query_task, check_task);
+
+        assert!(!active_slow_queries(true).contains_key("slow_test"));
+    }
+
+    #[tokio::test]
+    async fn instrumenting_connection() {
+        let pool = ConnectionPool::test_pool().await;
+
+        let mut conn = pool.access_storage_tagged("slow_conn_test").await.unwrap();
+        let query_task = async {
+            sqlx::query("SELECT pg_sleep(1.5)")
+                .execute(conn.conn())
+                .await
+                .unwrap();
+        };
+        let check_task = async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let active_queries = active_slow_queries(true);
+            let queries = &active_queries["slow_conn_test"];
+            assert_eq!(queries.len(), 1, "{queries:?}");
+            assert!(queries[0].name.starts_with("instrument.rs:"), "{queries:?}");
+            assert_eq!(queries[0].args.as_deref(), Some(""));
+        };
+        tokio::join!(query_task, check_task);
+
+        assert!(!active_slow_queries(true).contains_key("slow_conn_test"));
+    }
+
+    #[test]
+    fn call_site_names() {
+        let location = Location::caller();
+        let name = call_site_name(location);
+        assert_eq!(name, format!("instrument.rs:{}", location.line()));
+        assert!(std::ptr::eq(name, call_site_name(location)));
+    }
+
+    #[test]
+    fn long_query_args_are_truncated() {
+        let long_arg = vec![0_u8; 1_000];
+        let mut args = QueryArgs::default();
+        args.inner.push(("short", &1_u32));
+        args.inner.push(("long", &long_arg));
+        let args = args.to_string();
+        assert!(args.starts_with("(short=1, long=[0, 0"), "{args}");
+        assert!(args.ends_with("...)"), "{args}");
+        assert!(args.len() < MAX_ARG_LEN + 32, "{args}");
     }
 }
@@ -1,9 +1,10 @@
 #![allow(clippy::derive_partial_eq_without_eq, clippy::format_push_string)]
 
+use std::{panic::Location, time::Duration};
+
 use sqlx::{pool::PoolConnection, postgres::Postgres, Connection, PgConnection, Transaction};
 pub use sqlx::{types::BigDecimal, Error as SqlxError};
 
-pub use crate::connection::ConnectionPool;
 use crate::{
     accounts_dal::AccountsDal, basic_witness_input_producer_dal::BasicWitnessInputProducerDal,
     blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal, connection::holder::ConnectionHolder,
@@ -14,19 +15,25 @@ use crate::{
     fri_protocol_versions_dal::FriProtocolVersionsDal, fri_prover_dal::FriProverDal,
     fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDal,
     fri_witness_generator_dal::FriWitnessGeneratorDal, gpu_prover_queue_dal::GpuProverQueueDal,
-    proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
+    instrument::InstrumentedConnection, proof_generation_dal::ProofGenerationDal,
+    protocol_versions_dal::ProtocolVersionsDal,
     protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, prover_dal::ProverDal,
-    snapshots_creator_dal::SnapshotsCreatorDal, snapshots_dal::SnapshotsDal,
-    storage_dal::StorageDal, storage_logs_dal::StorageLogsDal,
+    pruning_dal::PruningDal, snapshots_creator_dal::SnapshotsCreatorDal,
+    snapshots_dal::SnapshotsDal, storage_dal::StorageDal, storage_logs_dal::StorageLogsDal,
     storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
     sync_dal::SyncDal, system_dal::SystemDal, tokens_dal::TokensDal,
     tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
     transactions_web3_dal::TransactionsWeb3Dal,
 };
+pub use crate::{
+    connection::ConnectionPool,
+    instrument::{active_slow_queries, ActiveSlowQuery},
+};
 
 #[macro_use]
 mod macro_utils;
 pub mod accounts_dal;
+pub mod backend;
 pub mod basic_witness_input_producer_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
@@ -50,6 +57,7 @@ pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
 pub mod prover_dal;
+pub mod pruning_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
 pub mod storage_dal;
@@ -74,13 +82,22 @@ mod tests;
 pub struct StorageProcessor<'a> {
     conn: ConnectionHolder<'a>,
     in_transaction: bool,
+    /// Tag provided when acquiring the connection from the pool; used to attribute slow queries.
+    tag: Option<&'static str>,
+    /// Time spent acquiring the connection from the pool. Reported for the first query executed
+    /// on the connection, so that acquisition waits can be attributed to DAL methods.
+    acquire_latency: Option<Duration>,
 }
 
 impl<'a> StorageProcessor<'a> {
     pub async fn start_transaction<'c: 'b, 'b>(&'c mut self) -> sqlx::Result<StorageProcessor<'b>> {
-        let transaction = self.conn().begin().await?;
+        let tag = self.tag;
+        let acquire_latency = self.acquire_latency.take();
+        let transaction = self.raw_conn().begin().await?;
         let mut processor = StorageProcessor::from_transaction(transaction);
         processor.in_transaction = true;
+        processor.tag = tag;
+        processor.acquire_latency = acquire_latency;
         Ok(processor)
     }
 
@@ -93,6 +110,8 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Transaction(conn),
             in_transaction: true,
+            tag: None,
+            acquire_latency: None,
         }
     }
 
@@ -111,16 +130,27 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Pooled(conn),
             in_transaction: false,
+            tag: None,
+            acquire_latency: None,
         }
     }
 
-    fn conn(&mut self) -> &mut PgConnection {
+    /// Returns the underlying connection without instrumentation. Should only be used for operations
+    /// not covered by [`InstrumentedConnection`], such as starting transactions and `COPY` statements.
+    fn raw_conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
             ConnectionHolder::Pooled(conn) => conn,
             ConnectionHolder::Transaction(conn) => conn,
         }
     }
 
+    /// Returns the connection wrapped in an executor that instruments all queries executed on it.
+    /// Queries are attributed to the DAL code location calling this method.
+    #[track_caller]
+    fn conn(&mut self) -> InstrumentedConnection<'_> {
+        InstrumentedConnection::new(self, Location::caller())
+    }
+
     pub fn transactions_dal(&mut self) -> TransactionsDal<'_, 'a> {
         TransactionsDal { storage: self }
     }
@@ -223,6 +253,10 @@ impl<'a> StorageProcessor<'a> {
         ProofGenerationDal { storage: self }
     }
 
+    pub fn pruning_dal(&mut self) -> PruningDal<'_, 'a> {
+        PruningDal { storage: self }
+    }
+
     pub fn fri_gpu_prover_queue_dal(&mut self) -> FriGpuProverQueueDal<'_, 'a> {
         FriGpuProverQueueDal { storage: self }
     }
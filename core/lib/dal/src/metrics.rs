@@ -3,10 +3,12 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics,
 };
 
+const ROWS_BUCKETS: Buckets = Buckets::exponential(1.0..=100_000.0, 10.0);
+
 /// Request-related DB metrics.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "sql")]
@@ -14,6 +16,9 @@ pub(crate) struct RequestMetrics {
     /// Latency of a DB request.
     #[metrics(buckets = Buckets::LATENCIES, labels = ["method"])]
     pub request: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of rows returned or affected by a DB request.
+    #[metrics(buckets = ROWS_BUCKETS, labels = ["method"])]
+    pub request_rows: LabeledFamily<&'static str, Histogram<usize>>,
     /// Counter of slow DB requests.
     #[metrics(labels = ["method"])]
     pub request_slow: LabeledFamily<&'static str, Counter>,
@@ -26,7 +31,7 @@ pub(crate) struct RequestMetrics {
 pub(crate) static REQUEST_METRICS: vise::Global<RequestMetrics> = vise::Global::new();
 
 /// Reporter of latency for DAL methods consisting of multiple DB queries. If there's a single query,
-/// use `.instrument().report_latency()` on it instead.
+/// use `.instrument()` on it instead.
 ///
 /// Should be created at the start of the relevant method and dropped when the latency needs to be reported.
 #[derive(Debug)]
@@ -69,6 +74,14 @@ impl From<&sqlx::Error> for ConnectionErrorKind {
     }
 }
 
+/// Database targeted by a connection acquired from a pool with read replicas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(crate) enum ConnectionTarget {
+    Primary,
+    Replica,
+}
+
 const POOL_SIZE_BUCKETS: Buckets = Buckets::linear(0.0..=100.0, 10.0);
 
 /// Connection-related metrics.
@@ -81,6 +94,9 @@ pub(crate) struct ConnectionMetrics {
     /// Latency of acquiring a DB connection, tagged with the requester label.
     #[metrics(buckets = Buckets::LATENCIES, labels = ["requester"])]
     pub acquire_tagged: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency of acquiring a DB connection, attributed to the first query executed on the connection.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["method"])]
+    pub acquire_by_method: LabeledFamily<&'static str, Histogram<Duration>>,
     /// Current DB pool size.
     #[metrics(buckets = POOL_SIZE_BUCKETS)]
     pub pool_size: Histogram<usize>,
@@ -89,6 +105,13 @@ pub(crate) struct ConnectionMetrics {
     pub pool_idle: Histogram<usize>,
     /// Number of errors occurred when acquiring a DB connection.
     pub pool_acquire_error: Family<ConnectionErrorKind, Counter>,
+    /// Number of connections acquired from a pool with read replicas, tagged with the requester label
+    /// and the targeted database.
+    #[metrics(labels = ["requester", "target"])]
+    pub routed: LabeledFamily<(&'static str, ConnectionTarget), Counter, 2>,
+    /// Last measured replication lag of read replicas in seconds.
+    #[metrics(labels = ["replica"])]
+    pub replication_lag: LabeledFamily<String, Gauge<u64>>,
 }
 
 #[vise::register]
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{str::FromStr, time::Duration};
 
 use strum::{Display, EnumString};
 use zksync_types::L1BatchNumber;
@@ -22,47 +22,151 @@ enum ProofGenerationJobStatus {
     Skipped,
 }
 
+/// Status of an L1 batch assignment to a prover cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+pub enum ProofGenerationAssignmentStatus {
+    #[strum(serialize = "assigned")]
+    Assigned,
+    #[strum(serialize = "submitted")]
+    Submitted,
+    #[strum(serialize = "rejected")]
+    Rejected,
+}
+
+/// Assignment of an L1 batch to a prover cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofGenerationAssignment {
+    pub status: ProofGenerationAssignmentStatus,
+    /// Whether the assignment lease has expired. Expired assignments may be given to other clusters.
+    pub is_expired: bool,
+}
+
 impl ProofGenerationDal<'_, '_> {
+    /// Assigns the next L1 batch to be proven to the specified prover cluster. A batch can be assigned
+    /// to several clusters at once (i.e., proven redundantly), but to no more than `max_provers_per_batch`
+    /// clusters with non-expired leases.
     pub async fn get_next_block_to_be_proven(
         &mut self,
-        processing_timeout: Duration,
-    ) -> Option<L1BatchNumber> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        let result: Option<L1BatchNumber> = sqlx::query!(
-            "UPDATE proof_generation_details \
-             SET status = 'picked_by_prover', updated_at = now(), prover_taken_at = now() \
-             WHERE l1_batch_number = ( \
-                 SELECT l1_batch_number \
-                 FROM proof_generation_details \
-                 WHERE status = 'ready_to_be_proven' \
-                 OR (status = 'picked_by_prover' AND prover_taken_at < now() - $1::interval) \
-                 ORDER BY l1_batch_number ASC \
-                 LIMIT 1 \
-                 FOR UPDATE \
-                 SKIP LOCKED \
-             ) \
-             RETURNING proof_generation_details.l1_batch_number",
-            &processing_timeout,
+        prover_cluster: &str,
+        lease_duration: Duration,
+        max_provers_per_batch: usize,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let lease_duration = pg_interval_from_duration(lease_duration);
+        let mut transaction = self.storage.start_transaction().await?;
+        let row = sqlx::query!(
+            "
+            SELECT l1_batch_number
+            FROM proof_generation_details
+            WHERE status IN ('ready_to_be_proven', 'picked_by_prover')
+                AND l1_batch_number NOT IN (
+                    SELECT l1_batch_number
+                    FROM proof_generation_assignments
+                    WHERE prover_cluster = $1 AND status = 'assigned' AND lease_expires_at > now()
+                )
+                AND (
+                    SELECT COUNT(*)
+                    FROM proof_generation_assignments
+                    WHERE proof_generation_assignments.l1_batch_number = proof_generation_details.l1_batch_number
+                        AND status = 'assigned' AND lease_expires_at > now()
+                ) < $2
+            ORDER BY l1_batch_number ASC
+            LIMIT 1
+            FOR UPDATE
+            SKIP LOCKED
+            ",
+            prover_cluster,
+            max_provers_per_batch as i64,
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            "
+            UPDATE proof_generation_details
+            SET status = 'picked_by_prover', updated_at = now(), prover_taken_at = now()
+            WHERE l1_batch_number = $1
+            ",
+            row.l1_batch_number,
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "
+            INSERT INTO proof_generation_assignments
+                (l1_batch_number, prover_cluster, status, assigned_at, lease_expires_at, updated_at)
+            VALUES ($1, $2, 'assigned', now(), now() + $3::interval, now())
+            ON CONFLICT (l1_batch_number, prover_cluster) DO UPDATE
+            SET status = 'assigned', error = NULL, assigned_at = now(),
+                lease_expires_at = now() + $3::interval, updated_at = now()
+            ",
+            row.l1_batch_number,
+            prover_cluster,
+            &lease_duration,
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+        Ok(Some(L1BatchNumber(row.l1_batch_number as u32)))
+    }
+
+    pub async fn get_assignment(
+        &mut self,
+        block_number: L1BatchNumber,
+        prover_cluster: &str,
+    ) -> sqlx::Result<Option<ProofGenerationAssignment>> {
+        let row = sqlx::query!(
+            "
+            SELECT status, lease_expires_at <= now() AS \"is_expired!\"
+            FROM proof_generation_assignments
+            WHERE l1_batch_number = $1 AND prover_cluster = $2
+            ",
+            block_number.0 as i64,
+            prover_cluster,
         )
         .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()
-        .map(|row| L1BatchNumber(row.l1_batch_number as u32));
+        .await?;
+        Ok(row.map(|row| ProofGenerationAssignment {
+            status: ProofGenerationAssignmentStatus::from_str(&row.status)
+                .expect("Invalid proof generation assignment status"),
+            is_expired: row.is_expired,
+        }))
+    }
 
-        result
+    /// Checks whether a proof for the specified L1 batch is already stored, or proof generation
+    /// for it was skipped.
+    pub async fn is_proof_generated(&mut self, block_number: L1BatchNumber) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            "SELECT status IN ('generated', 'skipped') AS \"is_generated!\" \
+             FROM proof_generation_details \
+             WHERE l1_batch_number = $1",
+            block_number.0 as i64,
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.is_generated)
     }
 
-    pub async fn save_proof_artifacts_metadata(
+    /// Records the outcome of proof submission by a prover cluster.
+    pub async fn update_assignment_status(
         &mut self,
         block_number: L1BatchNumber,
-        proof_blob_url: &str,
-    ) -> Result<(), SqlxError> {
+        prover_cluster: &str,
+        status: ProofGenerationAssignmentStatus,
+        error: Option<&str>,
+    ) -> sqlx::Result<()> {
         sqlx::query!(
-            "UPDATE proof_generation_details \
-             SET status='generated', proof_blob_url = $1, updated_at = now() \
-             WHERE l1_batch_number = $2",
-            proof_blob_url,
+            "
+            UPDATE proof_generation_assignments
+            SET status = $3, error = $4, updated_at = now()
+            WHERE l1_batch_number = $1 AND prover_cluster = $2
+            ",
             block_number.0 as i64,
+            prover_cluster,
+            status.to_string(),
+            error,
         )
         .execute(self.storage.conn())
         .await?
@@ -72,6 +176,26 @@ impl ProofGenerationDal<'_, '_> {
         .ok_or(sqlx::Error::RowNotFound)
     }
 
+    /// Saves the URL of the proof blob for the specified L1 batch. Returns `false` if a proof
+    /// for the batch is already stored (e.g., by another prover cluster); the stored proof is not overwritten.
+    pub async fn save_proof_artifacts_metadata(
+        &mut self,
+        block_number: L1BatchNumber,
+        proof_blob_url: &str,
+    ) -> Result<bool, SqlxError> {
+        let rows_affected = sqlx::query!(
+            "UPDATE proof_generation_details \
+             SET status='generated', proof_blob_url = $1, updated_at = now() \
+             WHERE l1_batch_number = $2 AND status NOT IN ('generated', 'skipped')",
+            proof_blob_url,
+            block_number.0 as i64,
+        )
+        .execute(self.storage.conn())
+        .await?
+        .rows_affected();
+        Ok(rows_affected == 1)
+    }
+
     pub async fn insert_proof_generation_details(
         &mut self,
         block_number: L1BatchNumber,
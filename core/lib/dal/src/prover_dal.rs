@@ -160,11 +160,10 @@ impl ProverDal<'_, '_> {
                     protocol_version
                 )
                 .instrument("save_witness")
-                .report_latency()
                 .with_arg("l1_batch_number", &l1_batch_number)
                 .with_arg("circuit", &circuit)
                 .with_arg("circuit_input_blob_url", &circuit_input_blob_url)
-                .execute(self.storage.conn())
+                .execute(self.storage)
                 .await
                 .unwrap();
             }
@@ -191,10 +190,9 @@ impl ProverDal<'_, '_> {
                 id as i64,
             )
             .instrument("save_proof")
-            .report_latency()
             .with_arg("id", &id)
             .with_arg("proof.len", &proof.len())
-            .execute(self.storage.conn())
+            .execute(self.storage)
             .await?;
         }
         Ok(())
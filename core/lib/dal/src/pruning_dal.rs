@@ -0,0 +1,512 @@
+use std::ops;
+
+use zksync_types::{
+    api::{PrunedData, PrunedRange},
+    L1BatchNumber, MiniblockNumber,
+};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+pub struct PruningDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl PruningDal<'_, '_> {
+    /// Returns the upper bounds of pruned data for all data classes that were pruned at least once.
+    pub async fn get_pruned_ranges(&mut self) -> sqlx::Result<Vec<PrunedRange>> {
+        let rows = sqlx::query!(
+            "SELECT pruned_data, last_pruned_l1_batch, last_pruned_miniblock FROM pruning_log"
+        )
+        .instrument("get_pruned_ranges")
+        .fetch_all(self.storage)
+        .await?;
+
+        let ranges = rows.into_iter().filter_map(|row| {
+            let data = PrunedData::ALL
+                .into_iter()
+                .find(|data| data.as_str() == row.pruned_data)?;
+            Some(PrunedRange {
+                data,
+                last_pruned_l1_batch: L1BatchNumber(row.last_pruned_l1_batch as u32),
+                last_pruned_miniblock: MiniblockNumber(row.last_pruned_miniblock as u32),
+            })
+        });
+        Ok(ranges.collect())
+    }
+
+    /// Returns the pruned range for the specified data class, or `None` if it was never pruned.
+    pub async fn get_pruned_range(
+        &mut self,
+        data: PrunedData,
+    ) -> sqlx::Result<Option<PrunedRange>> {
+        let row = sqlx::query!(
+            "SELECT last_pruned_l1_batch, last_pruned_miniblock FROM pruning_log \
+            WHERE pruned_data = $1",
+            data.as_str()
+        )
+        .instrument("get_pruned_range")
+        .with_arg("data", &data)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| PrunedRange {
+            data,
+            last_pruned_l1_batch: L1BatchNumber(row.last_pruned_l1_batch as u32),
+            last_pruned_miniblock: MiniblockNumber(row.last_pruned_miniblock as u32),
+        }))
+    }
+
+    /// Returns the last L1 batch for which the specified data class was pruned.
+    pub async fn get_last_pruned_l1_batch(
+        &mut self,
+        data: PrunedData,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            "SELECT last_pruned_l1_batch FROM pruning_log WHERE pruned_data = $1",
+            data.as_str()
+        )
+        .instrument("get_last_pruned_l1_batch")
+        .with_arg("data", &data)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| L1BatchNumber(row.last_pruned_l1_batch as u32)))
+    }
+
+    /// Persists the pruning cursor for the specified data class.
+    pub async fn set_pruned(
+        &mut self,
+        data: PrunedData,
+        last_pruned_l1_batch: L1BatchNumber,
+        last_pruned_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "INSERT INTO pruning_log \
+                (pruned_data, last_pruned_l1_batch, last_pruned_miniblock, created_at, updated_at) \
+            VALUES ($1, $2, $3, NOW(), NOW()) \
+            ON CONFLICT (pruned_data) DO UPDATE \
+            SET last_pruned_l1_batch = $2, last_pruned_miniblock = $3, updated_at = NOW()",
+            data.as_str(),
+            last_pruned_l1_batch.0 as i64,
+            last_pruned_miniblock.0 as i64
+        )
+        .instrument("set_pruned")
+        .with_arg("data", &data)
+        .with_arg("last_pruned_l1_batch", &last_pruned_l1_batch)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes at most `limit` storage logs in the specified miniblocks that are overwritten
+    /// by a later log for the same slot no later than the end of the range. Thus, the latest value
+    /// of each slot is always retained, and so is the state for all miniblocks after the range.
+    /// The caller should repeat the call until it returns less than `limit` removed rows.
+    pub async fn prune_storage_logs(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        limit: u32,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            "DELETE FROM storage_logs \
+            WHERE ctid IN ( \
+                SELECT ctid FROM storage_logs \
+                WHERE miniblock_number BETWEEN $1 AND $2 \
+                    AND EXISTS ( \
+                        SELECT 1 FROM storage_logs AS newer_logs \
+                        WHERE newer_logs.hashed_key = storage_logs.hashed_key \
+                            AND newer_logs.miniblock_number <= $2 \
+                            AND (newer_logs.miniblock_number, newer_logs.operation_number) \
+                                > (storage_logs.miniblock_number, storage_logs.operation_number) \
+                    ) \
+                LIMIT $3 \
+            )",
+            miniblocks.start().0 as i64,
+            miniblocks.end().0 as i64,
+            i64::from(limit)
+        )
+        .instrument("prune_storage_logs")
+        .with_arg("miniblocks", &miniblocks)
+        .with_arg("limit", &limit)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Removes events and L2-to-L1 logs emitted in the specified miniblocks.
+    pub async fn prune_events(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let events = sqlx::query!(
+            "DELETE FROM events WHERE miniblock_number BETWEEN $1 AND $2",
+            miniblocks.start().0 as i64,
+            miniblocks.end().0 as i64
+        )
+        .instrument("prune_events")
+        .with_arg("miniblocks", &miniblocks)
+        .execute(self.storage)
+        .await?;
+
+        let l2_to_l1_logs = sqlx::query!(
+            "DELETE FROM l2_to_l1_logs WHERE miniblock_number BETWEEN $1 AND $2",
+            miniblocks.start().0 as i64,
+            miniblocks.end().0 as i64
+        )
+        .instrument("prune_l2_to_l1_logs")
+        .with_arg("miniblocks", &miniblocks)
+        .execute(self.storage)
+        .await?;
+        Ok(events.rows_affected() + l2_to_l1_logs.rows_affected())
+    }
+
+    /// Removes L2 transactions included in the specified miniblocks together with their call traces.
+    /// Priority and protocol upgrade transactions are retained since they are referenced
+    /// by the L1 watcher and the protocol version logic.
+    pub async fn prune_transactions(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            "DELETE FROM transactions \
+            WHERE miniblock_number BETWEEN $1 AND $2 \
+                AND is_priority = FALSE \
+                AND hash NOT IN ( \
+                    SELECT upgrade_tx_hash FROM protocol_versions WHERE upgrade_tx_hash IS NOT NULL \
+                )",
+            miniblocks.start().0 as i64,
+            miniblocks.end().0 as i64
+        )
+        .instrument("prune_transactions")
+        .with_arg("miniblocks", &miniblocks)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Removes successfully completed FRI prover jobs and witness inputs for the specified L1 batches.
+    pub async fn prune_prover_jobs(
+        &mut self,
+        l1_batches: ops::RangeInclusive<L1BatchNumber>,
+    ) -> sqlx::Result<u64> {
+        let prover_jobs = sqlx::query!(
+            "DELETE FROM prover_jobs_fri \
+            WHERE l1_batch_number BETWEEN $1 AND $2 AND status = 'successful'",
+            l1_batches.start().0 as i64,
+            l1_batches.end().0 as i64
+        )
+        .instrument("prune_prover_jobs_fri")
+        .with_arg("l1_batches", &l1_batches)
+        .execute(self.storage)
+        .await?;
+
+        let witness_inputs = sqlx::query!(
+            "DELETE FROM witness_inputs_fri \
+            WHERE l1_batch_number BETWEEN $1 AND $2 AND status = 'successful'",
+            l1_batches.start().0 as i64,
+            l1_batches.end().0 as i64
+        )
+        .instrument("prune_witness_inputs_fri")
+        .with_arg("l1_batches", &l1_batches)
+        .execute(self.storage)
+        .await?;
+        Ok(prover_jobs.rows_affected() + witness_inputs.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use zksync_types::{
+        l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
+        proofs::AggregationRound,
+        protocol_version::{FriProtocolVersionId, L1VerifierConfig},
+        tx::IncludedTxLocation,
+        AccountTreeId, Address, ProtocolVersion, StorageKey, StorageLog, VmEvent, H256, U256,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    async fn prepare_miniblocks(storage: &mut StorageProcessor<'_>, count: u32) {
+        storage
+            .blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await
+            .unwrap();
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=count {
+            storage
+                .blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn count_rows(storage: &mut StorageProcessor<'_>, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(storage.conn())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn persisting_pruned_ranges() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let mut pruning_dal = storage.pruning_dal();
+        assert!(pruning_dal.get_pruned_ranges().await.unwrap().is_empty());
+        assert_eq!(
+            pruning_dal
+                .get_pruned_range(PrunedData::Events)
+                .await
+                .unwrap(),
+            None
+        );
+
+        for (l1_batch, miniblock) in [(1, 3), (5, 10)] {
+            pruning_dal
+                .set_pruned(
+                    PrunedData::Events,
+                    L1BatchNumber(l1_batch),
+                    MiniblockNumber(miniblock),
+                )
+                .await
+                .unwrap();
+        }
+        let range = pruning_dal
+            .get_pruned_range(PrunedData::Events)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(range.last_pruned_l1_batch, L1BatchNumber(5));
+        assert_eq!(range.first_available_miniblock(), MiniblockNumber(11));
+        assert_eq!(pruning_dal.get_pruned_ranges().await.unwrap(), [range]);
+        assert_eq!(
+            pruning_dal
+                .get_last_pruned_l1_batch(PrunedData::StorageLogs)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn pruning_storage_logs_retains_latest_values() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        prepare_miniblocks(&mut storage, 3).await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let keys: Vec<_> = (0..3)
+            .map(|i| StorageKey::new(account, H256::from_low_u64_be(i)))
+            .collect();
+        // Key #0 is written in all miniblocks, key #1 only in the first one,
+        // and key #2 in the first two.
+        let logs_by_miniblock = [
+            vec![(0, 1), (1, 1), (2, 1)],
+            vec![(0, 2), (2, 2)],
+            vec![(0, 3)],
+        ];
+        for (number, logs) in (1..).zip(logs_by_miniblock) {
+            let logs = logs
+                .into_iter()
+                .map(|(key_idx, value)| {
+                    StorageLog::new_write_log(keys[key_idx], H256::repeat_byte(value))
+                })
+                .collect();
+            storage
+                .storage_logs_dal()
+                .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+                .await;
+        }
+
+        let miniblocks = MiniblockNumber(1)..=MiniblockNumber(2);
+        let mut pruning_dal = storage.pruning_dal();
+        // Logs #0 and #2 in the first miniblock are overwritten within the range; removing them
+        // requires 2 queries with `limit == 1`.
+        for expected_removed in [1, 1, 0] {
+            let removed = pruning_dal
+                .prune_storage_logs(miniblocks.clone(), 1)
+                .await
+                .unwrap();
+            assert_eq!(removed, expected_removed);
+        }
+        assert_eq!(count_rows(&mut storage, "storage_logs").await, 4);
+
+        let expected_values = [
+            (MiniblockNumber(2), [2, 1, 2]),
+            (MiniblockNumber(3), [3, 1, 2]),
+        ];
+        for (miniblock, values) in expected_values {
+            for (key, value) in keys.iter().zip(values) {
+                let actual_value = storage
+                    .storage_web3_dal()
+                    .get_historical_value_unchecked(key, miniblock)
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    actual_value,
+                    H256::repeat_byte(value),
+                    "{key:?} at {miniblock}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn pruning_events_and_l2_to_l1_logs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .events_dal()
+            .rollback_events(MiniblockNumber(0))
+            .await;
+        prepare_miniblocks(&mut storage, 2).await;
+
+        for number in 1..=2 {
+            let location = IncludedTxLocation {
+                tx_hash: H256::repeat_byte(number),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::default(),
+            };
+            let event = VmEvent {
+                location: (L1BatchNumber(1), 0),
+                address: Address::repeat_byte(number),
+                indexed_topics: vec![H256::repeat_byte(number)],
+                value: vec![number],
+            };
+            let log = UserL2ToL1Log(L2ToL1Log {
+                sender: Address::repeat_byte(number),
+                ..L2ToL1Log::default()
+            });
+            let miniblock_number = MiniblockNumber(number.into());
+            storage
+                .events_dal()
+                .save_events(miniblock_number, &[(location, vec![&event])])
+                .await;
+            storage
+                .events_dal()
+                .save_user_l2_to_l1_logs(miniblock_number, &[(location, vec![&log])])
+                .await;
+        }
+
+        let removed = storage
+            .pruning_dal()
+            .prune_events(MiniblockNumber(1)..=MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(removed, 2);
+        let logs = storage
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].address, Address::repeat_byte(2));
+        assert_eq!(count_rows(&mut storage, "l2_to_l1_logs").await, 1);
+    }
+
+    #[tokio::test]
+    async fn pruning_transactions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        prepare_miniblocks(&mut storage, 2).await;
+
+        let mut tx_hashes = vec![];
+        for number in 1..=2 {
+            let tx = mock_l2_transaction();
+            tx_hashes.push(tx.hash());
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(tx.clone(), Default::default())
+                .await;
+            storage
+                .transactions_dal()
+                .mark_txs_as_executed_in_miniblock(
+                    MiniblockNumber(number),
+                    &[mock_execution_result(tx)],
+                    U256::from(1),
+                )
+                .await;
+        }
+
+        let removed = storage
+            .pruning_dal()
+            .prune_transactions(MiniblockNumber(1)..=MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        let mut web3_dal = storage.transactions_web3_dal();
+        let pruned_receipt = web3_dal
+            .get_transaction_receipt(tx_hashes[0])
+            .await
+            .unwrap();
+        assert!(pruned_receipt.is_none());
+        let retained_receipt = web3_dal
+            .get_transaction_receipt(tx_hashes[1])
+            .await
+            .unwrap();
+        assert!(retained_receipt.is_some());
+    }
+
+    #[tokio::test]
+    async fn pruning_prover_jobs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let protocol_version = FriProtocolVersionId::latest();
+        storage
+            .fri_protocol_versions_dal()
+            .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+            .await;
+        for number in 1..=2 {
+            let l1_batch_number = L1BatchNumber(number);
+            storage
+                .fri_prover_jobs_dal()
+                .insert_prover_jobs(
+                    l1_batch_number,
+                    vec![(1, "1.bin".to_owned())],
+                    AggregationRound::BasicCircuits,
+                    0,
+                    protocol_version,
+                )
+                .await;
+            storage
+                .fri_witness_generator_dal()
+                .save_witness_inputs(l1_batch_number, "witness.bin", protocol_version)
+                .await;
+        }
+        // Only successful jobs for the first L1 batch are pruned.
+        let job = storage
+            .fri_prover_jobs_dal()
+            .get_next_job(&[protocol_version], "test")
+            .await
+            .unwrap();
+        assert_eq!(job.block_number, L1BatchNumber(1));
+        storage
+            .fri_prover_jobs_dal()
+            .save_proof(job.id, Duration::from_secs(1), "proof.bin")
+            .await;
+        storage
+            .fri_witness_generator_dal()
+            .mark_witness_job_as_successful(L1BatchNumber(1), Duration::from_secs(1))
+            .await;
+
+        let removed = storage
+            .pruning_dal()
+            .prune_prover_jobs(L1BatchNumber(1)..=L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(removed, 2);
+        assert_eq!(count_rows(&mut storage, "prover_jobs_fri").await, 1);
+        assert_eq!(count_rows(&mut storage, "witness_inputs_fri").await, 1);
+    }
+}
@@ -26,8 +26,7 @@ impl SnapshotsCreatorDal<'_, '_> {
             l1_batch_number.0 as i32
         )
         .instrument("get_storage_logs_count")
-        .report_latency()
-        .fetch_one(self.storage.conn())
+        .fetch_one(self.storage)
         .await?
         .index;
         Ok(count as u64)
@@ -65,8 +64,7 @@ impl SnapshotsCreatorDal<'_, '_> {
         .with_arg("miniblock_number", &miniblock_number)
         .with_arg("min_hashed_key", &hashed_keys_range.start())
         .with_arg("max_hashed_key", &hashed_keys_range.end())
-        .report_latency()
-        .fetch_all(self.storage.conn())
+        .fetch_all(self.storage)
         .await?
         .iter()
         .map(|row| SnapshotStorageLog {
@@ -91,8 +89,7 @@ impl SnapshotsCreatorDal<'_, '_> {
             miniblock_number.0 as i64,
         )
         .instrument("get_all_factory_deps")
-        .report_latency()
-        .fetch_all(self.storage.conn())
+        .fetch_all(self.storage)
         .await?;
         Ok(rows
             .into_iter()
@@ -25,8 +25,7 @@ impl SnapshotsDal<'_, '_> {
             factory_deps_filepaths,
         )
         .instrument("add_snapshot")
-        .report_latency()
-        .execute(self.storage.conn())
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -36,8 +35,7 @@ impl SnapshotsDal<'_, '_> {
             "SELECT l1_batch_number, factory_deps_filepath, storage_logs_filepaths FROM snapshots"
         )
         .instrument("get_all_snapshots")
-        .report_latency()
-        .fetch_all(self.storage.conn())
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|r| L1BatchNumber(r.l1_batch_number as u32))
@@ -56,8 +54,7 @@ impl SnapshotsDal<'_, '_> {
             l1_batch_number.0 as i32
         )
         .instrument("get_snapshot_metadata")
-        .report_latency()
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?
         .map(|r| SnapshotMetadata {
             l1_batch_number: L1BatchNumber(r.l1_batch_number as u32),
@@ -1,4 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    ops,
+};
 
 use itertools::Itertools;
 use zksync_contracts::{BaseSystemContracts, SystemContractCode};
@@ -107,6 +110,25 @@ impl StorageDal<'_, '_> {
         .collect()
     }
 
+    /// Returns factory deps (keyed by the bytecode hash) added in the specified miniblock range (inclusive).
+    pub async fn get_factory_deps_for_miniblocks(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> HashMap<H256, Vec<u8>> {
+        sqlx::query!(
+            "SELECT bytecode_hash, bytecode FROM factory_deps \
+            WHERE miniblock_number BETWEEN $1 AND $2",
+            miniblocks.start().0 as i64,
+            miniblocks.end().0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
+        .collect()
+    }
+
     /// Returns bytecode hashes for factory deps from miniblocks with number strictly greater
     /// than `block_number`.
     pub async fn get_factory_deps_for_revert(
@@ -187,9 +209,8 @@ impl StorageDal<'_, '_> {
             hashed_key.as_bytes()
         )
         .instrument("get_by_key")
-        .report_latency()
         .with_arg("key", &hashed_key)
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| H256::from_slice(&row.value))
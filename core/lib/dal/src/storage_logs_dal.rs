@@ -1,4 +1,4 @@
-use std::{collections::HashMap, time::Instant};
+use std::{collections::HashMap, ops, time::Instant};
 
 use sqlx::{types::chrono::Utc, Row};
 use zksync_types::{
@@ -6,7 +6,7 @@ use zksync_types::{
     FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256,
 };
 
-use crate::{instrument::InstrumentExt, StorageProcessor};
+use crate::{instrument::InstrumentExt, metrics::MethodLatency, StorageProcessor};
 
 #[derive(Debug)]
 pub struct StorageLogsDal<'a, 'c> {
@@ -30,9 +30,10 @@ impl StorageLogsDal<'_, '_> {
         logs: &[(H256, Vec<StorageLog>)],
         mut operation_number: u32,
     ) {
+        let latency = MethodLatency::new("insert_storage_logs");
         let mut copy = self
             .storage
-            .conn()
+            .raw_conn()
             .copy_in_raw(
                 "COPY storage_logs(
                     hashed_key, address, key, value, operation_number, tx_hash, miniblock_number,
@@ -65,6 +66,7 @@ impl StorageLogsDal<'_, '_> {
         }
         copy.send(buffer.as_bytes()).await.unwrap();
         copy.finish().await.unwrap();
+        drop(latency);
     }
 
     pub async fn append_storage_logs(
@@ -238,6 +240,34 @@ impl StorageLogsDal<'_, '_> {
         touched_slots.collect()
     }
 
+    /// Returns latest values for all [`StorageKey`]s written to in the specified miniblock range
+    /// (inclusive) judging by storage logs.
+    pub async fn get_touched_slots_for_miniblocks(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> HashMap<StorageKey, H256> {
+        let rows = sqlx::query!(
+            "SELECT address, key, value \
+            FROM storage_logs \
+            WHERE miniblock_number BETWEEN $1 AND $2 \
+            ORDER BY miniblock_number, operation_number",
+            miniblocks.start().0 as i64,
+            miniblocks.end().0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        let touched_slots = rows.into_iter().map(|row| {
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            (key, H256::from_slice(&row.value))
+        });
+        touched_slots.collect()
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -338,8 +368,7 @@ impl StorageLogsDal<'_, '_> {
             &hashed_keys as &[&[u8]],
         )
         .instrument("get_l1_batches_and_indices_for_initial_writes")
-        .report_latency()
-        .fetch_all(self.storage.conn())
+        .fetch_all(self.storage)
         .await
         .unwrap();
 
@@ -4,7 +4,7 @@ use sqlx::types::chrono::Utc;
 use zksync_types::{AccountTreeId, Address, L1BatchNumber, LogQuery, StorageKey, H256};
 use zksync_utils::u256_to_h256;
 
-use crate::StorageProcessor;
+use crate::{metrics::MethodLatency, StorageProcessor};
 
 #[derive(Debug)]
 pub struct StorageLogsDedupDal<'a, 'c> {
@@ -17,9 +17,10 @@ impl StorageLogsDedupDal<'_, '_> {
         l1_batch_number: L1BatchNumber,
         read_logs: &[LogQuery],
     ) {
+        let latency = MethodLatency::new("insert_protective_reads");
         let mut copy = self
             .storage
-            .conn()
+            .raw_conn()
             .copy_in_raw(
                 "COPY protective_reads (l1_batch_number, address, key, created_at, updated_at) \
                 FROM STDIN WITH (DELIMITER '|')",
@@ -40,6 +41,7 @@ impl StorageLogsDedupDal<'_, '_> {
         }
         copy.send(bytes).await.unwrap();
         copy.finish().await.unwrap();
+        drop(latency);
     }
 
     /// Insert initial writes and assigns indices to them.
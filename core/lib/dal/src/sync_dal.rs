@@ -43,7 +43,7 @@ impl SyncDal<'_, '_> {
         )
         .instrument("sync_dal_sync_block.block")
         .with_arg("block_number", &block_number)
-        .fetch_optional(self.storage.conn())
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(storage_block_details) = storage_block_details else {
@@ -57,7 +57,7 @@ impl SyncDal<'_, '_> {
             )
             .instrument("sync_dal_sync_block.transactions")
             .with_arg("block_number", &block_number)
-            .fetch_all(self.storage.conn())
+            .fetch_all(self.storage)
             .await?;
 
             Some(transactions.into_iter().map(Transaction::from).collect())
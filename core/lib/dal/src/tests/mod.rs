@@ -1,25 +1,36 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
+use chrono::Utc;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
-    block::{L1BatchHeader, MiniblockHasher, MiniblockHeader},
+    basic_fri_types::CircuitIdRoundTuple,
+    block::{BlockGasCount, L1BatchHeader, MiniblockHasher, MiniblockHeader},
+    contract_verification_api::{
+        CompilationArtifacts, CompilerVersions, MatchLevel, SourceCodeData,
+        VerificationIncomingRequest, VerificationInfo, VerificationRequest,
+    },
     fee::{Fee, TransactionExecutionMetrics},
+    get_code_key,
     helpers::unix_timestamp_ms,
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
     l2::L2Tx,
-    proofs::AggregationRound,
+    proofs::{AggregationRound, FriProverJobPriority},
+    protocol_version::{FriProtocolVersionId, L1VerifierConfig},
     tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
     Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
-    PriorityOpId, ProtocolVersion, ProtocolVersionId, H160, H256, MAX_GAS_PER_PUBDATA_BYTE, U256,
+    PriorityOpId, ProtocolVersion, ProtocolVersionId, StorageLog, H160, H256,
+    MAX_GAS_PER_PUBDATA_BYTE, U256,
 };
 
 use crate::{
     blocks_dal::BlocksDal,
     connection::ConnectionPool,
+    proof_generation_dal::{ProofGenerationAssignment, ProofGenerationAssignmentStatus},
     protocol_versions_dal::ProtocolVersionsDal,
     prover_dal::{GetProverJobsParams, ProverDal},
     transactions_dal::{L2TxSubmissionResult, TransactionsDal},
     transactions_web3_dal::TransactionsWeb3Dal,
+    StorageProcessor,
 };
 
 const DEFAULT_GAS_PER_PUBDATA: u32 = 100;
@@ -397,3 +408,336 @@ async fn test_requeue_prover_jobs() {
         assert!(job.is_some());
     }
 }
+
+#[tokio::test]
+async fn fri_prover_jobs_are_picked_by_priority() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let protocol_version = FriProtocolVersionId::latest();
+    storage
+        .fri_protocol_versions_dal()
+        .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+        .await;
+
+    let circuits = vec![(1, "1.bin".to_owned()), (2, "2.bin".to_owned())];
+    for l1_batch_number in 1..=3 {
+        storage
+            .fri_prover_jobs_dal()
+            .insert_prover_jobs(
+                L1BatchNumber(l1_batch_number),
+                circuits.clone(),
+                AggregationRound::BasicCircuits,
+                0,
+                protocol_version,
+            )
+            .await;
+    }
+    let updated_jobs = storage
+        .fri_prover_jobs_dal()
+        .set_batch_priority(L1BatchNumber(3), FriProverJobPriority::Urgent)
+        .await
+        .unwrap();
+    assert_eq!(updated_jobs, 2);
+    storage
+        .fri_prover_jobs_dal()
+        .set_batch_priority(L1BatchNumber(1), FriProverJobPriority::Deprioritized)
+        .await
+        .unwrap();
+    // Jobs created after the priority is set must inherit it.
+    storage
+        .fri_prover_jobs_dal()
+        .insert_prover_jobs(
+            L1BatchNumber(3),
+            vec![(1, "leaf.bin".to_owned())],
+            AggregationRound::LeafAggregation,
+            0,
+            protocol_version,
+        )
+        .await;
+
+    let priorities = storage
+        .fri_prover_jobs_dal()
+        .get_batch_priorities()
+        .await
+        .unwrap();
+    assert_eq!(priorities.len(), 2);
+    let queued_by_priority = storage
+        .fri_prover_jobs_dal()
+        .get_queued_jobs_count_by_priority()
+        .await;
+    assert_eq!(queued_by_priority[&FriProverJobPriority::Urgent], 3);
+    assert_eq!(queued_by_priority[&FriProverJobPriority::Normal], 2);
+    assert_eq!(queued_by_priority[&FriProverJobPriority::Deprioritized], 2);
+
+    let mut picked_batches = vec![];
+    while let Some(job) = storage
+        .fri_prover_jobs_dal()
+        .get_next_job(&[protocol_version], "test")
+        .await
+    {
+        picked_batches.push(job.block_number.0);
+    }
+    assert_eq!(picked_batches, [3, 3, 3, 2, 2, 1, 1]);
+}
+
+#[tokio::test]
+async fn fri_prover_jobs_are_distributed_fairly_among_circuits() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let protocol_version = FriProtocolVersionId::latest();
+    storage
+        .fri_protocol_versions_dal()
+        .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+        .await;
+
+    let circuits = vec![
+        (1, "1_0.bin".to_owned()),
+        (1, "1_1.bin".to_owned()),
+        (1, "1_2.bin".to_owned()),
+        (2, "2_0.bin".to_owned()),
+    ];
+    storage
+        .fri_prover_jobs_dal()
+        .insert_prover_jobs(
+            L1BatchNumber(1),
+            circuits,
+            AggregationRound::BasicCircuits,
+            0,
+            protocol_version,
+        )
+        .await;
+
+    let circuits_to_pick = [
+        CircuitIdRoundTuple::new(1, 0),
+        CircuitIdRoundTuple::new(2, 0),
+    ];
+    let mut picked_circuits = vec![];
+    for _ in 0..2 {
+        let job = storage
+            .fri_prover_jobs_dal()
+            .get_next_job_for_circuit_id_round(&circuits_to_pick, &[protocol_version], "test")
+            .await
+            .unwrap();
+        picked_circuits.push(job.circuit_id);
+    }
+    // After a circuit 1 job is picked, circuit 2 has a lower share of in-progress jobs
+    // relative to its backlog (0 / 1 vs 1 / 2).
+    assert_eq!(picked_circuits, [1, 2]);
+}
+
+async fn insert_l1_batch_for_proving(storage: &mut StorageProcessor<'_>, number: L1BatchNumber) {
+    let header = L1BatchHeader::new(
+        number,
+        100,
+        Address::default(),
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+        .await
+        .unwrap();
+    storage
+        .proof_generation_dal()
+        .insert_proof_generation_details(number, "data.bin")
+        .await;
+}
+
+async fn assign_batch(
+    storage: &mut StorageProcessor<'_>,
+    prover_cluster: &str,
+) -> Option<L1BatchNumber> {
+    storage
+        .proof_generation_dal()
+        .get_next_block_to_be_proven(prover_cluster, Duration::from_secs(3_600), 2)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn proof_generation_batches_are_assigned_to_prover_clusters() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    for number in 1..=2 {
+        insert_l1_batch_for_proving(storage, L1BatchNumber(number)).await;
+    }
+
+    // Batch #1 is proven redundantly by 2 clusters; the third cluster gets batch #2.
+    assert_eq!(assign_batch(storage, "a").await, Some(L1BatchNumber(1)));
+    assert_eq!(assign_batch(storage, "b").await, Some(L1BatchNumber(1)));
+    assert_eq!(assign_batch(storage, "c").await, Some(L1BatchNumber(2)));
+    // A cluster cannot be assigned the same batch twice while its lease is active.
+    assert_eq!(assign_batch(storage, "a").await, Some(L1BatchNumber(2)));
+    assert_eq!(assign_batch(storage, "a").await, None);
+
+    let assignment = storage
+        .proof_generation_dal()
+        .get_assignment(L1BatchNumber(1), "b")
+        .await
+        .unwrap();
+    assert_eq!(
+        assignment,
+        Some(ProofGenerationAssignment {
+            status: ProofGenerationAssignmentStatus::Assigned,
+            is_expired: false,
+        })
+    );
+    let assignment = storage
+        .proof_generation_dal()
+        .get_assignment(L1BatchNumber(1), "c")
+        .await
+        .unwrap();
+    assert_eq!(assignment, None);
+
+    storage
+        .proof_generation_dal()
+        .update_assignment_status(
+            L1BatchNumber(1),
+            "b",
+            ProofGenerationAssignmentStatus::Rejected,
+            Some("invalid proof"),
+        )
+        .await
+        .unwrap();
+    // The rejected assignment frees a slot for batch #1.
+    assert_eq!(assign_batch(storage, "c").await, Some(L1BatchNumber(1)));
+    let is_saved = storage
+        .proof_generation_dal()
+        .save_proof_artifacts_metadata(L1BatchNumber(1), "proof.bin")
+        .await
+        .unwrap();
+    assert!(is_saved);
+    // The stored proof is not overwritten by proofs from other clusters.
+    let is_saved = storage
+        .proof_generation_dal()
+        .save_proof_artifacts_metadata(L1BatchNumber(1), "other_proof.bin")
+        .await
+        .unwrap();
+    assert!(!is_saved);
+    let is_generated = storage
+        .proof_generation_dal()
+        .is_proof_generated(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert!(is_generated);
+    storage
+        .proof_generation_dal()
+        .update_assignment_status(
+            L1BatchNumber(1),
+            "c",
+            ProofGenerationAssignmentStatus::Submitted,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(assign_batch(storage, "b").await, None);
+
+    // Assignments with an elapsed lease are reported as expired.
+    insert_l1_batch_for_proving(storage, L1BatchNumber(3)).await;
+    let l1_batch_number = storage
+        .proof_generation_dal()
+        .get_next_block_to_be_proven("d", Duration::ZERO, 2)
+        .await
+        .unwrap();
+    assert_eq!(l1_batch_number, Some(L1BatchNumber(3)));
+    let assignment = storage
+        .proof_generation_dal()
+        .get_assignment(L1BatchNumber(3), "d")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(assignment.is_expired);
+}
+
+fn mock_verification_info(contract_address: Address) -> VerificationInfo {
+    VerificationInfo {
+        request: VerificationRequest {
+            id: 1,
+            req: VerificationIncomingRequest {
+                contract_address,
+                source_code_data: SourceCodeData::SolSingleFile("contract Test {}".to_owned()),
+                contract_name: "Test".to_owned(),
+                compiler_versions: CompilerVersions::Solc {
+                    compiler_zksolc_version: "v1.3.14".to_owned(),
+                    compiler_solc_version: "0.8.17".to_owned(),
+                },
+                optimization_used: true,
+                optimizer_mode: None,
+                constructor_arguments: Default::default(),
+                is_system: false,
+            },
+        },
+        artifacts: CompilationArtifacts {
+            bytecode: vec![0; 32],
+            abi: serde_json::json!([]),
+        },
+        verified_at: Utc::now(),
+        match_level: MatchLevel::Full,
+    }
+}
+
+#[tokio::test]
+async fn verification_info_is_shared_by_contracts_with_same_bytecode() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock_header(0))
+        .await
+        .unwrap();
+    let bytecode_hash = H256::repeat_byte(1);
+    let factory_deps = HashMap::from([(bytecode_hash, vec![0; 32])]);
+    storage
+        .storage_dal()
+        .insert_factory_deps(MiniblockNumber(0), &factory_deps)
+        .await;
+
+    let verified_address = Address::repeat_byte(1);
+    let clone_address = Address::repeat_byte(2);
+    // Code hashes not present in factory deps (e.g., of contracts being constructed)
+    // must not be matched.
+    let unknown_address = Address::repeat_byte(3);
+    let unknown_clone_address = Address::repeat_byte(4);
+    let logs = vec![
+        StorageLog::new_write_log(get_code_key(&verified_address), bytecode_hash),
+        StorageLog::new_write_log(get_code_key(&clone_address), bytecode_hash),
+        StorageLog::new_write_log(get_code_key(&unknown_address), H256::repeat_byte(2)),
+        StorageLog::new_write_log(get_code_key(&unknown_clone_address), H256::repeat_byte(2)),
+    ];
+    storage
+        .storage_dal()
+        .apply_storage_logs(&[(H256::zero(), logs)])
+        .await;
+
+    for address in [verified_address, unknown_address] {
+        storage
+            .contract_verification_dal()
+            .save_verification_info(mock_verification_info(address))
+            .await
+            .unwrap();
+    }
+
+    let info = storage
+        .contract_verification_dal()
+        .get_contract_verification_info_by_bytecode(clone_address)
+        .await
+        .unwrap()
+        .expect("no verification info for clone");
+    assert_eq!(info.request.req.contract_address, verified_address);
+
+    let info = storage
+        .contract_verification_dal()
+        .get_contract_verification_info_by_bytecode(unknown_clone_address)
+        .await
+        .unwrap();
+    assert!(info.is_none(), "{info:?}");
+}
@@ -1,6 +1,9 @@
 use std::time::Duration;
 
-use sqlx::{postgres::types::PgInterval, types::chrono::NaiveTime};
+use sqlx::{
+    postgres::types::PgInterval,
+    types::chrono::{NaiveTime, Timelike},
+};
 
 pub fn duration_to_naive_time(duration: Duration) -> NaiveTime {
     let total_seconds = duration.as_secs() as u32;
@@ -12,6 +15,10 @@ pub fn duration_to_naive_time(duration: Duration) -> NaiveTime {
     .unwrap()
 }
 
+pub fn naive_time_to_millis(time: NaiveTime) -> u64 {
+    u64::from(time.num_seconds_from_midnight()) * 1_000 + u64::from(time.nanosecond() / 1_000_000)
+}
+
 pub const fn pg_interval_from_duration(processing_timeout: Duration) -> PgInterval {
     PgInterval {
         months: 0,
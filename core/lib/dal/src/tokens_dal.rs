@@ -7,7 +7,7 @@ use zksync_types::{
 };
 use zksync_utils::ratio_to_big_decimal;
 
-use crate::StorageProcessor;
+use crate::{metrics::MethodLatency, StorageProcessor};
 
 // Precision of the USD price per token
 pub(crate) const STORED_USD_PRICE_PRECISION: usize = 6;
@@ -19,10 +19,11 @@ pub struct TokensDal<'a, 'c> {
 
 impl TokensDal<'_, '_> {
     pub async fn add_tokens(&mut self, tokens: Vec<TokenInfo>) {
+        let latency = MethodLatency::new("add_tokens");
         {
             let mut copy = self
             .storage
-            .conn()
+            .raw_conn()
             .copy_in_raw(
                 "COPY tokens (l1_address, l2_address, name, symbol, decimals, well_known, created_at, updated_at)
                 FROM STDIN WITH (DELIMITER '|')",
@@ -53,6 +54,7 @@ impl TokensDal<'_, '_> {
             }
             copy.send(bytes).await.unwrap();
             copy.finish().await.unwrap();
+            drop(latency);
         }
     }
 
@@ -720,8 +720,7 @@ impl TransactionsDal<'_, '_> {
                     &bytea_call_traces
                 )
                 .instrument("insert_call_tracer")
-                .report_latency()
-                .execute(transaction.conn())
+                .execute(&mut transaction)
                 .await
                 .unwrap();
             }
@@ -1078,6 +1077,29 @@ impl TransactionsDal<'_, '_> {
             .collect())
     }
 
+    /// Returns hashes and refunded gas for all transactions in the specified L1 batch
+    /// in the order of their execution.
+    pub async fn get_refunded_gas_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<(H256, u64)>> {
+        let rows = sqlx::query!(
+            "SELECT hash, refunded_gas FROM transactions \
+            WHERE l1_batch_number = $1 \
+            ORDER BY miniblock_number, index_in_block",
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_refunded_gas_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.hash), row.refunded_gas as u64))
+            .collect())
+    }
+
     pub async fn get_tx_locations(&mut self, l1_batch_number: L1BatchNumber) -> TxLocations {
         {
             sqlx::query!(
@@ -66,7 +66,7 @@ impl TransactionsWeb3Dal<'_, '_> {
             )
             .instrument("get_transaction_receipt")
             .with_arg("hash", &hash)
-            .fetch_optional(self.storage.conn())
+            .fetch_optional(self.storage)
             .await?
             .map(|db_row| {
                 let status = match (db_row.block_number, db_row.error) {
@@ -141,7 +141,7 @@ impl TransactionsWeb3Dal<'_, '_> {
                     )
                     .instrument("get_transaction_receipt_events")
                     .with_arg("hash", &hash)
-                    .fetch_all(self.storage.conn())
+                    .fetch_all(self.storage)
                     .await?
                     .into_iter()
                     .map(|storage_log| {
@@ -247,7 +247,7 @@ impl TransactionsWeb3Dal<'_, '_> {
             )
             .instrument("get_transaction_details")
             .with_arg("hash", &hash)
-            .fetch_optional(self.storage.conn())
+            .fetch_optional(self.storage)
             .await?;
 
             let tx = storage_tx_details.map(|tx_details| tx_details.into());
@@ -88,6 +88,7 @@ mod tests {
                 max_response_body_size_mb: Some(10),
                 websocket_requests_per_minute_limit: Some(10),
                 tree_api_url: None,
+                warm_vm_state_db_path: Some("./db/main/warm_vm_state".into()),
             },
             contract_verification: ContractVerificationApiConfig {
                 port: 3070,
@@ -99,7 +100,11 @@ mod tests {
                 pushgateway_url: "http://127.0.0.1:9091".into(),
                 push_interval_ms: Some(100),
             },
-            healthcheck: HealthCheckConfig { port: 8081 },
+            healthcheck: HealthCheckConfig {
+                port: 8081,
+                expose_slow_queries: true,
+                expose_slow_query_args: false,
+            },
             merkle_tree: MerkleTreeApiConfig { port: 8082 },
         }
     }
@@ -134,6 +139,7 @@ mod tests {
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
+            API_WEB3_JSON_RPC_WARM_VM_STATE_DB_PATH="./db/main/warm_vm_state"
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_CONTRACT_VERIFICATION_THREADS_PER_SERVER=128
@@ -142,6 +148,7 @@ mod tests {
             API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             API_PROMETHEUS_PUSH_INTERVAL_MS=100
             API_HEALTHCHECK_PORT=8081
+            API_HEALTHCHECK_EXPOSE_SLOW_QUERIES=true
             API_MERKLE_TREE_PORT=8082
         "#;
         lock.set_env(config);
@@ -0,0 +1,37 @@
+use zksync_config::configs::BasicWitnessInputProducerConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for BasicWitnessInputProducerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load(
+            "basic_witness_input_producer",
+            "BASIC_WITNESS_INPUT_PRODUCER_",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            BASIC_WITNESS_INPUT_PRODUCER_WITNESS_INPUT_CACHE_SIZE_MB="256"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = BasicWitnessInputProducerConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            BasicWitnessInputProducerConfig {
+                witness_input_cache_size_mb: Some(256),
+            }
+        );
+        assert_eq!(actual.witness_input_cache_capacity(), 256 * 1_024 * 1_024);
+    }
+}
@@ -20,6 +20,9 @@ mod tests {
             compilation_timeout: 30,
             polling_interval: Some(1000),
             prometheus_port: 3314,
+            compilation_cpu_limit: Some(60),
+            compilation_memory_limit_mb: Some(4_096),
+            require_compiler_checksums: true,
         }
     }
 
@@ -30,6 +33,9 @@ mod tests {
             CONTRACT_VERIFIER_COMPILATION_TIMEOUT=30
             CONTRACT_VERIFIER_POLLING_INTERVAL=1000
             CONTRACT_VERIFIER_PROMETHEUS_PORT=3314
+            CONTRACT_VERIFIER_COMPILATION_CPU_LIMIT=60
+            CONTRACT_VERIFIER_COMPILATION_MEMORY_LIMIT_MB=4096
+            CONTRACT_VERIFIER_REQUIRE_COMPILER_CHECKSUMS=true
         "#;
         lock.set_env(config);
 
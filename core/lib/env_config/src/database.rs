@@ -20,6 +20,22 @@ impl FromEnv for PostgresConfig {
         let replica_url = env::var("DATABASE_REPLICA_URL")
             .ok()
             .or_else(|| master_url.clone());
+        let read_replica_urls = env::var("DATABASE_READ_REPLICA_URLS")
+            .map(|val| {
+                val.split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let read_replica_fallback_pool_size = env::var("DATABASE_READ_REPLICA_FALLBACK_POOL_SIZE")
+            .ok()
+            .map(|val| {
+                val.parse()
+                    .context("failed to parse DATABASE_READ_REPLICA_FALLBACK_POOL_SIZE")
+            })
+            .transpose()?;
         let prover_url = env::var("DATABASE_PROVER_URL")
             .ok()
             .or_else(|| master_url.clone());
@@ -38,6 +54,8 @@ impl FromEnv for PostgresConfig {
         Ok(Self {
             master_url,
             replica_url,
+            read_replica_urls,
+            read_replica_fallback_pool_size,
             prover_url,
             max_connections,
             statement_timeout_sec,
@@ -66,6 +84,7 @@ mod tests {
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_PRUNING_RETAINED_L1_BATCHES=1000
             DATABASE_BACKUP_COUNT=5
             DATABASE_BACKUP_INTERVAL_MS=60000
         "#;
@@ -80,6 +99,10 @@ mod tests {
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert_eq!(
+            db_config.merkle_tree.pruning_retained_l1_batches,
+            Some(1000)
+        );
         assert_eq!(db_config.backup_count, 5);
         assert_eq!(db_config.backup_interval().as_secs(), 60);
     }
@@ -97,6 +120,7 @@ mod tests {
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_PRUNING_RETAINED_L1_BATCHES",
             "DATABASE_BACKUP_COUNT",
             "DATABASE_BACKUP_INTERVAL_MS",
         ]);
@@ -111,6 +135,7 @@ mod tests {
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
+        assert_eq!(db_config.merkle_tree.pruning_retained_l1_batches, None);
         assert_eq!(db_config.backup_count, 5);
         assert_eq!(db_config.backup_interval().as_secs(), 60);
 
@@ -131,4 +156,34 @@ mod tests {
         let db_config = DBConfig::from_env().unwrap();
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
     }
+
+    #[test]
+    fn postgres_read_replicas_from_env() {
+        let mut lock = MUTEX.lock();
+        lock.set_env(
+            r#"
+            DATABASE_URL="postgres://postgres@localhost/zksync_local"
+            DATABASE_READ_REPLICA_URLS="postgres://postgres@replica-1/zksync_local, postgres://postgres@replica-2/zksync_local"
+            DATABASE_READ_REPLICA_FALLBACK_POOL_SIZE=5
+        "#,
+        );
+
+        let postgres_config = PostgresConfig::from_env().unwrap();
+        assert_eq!(
+            postgres_config.read_replica_urls,
+            [
+                "postgres://postgres@replica-1/zksync_local",
+                "postgres://postgres@replica-2/zksync_local"
+            ]
+        );
+        assert_eq!(postgres_config.read_replica_fallback_pool_size, Some(5));
+
+        lock.remove_env(&[
+            "DATABASE_READ_REPLICA_URLS",
+            "DATABASE_READ_REPLICA_FALLBACK_POOL_SIZE",
+        ]);
+        let postgres_config = PostgresConfig::from_env().unwrap();
+        assert!(postgres_config.read_replica_urls.is_empty());
+        assert_eq!(postgres_config.read_replica_fallback_pool_size, None);
+    }
 }
@@ -30,6 +30,7 @@ mod tests {
             witness_vector_generator_thread_count: Some(5),
             queue_capacity: 10,
             witness_vector_receiver_port: 3316,
+            job_lease_duration_in_secs: Some(60),
             shall_save_to_public_bucket: true,
         }
     }
@@ -49,6 +50,7 @@ mod tests {
             FRI_PROVER_WITNESS_VECTOR_GENERATOR_THREAD_COUNT="5"
             FRI_PROVER_QUEUE_CAPACITY="10"
             FRI_PROVER_WITNESS_VECTOR_RECEIVER_PORT="3316"
+            FRI_PROVER_JOB_LEASE_DURATION_IN_SECS="60"
             FRI_PROVER_SHALL_SAVE_TO_PUBLIC_BUCKET=true
         "#;
         lock.set_env(config);
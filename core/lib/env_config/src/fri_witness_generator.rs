@@ -24,6 +24,7 @@ mod tests {
             last_l1_batch_to_process: None,
             force_process_block: Some(1),
             shall_save_to_public_bucket: true,
+            input_cache_size_mb: Some(512),
         }
     }
 
@@ -37,6 +38,7 @@ mod tests {
             FRI_WITNESS_BLOCKS_PROVING_PERCENTAGE="30"
             FRI_WITNESS_FORCE_PROCESS_BLOCK="1"
             FRI_WITNESS_SHALL_SAVE_TO_PUBLIC_BUCKET=true
+            FRI_WITNESS_INPUT_CACHE_SIZE_MB=512
         "#;
         lock.set_env(config);
 
@@ -3,6 +3,7 @@ use serde::de::DeserializeOwned;
 
 mod alerts;
 mod api;
+mod basic_witness_input_producer;
 mod chain;
 mod circuit_synthesizer;
 mod contract_verifier;
@@ -23,6 +24,7 @@ pub mod object_store;
 mod proof_data_handler;
 mod prover;
 mod prover_group;
+mod pruning;
 mod snapshots_creator;
 mod utils;
 mod witness_generator;
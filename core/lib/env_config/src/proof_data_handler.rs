@@ -23,6 +23,15 @@ mod tests {
             proof_generation_timeout_in_secs: 18000,
             protocol_version_loading_mode: ProtocolVersionLoadingMode::FromEnvVar,
             fri_protocol_version_id: 2,
+            prover_cluster_api_keys: vec![
+                "cluster_a:secret_a".to_owned(),
+                "cluster_b:secret_b".to_owned(),
+            ],
+            client_identity_header: Some("x-client-cert-subject".to_owned()),
+            trust_client_identity_header: true,
+            prover_cluster_identities: vec!["cluster_c:CN=prover-c".to_owned()],
+            max_provers_per_batch: Some(2),
+            snark_wrapper_vk_path: Some("snark_verification_scheduler_key.json".to_owned()),
         }
     }
 
@@ -33,6 +42,12 @@ mod tests {
             PROOF_DATA_HANDLER_HTTP_PORT="3320"
             PROOF_DATA_HANDLER_PROTOCOL_VERSION_LOADING_MODE="FromEnvVar"
             PROOF_DATA_HANDLER_FRI_PROTOCOL_VERSION_ID="2"
+            PROOF_DATA_HANDLER_PROVER_CLUSTER_API_KEYS="cluster_a:secret_a,cluster_b:secret_b"
+            PROOF_DATA_HANDLER_CLIENT_IDENTITY_HEADER="x-client-cert-subject"
+            PROOF_DATA_HANDLER_TRUST_CLIENT_IDENTITY_HEADER="true"
+            PROOF_DATA_HANDLER_PROVER_CLUSTER_IDENTITIES="cluster_c:CN=prover-c"
+            PROOF_DATA_HANDLER_MAX_PROVERS_PER_BATCH="2"
+            PROOF_DATA_HANDLER_SNARK_WRAPPER_VK_PATH="snark_verification_scheduler_key.json"
         "#;
         let mut lock = MUTEX.lock();
         lock.set_env(config);
@@ -0,0 +1,42 @@
+use zksync_config::configs::PruningConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for PruningConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("pruning", "PRUNING_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            PRUNING_POLL_INTERVAL_MS="5000"
+            PRUNING_CHUNK_SIZE="5"
+            PRUNING_STORAGE_LOGS_RETENTION_L1_BATCHES="1000"
+            PRUNING_EVENTS_RETENTION_L1_BATCHES="10000"
+            PRUNING_PROVER_JOBS_RETENTION_L1_BATCHES="100"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = PruningConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            PruningConfig {
+                poll_interval_ms: 5_000,
+                chunk_size: 5,
+                storage_logs_retention_l1_batches: Some(1_000),
+                events_retention_l1_batches: Some(10_000),
+                transactions_retention_l1_batches: None,
+                prover_jobs_retention_l1_batches: Some(100),
+            }
+        );
+    }
+}
@@ -25,6 +25,9 @@ struct Cli {
     /// Perform random recovery instead of linear recovery.
     #[arg(name = "random", long)]
     random: bool,
+    /// Perform parallel recovery instead of linear recovery.
+    #[arg(name = "parallel", long, conflicts_with = "random")]
+    parallel: bool,
     /// Use a no-op hashing function.
     #[arg(name = "no-hash", long)]
     no_hashing: bool,
@@ -111,6 +114,8 @@ impl Cli {
                 .collect();
             if self.random {
                 recovery.extend_random(recovery_entries);
+            } else if self.parallel {
+                recovery.extend_parallel(recovery_entries);
             } else {
                 recovery.extend_linear(recovery_entries);
             }
@@ -1,69 +1,265 @@
-//! Consistency verification for the Merkle tree.
+//! Consistency verification and repair for the Merkle tree.
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    collections::HashSet,
+    ops,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
 
 use rayon::prelude::*;
 
 use crate::{
     errors::DeserializeError,
     hasher::{HashTree, HasherWithStats},
-    types::{LeafNode, Nibbles, Node, NodeKey, Root},
-    Database, Key, MerkleTree, ValueHash,
+    recovery::{key_partition, PARTITION_COUNT},
+    storage::Storage,
+    types::{InternalNode, LeafNode, Nibbles, Node, NodeKey, Root},
+    Database, Key, MerkleTree, PruneDatabase, TreeEntry, ValueHash,
 };
 
+/// Inconsistency in the Merkle tree found by [`MerkleTree::verify_consistency()`]
+/// or [`MerkleTree::check_consistency()`].
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum ConsistencyError {
+    /// Error deserializing tree data from the database.
     #[error("failed deserializing node from DB: {0}")]
     Deserialize(#[from] DeserializeError),
+    /// Checked tree version does not exist.
     #[error("tree version {0} does not exist")]
     MissingVersion(u64),
+    /// Root for the checked tree version is missing (e.g., because it was pruned).
     #[error("missing root for tree version {0}")]
     MissingRoot(u64),
+    /// Node referenced by its parent is missing.
     #[error(
         "missing {node_str} at {key}",
         node_str = if *is_leaf { "leaf" } else { "internal node" }
     )]
-    MissingNode { key: NodeKey, is_leaf: bool },
+    MissingNode {
+        /// Key of the missing node.
+        key: NodeKey,
+        /// Is the missing node a leaf?
+        is_leaf: bool,
+    },
+    /// Internal node is located at the terminal tree level.
     #[error("internal node at terminal tree level {key}")]
-    TerminalInternalNode { key: NodeKey },
+    TerminalInternalNode {
+        /// Key of the internal node.
+        key: NodeKey,
+    },
+    /// Number of leaves in the tree differs from one specified at the tree root.
     #[error("tree root specifies that tree has {expected} leaves, but it actually has {actual}")]
-    LeafCountMismatch { expected: u64, actual: u64 },
+    LeafCountMismatch {
+        /// Number of leaves specified at the tree root.
+        expected: u64,
+        /// Actual number of leaves.
+        actual: u64,
+    },
+    /// Child hash stored in an internal node differs from the actual hash of the child.
     #[error(
         "internal node at {key} specifies that child hash at `{nibble:x}` \
          is {expected}, but it actually is {actual}"
     )]
     HashMismatch {
+        /// Key of the internal node.
         key: NodeKey,
+        /// Nibble of the child.
         nibble: u8,
+        /// Hash stored in the internal node.
         expected: ValueHash,
+        /// Actual hash of the child.
         actual: ValueHash,
     },
+    /// Full key of a leaf doesn't start with the leaf key.
     #[error(
         "leaf at {key} specifies its full key as {full_key}, which doesn't start with the node key"
     )]
-    FullKeyMismatch { key: NodeKey, full_key: Key },
+    FullKeyMismatch {
+        /// Key of the leaf node.
+        key: NodeKey,
+        /// Full key stored in the leaf.
+        full_key: Key,
+    },
+    /// Leaf has zero index.
     #[error("leaf with key {full_key} has zero index, while leaf indices must start with 1")]
-    ZeroIndex { full_key: Key },
+    ZeroIndex {
+        /// Full key of the leaf.
+        full_key: Key,
+    },
+    /// Leaf index exceeds the number of leaves in the tree.
     #[error(
         "leaf with key {full_key} has index {index}, which is greater than \
          leaf count {leaf_count} specified at tree root"
     )]
     LeafIndexOverflow {
+        /// Index of the leaf.
         index: u64,
+        /// Number of leaves specified at the tree root.
         leaf_count: u64,
+        /// Full key of the leaf.
         full_key: Key,
     },
+    /// Several leaves have the same index.
     #[error("leaf with key {full_key} has same index {index} as another key")]
-    DuplicateLeafIndex { index: u64, full_key: Key },
+    DuplicateLeafIndex {
+        /// Duplicate index.
+        index: u64,
+        /// Full key of one of the leaves with the duplicate index.
+        full_key: Key,
+    },
+    /// Internal node has no children.
     #[error("internal node with key {key} does not have children")]
-    EmptyInternalNode { key: NodeKey },
+    EmptyInternalNode {
+        /// Key of the internal node.
+        key: NodeKey,
+    },
+    /// Version of an internal node differs from the maximum version among its children.
     #[error(
         "internal node with key {key} should have version {expected_version} (max among child ref versions)"
     )]
-    KeyVersionMismatch { key: NodeKey, expected_version: u64 },
+    KeyVersionMismatch {
+        /// Key of the internal node.
+        key: NodeKey,
+        /// Maximum version among child refs.
+        expected_version: u64,
+    },
+    /// Version of the root node is less than the maximum version among its children.
     #[error("root node should have version >={max_child_version} (max among child ref versions)")]
-    RootVersionMismatch { max_child_version: u64 },
+    RootVersionMismatch {
+        /// Maximum version among child refs.
+        max_child_version: u64,
+    },
+    /// Node replaced in a tree version is not registered for pruning, i.e., it will never be pruned.
+    #[error(
+        "node {key} replaced in tree version {replaced_in_version} is not registered for pruning"
+    )]
+    MissingStaleKey {
+        /// Key of the replaced node.
+        key: NodeKey,
+        /// Version in which the node was replaced.
+        replaced_in_version: u64,
+    },
+    /// Node is not reachable from the root of the tree version it was created in.
+    #[error("node {key} is not reachable from the root of its tree version")]
+    OrphanedNode {
+        /// Key of the orphaned node.
+        key: NodeKey,
+    },
+}
+
+impl ConsistencyError {
+    /// Returns the range of keys affected by this error, or `None` if the error affects
+    /// the entire tree (e.g., the tree root is missing).
+    pub fn affected_key_range(&self) -> Option<ops::RangeInclusive<Key>> {
+        match self {
+            Self::MissingNode { key, .. }
+            | Self::TerminalInternalNode { key }
+            | Self::FullKeyMismatch { key, .. }
+            | Self::EmptyInternalNode { key }
+            | Self::KeyVersionMismatch { key, .. }
+            | Self::MissingStaleKey { key, .. }
+            | Self::OrphanedNode { key } => Some(key.nibbles.key_range()),
+            Self::HashMismatch { key, nibble, .. } => {
+                let child_nibbles = key.nibbles.push(*nibble).unwrap_or(key.nibbles);
+                Some(child_nibbles.key_range())
+            }
+            Self::ZeroIndex { full_key }
+            | Self::LeafIndexOverflow { full_key, .. }
+            | Self::DuplicateLeafIndex { full_key, .. } => Some(*full_key..=*full_key),
+            Self::Deserialize(_)
+            | Self::MissingVersion(_)
+            | Self::MissingRoot(_)
+            | Self::LeafCountMismatch { .. }
+            | Self::RootVersionMismatch { .. } => None,
+        }
+    }
+}
+
+/// Options for [`MerkleTree::check_consistency()`].
+#[derive(Debug, Clone)]
+pub struct ConsistencyCheckOptions {
+    /// Checks that indices for all tree leaves are unique and are sequentially assigned starting from 1.
+    /// If [`Self::key_range`] is set, only uniqueness of indices is checked.
+    pub validate_indices: bool,
+    /// If set, only the part of the tree with keys in this range will be checked.
+    pub key_range: Option<ops::RangeInclusive<Key>>,
+    /// Checks that nodes replaced in the checked tree version are registered for pruning.
+    pub check_stale_keys: bool,
+    /// Checks that all nodes created in the checked tree version are reachable from its root.
+    pub check_orphaned_nodes: bool,
+}
+
+impl Default for ConsistencyCheckOptions {
+    fn default() -> Self {
+        Self {
+            validate_indices: true,
+            key_range: None,
+            check_stale_keys: true,
+            check_orphaned_nodes: true,
+        }
+    }
+}
+
+/// Report produced by [`MerkleTree::check_consistency()`].
+#[derive(Debug)]
+pub struct ConsistencyReport {
+    /// Checked version of the tree.
+    pub version: u64,
+    /// Checked key range, or `None` if the entire tree was checked.
+    pub key_range: Option<ops::RangeInclusive<Key>>,
+    /// Number of checked tree nodes (including the root node and leaves).
+    pub checked_nodes: u64,
+    /// Number of checked leaves.
+    pub checked_leaves: u64,
+    /// All found inconsistencies, ordered by the start of the affected key range. Errors affecting
+    /// the entire tree go first.
+    pub errors: Vec<ConsistencyError>,
+}
+
+impl ConsistencyReport {
+    /// Checks whether the tree is consistent, i.e., no errors were found.
+    pub fn is_consistent(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns disjoint key ranges affected by inconsistencies, ordered by increasing key.
+    pub fn affected_key_ranges(&self) -> Vec<ops::RangeInclusive<Key>> {
+        let mut ranges: Vec<_> = self
+            .errors
+            .iter()
+            .map(|err| err.affected_key_range().unwrap_or(Key::zero()..=Key::MAX))
+            .collect();
+        ranges.sort_unstable_by_key(|range| *range.start());
+
+        let mut merged_ranges: Vec<ops::RangeInclusive<Key>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            if let Some(last) = merged_ranges.last_mut() {
+                if *range.start() <= *last.end() {
+                    if *range.end() > *last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                    continue;
+                }
+            }
+            merged_ranges.push(range);
+        }
+        merged_ranges
+    }
+
+    /// Returns indices of [key partitions](crate::recovery::key_partition()) affected by inconsistencies.
+    /// These partitions can be repaired using [`MerkleTree::rebuild_partitions()`].
+    pub fn affected_partitions(&self) -> Vec<usize> {
+        let mut is_affected = [false; PARTITION_COUNT];
+        for range in self.affected_key_ranges() {
+            let (start, end) = (key_partition(range.start()), key_partition(range.end()));
+            is_affected[start..=end].fill(true);
+        }
+        (0..PARTITION_COUNT).filter(|&i| is_affected[i]).collect()
+    }
 }
 
 impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
@@ -80,16 +276,7 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         version: u64,
         validate_indices: bool,
     ) -> Result<(), ConsistencyError> {
-        let manifest = self.db.try_manifest()?;
-        let manifest = manifest.ok_or(ConsistencyError::MissingVersion(version))?;
-        if version >= manifest.version_count {
-            return Err(ConsistencyError::MissingVersion(version));
-        }
-
-        let root = self
-            .db
-            .try_root(version)?
-            .ok_or(ConsistencyError::MissingRoot(version))?;
+        let root = self.checked_root(version)?;
         let (leaf_count, root_node) = match root {
             Root::Empty => return Ok(()),
             Root::Filled { leaf_count, node } => (leaf_count.get(), node),
@@ -99,47 +286,64 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         // much in memory.
         let root_key = Nibbles::EMPTY.with_version(version);
         let leaf_data = validate_indices.then(|| LeafConsistencyData::new(leaf_count));
-        self.validate_node(&root_node, root_key, leaf_data.as_ref())?;
-        if let Some(leaf_data) = leaf_data {
+        let context = ValidationContext::new(leaf_data);
+        self.validate_node(&root_node, root_key, &context)?;
+        if let Some(leaf_data) = context.leaf_data {
             leaf_data.validate_count()?;
         }
         Ok(())
     }
 
+    fn checked_root(&self, version: u64) -> Result<Root, ConsistencyError> {
+        let manifest = self.db.try_manifest()?;
+        let manifest = manifest.ok_or(ConsistencyError::MissingVersion(version))?;
+        if version >= manifest.version_count {
+            return Err(ConsistencyError::MissingVersion(version));
+        }
+
+        self.db
+            .try_root(version)?
+            .ok_or(ConsistencyError::MissingRoot(version))
+    }
+
     fn validate_node(
         &self,
         node: &Node,
         key: NodeKey,
-        leaf_data: Option<&LeafConsistencyData>,
+        context: &ValidationContext,
     ) -> Result<ValueHash, ConsistencyError> {
+        context.record_node(node, key);
         match node {
             Node::Leaf(leaf) => {
                 let full_key_nibbles = Nibbles::new(&leaf.full_key, key.nibbles.nibble_count());
                 if full_key_nibbles != key.nibbles {
-                    return Err(ConsistencyError::FullKeyMismatch {
+                    context.report(ConsistencyError::FullKeyMismatch {
                         key,
                         full_key: leaf.full_key,
-                    });
+                    })?;
                 }
-                if let Some(leaf_data) = leaf_data {
-                    leaf_data.insert_leaf(leaf)?;
+                if let Some(leaf_data) = &context.leaf_data {
+                    if let Err(err) = leaf_data.insert_leaf(leaf) {
+                        context.report(err)?;
+                    }
                 }
             }
 
             Node::Internal(node) => {
                 let expected_version = node.child_refs().map(|child_ref| child_ref.version).max();
-                let Some(expected_version) = expected_version else {
-                    return Err(ConsistencyError::EmptyInternalNode { key });
-                };
-                if !key.is_empty() && expected_version != key.version {
-                    return Err(ConsistencyError::KeyVersionMismatch {
-                        key,
-                        expected_version,
-                    });
-                } else if key.is_empty() && expected_version > key.version {
-                    return Err(ConsistencyError::RootVersionMismatch {
-                        max_child_version: expected_version,
-                    });
+                if let Some(expected_version) = expected_version {
+                    if !key.is_empty() && expected_version != key.version {
+                        context.report(ConsistencyError::KeyVersionMismatch {
+                            key,
+                            expected_version,
+                        })?;
+                    } else if key.is_empty() && expected_version > key.version {
+                        context.report(ConsistencyError::RootVersionMismatch {
+                            max_child_version: expected_version,
+                        })?;
+                    }
+                } else {
+                    context.report(ConsistencyError::EmptyInternalNode { key })?;
                 }
 
                 // `.into_par_iter()` below is the only place where `rayon`-based parallelism
@@ -148,26 +352,31 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
                 children
                     .into_par_iter()
                     .try_for_each(|(nibble, child_ref)| {
-                        let child_key = key
-                            .nibbles
-                            .push(nibble)
-                            .ok_or(ConsistencyError::TerminalInternalNode { key })?;
-                        let child_key = child_key.with_version(child_ref.version);
-                        let child = self
-                            .db
-                            .try_tree_node(&child_key, child_ref.is_leaf)?
-                            .ok_or(ConsistencyError::MissingNode {
-                                key: child_key,
-                                is_leaf: child_ref.is_leaf,
-                            })?;
+                        let Some(child_nibbles) = key.nibbles.push(nibble) else {
+                            return context.report(ConsistencyError::TerminalInternalNode { key });
+                        };
+                        if !context.covers(&child_nibbles) {
+                            return Ok(());
+                        }
+                        let child_key = child_nibbles.with_version(child_ref.version);
+                        let child = match self.db.try_tree_node(&child_key, child_ref.is_leaf) {
+                            Ok(Some(child)) => child,
+                            Ok(None) => {
+                                return context.report(ConsistencyError::MissingNode {
+                                    key: child_key,
+                                    is_leaf: child_ref.is_leaf,
+                                });
+                            }
+                            Err(err) => return context.report(err.into()),
+                        };
 
                         // Recursion here is OK; the tree isn't that deep (~8 nibbles for a tree with
                         // ~1B entries).
-                        let child_hash = self.validate_node(&child, child_key, leaf_data)?;
+                        let child_hash = self.validate_node(&child, child_key, context)?;
                         if child_hash == child_ref.hash {
                             Ok(())
                         } else {
-                            Err(ConsistencyError::HashMismatch {
+                            context.report(ConsistencyError::HashMismatch {
                                 key,
                                 nibble,
                                 expected: child_ref.hash,
@@ -181,6 +390,272 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         let level = key.nibbles.nibble_count() * 4;
         Ok(node.hash(&mut HasherWithStats::new(&self.hasher), level))
     }
+
+    /// Rebuilds subtrees for the specified [key partitions](crate::recovery::key_partition())
+    /// in the latest tree version from scratch. This can be used to repair the tree after
+    /// inconsistencies are found by [`Self::check_consistency()`].
+    ///
+    /// `entries` must contain all leaves in the rebuilt partitions (and no other leaves)
+    /// as of the latest tree version; they don't need to be sorted. The latest version
+    /// is updated in place, and the nodes of the replaced subtrees are registered for pruning.
+    /// The number of leaves specified at the tree root is recomputed, so this method can also be used
+    /// to repair a [`ConsistencyError::LeafCountMismatch`] (`partitions` may be empty in this case).
+    ///
+    /// # Return value
+    ///
+    /// Returns the root hash of the repaired tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is empty, if `partitions` contain an out-of-range index, or if `entries`
+    /// contain a key outside of the rebuilt partitions.
+    pub fn rebuild_partitions(
+        &mut self,
+        partitions: &[usize],
+        entries: Vec<TreeEntry>,
+    ) -> ValueHash {
+        let version = self
+            .latest_version()
+            .expect("cannot rebuild partitions in an empty tree");
+        let first_nibbles: Vec<_> = partitions
+            .iter()
+            .map(|&partition| {
+                assert!(
+                    partition < PARTITION_COUNT,
+                    "Partition index {partition} is out of bounds"
+                );
+                u8::try_from(partition).unwrap()
+            })
+            .collect();
+
+        let storage = Storage::new(&self.db, &self.hasher, version, false);
+        let (root_hash, patch) = storage.rebuild_subtrees(&first_nibbles, entries);
+        self.db.apply_patch(patch);
+        root_hash
+    }
+}
+
+impl<DB: PruneDatabase, H: HashTree> MerkleTree<DB, H> {
+    /// Checks the internal tree consistency as stored in the database. Unlike [`Self::verify_consistency()`],
+    /// this method doesn't stop on the first error; instead, it collects all found inconsistencies
+    /// into a report. Besides the checks performed by `verify_consistency()`, this method can check
+    /// that the nodes replaced in the checked version are registered for pruning, and that there are
+    /// no orphaned nodes (i.e., nodes not reachable from the root) created in the checked version.
+    ///
+    /// [First-level subtrees](crate::recovery::key_partition()) are checked in parallel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checked version or its root is missing, i.e. the check cannot proceed.
+    #[allow(clippy::missing_panics_doc)] // panics only on poisoned mutexes
+    pub fn check_consistency(
+        &self,
+        version: u64,
+        options: &ConsistencyCheckOptions,
+    ) -> Result<ConsistencyReport, ConsistencyError> {
+        let root = self.checked_root(version)?;
+        let mut context = ValidationContext::new(None);
+        context.version = version;
+        context.key_range = options.key_range.clone();
+        context.collected_errors = Some(Mutex::default());
+        if options.check_orphaned_nodes {
+            context.new_node_keys = Some(Mutex::default());
+        }
+
+        if let Root::Filled { leaf_count, node } = &root {
+            if options.validate_indices {
+                context.leaf_data = Some(LeafConsistencyData::new(leaf_count.get()));
+            }
+            let root_key = Nibbles::EMPTY.with_version(version);
+            self.validate_node(node, root_key, &context)?;
+        }
+
+        let errors = context.collected_errors.take().unwrap();
+        let mut errors = errors.into_inner().unwrap();
+        if let Some(leaf_data) = context.leaf_data.take() {
+            if options.key_range.is_none() {
+                if let Err(err) = leaf_data.validate_count() {
+                    errors.push(err);
+                }
+            }
+        }
+        if options.check_stale_keys {
+            self.check_stale_keys(version, &context, &mut errors);
+        }
+        if let Some(new_node_keys) = context.new_node_keys.take() {
+            let new_node_keys = new_node_keys.into_inner().unwrap();
+            let orphaned_keys = self.db.node_keys(version).into_iter().filter(|key| {
+                !key.is_empty() && context.covers(&key.nibbles) && !new_node_keys.contains(key)
+            });
+            errors.extend(orphaned_keys.map(|key| ConsistencyError::OrphanedNode { key }));
+        }
+
+        errors.sort_by_key(|err| err.affected_key_range().map(|range| *range.start()));
+        Ok(ConsistencyReport {
+            version,
+            key_range: options.key_range.clone(),
+            checked_nodes: context.node_count.into_inner(),
+            checked_leaves: context.leaf_count.into_inner(),
+            errors,
+        })
+    }
+
+    /// Checks that all nodes from the previous tree version that are not present in `version`
+    /// are registered as stale in `version`. If the previous version is pruned or empty,
+    /// the check is skipped.
+    fn check_stale_keys(
+        &self,
+        version: u64,
+        context: &ValidationContext,
+        errors: &mut Vec<ConsistencyError>,
+    ) {
+        let Some(prev_version) = version.checked_sub(1) else {
+            return;
+        };
+        let prev_root = match self.db.try_root(prev_version) {
+            Ok(Some(Root::Filled { node, .. })) => node,
+            Ok(_) => return,
+            Err(err) => {
+                errors.push(err.into());
+                return;
+            }
+        };
+        // The root node is replaced in each new version.
+        let mut replaced_keys = vec![NodeKey::empty(prev_version)];
+        if let (Node::Internal(prev_root), Ok(Some(Root::Filled { node, .. }))) =
+            (&prev_root, self.db.try_root(version))
+        {
+            if let Node::Internal(root) = &node {
+                let mut walk = ReplacedKeysWalk {
+                    context,
+                    replaced_keys: &mut replaced_keys,
+                    errors,
+                };
+                walk.visit(&self.db, prev_root, root, Nibbles::EMPTY);
+            }
+        }
+
+        let stale_keys: HashSet<_> = self.db.stale_keys(version).into_iter().collect();
+        let missing_keys = replaced_keys
+            .into_iter()
+            .filter(|key| !stale_keys.contains(key));
+        errors.extend(missing_keys.map(|key| ConsistencyError::MissingStaleKey {
+            key,
+            replaced_in_version: version,
+        }));
+    }
+}
+
+/// Shared context for validating tree nodes.
+#[derive(Debug)]
+struct ValidationContext {
+    version: u64,
+    leaf_data: Option<LeafConsistencyData>,
+    key_range: Option<ops::RangeInclusive<Key>>,
+    /// If set, errors are collected here instead of terminating the validation.
+    collected_errors: Option<Mutex<Vec<ConsistencyError>>>,
+    /// If set, keys of visited nodes created in the checked version are recorded here.
+    new_node_keys: Option<Mutex<HashSet<NodeKey>>>,
+    node_count: AtomicU64,
+    leaf_count: AtomicU64,
+}
+
+impl ValidationContext {
+    fn new(leaf_data: Option<LeafConsistencyData>) -> Self {
+        Self {
+            version: 0,
+            leaf_data,
+            key_range: None,
+            collected_errors: None,
+            new_node_keys: None,
+            node_count: AtomicU64::new(0),
+            leaf_count: AtomicU64::new(0),
+        }
+    }
+
+    /// Reports an error. Returns the error back if errors are not collected, which terminates
+    /// the validation.
+    fn report(&self, err: ConsistencyError) -> Result<(), ConsistencyError> {
+        if let Some(errors) = &self.collected_errors {
+            errors.lock().unwrap().push(err);
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Checks whether the subtree with the specified root intersects with the checked key range.
+    fn covers(&self, nibbles: &Nibbles) -> bool {
+        self.key_range.as_ref().map_or(true, |key_range| {
+            let subtree_range = nibbles.key_range();
+            subtree_range.start() <= key_range.end() && key_range.start() <= subtree_range.end()
+        })
+    }
+
+    fn record_node(&self, node: &Node, key: NodeKey) {
+        self.node_count.fetch_add(1, Ordering::Relaxed);
+        if matches!(node, Node::Leaf(_)) {
+            self.leaf_count.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(new_node_keys) = &self.new_node_keys {
+            if key.version == self.version {
+                new_node_keys.lock().unwrap().insert(key);
+            }
+        }
+    }
+}
+
+/// Simultaneous walk of two consecutive tree versions that collects keys of nodes replaced
+/// in the newer version.
+#[derive(Debug)]
+struct ReplacedKeysWalk<'a> {
+    context: &'a ValidationContext,
+    replaced_keys: &'a mut Vec<NodeKey>,
+    errors: &'a mut Vec<ConsistencyError>,
+}
+
+impl ReplacedKeysWalk<'_> {
+    fn visit<DB: Database>(
+        &mut self,
+        db: &DB,
+        prev_node: &InternalNode,
+        node: &InternalNode,
+        nibbles: Nibbles,
+    ) {
+        for (nibble, prev_ref) in prev_node.children() {
+            let Some(child_nibbles) = nibbles.push(nibble) else {
+                continue; // Reported by the main validation
+            };
+            if !self.context.covers(&child_nibbles) {
+                continue;
+            }
+            let child_ref = node.child_ref(nibble);
+            if child_ref.map_or(false, |child_ref| child_ref.version == prev_ref.version) {
+                continue; // The subtree is not changed
+            }
+
+            let prev_key = child_nibbles.with_version(prev_ref.version);
+            self.replaced_keys.push(prev_key);
+            let Some(child_ref) = child_ref else {
+                continue;
+            };
+            if prev_ref.is_leaf || child_ref.is_leaf {
+                continue;
+            }
+
+            let key = child_nibbles.with_version(child_ref.version);
+            let nodes = db
+                .try_tree_node(&prev_key, false)
+                .and_then(|prev_node| Ok((prev_node, db.try_tree_node(&key, false)?)));
+            match nodes {
+                Ok((Some(Node::Internal(prev_node)), Some(Node::Internal(node)))) => {
+                    self.visit(db, &prev_node, &node, child_nibbles);
+                }
+                Ok(_) => { /* Missing nodes are reported by the main validation */ }
+                Err(err) => self.errors.push(err.into()),
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -201,6 +676,9 @@ impl LeafConsistencyData {
     }
 
     fn insert_leaf(&self, leaf: &LeafNode) -> Result<(), ConsistencyError> {
+        // Count all leaves (even invalid ones), so that index errors don't result
+        // in a leaf count mismatch if errors are collected.
+        self.actual_leaf_count.fetch_add(1, Ordering::Relaxed);
         if leaf.leaf_index == 0 {
             return Err(ConsistencyError::ZeroIndex {
                 full_key: leaf.full_key,
@@ -221,7 +699,6 @@ impl LeafConsistencyData {
                 full_key: leaf.full_key,
             });
         }
-        self.actual_leaf_count.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
 
@@ -516,4 +993,220 @@ mod tests {
             }
         );
     }
+
+    const THIRD_KEY: Key = U256([0, 0, 0, 0x_1234_5678_0000_0000]);
+
+    fn prepare_multi_partition_database() -> PatchSet {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        tree.extend(vec![
+            TreeEntry::new(FIRST_KEY, 1, H256([1; 32])),
+            TreeEntry::new(SECOND_KEY, 2, H256([2; 32])),
+            TreeEntry::new(THIRD_KEY, 3, H256([3; 32])),
+        ]);
+        tree.db
+    }
+
+    fn deterministic_check_consistency(
+        db: &PatchSet,
+        version: u64,
+        options: &ConsistencyCheckOptions,
+    ) -> ConsistencyReport {
+        let thread_pool = ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .expect("failed initializing `rayon` thread pool");
+        thread_pool
+            .install(|| MerkleTree::new(db).check_consistency(version, options))
+            .unwrap()
+    }
+
+    fn corrupt_multi_partition_database(db: &mut PatchSet) {
+        let root = db.root_mut(0).unwrap();
+        let Root::Filled {
+            node: Node::Internal(node),
+            ..
+        } = root
+        else {
+            panic!("unexpected root: {root:?}");
+        };
+        node.child_ref_mut(0x1).unwrap().hash = ValueHash::zero();
+
+        for (_, node) in db.nodes_mut() {
+            if let Node::Leaf(leaf) = node {
+                if leaf.full_key == FIRST_KEY {
+                    leaf.full_key = U256::zero();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn collecting_multiple_errors() {
+        let db = prepare_multi_partition_database();
+        let report = deterministic_check_consistency(&db, 0, &ConsistencyCheckOptions::default());
+        assert!(report.is_consistent(), "{report:#?}");
+        assert_eq!(report.checked_leaves, 3);
+
+        let mut db = db;
+        corrupt_multi_partition_database(&mut db);
+        let report = deterministic_check_consistency(&db, 0, &ConsistencyCheckOptions::default());
+        assert_eq!(report.errors.len(), 2, "{report:#?}");
+        assert_matches!(
+            &report.errors[0],
+            ConsistencyError::HashMismatch { key, nibble: 0x1, .. } if key.is_empty()
+        );
+        assert_matches!(
+            &report.errors[1],
+            ConsistencyError::FullKeyMismatch { full_key, .. } if full_key.is_zero()
+        );
+        assert_eq!(report.affected_partitions(), [0x1, 0xd]);
+        assert_eq!(
+            report.affected_key_ranges(),
+            [
+                crate::recovery::partition_key_range(0x1),
+                Nibbles::new(&FIRST_KEY, 10).key_range()
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_consistency_check() {
+        let mut db = prepare_multi_partition_database();
+        corrupt_multi_partition_database(&mut db);
+
+        let options = ConsistencyCheckOptions {
+            key_range: Some(crate::recovery::partition_key_range(0x1)),
+            ..ConsistencyCheckOptions::default()
+        };
+        let report = deterministic_check_consistency(&db, 0, &options);
+        assert_eq!(report.checked_leaves, 1);
+        assert_eq!(report.errors.len(), 1, "{report:#?}");
+        assert_matches!(report.errors[0], ConsistencyError::HashMismatch { .. });
+
+        let options = ConsistencyCheckOptions {
+            key_range: Some(crate::recovery::partition_key_range(0x5)),
+            ..ConsistencyCheckOptions::default()
+        };
+        let report = deterministic_check_consistency(&db, 0, &options);
+        assert_eq!(report.checked_leaves, 0);
+        assert!(report.is_consistent(), "{report:#?}");
+    }
+
+    #[test]
+    fn missing_stale_keys_error() {
+        let mut tree = MerkleTree::new(prepare_multi_partition_database());
+        tree.extend(vec![TreeEntry::new(SECOND_KEY, 2, H256([4; 32]))]);
+        let mut db = tree.db;
+        let report = deterministic_check_consistency(&db, 1, &ConsistencyCheckOptions::default());
+        assert!(report.is_consistent(), "{report:#?}");
+
+        let stale_keys: HashSet<_> = std::mem::take(db.stale_keys_mut(1)).into_iter().collect();
+        assert!(stale_keys.contains(&NodeKey::empty(0)));
+        let report = deterministic_check_consistency(&db, 1, &ConsistencyCheckOptions::default());
+        let reported_keys: HashSet<_> = report
+            .errors
+            .iter()
+            .map(|err| match err {
+                ConsistencyError::MissingStaleKey {
+                    key,
+                    replaced_in_version: 1,
+                } => *key,
+                _ => panic!("unexpected error: {err:?}"),
+            })
+            .collect();
+        assert_eq!(reported_keys, stale_keys);
+    }
+
+    #[test]
+    fn orphaned_node_error() {
+        let mut db = prepare_multi_partition_database();
+        let leaf = db
+            .nodes_mut()
+            .find_map(|(_, node)| matches!(node, Node::Leaf(_)).then(|| node.clone()));
+        let orphan_key = Nibbles::single(0x5).with_version(0);
+        db.insert_node(orphan_key, leaf.unwrap());
+
+        let report = deterministic_check_consistency(&db, 0, &ConsistencyCheckOptions::default());
+        assert_eq!(report.errors.len(), 1, "{report:#?}");
+        assert_matches!(
+            report.errors[0],
+            ConsistencyError::OrphanedNode { key } if key == orphan_key
+        );
+        assert_eq!(report.affected_partitions(), [0x5]);
+
+        let options = ConsistencyCheckOptions {
+            check_orphaned_nodes: false,
+            ..ConsistencyCheckOptions::default()
+        };
+        let report = deterministic_check_consistency(&db, 0, &options);
+        assert!(report.is_consistent(), "{report:#?}");
+    }
+
+    #[test]
+    fn repairing_leaf_count_mismatch() {
+        let mut db = prepare_multi_partition_database();
+        let root = db.root_mut(0).unwrap();
+        let Root::Filled { leaf_count, .. } = root else {
+            panic!("unexpected root: {root:?}");
+        };
+        *leaf_count = NonZeroU64::new(42).unwrap();
+
+        let report = deterministic_check_consistency(&db, 0, &ConsistencyCheckOptions::default());
+        assert_matches!(
+            report.errors.as_slice(),
+            [ConsistencyError::LeafCountMismatch {
+                expected: 42,
+                actual: 3
+            }]
+        );
+        assert!(report.affected_partitions().is_empty());
+
+        let mut tree = MerkleTree::new(db);
+        let root_hash = tree.latest_root_hash();
+        assert_eq!(tree.rebuild_partitions(&[], vec![]), root_hash);
+        assert_eq!(tree.latest_root().leaf_count(), 3);
+        let report =
+            deterministic_check_consistency(&tree.db, 0, &ConsistencyCheckOptions::default());
+        assert!(report.is_consistent(), "{report:#?}");
+    }
+
+    #[test]
+    fn rebuilding_partitions() {
+        let mut tree = MerkleTree::new(prepare_multi_partition_database());
+        let output = tree.extend(vec![TreeEntry::new(SECOND_KEY, 2, H256([4; 32]))]);
+        let mut db = tree.db;
+
+        let leaf_key = db.nodes_mut().find_map(|(key, node)| match node {
+            Node::Leaf(leaf) if leaf.full_key == SECOND_KEY && key.version == 1 => Some(*key),
+            _ => None,
+        });
+        db.remove_node(&leaf_key.unwrap());
+        let options = ConsistencyCheckOptions {
+            validate_indices: false,
+            ..ConsistencyCheckOptions::default()
+        };
+        let report = deterministic_check_consistency(&db, 1, &options);
+        assert_matches!(
+            report.errors.as_slice(),
+            [ConsistencyError::MissingNode { is_leaf: true, .. }]
+        );
+        assert_eq!(report.affected_partitions(), [0xd]);
+
+        let mut tree = MerkleTree::new(db);
+        let entries = vec![
+            TreeEntry::new(SECOND_KEY, 2, H256([4; 32])),
+            TreeEntry::new(FIRST_KEY, 1, H256([1; 32])),
+        ];
+        let root_hash = tree.rebuild_partitions(&[0xd], entries);
+        assert_eq!(root_hash, output.root_hash);
+        assert_eq!(tree.latest_root_hash(), output.root_hash);
+
+        let report =
+            deterministic_check_consistency(&tree.db, 1, &ConsistencyCheckOptions::default());
+        assert!(report.is_consistent(), "{report:#?}");
+        // The previous version should be intact.
+        let report =
+            deterministic_check_consistency(&tree.db, 0, &ConsistencyCheckOptions::default());
+        assert!(report.is_consistent(), "{report:#?}");
+    }
 }
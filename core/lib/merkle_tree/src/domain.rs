@@ -1,5 +1,7 @@
 //! Tying the Merkle tree implementation to the problem domain.
 
+use std::path::Path;
+
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_storage::RocksDB;
@@ -16,9 +18,21 @@ use crate::{
         Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, ValueHash,
         TREE_DEPTH,
     },
-    BlockOutput, HashTree, MerkleTree, NoVersionError,
+    BlockOutput, ConsistencyCheckOptions, ConsistencyError, ConsistencyReport, HashTree,
+    MerkleTree, MerkleTreePruner, MerkleTreePrunerHandle, NoVersionError,
 };
 
+/// Error returned by [`ZkSyncTree::create_checkpoint()`].
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    /// The requested tree version is missing (e.g., because it was pruned).
+    #[error("cannot create checkpoint: {0}")]
+    NoVersion(#[from] NoVersionError),
+    /// Error creating or accessing the RocksDB checkpoint.
+    #[error("RocksDB error creating checkpoint: {0}")]
+    RocksDB(#[from] zksync_storage::rocksdb::Error),
+}
+
 /// Metadata for the current tree state.
 #[derive(Debug, Clone)]
 pub struct TreeMetadata {
@@ -117,6 +131,71 @@ impl ZkSyncTree {
         ZkSyncTreeReader(MerkleTree::new(db))
     }
 
+    /// Creates a pruner for this tree retaining the specified number of past L1 batches
+    /// (i.e., tree versions) besides the latest one. The pruner should be run on a dedicated thread
+    /// via [`MerkleTreePruner::run()`].
+    ///
+    /// Like [`Self::reader()`], the pruner only sees changes flushed to RocksDB.
+    pub fn pruner(
+        &self,
+        retained_l1_batches: u64,
+    ) -> (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle) {
+        let db = self.tree.db.inner().clone();
+        MerkleTreePruner::new(db, retained_l1_batches)
+    }
+
+    /// Creates a consistent RocksDB checkpoint of this tree at `path` truncated to the specified
+    /// L1 batch. The checkpoint is a self-contained RocksDB instance that can be opened as a tree
+    /// (e.g., after copying it to another machine).
+    ///
+    /// Only changes [saved](Self::save()) to RocksDB are included in the checkpoint.
+    ///
+    /// # Return value
+    ///
+    /// Returns the root hash of the tree after `l1_batch_number`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version for `l1_batch_number` was pruned, and propagates
+    /// RocksDB I/O errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree has unsaved changes, or if `l1_batch_number` is not processed by the tree yet.
+    pub fn create_checkpoint(
+        &self,
+        path: &Path,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<ValueHash, CheckpointError> {
+        assert!(
+            self.tree.db.patched_versions().is_empty(),
+            "Cannot create a checkpoint for a tree with unsaved changes"
+        );
+        assert!(
+            l1_batch_number < self.next_l1_batch_number(),
+            "Cannot create a checkpoint for L1 batch #{l1_batch_number}, which is not processed by the tree yet"
+        );
+        let version = u64::from(l1_batch_number.0);
+        // Check the version beforehand so that a checkpoint isn't created needlessly.
+        self.tree.try_root_hash(version)?;
+
+        tracing::info!(
+            "Creating Merkle tree checkpoint for L1 batch #{l1_batch_number} at `{}`",
+            path.display()
+        );
+        self.tree.db.inner().db().create_checkpoint(path)?;
+
+        let checkpoint_db = RocksDBWrapper::from(RocksDB::new(path));
+        let mut checkpoint_tree = MerkleTree::new(checkpoint_db);
+        checkpoint_tree.truncate_recent_versions(version + 1);
+        // The version may have been pruned after the check above.
+        let root_hash = checkpoint_tree.try_root_hash(version)?;
+        tracing::info!(
+            "Created Merkle tree checkpoint for L1 batch #{l1_batch_number} with root hash {root_hash:?}"
+        );
+        Ok(root_hash)
+    }
+
     /// Sets the chunk size for multi-get operations. The requested keys will be split
     /// into chunks of this size and requested in parallel using `rayon`. Setting chunk size
     /// to a large value (e.g., `usize::MAX`) will effectively disable parallelism.
@@ -180,6 +259,59 @@ impl ZkSyncTree {
             });
     }
 
+    /// Checks tree consistency collecting all found inconsistencies. `l1_batch_number` specifies
+    /// the version of the tree to be checked, expressed as the number of latest L1 batch applied
+    /// to the tree.
+    ///
+    /// Like [`Self::reader()`], the check only sees changes flushed to RocksDB.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checked tree version or its root is missing.
+    pub fn check_consistency(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        options: &ConsistencyCheckOptions,
+    ) -> Result<ConsistencyReport, ConsistencyError> {
+        let version = u64::from(l1_batch_number.0);
+        let tree = MerkleTree::new(self.tree.db.inner().clone());
+        tree.check_consistency(version, options)
+    }
+
+    /// Rebuilds subtrees for the specified [key partitions](crate::recovery::key_partition())
+    /// in the latest tree version. `entries` must contain all storage entries in these partitions
+    /// as of the latest processed L1 batch. See [`MerkleTree::rebuild_partitions()`] for details.
+    ///
+    /// Changes are written to RocksDB directly.
+    ///
+    /// # Return value
+    ///
+    /// Returns the root hash of the repaired tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree has unsaved changes, or in the same cases as `MerkleTree::rebuild_partitions()`.
+    pub fn rebuild_partitions(
+        &mut self,
+        partitions: &[usize],
+        entries: &[TreeEntry<StorageKey>],
+    ) -> ValueHash {
+        assert!(
+            self.tree.db.patched_versions().is_empty(),
+            "Cannot rebuild partitions for a tree with unsaved changes"
+        );
+        let entries = entries
+            .iter()
+            .map(|entry| entry.map_key(StorageKey::hashed_key_u256))
+            .collect();
+        let mut tree = MerkleTree::new(self.tree.db.inner_mut());
+        let root_hash = tree.rebuild_partitions(partitions, entries);
+        tracing::info!(
+            "Rebuilt Merkle tree partitions {partitions:?}; new root hash is {root_hash:?}"
+        );
+        root_hash
+    }
+
     /// Processes an iterator of storage logs comprising a single L1 batch.
     pub fn process_l1_batch(
         &mut self,
@@ -460,6 +592,26 @@ impl ZkSyncTreeReader {
         self.0.latest_root().leaf_count()
     }
 
+    /// Returns the root hash of the tree after the specified L1 batch, or `None` if the corresponding
+    /// tree version is not present (either because it was not created yet, or because it was pruned).
+    pub fn l1_batch_root_hash(&self, l1_batch_number: L1BatchNumber) -> Option<ValueHash> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.root_hash(version)
+    }
+
+    /// Returns the root hash of the tree after the specified L1 batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version is missing.
+    pub fn try_l1_batch_root_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<ValueHash, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.try_root_hash(version)
+    }
+
     /// Reads entries together with Merkle proofs with the specified keys from the tree. The entries are returned
     /// in the same order as requested.
     ///
@@ -2,7 +2,7 @@
 
 use std::{error, fmt, str::Utf8Error};
 
-use crate::types::NodeKey;
+use crate::types::{Key, NodeKey, ValueHash};
 
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -142,6 +142,19 @@ pub struct NoVersionError {
     pub(crate) version_count: u64,
 }
 
+impl NoVersionError {
+    /// Returns the requested tree version.
+    pub fn missing_version(&self) -> u64 {
+        self.missing_version
+    }
+
+    /// Checks whether the requested version was removed from the tree by pruning (as opposed
+    /// to not being created yet).
+    pub fn is_pruned(&self) -> bool {
+        self.missing_version < self.version_count
+    }
+}
+
 impl fmt::Display for NoVersionError {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         let &Self {
@@ -164,6 +177,41 @@ impl fmt::Display for NoVersionError {
 
 impl error::Error for NoVersionError {}
 
+/// Error verifying a Merkle proof, e.g. [`TreeNonMembershipProof`](crate::TreeNonMembershipProof)
+/// or [`TreeVersionDiffProof`](crate::TreeVersionDiffProof).
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ProofVerificationError {
+    /// Merkle path is longer than the tree depth.
+    #[error("Merkle path has length {0}, which exceeds the tree depth")]
+    MerklePathTooLong(usize),
+    /// Root hash computed from the proof differs from the trusted one.
+    #[error("root hash mismatch: expected {expected:?}, computed {computed:?}")]
+    RootHashMismatch {
+        /// Trusted root hash.
+        expected: ValueHash,
+        /// Root hash computed from the proof.
+        computed: ValueHash,
+    },
+    /// Entry has zero leaf index, but a non-zero value.
+    #[error("entry for key {0:0>64x} has zero leaf index, but non-zero value")]
+    InvalidMissingValue(Key),
+    /// Old and new entries in a diff have different keys.
+    #[error("old and new entries in a diff have different keys: {old:0>64x} and {new:0>64x}")]
+    KeyMismatch {
+        /// Key of the old entry.
+        old: Key,
+        /// Key of the new entry.
+        new: Key,
+    },
+    /// An existing entry is removed in a diff.
+    #[error("entry for key {0:0>64x} is removed, which is not supported by the tree")]
+    RemovedEntry(Key),
+    /// Changes in a diff are not ordered by increasing key.
+    #[error("diff changes are not ordered by increasing key; offending key: {0:0>64x}")]
+    UnorderedChanges(Key),
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_types::U256;
@@ -2,8 +2,11 @@
 
 use crate::{
     hasher::HasherWithStats,
-    storage::{LoadAncestorsResult, SortedKeys, WorkingPatchSet},
-    types::{Nibbles, Node, TreeEntry, TreeEntryWithProof},
+    storage::{LoadAncestorsResult, SortedKeys, Storage, WorkingPatchSet},
+    types::{
+        LeafNode, Nibbles, Node, Root, TreeEntry, TreeEntryDiff, TreeEntryWithProof,
+        TreeInstruction, TreeLogEntry, TreeNonMembershipProof, TreeVersionDiffProof,
+    },
     Database, HashTree, Key, MerkleTree, NoVersionError, ValueHash,
 };
 
@@ -32,19 +35,36 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         )
     }
 
+    /// Returns the root hash of a tree at the specified `version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn try_root_hash(&self, version: u64) -> Result<ValueHash, NoVersionError> {
+        let root = self.try_root(version)?;
+        let Root::Filled { node, .. } = root else {
+            return Ok(self.hasher.empty_tree_hash());
+        };
+        Ok(node.hash(&mut HasherWithStats::new(&self.hasher), 0))
+    }
+
+    fn try_root(&self, version: u64) -> Result<Root, NoVersionError> {
+        self.db.root(version).ok_or_else(|| {
+            let manifest = self.db.manifest().unwrap_or_default();
+            NoVersionError {
+                missing_version: version,
+                version_count: manifest.version_count,
+            }
+        })
+    }
+
     fn load_and_transform_entries<T>(
         &self,
         version: u64,
         leaf_keys: &[Key],
         mut transform: impl FnMut(&mut WorkingPatchSet, &Key, &Nibbles) -> T,
     ) -> Result<Vec<T>, NoVersionError> {
-        let root = self.db.root(version).ok_or_else(|| {
-            let manifest = self.db.manifest().unwrap_or_default();
-            NoVersionError {
-                missing_version: version,
-                version_count: manifest.version_count,
-            }
-        })?;
+        let root = self.try_root(version)?;
         let sorted_keys = SortedKeys::new(leaf_keys.iter().copied());
         let mut patch_set = WorkingPatchSet::new(version, root);
         let LoadAncestorsResult {
@@ -88,6 +108,142 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
             },
         )
     }
+
+    /// Creates non-membership proofs for the specified keys. The proofs are returned in the same order
+    /// as requested. If a key is present in the tree, `None` is returned in its place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn non_membership_proofs(
+        &self,
+        version: u64,
+        leaf_keys: &[Key],
+    ) -> Result<Vec<Option<TreeNonMembershipProof>>, NoVersionError> {
+        let entries = self.entries_with_proofs(version, leaf_keys)?;
+        let proofs = entries.into_iter().map(|entry| {
+            entry.base.is_empty().then(|| TreeNonMembershipProof {
+                key: entry.base.key,
+                merkle_path: entry.merkle_path,
+            })
+        });
+        Ok(proofs.collect())
+    }
+
+    /// Creates a proof of all changes in the tree between `old_version` and `new_version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the tree versions is missing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `old_version > new_version`.
+    pub fn version_diff_proof(
+        &self,
+        old_version: u64,
+        new_version: u64,
+    ) -> Result<TreeVersionDiffProof, NoVersionError> {
+        assert!(
+            old_version <= new_version,
+            "Old tree version {old_version} is greater than the new version {new_version}"
+        );
+        self.try_root(old_version)?;
+        let new_root = self.try_root(new_version)?;
+        if old_version == new_version {
+            return Ok(TreeVersionDiffProof::default());
+        }
+
+        let mut new_entries: Vec<TreeEntry> = self
+            .load_changed_leaves(new_root, old_version)
+            .into_iter()
+            .map(TreeEntry::from)
+            .collect();
+        new_entries.sort_unstable_by_key(|entry| entry.key);
+        let keys: Vec<_> = new_entries.iter().map(|entry| entry.key).collect();
+        let old_entries = self.entries(old_version, &keys)?;
+        // Leaves may be rewritten without changes to their contents, e.g. when they are moved
+        // down the tree, so we filter them out.
+        let instructions: Vec<_> = new_entries
+            .into_iter()
+            .zip(old_entries)
+            .filter_map(|(new_entry, old_entry)| {
+                (new_entry != old_entry).then_some(TreeInstruction::Write(new_entry))
+            })
+            .collect();
+
+        // Replay changes on top of `old_version` without persisting them.
+        let storage = Storage::new(&self.db, &self.hasher, old_version + 1, true);
+        let (output, _) = storage.extend_with_proofs(instructions.clone());
+        if let Some(root_hash) = output.root_hash() {
+            debug_assert_eq!(Some(root_hash), self.root_hash(new_version));
+        }
+
+        let changes = output.logs.into_iter().zip(instructions);
+        let changes = changes.map(|(log, instruction)| {
+            let TreeInstruction::Write(new) = instruction else {
+                unreachable!("only write instructions are replayed");
+            };
+            let old = match log.base {
+                TreeLogEntry::Updated {
+                    leaf_index,
+                    previous_value,
+                } => TreeEntry::new(new.key, leaf_index, previous_value),
+                _ => TreeEntry::empty(new.key),
+            };
+            TreeEntryDiff {
+                old,
+                new,
+                merkle_path: log.merkle_path,
+            }
+        });
+        Ok(TreeVersionDiffProof {
+            changes: changes.collect(),
+        })
+    }
+
+    /// Loads leaves of the tree with the specified root that were inserted or updated
+    /// after `old_version`. Relies on the fact that versions in child references are updated
+    /// each time a descendant of the child is changed.
+    fn load_changed_leaves(&self, root: Root, old_version: u64) -> Vec<LeafNode> {
+        let mut internal_nodes = match root {
+            Root::Empty => return vec![],
+            Root::Filled {
+                node: Node::Leaf(leaf),
+                ..
+            } => return vec![leaf],
+            Root::Filled {
+                node: Node::Internal(node),
+                ..
+            } => vec![(Nibbles::EMPTY, node)],
+        };
+
+        let mut leaves = vec![];
+        while !internal_nodes.is_empty() {
+            let mut child_keys = vec![];
+            for (nibbles, node) in &internal_nodes {
+                for (nibble, child_ref) in node.children() {
+                    if child_ref.version > old_version {
+                        let child_nibbles = nibbles.push(nibble).unwrap();
+                        // ^ `unwrap()` is safe; there can be no internal nodes on the bottom-most tree level
+                        let child_key = child_nibbles.with_version(child_ref.version);
+                        child_keys.push((child_key, child_ref.is_leaf));
+                    }
+                }
+            }
+
+            let child_nodes = self.db.tree_nodes(&child_keys);
+            internal_nodes.clear();
+            for ((child_key, _), node) in child_keys.iter().zip(child_nodes) {
+                match node.unwrap() {
+                    // ^ `unwrap()` is safe by construction
+                    Node::Leaf(leaf) => leaves.push(leaf),
+                    Node::Internal(node) => internal_nodes.push((child_key.nibbles, node)),
+                }
+            }
+        }
+        leaves
+    }
 }
 
 #[cfg(test)]
@@ -111,6 +267,50 @@ mod tests {
         entries[0].verify(&tree.hasher, tree.hasher.empty_tree_hash());
     }
 
+    #[test]
+    fn non_membership_proofs_in_single_node_tree() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        let key = Key::from(987_654);
+        let output = tree.extend(vec![TreeEntry::new(key, 1, ValueHash::repeat_byte(1))]);
+        let missing_key = Key::from(123);
+
+        let proofs = tree.non_membership_proofs(0, &[key, missing_key]).unwrap();
+        assert_eq!(proofs.len(), 2);
+        assert!(proofs[0].is_none());
+        let proof = proofs[1].as_ref().unwrap();
+        assert_eq!(proof.key, missing_key);
+        proof.verify(&tree.hasher, output.root_hash).unwrap();
+        let empty_tree_hash = tree.hasher.empty_tree_hash();
+        proof.verify(&tree.hasher, empty_tree_hash).unwrap_err();
+    }
+
+    #[test]
+    fn diff_proof_for_single_node_tree() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        let key = Key::from(987_654);
+        let entry = TreeEntry::new(key, 1, ValueHash::repeat_byte(1));
+        let old_root_hash = tree.extend(vec![entry]).root_hash;
+        let updated_entry = entry.with_value(ValueHash::repeat_byte(2));
+        let new_root_hash = tree.extend(vec![updated_entry]).root_hash;
+
+        let proof = tree.version_diff_proof(0, 1).unwrap();
+        assert_eq!(proof.changes.len(), 1);
+        assert_eq!(proof.changes[0].old, entry);
+        assert_eq!(proof.changes[0].new, updated_entry);
+        proof
+            .verify(&tree.hasher, old_root_hash, new_root_hash)
+            .unwrap();
+        proof
+            .verify(&tree.hasher, new_root_hash, old_root_hash)
+            .unwrap_err();
+
+        let proof = tree.version_diff_proof(1, 1).unwrap();
+        assert!(proof.changes.is_empty());
+        proof
+            .verify(&tree.hasher, new_root_hash, new_root_hash)
+            .unwrap();
+    }
+
     #[test]
     fn entries_in_single_node_tree() {
         let mut tree = MerkleTree::new(PatchSet::default());
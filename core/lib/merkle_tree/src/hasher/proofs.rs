@@ -3,10 +3,12 @@
 use std::mem;
 
 use crate::{
+    errors::ProofVerificationError,
     hasher::{HashTree, HasherWithStats},
     types::{
-        BlockOutputWithProofs, Key, LeafNode, TreeEntry, TreeEntryWithProof, TreeInstruction,
-        TreeLogEntry, ValueHash, TREE_DEPTH,
+        BlockOutputWithProofs, Key, LeafNode, TreeEntry, TreeEntryDiff, TreeEntryWithProof,
+        TreeInstruction, TreeLogEntry, TreeNonMembershipProof, TreeVersionDiffProof, ValueHash,
+        TREE_DEPTH,
     },
     utils,
 };
@@ -78,6 +80,96 @@ impl TreeEntryWithProof {
     }
 }
 
+impl TreeNonMembershipProof {
+    /// Verifies this proof against the trusted root hash of the tree. This method doesn't require
+    /// access to the tree and can be used by light clients.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof doesn't verify.
+    pub fn verify(
+        &self,
+        hasher: &dyn HashTree,
+        trusted_root_hash: ValueHash,
+    ) -> Result<(), ProofVerificationError> {
+        check_merkle_path(&self.merkle_path)?;
+        let root_hash = hasher.fold_merkle_path(&self.merkle_path, TreeEntry::empty(self.key));
+        check_root_hash(root_hash, trusted_root_hash)
+    }
+}
+
+impl TreeEntryDiff {
+    /// Checks this diff against the current root hash and returns the root hash after the diff is applied.
+    fn apply(
+        &self,
+        hasher: &dyn HashTree,
+        root_hash: ValueHash,
+    ) -> Result<ValueHash, ProofVerificationError> {
+        let (old, new) = (&self.old, &self.new);
+        if old.key != new.key {
+            return Err(ProofVerificationError::KeyMismatch {
+                old: old.key,
+                new: new.key,
+            });
+        }
+        for entry in [old, new] {
+            if entry.leaf_index == 0 && !entry.value.is_zero() {
+                return Err(ProofVerificationError::InvalidMissingValue(entry.key));
+            }
+        }
+        if new.is_empty() && !old.is_empty() {
+            return Err(ProofVerificationError::RemovedEntry(new.key));
+        }
+        check_merkle_path(&self.merkle_path)?;
+
+        let old_root_hash = hasher.fold_merkle_path(&self.merkle_path, *old);
+        check_root_hash(old_root_hash, root_hash)?;
+        Ok(hasher.fold_merkle_path(&self.merkle_path, *new))
+    }
+}
+
+impl TreeVersionDiffProof {
+    /// Verifies this proof against the trusted root hashes of the older and newer tree versions.
+    /// This method doesn't require access to the tree and can be used by light clients.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof doesn't verify.
+    pub fn verify(
+        &self,
+        hasher: &dyn HashTree,
+        trusted_old_root_hash: ValueHash,
+        trusted_new_root_hash: ValueHash,
+    ) -> Result<(), ProofVerificationError> {
+        let mut root_hash = trusted_old_root_hash;
+        let mut prev_key = None;
+        for diff in &self.changes {
+            let key = diff.new.key;
+            if prev_key.map_or(false, |prev_key| prev_key >= key) {
+                return Err(ProofVerificationError::UnorderedChanges(key));
+            }
+            prev_key = Some(key);
+            root_hash = diff.apply(hasher, root_hash)?;
+        }
+        check_root_hash(root_hash, trusted_new_root_hash)
+    }
+}
+
+fn check_merkle_path(merkle_path: &[ValueHash]) -> Result<(), ProofVerificationError> {
+    if merkle_path.len() > TREE_DEPTH {
+        return Err(ProofVerificationError::MerklePathTooLong(merkle_path.len()));
+    }
+    Ok(())
+}
+
+fn check_root_hash(computed: ValueHash, expected: ValueHash) -> Result<(), ProofVerificationError> {
+    if computed == expected {
+        Ok(())
+    } else {
+        Err(ProofVerificationError::RootHashMismatch { expected, computed })
+    }
+}
+
 /// Range digest in a Merkle tree allowing to compute its root hash based on the provided entries.
 ///
 /// - The entries must be ordered by key. I.e., the first entry must have the numerically smallest key,
@@ -49,7 +49,8 @@
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 
 pub use crate::{
-    errors::NoVersionError,
+    consistency::{ConsistencyCheckOptions, ConsistencyError, ConsistencyReport},
+    errors::{NoVersionError, ProofVerificationError},
     hasher::{HashTree, TreeRangeDigest},
     pruning::{MerkleTreePruner, MerkleTreePrunerHandle},
     storage::{
@@ -57,11 +58,12 @@ pub use crate::{
         RocksDBWrapper,
     },
     types::{
-        BlockOutput, BlockOutputWithProofs, Key, TreeEntry, TreeEntryWithProof, TreeInstruction,
-        TreeLogEntry, TreeLogEntryWithProof, ValueHash,
+        BlockOutput, BlockOutputWithProofs, Key, TreeEntry, TreeEntryDiff, TreeEntryWithProof,
+        TreeInstruction, TreeLogEntry, TreeLogEntryWithProof, TreeNonMembershipProof,
+        TreeVersionDiffProof, ValueHash,
     },
 };
-use crate::{hasher::HasherWithStats, storage::Storage, types::Root};
+use crate::{hasher::HasherWithStats, metrics::GENERAL_METRICS, storage::Storage, types::Root};
 
 mod consistency;
 pub mod domain;
@@ -237,6 +239,7 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         let storage = Storage::new(&self.db, &self.hasher, next_version, true);
         let (output, patch) = storage.extend_with_proofs(instructions);
         self.db.apply_patch(patch);
+        GENERAL_METRICS.leaf_count.set(output.leaf_count);
         output
     }
 }
@@ -7,7 +7,8 @@ use std::{
 };
 
 use vise::{
-    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Global, Histogram, Metrics, Unit,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Global, Histogram, Metrics,
+    Unit,
 };
 
 use crate::types::Nibbles;
@@ -359,3 +360,54 @@ pub(crate) struct PruningTimings {
 
 #[vise::register]
 pub(crate) static PRUNING_TIMINGS: Global<PruningTimings> = Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(crate) enum RecoveryKind {
+    Linear,
+    Random,
+    Parallel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum RecoveryStage {
+    Extend,
+    ApplyPatch,
+}
+
+/// Key partition used in parallel recovery, identified by the first nibble of the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "partition")]
+pub(crate) struct RecoveryPartition(pub u8);
+
+impl fmt::Display for RecoveryPartition {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{:x}", self.0)
+    }
+}
+
+const ENTRY_COUNT_BUCKETS: Buckets = Buckets::exponential(1_000.0..=1_000_000.0, 4.0);
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "merkle_tree_recovery")]
+pub(crate) struct RecoveryMetrics {
+    /// Total number of entries recovered, grouped by the recovery kind. Recovery throughput
+    /// can be estimated as the rate of this counter.
+    pub entries: Family<RecoveryKind, Counter>,
+    /// Number of entries in a single recovery chunk.
+    #[metrics(buckets = ENTRY_COUNT_BUCKETS)]
+    pub chunk_size: Family<RecoveryKind, Histogram<usize>>,
+    /// Latency of processing a single recovery chunk, grouped by the processing stage.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub chunk_latency: Family<RecoveryStage, Histogram<Duration>>,
+    /// Total number of entries recovered in each key partition during parallel recovery.
+    pub partition_entries: Family<RecoveryPartition, Counter>,
+    /// Time spent traversing the subtree for a single key partition per recovery chunk
+    /// during parallel recovery. Allows to estimate partition imbalance.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub partition_latency: Family<RecoveryPartition, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(crate) static RECOVERY_METRICS: Global<RecoveryMetrics> = Global::new();
@@ -24,6 +24,15 @@
 //! The recovery process is tolerant to crashes and may be resumed from the middle. To find the latest
 //! recovered key, you may use [`MerkleTreeRecovery::last_processed_key()`].
 //!
+//! # Parallel recovery
+//!
+//! Recovery can be parallelized using [`MerkleTreeRecovery::extend_parallel()`]. In this case,
+//! the key space is split into [`PARTITION_COUNT`] partitions by the first nibble of the key
+//! (i.e., the partitions correspond to subtrees with roots at level 4); see [`partition_key_range()`].
+//! Each partition is recovered linearly, independently of other partitions, and partitions
+//! are processed on separate threads. Recovery progress is tracked for each partition separately;
+//! it can be obtained using [`MerkleTreeRecovery::last_processed_keys()`].
+//!
 //! `RecoveryEntry` chunks are not validated during recovery. They can be authenticated using
 //! [`TreeRangeDigest`](crate::TreeRangeDigest)s provided that the tree root hash is authenticated
 //! using external means.
@@ -35,17 +44,43 @@
 //! before extending the tree; these nodes are guaranteed to be the *only* DB reads necessary
 //! to insert new entries.
 
-use std::time::Instant;
+use std::{ops, time::Instant};
 
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 
 use crate::{
     hasher::{HashTree, HasherWithStats},
-    storage::{PatchSet, PruneDatabase, PrunePatchSet, Storage},
+    metrics::{RecoveryKind, RecoveryStage, RECOVERY_METRICS},
+    storage::{PatchSet, PruneDatabase, PrunePatchSet, Storage, SUBTREE_COUNT},
     types::{Key, Manifest, Root, TreeEntry, TreeTags, ValueHash},
     MerkleTree,
 };
 
+/// Number of key partitions used in [parallel recovery](MerkleTreeRecovery::extend_parallel()).
+pub const PARTITION_COUNT: usize = SUBTREE_COUNT;
+
+/// Returns the index of the key partition used in [parallel recovery](MerkleTreeRecovery::extend_parallel())
+/// that the specified `key` belongs to.
+pub fn key_partition(key: &Key) -> usize {
+    (*key >> 252).as_usize()
+}
+
+/// Returns the inclusive key range for the specified key partition used
+/// in [parallel recovery](MerkleTreeRecovery::extend_parallel()).
+///
+/// # Panics
+///
+/// Panics if `partition >= PARTITION_COUNT`.
+pub fn partition_key_range(partition: usize) -> ops::RangeInclusive<Key> {
+    assert!(
+        partition < PARTITION_COUNT,
+        "Partition index {partition} is out of bounds"
+    );
+    let start = Key::from(partition) << 252;
+    let end = start | (Key::MAX >> 4);
+    start..=end
+}
+
 /// Handle to a Merkle tree during its recovery.
 #[derive(Debug)]
 pub struct MerkleTreeRecovery<DB, H = Blake2Hasher> {
@@ -126,6 +161,14 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
         storage.greatest_key()
     }
 
+    /// Returns the last key processed during the recovery process for each of [`PARTITION_COUNT`]
+    /// key partitions. This information should be used to resume [parallel recovery](Self::extend_parallel())
+    /// after a restart.
+    pub fn last_processed_keys(&self) -> [Option<Key>; PARTITION_COUNT] {
+        let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
+        storage.greatest_keys_by_subtree()
+    }
+
     /// Extends a tree with a chunk of linearly ordered entries.
     ///
     /// Entries must be ordered by increasing `key`, and the key of the first entry must be greater
@@ -146,14 +189,13 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
     pub fn extend_linear(&mut self, entries: Vec<TreeEntry>) {
         tracing::debug!("Started extending tree");
 
+        let entry_count = entries.len();
         let started_at = Instant::now();
         let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
         let patch = storage.extend_during_linear_recovery(entries);
         tracing::debug!("Finished processing keys; took {:?}", started_at.elapsed());
 
-        let started_at = Instant::now();
-        self.db.apply_patch(patch);
-        tracing::debug!("Finished persisting to DB; took {:?}", started_at.elapsed());
+        self.apply_patch(patch, RecoveryKind::Linear, entry_count, started_at);
     }
 
     /// Extends a tree with a chunk of entries. Unlike [`Self::extend_linear()`], entries may be
@@ -169,14 +211,65 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
     pub fn extend_random(&mut self, entries: Vec<TreeEntry>) {
         tracing::debug!("Started extending tree");
 
+        let entry_count = entries.len();
         let started_at = Instant::now();
         let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
         let patch = storage.extend_during_random_recovery(entries);
         tracing::debug!("Finished processing keys; took {:?}", started_at.elapsed());
 
+        self.apply_patch(patch, RecoveryKind::Random, entry_count, started_at);
+    }
+
+    /// Extends a tree with a chunk of entries, processing [`PARTITION_COUNT`] key partitions
+    /// in parallel.
+    ///
+    /// Entries must be ordered by increasing `key` within each partition (but not necessarily
+    /// across partitions), and the key of the first entry in each partition must be greater
+    /// than the corresponding key returned by [`Self::last_processed_keys()`]. A chunk may contain
+    /// entries only for some of partitions, i.e., partitions may be recovered at different paces.
+    ///
+    /// All changes from a chunk are persisted atomically, so recovery progress for all partitions
+    /// survives restarts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if entry keys within a partition are not correctly ordered.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            recovered_version = self.recovered_version,
+            entries.len = entries.len(),
+        ),
+    )]
+    pub fn extend_parallel(&mut self, entries: Vec<TreeEntry>) {
+        tracing::debug!("Started extending tree");
+
+        let entry_count = entries.len();
         let started_at = Instant::now();
+        let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
+        let patch = storage.extend_during_parallel_recovery(entries);
+        tracing::debug!("Finished processing keys; took {:?}", started_at.elapsed());
+
+        self.apply_patch(patch, RecoveryKind::Parallel, entry_count, started_at);
+    }
+
+    fn apply_patch(
+        &mut self,
+        patch: PatchSet,
+        kind: RecoveryKind,
+        entry_count: usize,
+        extend_started_at: Instant,
+    ) {
+        RECOVERY_METRICS.chunk_latency[&RecoveryStage::Extend].observe(extend_started_at.elapsed());
+
+        let latency = RECOVERY_METRICS.chunk_latency[&RecoveryStage::ApplyPatch].start();
         self.db.apply_patch(patch);
-        tracing::debug!("Finished persisting to DB; took {:?}", started_at.elapsed());
+        let latency = latency.observe();
+        tracing::debug!("Finished persisting to DB; took {latency:?}");
+
+        RECOVERY_METRICS.chunk_size[&kind].observe(entry_count);
+        RECOVERY_METRICS.entries[&kind].inc_by(entry_count as u64);
     }
 
     /// Finalizes the recovery process marking it as complete in the tree manifest.
@@ -266,6 +359,37 @@ mod tests {
         assert_eq!(tree.root(42), Some(Root::Empty));
     }
 
+    #[test]
+    fn key_partitions() {
+        for partition in 0..PARTITION_COUNT {
+            let range = partition_key_range(partition);
+            assert_eq!(key_partition(range.start()), partition);
+            assert_eq!(key_partition(range.end()), partition);
+            if partition > 0 {
+                let prev_range = partition_key_range(partition - 1);
+                assert_eq!(*prev_range.end() + 1, *range.start());
+            }
+        }
+        assert_eq!(*partition_key_range(0).start(), Key::zero());
+        assert_eq!(*partition_key_range(PARTITION_COUNT - 1).end(), Key::MAX);
+    }
+
+    #[test]
+    fn parallel_recovery_of_tree_with_single_node() {
+        let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 42);
+        let recovery_entry = TreeEntry::new(Key::from(123), 1, ValueHash::repeat_byte(1));
+        recovery.extend_parallel(vec![recovery_entry]);
+        assert_eq!(recovery.last_processed_keys()[0], Some(recovery_entry.key));
+        let tree = recovery.finalize();
+
+        let mut hasher = HasherWithStats::new(&Blake2Hasher);
+        assert_eq!(
+            tree.latest_root_hash(),
+            LeafNode::new(recovery_entry).hash(&mut hasher, 0)
+        );
+        tree.verify_consistency(42, true).unwrap();
+    }
+
     #[test]
     fn recovering_tree_with_single_node() {
         let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 42);
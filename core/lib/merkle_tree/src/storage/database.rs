@@ -147,6 +147,13 @@ impl Database for PatchSet {
                 let other_patch = other.patches_by_version.remove(&updated_version).unwrap();
                 // ^ `unwrap()`s are safe by design.
                 patch.merge(other_patch);
+            } else if let Some(patch) = self.patches_by_version.get_mut(&other_updated_version) {
+                // An inserted version is updated in place (e.g., when the tree is repaired).
+                let other_patch = other
+                    .patches_by_version
+                    .remove(&other_updated_version)
+                    .unwrap();
+                patch.merge(other_patch);
             } else {
                 assert!(
                     self.patches_by_version.keys().all(|&ver| ver > other_updated_version),
@@ -364,6 +371,11 @@ pub trait PruneDatabase: Database {
     /// Returns a list of node keys obsoleted in the specified `version` of the tree.
     fn stale_keys(&self, version: u64) -> Vec<NodeKey>;
 
+    /// Returns keys of all nodes (including the root node) created in the specified `version`
+    /// of the tree. This is a diagnostic method used to detect orphaned nodes; it may be slow
+    /// for versions with many nodes.
+    fn node_keys(&self, version: u64) -> Vec<NodeKey>;
+
     /// Atomically prunes the tree and updates information about the minimum retained version.
     fn prune(&mut self, patch: PrunePatchSet);
 }
@@ -377,6 +389,10 @@ impl<T: PruneDatabase + ?Sized> PruneDatabase for &mut T {
         (**self).stale_keys(version)
     }
 
+    fn node_keys(&self, version: u64) -> Vec<NodeKey> {
+        (**self).node_keys(version)
+    }
+
     fn prune(&mut self, patch: PrunePatchSet) {
         (**self).prune(patch);
     }
@@ -397,6 +413,17 @@ impl PruneDatabase for PatchSet {
             .unwrap_or_default()
     }
 
+    fn node_keys(&self, version: u64) -> Vec<NodeKey> {
+        let Some(patch) = self.patches_by_version.get(&version) else {
+            return vec![];
+        };
+        let root_key = patch.root.as_ref().map(|_| NodeKey::empty(version));
+        root_key
+            .into_iter()
+            .chain(patch.nodes.keys().copied())
+            .collect()
+    }
+
     fn prune(&mut self, patch: PrunePatchSet) {
         for key in &patch.pruned_node_keys {
             let Some(patch) = self.patches_by_version.get_mut(&key.version) else {
@@ -1,16 +1,23 @@
 //! Storage-related logic.
 
-pub(crate) use self::patch::{LoadAncestorsResult, WorkingPatchSet};
+use rayon::prelude::*;
+
 pub use self::{
     database::{Database, NodeKeys, Patched, PruneDatabase, PrunePatchSet},
     patch::PatchSet,
     rocksdb::{MerkleTreeColumnFamily, RocksDBWrapper},
 };
+pub(crate) use self::{
+    patch::{LoadAncestorsResult, WorkingPatchSet},
+    proofs::SUBTREE_COUNT,
+};
 use crate::{
     hasher::HashTree,
-    metrics::{TreeUpdaterStats, BLOCK_TIMINGS, GENERAL_METRICS},
+    metrics::{
+        RecoveryPartition, TreeUpdaterStats, BLOCK_TIMINGS, GENERAL_METRICS, RECOVERY_METRICS,
+    },
     types::{
-        BlockOutput, ChildRef, InternalNode, Key, LeafNode, Manifest, Nibbles, Node, Root,
+        BlockOutput, ChildRef, InternalNode, Key, LeafNode, Manifest, Nibbles, Node, NodeKey, Root,
         TreeEntry, TreeLogEntry, TreeTags, ValueHash,
     },
 };
@@ -106,6 +113,52 @@ impl TreeUpdater {
         Some((leaf, load_result.longest_prefixes[0]))
     }
 
+    /// Loads the greatest key in the subtree with the root at the specified first nibble.
+    /// The root node must be an internal node.
+    fn load_greatest_key_in_subtree<DB: Database + ?Sized>(
+        &mut self,
+        db: &DB,
+        first_nibble: u8,
+    ) -> Option<(LeafNode, Nibbles)> {
+        let subtree_nibbles = Nibbles::single(first_nibble);
+        let (leaf, load_result) = self
+            .patch_set
+            .load_greatest_key_in_subtree(db, subtree_nibbles)?;
+        self.metrics.db_reads += load_result.db_reads;
+        assert_eq!(load_result.longest_prefixes.len(), 1);
+        Some((leaf, load_result.longest_prefixes[0]))
+    }
+
+    /// Inserts linearly ordered `entries` into the tree. `greatest_leaf` is the leaf with
+    /// the greatest key (together with its nibbles) in the part of the tree that `entries`
+    /// are inserted into; all ancestors of this leaf must be loaded into the patch set.
+    fn insert_linearly(
+        &mut self,
+        greatest_leaf: Option<(LeafNode, Nibbles)>,
+        entries: Vec<TreeEntry>,
+    ) {
+        let (mut prev_key, mut prev_nibbles) = match greatest_leaf {
+            Some((leaf, nibbles)) => (Some(leaf.full_key), nibbles),
+            None => (None, Nibbles::EMPTY),
+        };
+
+        for entry in entries {
+            if let Some(prev_key) = prev_key {
+                assert!(
+                    entry.key > prev_key,
+                    "Recovery entries must be ordered by increasing key (previous key: {prev_key:0>64x}, \
+                     offending entry: {entry:?})"
+                );
+            }
+            prev_key = Some(entry.key);
+
+            let key_nibbles = Nibbles::new(&entry.key, prev_nibbles.nibble_count());
+            let parent_nibbles = prev_nibbles.common_prefix(&key_nibbles);
+            let (_, new_leaf) = self.insert(entry, &parent_nibbles);
+            prev_nibbles = new_leaf.nibbles;
+        }
+    }
+
     /// Inserts or updates a value hash for the specified `key`. This implementation
     /// is almost verbatim the algorithm described in the Jellyfish Merkle tree white paper.
     /// The algorithm from the paper is as follows:
@@ -316,28 +369,12 @@ impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
     }
 
     pub fn extend_during_linear_recovery(mut self, recovery_entries: Vec<TreeEntry>) -> PatchSet {
-        let (mut prev_key, mut prev_nibbles) = match self.updater.load_greatest_key(self.db) {
-            Some((leaf, nibbles)) => (Some(leaf.full_key), nibbles),
-            None => (None, Nibbles::EMPTY),
-        };
+        let greatest_leaf = self.updater.load_greatest_key(self.db);
 
         let extend_patch_latency = BLOCK_TIMINGS.extend_patch.start();
-        for entry in recovery_entries {
-            if let Some(prev_key) = prev_key {
-                assert!(
-                    entry.key > prev_key,
-                    "Recovery entries must be ordered by increasing key (previous key: {prev_key:0>64x}, \
-                     offending entry: {entry:?})"
-                );
-            }
-            prev_key = Some(entry.key);
-
-            let key_nibbles = Nibbles::new(&entry.key, prev_nibbles.nibble_count());
-            let parent_nibbles = prev_nibbles.common_prefix(&key_nibbles);
-            let (_, new_leaf) = self.updater.insert(entry, &parent_nibbles);
-            prev_nibbles = new_leaf.nibbles;
-            self.leaf_count += 1;
-        }
+        self.leaf_count += recovery_entries.len() as u64;
+        self.updater
+            .insert_linearly(greatest_leaf, recovery_entries);
         let extend_patch_latency = extend_patch_latency.observe();
         tracing::debug!("Tree traversal stage took {extend_patch_latency:?}");
 
@@ -364,6 +401,168 @@ impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
         patch
     }
 
+    /// Returns the greatest key in each of [`SUBTREE_COUNT`] subtrees of the tree.
+    pub fn greatest_keys_by_subtree(mut self) -> [Option<Key>; SUBTREE_COUNT] {
+        self.updater.patch_set.ensure_internal_root_node();
+        let mut greatest_keys = [None; SUBTREE_COUNT];
+        for (i, greatest_key) in greatest_keys.iter_mut().enumerate() {
+            let first_nibble = u8::try_from(i).unwrap();
+            *greatest_key = self
+                .updater
+                .load_greatest_key_in_subtree(self.db, first_nibble)
+                .map(|(leaf, _)| leaf.full_key);
+        }
+        greatest_keys
+    }
+
+    /// Extends the tree during recovery, processing subtrees in parallel. `recovery_entries` are split
+    /// into [`SUBTREE_COUNT`] groups by the first key nibble; each group is inserted into the tree
+    /// as in [`Self::extend_during_linear_recovery()`], but on a separate thread. Thus, entries
+    /// must be ordered by increasing key only within each group.
+    ///
+    /// Groups are mostly independent since they are inserted into disjoint subtrees with roots
+    /// at level 4 (= 1 nibble). The only shared node is the tree root; it is merged by copying
+    /// child references for each subtree from the corresponding patch set.
+    pub fn extend_during_parallel_recovery(mut self, recovery_entries: Vec<TreeEntry>) -> PatchSet {
+        self.leaf_count += recovery_entries.len() as u64;
+        let entry_parts = split_entries_by_subtree(recovery_entries);
+        let (_, patch) = self.extend_subtrees_in_parallel(entry_parts).finalize();
+        patch
+    }
+
+    /// Rebuilds the specified subtrees (identified by the first key nibble) from scratch. `entries`
+    /// must contain all leaves in these subtrees and no other leaves. Nodes of the replaced subtrees
+    /// are registered as stale in the updated version, so that they are eventually pruned.
+    /// The leaf count at the tree root is recomputed by traversing the retained subtrees.
+    ///
+    /// Unlike other operations, this one doesn't assume that the replaced subtrees are consistent;
+    /// nodes that are missing or cannot be deserialized are skipped.
+    pub fn rebuild_subtrees(
+        mut self,
+        first_nibbles: &[u8],
+        mut entries: Vec<TreeEntry>,
+    ) -> (ValueHash, PatchSet) {
+        let version = self.updater.patch_set.root_version();
+        let root = match self.updater.patch_set.get(&Nibbles::EMPTY) {
+            Some(Node::Leaf(leaf))
+                if first_nibbles.contains(&Nibbles::nibble(&leaf.full_key, 0)) =>
+            {
+                InternalNode::default() // The only leaf in the tree is rebuilt
+            }
+            _ => self.updater.patch_set.ensure_internal_root_node(),
+        };
+        let mut retained_root = InternalNode::with_capacity(root.child_count());
+        let mut replaced_keys = vec![];
+        for (nibble, child_ref) in root.children() {
+            if first_nibbles.contains(&nibble) {
+                let child_key = Nibbles::single(nibble).with_version(child_ref.version);
+                collect_subtree_keys(self.db, child_key, child_ref.is_leaf, &mut replaced_keys);
+            } else {
+                retained_root.insert_child_ref(nibble, *child_ref);
+            }
+        }
+        // The leaf count specified at the root may be incorrect, so we recompute it.
+        let retained_leaf_count: u64 = retained_root
+            .children()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(nibble, child_ref)| {
+                let child_key = Nibbles::single(nibble).with_version(child_ref.version);
+                count_subtree_leaves(self.db, child_key, child_ref.is_leaf)
+            })
+            .sum();
+        self.leaf_count = retained_leaf_count + entries.len() as u64;
+        self.updater.set_root_node(retained_root.into());
+
+        entries.sort_unstable_by_key(|entry| entry.key);
+        let entry_parts = split_entries_by_subtree(entries);
+        for (i, part) in entry_parts.iter().enumerate() {
+            let first_nibble = u8::try_from(i).unwrap();
+            assert!(
+                part.is_empty() || first_nibbles.contains(&first_nibble),
+                "Entries for subtree {first_nibble:x} were supplied, but the subtree is not rebuilt"
+            );
+        }
+        let (root_hash, mut patch) = self.extend_subtrees_in_parallel(entry_parts).finalize();
+        if let Some(partial_patch) = patch.patches_by_version.get(&version) {
+            // Nodes overwritten by the rebuilt subtrees must not be pruned.
+            replaced_keys
+                .retain(|key| key.version < version || !partial_patch.nodes.contains_key(key));
+        }
+        patch
+            .stale_keys_by_version
+            .entry(version)
+            .or_default()
+            .extend(replaced_keys);
+        (root_hash, patch)
+    }
+
+    /// Inserts `entry_parts` into the corresponding subtrees in parallel. Nodes are loaded
+    /// from the database as necessary.
+    fn extend_subtrees_in_parallel(mut self, entry_parts: [Vec<TreeEntry>; SUBTREE_COUNT]) -> Self {
+        let load_nodes_latency = BLOCK_TIMINGS.load_nodes.start();
+        let mut root = self.updater.patch_set.ensure_internal_root_node();
+        let initial_metrics = self.updater.metrics;
+        let mut storage_parts = self.updater.split();
+        let greatest_leaves: Vec<_> = storage_parts
+            .iter_mut()
+            .zip(&entry_parts)
+            .enumerate()
+            .map(|(i, (storage, entries))| {
+                if entries.is_empty() {
+                    return None; // No need to load nodes for the subtree
+                }
+                let first_nibble = u8::try_from(i).unwrap();
+                storage.load_greatest_key_in_subtree(self.db, first_nibble)
+            })
+            .collect();
+        let load_nodes_latency = load_nodes_latency.observe();
+        tracing::debug!("Load stage took {load_nodes_latency:?}");
+
+        let extend_patch_latency = BLOCK_TIMINGS.extend_patch.start();
+        // `into_par_iter()` below uses `rayon` to parallelize tree traversal.
+        let storage_parts: Vec<_> = storage_parts
+            .into_par_iter()
+            .zip_eq(entry_parts)
+            .zip_eq(greatest_leaves)
+            .enumerate()
+            .map(|(i, ((mut storage, entries), greatest_leaf))| {
+                if entries.is_empty() {
+                    return storage;
+                }
+                let partition = RecoveryPartition(u8::try_from(i).unwrap());
+                let latency = RECOVERY_METRICS.partition_latency[&partition].start();
+                RECOVERY_METRICS.partition_entries[&partition].inc_by(entries.len() as u64);
+                storage.insert_linearly(greatest_leaf, entries);
+                latency.observe();
+                storage
+            })
+            .collect();
+
+        for (i, storage) in storage_parts.iter().enumerate() {
+            let first_nibble = u8::try_from(i).unwrap();
+            if let Some(child_ref) = storage.patch_set.child_ref(&Nibbles::EMPTY, first_nibble) {
+                root.insert_child_ref(first_nibble, *child_ref);
+            }
+        }
+        self.updater = storage_parts
+            .into_iter()
+            .reduce(TreeUpdater::merge)
+            .unwrap();
+        // ^ `unwrap()` is safe: `storage_parts` is non-empty
+        self.updater.metrics += initial_metrics;
+        if root.child_count() == 0 {
+            // We cannot save the empty internal root node because it'll fail deserialization
+            // checks later.
+            self.updater.patch_set.take_root();
+        } else {
+            self.updater.set_root_node(root.into());
+        }
+        let extend_patch_latency = extend_patch_latency.observe();
+        tracing::debug!("Tree traversal stage took {extend_patch_latency:?}");
+        self
+    }
+
     fn finalize(self) -> (ValueHash, PatchSet) {
         tracing::debug!(
             "Finished updating tree; total leaf count: {}, stats: {:?}",
@@ -404,6 +603,52 @@ impl SortedKeys {
     }
 }
 
+/// Collects keys of all nodes in the subtree with the specified root, skipping missing nodes
+/// and nodes that cannot be deserialized.
+fn collect_subtree_keys<DB: Database + ?Sized>(
+    db: &DB,
+    key: NodeKey,
+    is_leaf: bool,
+    keys: &mut Vec<NodeKey>,
+) {
+    keys.push(key);
+    if let Ok(Some(Node::Internal(node))) = db.try_tree_node(&key, is_leaf) {
+        for (nibble, child_ref) in node.children() {
+            if let Some(child_nibbles) = key.nibbles.push(nibble) {
+                let child_key = child_nibbles.with_version(child_ref.version);
+                collect_subtree_keys(db, child_key, child_ref.is_leaf, keys);
+            }
+        }
+    }
+}
+
+/// Counts leaves in the subtree with the specified root, skipping missing nodes and nodes
+/// that cannot be deserialized.
+fn count_subtree_leaves<DB: Database + ?Sized>(db: &DB, key: NodeKey, is_leaf: bool) -> u64 {
+    if is_leaf {
+        return 1;
+    }
+    let Ok(Some(Node::Internal(node))) = db.try_tree_node(&key, false) else {
+        return 0;
+    };
+    node.children()
+        .filter_map(|(nibble, child_ref)| {
+            let child_key = key.nibbles.push(nibble)?.with_version(child_ref.version);
+            Some(count_subtree_leaves(db, child_key, child_ref.is_leaf))
+        })
+        .sum()
+}
+
+/// Splits `entries` by the first key nibble, retaining the relative entry order in each group.
+fn split_entries_by_subtree(entries: Vec<TreeEntry>) -> [Vec<TreeEntry>; SUBTREE_COUNT] {
+    let mut parts = [(); SUBTREE_COUNT].map(|()| vec![]);
+    for entry in entries {
+        let first_nibble = Nibbles::nibble(&entry.key, 0);
+        parts[first_nibble as usize].push(entry);
+    }
+    parts
+}
+
 /// Outcome of traversing a tree for a specific key.
 #[derive(Debug)]
 enum TraverseOutcome {
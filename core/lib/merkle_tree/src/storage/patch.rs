@@ -152,6 +152,15 @@ impl PatchSet {
         let patch = self.patches_by_version.get_mut(&key.version).unwrap();
         patch.nodes.remove(key);
     }
+
+    pub(crate) fn insert_node(&mut self, key: NodeKey, node: Node) {
+        let patch = self.patches_by_version.get_mut(&key.version).unwrap();
+        patch.nodes.insert(key, node);
+    }
+
+    pub(crate) fn stale_keys_mut(&mut self, version: u64) -> &mut Vec<NodeKey> {
+        self.stale_keys_by_version.entry(version).or_default()
+    }
 }
 
 /// [`Node`] together with a flag indicating whether it was changed in the tree version
@@ -614,6 +623,56 @@ impl WorkingPatchSet {
         Some((greatest_leaf, result))
     }
 
+    /// Loads the greatest key in the subtree with the root at `subtree_nibbles`. The parent
+    /// of the subtree root must be present in this patch set.
+    pub fn load_greatest_key_in_subtree<DB: Database + ?Sized>(
+        &mut self,
+        db: &DB,
+        subtree_nibbles: Nibbles,
+    ) -> Option<(LeafNode, LoadAncestorsResult)> {
+        let mut nibbles = subtree_nibbles;
+        let mut db_reads = 0;
+        let greatest_leaf = loop {
+            if self.get(&nibbles).is_none() {
+                let (parent_nibbles, last_nibble) = nibbles.split_last()?;
+                let child_ref = *self.child_ref(&parent_nibbles, last_nibble)?;
+                // ^ If the parent node doesn't have a child ref, the subtree is empty.
+                let child_key = nibbles.with_version(child_ref.version);
+                let child_node = db.tree_node(&child_key, child_ref.is_leaf).unwrap();
+                // ^ `unwrap()` is safe by construction
+                self.insert_from_db(&child_key, child_node);
+                db_reads += 1;
+            }
+
+            match self.get(&nibbles).unwrap() {
+                Node::Leaf(leaf) => break *leaf,
+                Node::Internal(node) => {
+                    let (next_nibble, _) = node.last_child_ref();
+                    nibbles = nibbles.push(next_nibble).unwrap();
+                    // ^ `unwrap()` is safe; there can be no internal nodes on the bottom-most tree level
+                }
+            }
+        };
+
+        let result = LoadAncestorsResult {
+            longest_prefixes: vec![nibbles],
+            db_reads,
+        };
+        Some((greatest_leaf, result))
+    }
+
+    /// Inserts a node loaded from the DB. Unlike [`Self::push_level_from_db()`], the node
+    /// may be inserted at any level. The node is not marked as changed.
+    fn insert_from_db(&mut self, key: &NodeKey, node: Node) {
+        let nibble_count = key.nibbles.nibble_count();
+        if nibble_count >= self.changes_by_nibble_count.len() {
+            self.changes_by_nibble_count
+                .resize_with(nibble_count + 1, HashMap::new);
+        }
+        let node = WorkingNode::new(node, Some(key.version));
+        self.changes_by_nibble_count[nibble_count].insert(*key.nibbles.bytes(), node);
+    }
+
     /// Creates a Merkle proof for the specified `key`, which has given `parent_nibbles`
     /// in this patch set. `root_nibble_count` specifies to which level the proof needs to be constructed.
     pub(crate) fn create_proof(
@@ -42,7 +42,7 @@ use rayon::prelude::*;
 
 use crate::{
     hasher::{HasherWithStats, MerklePath},
-    metrics::{HashingStats, TreeUpdaterStats, BLOCK_TIMINGS, GENERAL_METRICS},
+    metrics::{HashingStats, TreeUpdaterStats, BLOCK_TIMINGS},
     storage::{Database, NewLeafData, PatchSet, SortedKeys, Storage, TreeUpdater},
     types::{
         BlockOutputWithProofs, InternalNode, Key, Nibbles, Node, TreeInstruction, TreeLogEntry,
@@ -52,7 +52,7 @@ use crate::{
 };
 
 /// Number of subtrees used for parallel computations.
-pub(super) const SUBTREE_COUNT: usize = 16;
+pub(crate) const SUBTREE_COUNT: usize = 16;
 /// 0-based tree level at which subtree roots are located.
 const SUBTREE_ROOT_LEVEL: usize = 4;
 
@@ -171,14 +171,14 @@ impl TreeUpdater {
         (operation, merkle_path)
     }
 
-    fn split(self) -> [Self; SUBTREE_COUNT] {
+    pub(super) fn split(self) -> [Self; SUBTREE_COUNT] {
         self.patch_set.split().map(|patch_set| Self {
             metrics: TreeUpdaterStats::default(),
             patch_set,
         })
     }
 
-    fn merge(mut self, other: Self) -> Self {
+    pub(super) fn merge(mut self, other: Self) -> Self {
         self.patch_set.merge(other.patch_set);
         self.metrics += other.metrics;
         self
@@ -308,7 +308,6 @@ impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
             logs,
             leaf_count: self.leaf_count,
         };
-        GENERAL_METRICS.leaf_count.set(self.leaf_count);
 
         (block_output, patch)
     }
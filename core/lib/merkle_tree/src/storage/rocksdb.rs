@@ -129,6 +129,11 @@ impl RocksDBWrapper {
         })
     }
 
+    /// Returns a reference to the wrapped RocksDB instance.
+    pub fn db(&self) -> &RocksDB<MerkleTreeColumnFamily> {
+        &self.db
+    }
+
     /// Returns the wrapped RocksDB instance.
     pub fn into_inner(self) -> RocksDB<MerkleTreeColumnFamily> {
         self.db
@@ -264,6 +269,16 @@ impl PruneDatabase for RocksDBWrapper {
         keys.collect()
     }
 
+    fn node_keys(&self, version: u64) -> Vec<NodeKey> {
+        let tree_cf = MerkleTreeColumnFamily::Tree;
+        let version_prefix = version.to_be_bytes();
+        let keys = self
+            .db
+            .prefix_iterator_cf(tree_cf, &version_prefix)
+            .map(|entry| NodeKey::from_db_key(&entry.0));
+        keys.collect()
+    }
+
     fn prune(&mut self, patch: PrunePatchSet) {
         let mut write_batch = self.db.new_write_batch();
 
@@ -336,6 +351,30 @@ mod tests {
         assert_contains_exactly_keys(&db, &expected_keys);
     }
 
+    #[test]
+    fn listing_node_keys_by_version() {
+        let dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+        let mut db = RocksDBWrapper::new(dir.path());
+
+        let root = Root::new(2, Node::Internal(InternalNode::default()));
+        let nodes = generate_nodes(0, &[1, 2]);
+        let mut expected_keys: HashSet<_> = nodes.keys().copied().collect();
+        expected_keys.insert(NodeKey::empty(0));
+        let mut patch = create_patch(0, root, nodes);
+        let root = Root::new(3, Node::Internal(InternalNode::default()));
+        let new_nodes = generate_nodes(1, &[3]);
+        let mut new_expected_keys: HashSet<_> = new_nodes.keys().copied().collect();
+        new_expected_keys.insert(NodeKey::empty(1));
+        patch.apply_patch(create_patch(1, root, new_nodes));
+        db.apply_patch(patch);
+
+        let keys: HashSet<_> = db.node_keys(0).into_iter().collect();
+        assert_eq!(keys, expected_keys);
+        let keys: HashSet<_> = db.node_keys(1).into_iter().collect();
+        assert_eq!(keys, new_expected_keys);
+        assert!(db.node_keys(2).is_empty());
+    }
+
     fn assert_contains_exactly_keys(db: &RocksDBWrapper, expected_keys: &HashSet<NodeKey>) {
         let cf = MerkleTreeColumnFamily::Tree;
         let actual_keys: HashSet<_> = db
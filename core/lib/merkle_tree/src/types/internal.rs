@@ -2,7 +2,7 @@
 //! some of these types are declared as public and can be even exported using the `unstable` module.
 //! Still, logically these types are private, so adding them to new public APIs etc. is a logical error.
 
-use std::{fmt, num::NonZeroU64};
+use std::{fmt, num::NonZeroU64, ops};
 
 use crate::{
     hasher::{HashTree, InternalNodeCache},
@@ -173,6 +173,18 @@ impl Nibbles {
         &self.bytes
     }
 
+    /// Returns the range of keys starting with these nibbles.
+    pub fn key_range(&self) -> ops::RangeInclusive<Key> {
+        let start = Key::from_big_endian(&self.bytes);
+        let prefix_bits = self.nibble_count * 4;
+        let end = if prefix_bits == KEY_SIZE * 8 {
+            start
+        } else {
+            start | (Key::MAX >> prefix_bits)
+        };
+        start..=end
+    }
+
     /// Extracts the last nibble and the parent sequence of nibbles
     /// (i.e., one with the last nibble truncated). If this sequence of nibbles is empty,
     /// returns `None`.
@@ -634,6 +646,23 @@ mod tests {
         assert_eq!(nibbles.common_prefix(&diverging_nibbles), Nibbles::EMPTY);
     }
 
+    #[test]
+    fn nibbles_key_range() {
+        assert_eq!(Nibbles::EMPTY.key_range(), Key::zero()..=Key::MAX);
+
+        let nibbles = Nibbles::new(&TEST_KEY, 3);
+        let range = nibbles.key_range();
+        assert_eq!(*range.start(), U256([0, 0, 0, 0x_dea0_0000_0000_0000]));
+        assert_eq!(
+            *range.end(),
+            U256([u64::MAX, u64::MAX, u64::MAX, 0x_deaf_ffff_ffff_ffff])
+        );
+        assert!(range.contains(&TEST_KEY));
+
+        let nibbles = Nibbles::new(&TEST_KEY, 64);
+        assert_eq!(nibbles.key_range(), TEST_KEY..=TEST_KEY);
+    }
+
     #[test]
     fn node_key_serialization() {
         let nibbles = Nibbles::new(&TEST_KEY, 6);
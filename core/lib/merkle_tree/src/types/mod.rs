@@ -125,6 +125,46 @@ pub struct TreeEntryWithProof {
     pub merkle_path: Vec<ValueHash>,
 }
 
+/// Proof that a key is absent from a Merkle tree at a certain version, i.e., that the key
+/// was never inserted into the tree.
+///
+/// Can be verified offline, without access to the tree, using
+/// [`TreeNonMembershipProof::verify()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNonMembershipProof {
+    /// Key proven to be absent from the tree.
+    pub key: Key,
+    /// Merkle path for the empty leaf at `key`. Has the same format as
+    /// [`TreeEntryWithProof::merkle_path`].
+    pub merkle_path: Vec<ValueHash>,
+}
+
+/// Change of a single entry in a Merkle tree together with a Merkle path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntryDiff {
+    /// Entry before the change. If the entry was inserted, this entry is [empty](TreeEntry::is_empty()).
+    pub old: TreeEntry,
+    /// Entry after the change. Has the same key as `old`.
+    pub new: TreeEntry,
+    /// Merkle path for the entry. The path is valid both for the `old` and `new` entries;
+    /// it is specified in the intermediate tree state obtained by applying all preceding changes
+    /// in the proof.
+    pub merkle_path: Vec<ValueHash>,
+}
+
+/// Compact proof of all changes in a Merkle tree between two versions.
+///
+/// Changes are ordered by increasing key. Applying the changes one by one transforms the root hash
+/// of the older tree version into the root hash of the newer version; since each Merkle path
+/// is verified against the intermediate root hash, the proof attests that the tree contains no changes
+/// other than the listed ones. The proof can be verified offline, without access to the tree, using
+/// [`TreeVersionDiffProof::verify()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeVersionDiffProof {
+    /// Changed entries ordered by increasing key.
+    pub changes: Vec<TreeEntryDiff>,
+}
+
 /// Output of inserting a block of entries into a Merkle tree.
 #[derive(Debug, PartialEq, Eq)]
 pub struct BlockOutput {
@@ -6,7 +6,10 @@ use serde::{Deserialize, Serialize};
 use serde_with::{hex::Hex, serde_as};
 use tempfile::TempDir;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
-use zksync_merkle_tree::{domain::ZkSyncTree, HashTree, TreeEntry, TreeInstruction};
+use zksync_merkle_tree::{
+    domain::{CheckpointError, ZkSyncTree},
+    HashTree, TreeEntry, TreeInstruction,
+};
 use zksync_storage::RocksDB;
 use zksync_system_constants::ACCOUNT_CODE_STORAGE_ADDRESS;
 use zksync_types::{
@@ -296,6 +299,105 @@ fn read_logs() {
     assert_eq!(read_metadata.root_hash, write_metadata.root_hash);
 }
 
+#[test]
+fn accessing_pruned_versions() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let db = RocksDB::new(temp_dir.as_ref());
+    let mut tree = ZkSyncTree::new_lightweight(db);
+    for chunk in logs.chunks(9) {
+        tree.process_l1_batch(chunk);
+    }
+    tree.save();
+    let latest_root_hash = tree.root_hash();
+
+    let (mut pruner, _handle) = tree.pruner(1);
+    let stats = pruner.run_once().expect("nothing was pruned");
+    assert!(stats.pruned_key_count > 0);
+
+    let reader = tree.reader();
+    let key = logs[0].key().hashed_key_u256();
+    for l1_batch_number in [L1BatchNumber(10), L1BatchNumber(11)] {
+        assert!(reader.l1_batch_root_hash(l1_batch_number).is_some());
+        let entries = reader.entries_with_proofs(l1_batch_number, &[key]).unwrap();
+        assert_eq!(entries[0].base.leaf_index, 1);
+    }
+    assert_eq!(
+        reader.l1_batch_root_hash(L1BatchNumber(11)),
+        Some(latest_root_hash)
+    );
+
+    assert!(reader.l1_batch_root_hash(L1BatchNumber(0)).is_none());
+    let err = reader
+        .entries_with_proofs(L1BatchNumber(0), &[key])
+        .unwrap_err();
+    assert!(err.is_pruned(), "{err}");
+    assert_eq!(err.missing_version(), 0);
+
+    let err = reader
+        .entries_with_proofs(L1BatchNumber(12), &[key])
+        .unwrap_err();
+    assert!(!err.is_pruned(), "{err}");
+}
+
+#[test]
+fn creating_checkpoint() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let db = RocksDB::new(&temp_dir.path().join("tree"));
+    let mut tree = ZkSyncTree::new_lightweight(db);
+    let mut root_hashes = vec![];
+    for chunk in logs.chunks(9) {
+        root_hashes.push(tree.process_l1_batch(chunk).root_hash);
+    }
+    tree.save();
+
+    let checkpoint_path = temp_dir.path().join("checkpoint");
+    let root_hash = tree
+        .create_checkpoint(&checkpoint_path, L1BatchNumber(5))
+        .unwrap();
+    assert_eq!(root_hash, root_hashes[5]);
+    // The original tree must not be affected.
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(12));
+    drop(tree);
+
+    let db = RocksDB::new(&checkpoint_path);
+    let mut checkpoint_tree = ZkSyncTree::new_lightweight(db);
+    assert_eq!(checkpoint_tree.next_l1_batch_number(), L1BatchNumber(6));
+    assert_eq!(checkpoint_tree.root_hash(), root_hashes[5]);
+    checkpoint_tree.verify_consistency(L1BatchNumber(5));
+
+    // The checkpoint tree can be extended, producing the same results as the original tree.
+    for (i, chunk) in logs.chunks(9).enumerate().skip(6) {
+        let metadata = checkpoint_tree.process_l1_batch(chunk);
+        assert_eq!(metadata.root_hash, root_hashes[i]);
+    }
+}
+
+#[test]
+fn creating_checkpoint_for_pruned_version() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let db = RocksDB::new(&temp_dir.path().join("tree"));
+    let mut tree = ZkSyncTree::new_lightweight(db);
+    for chunk in logs.chunks(9) {
+        tree.process_l1_batch(chunk);
+    }
+    tree.save();
+    let (mut pruner, _handle) = tree.pruner(1);
+    pruner.run_once().expect("nothing was pruned");
+
+    let checkpoint_path = temp_dir.path().join("checkpoint");
+    let err = tree
+        .create_checkpoint(&checkpoint_path, L1BatchNumber(5))
+        .unwrap_err();
+    let CheckpointError::NoVersion(err) = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert!(err.is_pruned(), "{err}");
+    assert!(!checkpoint_path.exists());
+}
+
 fn create_write_log(
     leaf_index: u64,
     address: Address,
@@ -6,8 +6,8 @@ use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use test_casing::test_casing;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_merkle_tree::{
-    Database, HashTree, MerkleTree, PatchSet, Patched, TreeEntry, TreeInstruction, TreeLogEntry,
-    TreeRangeDigest,
+    Database, HashTree, MerkleTree, PatchSet, Patched, ProofVerificationError, TreeEntry,
+    TreeInstruction, TreeLogEntry, TreeNonMembershipProof, TreeRangeDigest,
 };
 use zksync_types::{AccountTreeId, Address, StorageKey, H256, U256};
 
@@ -166,6 +166,186 @@ fn proofs_are_computed_correctly_for_missing_keys() {
     output.verify_proofs(&Blake2Hasher, empty_tree_hash, &instructions);
 }
 
+#[test_casing(4, [10, 17, 28, 42])]
+fn non_membership_proofs_with_intermediate_commits(chunk_size: usize) {
+    let (kvs, _) = &*ENTRIES_AND_HASH;
+    let all_keys: Vec<_> = kvs.iter().map(|entry| entry.key).collect();
+    let missing_keys: Vec<_> = generate_key_value_pairs(100..120)
+        .into_iter()
+        .map(|entry| entry.key)
+        .collect();
+    let mut tree = MerkleTree::new(PatchSet::default());
+    for (version, chunk) in kvs.chunks(chunk_size).enumerate() {
+        let root_hash = tree.extend(chunk.to_vec()).root_hash;
+        let proofs = tree
+            .non_membership_proofs(version as u64, &all_keys)
+            .unwrap();
+        for (i, (key, proof)) in all_keys.iter().zip(proofs).enumerate() {
+            let is_missing = i >= (version + 1) * chunk_size;
+            assert_eq!(proof.is_some(), is_missing, "{key:?}");
+            if let Some(proof) = proof {
+                assert_eq!(proof.key, *key);
+                proof.verify(&Blake2Hasher, root_hash).unwrap();
+            }
+        }
+
+        let proofs = tree
+            .non_membership_proofs(version as u64, &missing_keys)
+            .unwrap();
+        for proof in proofs {
+            proof.unwrap().verify(&Blake2Hasher, root_hash).unwrap();
+        }
+    }
+}
+
+#[test]
+fn non_membership_proof_cannot_be_forged_for_existing_key() {
+    let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
+    let mut tree = MerkleTree::new(PatchSet::default());
+    tree.extend(kvs.clone());
+
+    let existing_key = kvs[0].key;
+    let proof = tree.entries_with_proofs(0, &[existing_key]).unwrap();
+    let forged_proof = TreeNonMembershipProof {
+        key: existing_key,
+        merkle_path: proof[0].merkle_path.clone(),
+    };
+    let err = forged_proof
+        .verify(&Blake2Hasher, *expected_hash)
+        .unwrap_err();
+    assert!(
+        matches!(err, ProofVerificationError::RootHashMismatch { .. }),
+        "{err:?}"
+    );
+
+    let mut long_proof = forged_proof;
+    long_proof.merkle_path = vec![H256::zero(); 257];
+    let err = long_proof
+        .verify(&Blake2Hasher, *expected_hash)
+        .unwrap_err();
+    assert!(
+        matches!(err, ProofVerificationError::MerklePathTooLong(257)),
+        "{err:?}"
+    );
+}
+
+#[test_casing(4, [10, 17, 28, 42])]
+fn version_diff_proofs_with_key_updates(chunk_size: usize) {
+    const RNG_SEED: u64 = 123;
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let (kvs, _) = &*ENTRIES_AND_HASH;
+    let mut tree = MerkleTree::new(PatchSet::default());
+    let mut root_hashes = vec![];
+    for chunk in kvs.chunks(chunk_size) {
+        let mut entries = chunk.to_vec();
+        // Update some of existing keys and rewrite some keys with the same value.
+        let existing_count = root_hashes.len() * chunk_size;
+        for _ in 0..3.min(existing_count) {
+            let mut entry = kvs[rng.gen_range(0..existing_count)];
+            if rng.gen() {
+                entry.value = H256(rng.gen());
+            }
+            entries.push(entry);
+        }
+        entries.shuffle(&mut rng);
+        root_hashes.push(tree.extend(entries).root_hash);
+    }
+
+    let latest_version = root_hashes.len() as u64 - 1;
+    for old_version in 0..=latest_version {
+        for new_version in old_version..=latest_version {
+            let proof = tree.version_diff_proof(old_version, new_version).unwrap();
+            let old_root_hash = root_hashes[old_version as usize];
+            let new_root_hash = root_hashes[new_version as usize];
+            proof
+                .verify(&Blake2Hasher, old_root_hash, new_root_hash)
+                .unwrap();
+
+            let keys: Vec<_> = proof.changes.iter().map(|diff| diff.new.key).collect();
+            let old_entries = tree.entries(old_version, &keys).unwrap();
+            let new_entries = tree.entries(new_version, &keys).unwrap();
+            for ((diff, old_entry), new_entry) in
+                proof.changes.iter().zip(old_entries).zip(new_entries)
+            {
+                assert_ne!(diff.old, diff.new);
+                assert_eq!(diff.old, old_entry);
+                assert_eq!(diff.new, new_entry);
+            }
+            if new_version > old_version {
+                let remaining_count = kvs.len() - (old_version as usize + 1) * chunk_size;
+                let min_changes = chunk_size.min(remaining_count);
+                assert!(proof.changes.len() >= min_changes);
+            }
+        }
+    }
+}
+
+#[test]
+fn tampered_version_diff_proofs_do_not_verify() {
+    let (kvs, _) = &*ENTRIES_AND_HASH;
+    let mut tree = MerkleTree::new(PatchSet::default());
+    let old_root_hash = tree.extend(kvs[..50].to_vec()).root_hash;
+    let mut updates = kvs[50..].to_vec();
+    updates.extend(
+        kvs[..10]
+            .iter()
+            .map(|entry| entry.with_value(H256::repeat_byte(0xff))),
+    );
+    let new_root_hash = tree.extend(updates).root_hash;
+
+    let proof = tree.version_diff_proof(0, 1).unwrap();
+    assert_eq!(proof.changes.len(), 60);
+    proof
+        .verify(&Blake2Hasher, old_root_hash, new_root_hash)
+        .unwrap();
+
+    // Omitted change
+    let mut tampered_proof = proof.clone();
+    tampered_proof.changes.remove(5);
+    let err = tampered_proof
+        .verify(&Blake2Hasher, old_root_hash, new_root_hash)
+        .unwrap_err();
+    assert!(
+        matches!(err, ProofVerificationError::RootHashMismatch { .. }),
+        "{err:?}"
+    );
+
+    // Modified value
+    let mut tampered_proof = proof.clone();
+    tampered_proof.changes[3].new.value = H256::repeat_byte(0x23);
+    tampered_proof
+        .verify(&Blake2Hasher, old_root_hash, new_root_hash)
+        .unwrap_err();
+
+    // Reordered changes
+    let mut tampered_proof = proof.clone();
+    tampered_proof.changes.swap(0, 1);
+    let err = tampered_proof
+        .verify(&Blake2Hasher, old_root_hash, new_root_hash)
+        .unwrap_err();
+    assert!(
+        matches!(err, ProofVerificationError::UnorderedChanges(_)),
+        "{err:?}"
+    );
+
+    // Removed entry
+    let mut tampered_proof = proof;
+    let diff = tampered_proof
+        .changes
+        .iter_mut()
+        .find(|diff| !diff.old.is_empty())
+        .unwrap();
+    diff.new = TreeEntry::new(diff.new.key, 0, H256::zero());
+    let err = tampered_proof
+        .verify(&Blake2Hasher, old_root_hash, new_root_hash)
+        .unwrap_err();
+    assert!(
+        matches!(err, ProofVerificationError::RemovedEntry(_)),
+        "{err:?}"
+    );
+}
+
 fn test_intermediate_commits(db: &mut impl Database, chunk_size: usize) {
     let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
     let mut final_hash = H256::zero();
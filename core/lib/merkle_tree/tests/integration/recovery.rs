@@ -4,7 +4,8 @@ use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use test_casing::test_casing;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_merkle_tree::{
-    recovery::MerkleTreeRecovery, Database, MerkleTree, PatchSet, PruneDatabase, ValueHash,
+    recovery::{self, MerkleTreeRecovery},
+    Database, MerkleTree, PatchSet, PruneDatabase, TreeEntry, ValueHash,
 };
 
 use crate::common::{convert_to_writes, generate_key_value_pairs, TreeMap, ENTRIES_AND_HASH};
@@ -13,10 +14,29 @@ use crate::common::{convert_to_writes, generate_key_value_pairs, TreeMap, ENTRIE
 enum RecoveryKind {
     Linear,
     Random,
+    Parallel,
 }
 
 impl RecoveryKind {
-    const ALL: [Self; 2] = [Self::Linear, Self::Random];
+    const ALL: [Self; 3] = [Self::Linear, Self::Random, Self::Parallel];
+}
+
+/// Orders entries so that they are ordered by increasing key within each partition,
+/// but partitions are interleaved.
+fn interleave_partitions(entries: &mut [TreeEntry]) {
+    entries.sort_unstable_by_key(|entry| entry.key);
+    let mut entries_by_partition = [0_usize; recovery::PARTITION_COUNT];
+    let mut ranks = vec![];
+    for entry in &*entries {
+        let partition = recovery::key_partition(&entry.key);
+        ranks.push((entries_by_partition[partition], partition));
+        entries_by_partition[partition] += 1;
+    }
+    let mut entries_with_ranks: Vec<_> = ranks.into_iter().zip(entries.iter().copied()).collect();
+    entries_with_ranks.sort_unstable_by_key(|(rank, _)| *rank);
+    for (dest, (_, entry)) in entries.iter_mut().zip(entries_with_ranks) {
+        *dest = entry;
+    }
 }
 
 #[test]
@@ -37,11 +57,48 @@ fn recovery_basics() {
     tree.verify_consistency(recovered_version, true).unwrap();
 }
 
+#[test]
+fn parallel_recovery_with_single_partition() {
+    let (kvs, _) = &*ENTRIES_AND_HASH;
+    let mut recovery_entries: Vec<_> = kvs
+        .iter()
+        .filter(|entry| recovery::key_partition(&entry.key) == 3)
+        .copied()
+        .collect();
+    assert!(!recovery_entries.is_empty());
+    recovery_entries.sort_unstable_by_key(|entry| entry.key);
+    let expected_hash = {
+        let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 42);
+        recovery.extend_linear(recovery_entries.clone());
+        recovery.root_hash()
+    };
+
+    let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 42);
+    for chunk in recovery_entries.chunks(3) {
+        recovery.extend_parallel(chunk.to_vec());
+    }
+    assert_eq!(recovery.root_hash(), expected_hash);
+    let last_processed_keys = recovery.last_processed_keys();
+    assert_eq!(
+        last_processed_keys[3],
+        recovery_entries.last().map(|entry| entry.key)
+    );
+    assert!(last_processed_keys
+        .iter()
+        .enumerate()
+        .all(|(i, key)| i == 3 || key.is_none()));
+
+    let tree = recovery.finalize();
+    tree.verify_consistency(42, true).unwrap();
+}
+
 fn test_recovery_in_chunks(mut db: impl PruneDatabase, kind: RecoveryKind, chunk_size: usize) {
     let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
     let mut recovery_entries = kvs.clone();
-    if matches!(kind, RecoveryKind::Linear) {
-        recovery_entries.sort_unstable_by_key(|entry| entry.key);
+    match kind {
+        RecoveryKind::Linear => recovery_entries.sort_unstable_by_key(|entry| entry.key),
+        RecoveryKind::Random => { /* keep the original order */ }
+        RecoveryKind::Parallel => interleave_partitions(&mut recovery_entries),
     }
     let greatest_key = recovery_entries
         .iter()
@@ -55,6 +112,7 @@ fn test_recovery_in_chunks(mut db: impl PruneDatabase, kind: RecoveryKind, chunk
         match kind {
             RecoveryKind::Linear => recovery.extend_linear(chunk.to_vec()),
             RecoveryKind::Random => recovery.extend_random(chunk.to_vec()),
+            RecoveryKind::Parallel => recovery.extend_parallel(chunk.to_vec()),
         }
         if i % 3 == 1 {
             recovery = MerkleTreeRecovery::new(&mut db, recovered_version);
@@ -64,6 +122,15 @@ fn test_recovery_in_chunks(mut db: impl PruneDatabase, kind: RecoveryKind, chunk
 
     assert_eq!(recovery.last_processed_key(), Some(greatest_key));
     assert_eq!(recovery.root_hash(), *expected_hash);
+    let last_processed_keys = recovery.last_processed_keys();
+    for (partition, &last_key) in last_processed_keys.iter().enumerate() {
+        let expected_last_key = recovery_entries
+            .iter()
+            .map(|entry| entry.key)
+            .filter(|key| recovery::key_partition(key) == partition)
+            .max();
+        assert_eq!(last_key, expected_last_key, "partition {partition}");
+    }
 
     let mut tree = recovery.finalize();
     tree.verify_consistency(recovered_version, true).unwrap();
@@ -117,7 +184,7 @@ fn test_tree_after_recovery<DB: Database>(
     }
 }
 
-#[test_casing(8, test_casing::Product((RecoveryKind::ALL, [6, 10, 17, 42])))]
+#[test_casing(12, test_casing::Product((RecoveryKind::ALL, [6, 10, 17, 42])))]
 fn recovery_in_chunks(kind: RecoveryKind, chunk_size: usize) {
     test_recovery_in_chunks(PatchSet::default(), kind, chunk_size);
 }
@@ -128,7 +195,7 @@ mod rocksdb {
 
     use super::*;
 
-    #[test_casing(8, test_casing::Product((RecoveryKind::ALL, [6, 10, 17, 42])))]
+    #[test_casing(12, test_casing::Product((RecoveryKind::ALL, [6, 10, 17, 42])))]
     fn recovery_in_chunks(kind: RecoveryKind, chunk_size: usize) {
         let temp_dir = TempDir::new().unwrap();
         let db = RocksDBWrapper::new(temp_dir.path());
@@ -0,0 +1,191 @@
+use std::{collections::HashMap, sync::Arc};
+
+use once_cell::sync::OnceCell;
+use zksync_types::{
+    vm_trace::{ContractGasProfile, FunctionGasProfile, GasProfile},
+    Address,
+};
+
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+type Selector = [u8; 4];
+
+/// State of the VM before executing an opcode.
+#[derive(Debug, Clone, Copy)]
+struct PendingStep {
+    depth: usize,
+    ergs_remaining: u32,
+    spent_pubdata_counter: u32,
+    contract: Address,
+    selector: Option<Selector>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct GasUsage {
+    computation: u64,
+    pubdata: u64,
+}
+
+/// Tracer attributing ergs spent during VM execution to contracts and their functions (identified by selectors).
+///
+/// Computational ergs are measured as the difference in ergs remaining before and after each opcode.
+/// Opcodes switching the call frame (calls and returns) are not accounted for, since ergs are passed
+/// between frames on these opcodes.
+#[derive(Debug, Clone)]
+pub struct GasProfiler {
+    /// Selectors of the functions executed in each frame of the call stack, including near call frames.
+    frame_selectors: Vec<Option<Selector>>,
+    pending_step: Option<PendingStep>,
+    usage: HashMap<(Address, Option<Selector>), GasUsage>,
+    result: Arc<OnceCell<GasProfile>>,
+}
+
+impl GasProfiler {
+    pub fn new(result: Arc<OnceCell<GasProfile>>) -> Self {
+        Self {
+            frame_selectors: vec![],
+            pending_step: None,
+            usage: HashMap::new(),
+            result,
+        }
+    }
+
+    /// Adjusts tracked frames to the current call stack depth. New frames are assigned `new_frame_selector`
+    /// if provided, or inherit the selector of the parent frame otherwise (e.g., for near calls).
+    fn sync_frames(&mut self, depth: usize, new_frame_selector: Option<Option<Selector>>) {
+        self.frame_selectors.truncate(depth);
+        while self.frame_selectors.len() < depth {
+            let parent_selector = self.frame_selectors.last().copied().flatten();
+            self.frame_selectors
+                .push(new_frame_selector.unwrap_or(parent_selector));
+        }
+    }
+
+    fn before_step(
+        &mut self,
+        depth: usize,
+        ergs_remaining: u32,
+        spent_pubdata_counter: u32,
+        contract: Address,
+    ) {
+        self.sync_frames(depth, None);
+        self.pending_step = Some(PendingStep {
+            depth,
+            ergs_remaining,
+            spent_pubdata_counter,
+            contract,
+            selector: self.frame_selectors.last().copied().flatten(),
+        });
+    }
+
+    /// Accounts for the executed opcode. `far_call_selector` is only queried if the opcode has entered a new frame.
+    fn after_step(
+        &mut self,
+        depth: usize,
+        ergs_remaining: u32,
+        spent_pubdata_counter: u32,
+        far_call_selector: impl FnOnce() -> Option<Option<Selector>>,
+    ) {
+        let Some(step) = self.pending_step.take() else {
+            return;
+        };
+        let usage = self
+            .usage
+            .entry((step.contract, step.selector))
+            .or_default();
+        if step.depth == depth {
+            usage.computation += u64::from(step.ergs_remaining.saturating_sub(ergs_remaining));
+        }
+        usage.pubdata +=
+            u64::from(spent_pubdata_counter.saturating_sub(step.spent_pubdata_counter));
+
+        let new_frame_selector = if depth > step.depth {
+            far_call_selector()
+        } else {
+            None
+        };
+        self.sync_frames(depth, new_frame_selector);
+    }
+
+    fn build_profile(&self) -> GasProfile {
+        let mut contracts = HashMap::<Address, ContractGasProfile>::new();
+        for (&(address, selector), usage) in &self.usage {
+            let contract = contracts
+                .entry(address)
+                .or_insert_with(|| ContractGasProfile {
+                    address,
+                    ..ContractGasProfile::default()
+                });
+            contract.computation += usage.computation;
+            contract.pubdata += usage.pubdata;
+            contract.functions.push(FunctionGasProfile {
+                selector: selector.map(|selector| format!("0x{}", hex::encode(selector))),
+                computation: usage.computation,
+                pubdata: usage.pubdata,
+            });
+        }
+
+        let mut contracts: Vec<_> = contracts.into_values().collect();
+        for contract in &mut contracts {
+            contract
+                .functions
+                .sort_unstable_by(|a, b| b.computation.cmp(&a.computation));
+        }
+        contracts.sort_unstable_by(|a, b| b.computation.cmp(&a.computation));
+        GasProfile {
+            computation: contracts.iter().map(|contract| contract.computation).sum(),
+            pubdata: contracts.iter().map(|contract| contract.pubdata).sum(),
+            contracts,
+        }
+    }
+
+    fn store_result(&mut self) {
+        let profile = self.build_profile();
+        // The tracer may be invoked for several VM runs; we're interested in the first one.
+        self.result.set(profile).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ergs_are_attributed_to_frames() {
+        let caller = Address::repeat_byte(1);
+        let callee = Address::repeat_byte(2);
+        let selector = [1, 2, 3, 4];
+        let mut profiler = GasProfiler::new(Arc::default());
+
+        profiler.before_step(1, 1_000, 0, caller);
+        profiler.after_step(1, 990, 0, || unreachable!());
+        // Far call: ergs are passed to the callee, so they are not attributed.
+        profiler.before_step(1, 990, 0, caller);
+        profiler.after_step(2, 500, 0, || Some(Some(selector)));
+        profiler.before_step(2, 500, 0, callee);
+        profiler.after_step(2, 450, 64, || unreachable!());
+        // Near call inherits the selector of the parent frame.
+        profiler.before_step(2, 450, 64, callee);
+        profiler.after_step(3, 400, 64, || None);
+        profiler.before_step(3, 400, 64, callee);
+        profiler.after_step(3, 380, 64, || unreachable!());
+
+        let profile = profiler.build_profile();
+        assert_eq!(profile.computation, 80);
+        assert_eq!(profile.pubdata, 64);
+        assert_eq!(profile.contracts[0].address, callee);
+        assert_eq!(profile.contracts[0].computation, 70);
+        assert_eq!(
+            profile.contracts[0].functions,
+            [FunctionGasProfile {
+                selector: Some("0x01020304".to_owned()),
+                computation: 70,
+                pubdata: 64,
+            }]
+        );
+        assert_eq!(profile.contracts[1].address, caller);
+        assert_eq!(profile.contracts[1].functions[0].selector, None);
+    }
+}
@@ -0,0 +1,79 @@
+use zk_evm_1_4_0::{
+    tracing::{AfterExecutionData, BeforeExecutionData, VmLocalStateData},
+    vm_state::PrimitiveValue,
+    zkevm_opcode_defs::{FarCallABI, Opcode, CALL_IMPLICIT_CALLDATA_FAT_PTR_REGISTER},
+};
+use zksync_state::{StoragePtr, WriteStorage};
+
+use crate::{
+    interface::{tracer::VmExecutionStopReason, traits::tracers::dyn_tracers::vm_1_4_0::DynTracer},
+    tracers::gas_profiler::{GasProfiler, Selector},
+    vm_latest::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for GasProfiler {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        _data: BeforeExecutionData,
+        _memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let local_state = state.vm_local_state;
+        self.before_step(
+            local_state.callstack.depth(),
+            local_state.callstack.current.ergs_remaining,
+            local_state.spent_pubdata_counter,
+            local_state.callstack.current.this_address,
+        );
+    }
+
+    fn after_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: AfterExecutionData,
+        memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let local_state = state.vm_local_state;
+        let is_far_call = matches!(data.opcode.variant.opcode, Opcode::FarCall(_));
+        let calldata_abi = local_state.registers[CALL_IMPLICIT_CALLDATA_FAT_PTR_REGISTER as usize];
+        self.after_step(
+            local_state.callstack.depth(),
+            local_state.callstack.current.ergs_remaining,
+            local_state.spent_pubdata_counter,
+            || is_far_call.then(|| read_selector(calldata_abi, memory)),
+        );
+    }
+}
+
+/// Reads the function selector from the calldata of the far call that has just been executed.
+fn read_selector<H: HistoryMode>(
+    packed_abi: PrimitiveValue,
+    memory: &SimpleMemory<H>,
+) -> Option<Selector> {
+    if !packed_abi.is_pointer {
+        return None;
+    }
+    let calldata_ptr = FarCallABI::from_u256(packed_abi.value).memory_quasi_fat_pointer;
+    if calldata_ptr.length < 4 {
+        return None;
+    }
+    let selector = memory.read_unaligned_bytes(
+        calldata_ptr.memory_page as usize,
+        calldata_ptr.start as usize,
+        4,
+    );
+    selector.try_into().ok()
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for GasProfiler {
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        self.store_result();
+    }
+}
@@ -1,8 +1,14 @@
 pub mod call_tracer;
+pub mod gas_profiler;
 mod multivm_dispatcher;
+pub mod prestate_tracer;
 pub mod storage_invocation;
+pub mod struct_log_tracer;
 pub mod validator;
 
 pub use call_tracer::CallTracer;
+pub use gas_profiler::GasProfiler;
 pub use multivm_dispatcher::TracerDispatcher;
+pub use prestate_tracer::PrestateTracer;
 pub use storage_invocation::StorageInvocations;
+pub use struct_log_tracer::{StructLogConfig, StructLogTracer};
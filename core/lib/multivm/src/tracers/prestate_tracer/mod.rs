@@ -0,0 +1,66 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use once_cell::sync::OnceCell;
+use zksync_state::{StoragePtr, WriteStorage};
+use zksync_types::{AccountTreeId, Address, StorageKey, H256, U256};
+use zksync_utils::u256_to_h256;
+
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Values of a storage slot before and after VM execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageSlotValues {
+    pub before: H256,
+    pub after: H256,
+}
+
+impl StorageSlotValues {
+    pub fn is_modified(&self) -> bool {
+        self.before != self.after
+    }
+}
+
+/// Tracer collecting storage slots read or written during VM execution together with their values
+/// before and after the execution. This is the data necessary to build a geth-style prestate trace.
+#[derive(Debug, Clone)]
+pub struct PrestateTracer {
+    pre_state: BTreeMap<StorageKey, H256>,
+    result: Arc<OnceCell<BTreeMap<StorageKey, StorageSlotValues>>>,
+}
+
+impl PrestateTracer {
+    pub fn new(result: Arc<OnceCell<BTreeMap<StorageKey, StorageSlotValues>>>) -> Self {
+        Self {
+            pre_state: BTreeMap::new(),
+            result,
+        }
+    }
+
+    /// Records the value of a storage slot before it's accessed for the first time.
+    fn record_access<S: WriteStorage>(
+        &mut self,
+        address: Address,
+        key: U256,
+        storage: &StoragePtr<S>,
+    ) {
+        let storage_key = StorageKey::new(AccountTreeId::new(address), u256_to_h256(key));
+        self.pre_state
+            .entry(storage_key)
+            .or_insert_with(|| storage.borrow_mut().read_value(&storage_key));
+    }
+
+    fn store_result<S: WriteStorage>(&mut self, storage: StoragePtr<S>) {
+        let mut storage = storage.borrow_mut();
+        let slots = std::mem::take(&mut self.pre_state)
+            .into_iter()
+            .map(|(key, before)| {
+                let after = storage.read_value(&key);
+                (key, StorageSlotValues { before, after })
+            })
+            .collect();
+        // The tracer may be invoked for several VM runs; we're interested in the first one.
+        self.result.set(slots).ok();
+    }
+}
@@ -0,0 +1,39 @@
+use zk_evm_1_4_0::{
+    tracing::{BeforeExecutionData, VmLocalStateData},
+    zkevm_opcode_defs::{LogOpcode, Opcode},
+};
+use zksync_state::{StoragePtr, WriteStorage};
+
+use crate::{
+    interface::{tracer::VmExecutionStopReason, traits::tracers::dyn_tracers::vm_1_4_0::DynTracer},
+    tracers::prestate_tracer::PrestateTracer,
+    vm_latest::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for PrestateTracer {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        _memory: &SimpleMemory<H>,
+        storage: StoragePtr<S>,
+    ) {
+        if let Opcode::Log(LogOpcode::StorageRead | LogOpcode::StorageWrite) =
+            data.opcode.variant.opcode
+        {
+            let address = state.vm_local_state.callstack.current.this_address;
+            self.record_access(address, data.src0_value.value, &storage);
+        }
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for PrestateTracer {
+    fn after_vm_execution(
+        &mut self,
+        state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        self.store_result(state.storage.storage.get_ptr());
+    }
+}
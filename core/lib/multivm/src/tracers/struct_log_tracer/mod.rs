@@ -0,0 +1,210 @@
+use std::{ops::Range, sync::Arc};
+
+use once_cell::sync::OnceCell;
+use zksync_types::{vm_trace::StructLog, Address, U256};
+
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Offsets of the stack and heap pages relative to the base memory page of a call frame.
+const STACK_PAGE_OFFSET: u32 = 1;
+const HEAP_PAGE_OFFSET: u32 = 2;
+
+/// Configuration of [`StructLogTracer`].
+#[derive(Debug, Clone)]
+pub struct StructLogConfig {
+    /// Maximum number of recorded steps. Steps after the limit are not recorded.
+    pub limit: Option<usize>,
+    pub disable_registers: bool,
+    pub disable_stack: bool,
+    pub enable_memory: bool,
+    /// Maximum number of topmost stack words recorded for each step.
+    pub max_stack_words: u32,
+    /// Maximum number of heap words recorded for each step.
+    pub max_memory_words: u32,
+}
+
+impl Default for StructLogConfig {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            disable_registers: false,
+            disable_stack: false,
+            enable_memory: false,
+            max_stack_words: 32,
+            max_memory_words: 1_024,
+        }
+    }
+}
+
+/// Frame-level state of the VM before executing an opcode.
+#[derive(Debug)]
+struct StepState<R> {
+    pc: u16,
+    opcode: String,
+    ergs_remaining: u32,
+    depth: usize,
+    contract: Address,
+    base_memory_page: u32,
+    sp: u32,
+    registers: R,
+}
+
+/// Tracer recording the opcode-level execution trace, similar to the geth struct logger.
+#[derive(Debug, Clone)]
+pub struct StructLogTracer {
+    config: StructLogConfig,
+    logs: Vec<StructLog>,
+    /// Whether the last recorded step is awaiting its ergs cost.
+    has_pending_step: bool,
+    result: Arc<OnceCell<Vec<StructLog>>>,
+}
+
+impl StructLogTracer {
+    pub fn new(config: StructLogConfig, result: Arc<OnceCell<Vec<StructLog>>>) -> Self {
+        Self {
+            config,
+            logs: vec![],
+            has_pending_step: false,
+            result,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.config
+            .limit
+            .map_or(false, |limit| self.logs.len() >= limit)
+    }
+
+    fn record_step(
+        &mut self,
+        step: StepState<impl Iterator<Item = U256>>,
+        read_words: impl Fn(u32, Range<u32>) -> Vec<U256>,
+    ) {
+        let registers = (!self.config.disable_registers).then(|| step.registers.collect());
+        let stack = (!self.config.disable_stack).then(|| {
+            let start = step.sp.saturating_sub(self.config.max_stack_words);
+            read_words(step.base_memory_page + STACK_PAGE_OFFSET, start..step.sp)
+        });
+        let memory = self.config.enable_memory.then(|| {
+            let mut words = read_words(
+                step.base_memory_page + HEAP_PAGE_OFFSET,
+                0..self.config.max_memory_words,
+            );
+            let used_len = words
+                .iter()
+                .rposition(|word| !word.is_zero())
+                .map_or(0, |pos| pos + 1);
+            words.truncate(used_len);
+            words
+        });
+
+        self.logs.push(StructLog {
+            pc: step.pc,
+            op: step.opcode,
+            gas: step.ergs_remaining,
+            gas_cost: 0,
+            depth: step.depth,
+            contract: step.contract,
+            registers,
+            stack,
+            memory,
+        });
+        self.has_pending_step = true;
+    }
+
+    /// Sets the ergs cost of the last recorded step once it's executed.
+    fn finish_step(&mut self, depth: usize, ergs_remaining: u32) {
+        if !std::mem::take(&mut self.has_pending_step) {
+            return;
+        }
+        let last_log = self.logs.last_mut().expect("no pending step");
+        if last_log.depth == depth {
+            last_log.gas_cost = last_log.gas.saturating_sub(ergs_remaining);
+        }
+    }
+
+    fn store_result(&mut self) {
+        let logs = std::mem::take(&mut self.logs);
+        // The tracer may be invoked for several VM runs; we're interested in the first one.
+        self.result.set(logs).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+
+    const BASE_MEMORY_PAGE: u32 = 8;
+
+    fn step(
+        pc: u16,
+        opcode: &str,
+        ergs_remaining: u32,
+        depth: usize,
+    ) -> StepState<iter::Empty<U256>> {
+        StepState {
+            pc,
+            opcode: opcode.to_owned(),
+            ergs_remaining,
+            depth,
+            contract: Address::repeat_byte(1),
+            base_memory_page: BASE_MEMORY_PAGE,
+            sp: 2,
+            registers: iter::empty(),
+        }
+    }
+
+    fn read_words(page: u32, range: Range<u32>) -> Vec<U256> {
+        assert_eq!(page, BASE_MEMORY_PAGE + STACK_PAGE_OFFSET);
+        range.map(U256::from).collect()
+    }
+
+    #[test]
+    fn steps_are_recorded_up_to_limit() {
+        let config = StructLogConfig {
+            limit: Some(3),
+            ..StructLogConfig::default()
+        };
+        let result = Arc::new(OnceCell::new());
+        let mut tracer = StructLogTracer::new(config, result.clone());
+
+        tracer.record_step(step(0, "Add", 1_000, 1), read_words);
+        tracer.finish_step(1, 994);
+        // Far call: ergs are passed to the callee, so the cost is not recorded.
+        tracer.record_step(step(1, "FarCall", 994, 1), read_words);
+        tracer.finish_step(2, 500);
+        tracer.record_step(step(0, "Sub", 500, 2), read_words);
+        tracer.finish_step(2, 490);
+        assert!(tracer.is_full());
+        // Finishing a step that wasn't recorded is a no-op.
+        tracer.finish_step(2, 480);
+        tracer.store_result();
+
+        let logs = result.get().unwrap();
+        assert_eq!(logs.len(), 3);
+        let fields: Vec<_> = logs
+            .iter()
+            .map(|log| (log.pc, log.op.as_str(), log.gas, log.gas_cost, log.depth))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (0, "Add", 1_000, 6, 1),
+                (1, "FarCall", 994, 0, 1),
+                (0, "Sub", 500, 10, 2),
+            ]
+        );
+        for log in logs {
+            assert_eq!(log.registers.as_deref(), Some(&[] as &[U256]));
+            assert_eq!(
+                log.stack.as_deref(),
+                Some(&[U256::zero(), U256::one()] as &[_])
+            );
+            assert_eq!(log.memory, None);
+        }
+    }
+}
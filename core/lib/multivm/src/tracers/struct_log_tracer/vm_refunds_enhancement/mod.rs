@@ -0,0 +1,59 @@
+use zk_evm_1_3_3::tracing::{AfterExecutionData, BeforeExecutionData, VmLocalStateData};
+use zksync_state::{StoragePtr, WriteStorage};
+
+use crate::{
+    interface::{tracer::VmExecutionStopReason, traits::tracers::dyn_tracers::vm_1_3_3::DynTracer},
+    tracers::struct_log_tracer::{StepState, StructLogTracer},
+    vm_refunds_enhancement::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for StructLogTracer {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        if self.is_full() {
+            return;
+        }
+        let local_state = state.vm_local_state;
+        let current = &local_state.callstack.current;
+        let step = StepState {
+            pc: current.pc,
+            opcode: format!("{:?}", data.opcode.variant.opcode),
+            ergs_remaining: current.ergs_remaining,
+            depth: local_state.callstack.depth(),
+            contract: current.this_address,
+            base_memory_page: current.base_memory_page.0,
+            sp: u32::from(current.sp),
+            registers: local_state.registers.iter().map(|register| register.value),
+        };
+        self.record_step(step, |page, range| {
+            memory.dump_page_content_as_u256_words(page, range)
+        });
+    }
+
+    fn after_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        _data: AfterExecutionData,
+        _memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let callstack = &state.vm_local_state.callstack;
+        self.finish_step(callstack.depth(), callstack.current.ergs_remaining);
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for StructLogTracer {
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        self.store_result();
+    }
+}
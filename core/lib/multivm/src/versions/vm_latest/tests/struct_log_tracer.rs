@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+use zksync_types::{Address, Execute};
+
+use crate::{
+    interface::{TxExecutionMode, VmExecutionMode, VmInterface},
+    tracers::{StructLogConfig, StructLogTracer},
+    vm_latest::{
+        constants::BLOCK_GAS_LIMIT,
+        tests::{tester::VmTesterBuilder, utils::read_test_contract},
+        HistoryEnabled, ToTracerPointer,
+    },
+};
+
+#[test]
+fn test_basic_behavior() {
+    let contract = read_test_contract();
+    let address = Address::random();
+    let mut vm = VmTesterBuilder::new(HistoryEnabled)
+        .with_empty_in_memory_storage()
+        .with_random_rich_accounts(1)
+        .with_deployer()
+        .with_gas_limit(BLOCK_GAS_LIMIT)
+        .with_execution_mode(TxExecutionMode::VerifyExecute)
+        .with_custom_contracts(vec![(contract, address, true)])
+        .build();
+
+    let increment_by_6_calldata =
+        "7cf5dab00000000000000000000000000000000000000000000000000000000000000006";
+
+    let account = &mut vm.rich_accounts[0];
+    let tx = account.get_l2_tx_for_execute(
+        Execute {
+            contract_address: address,
+            calldata: hex::decode(increment_by_6_calldata).unwrap(),
+            value: Default::default(),
+            factory_deps: None,
+        },
+        None,
+    );
+
+    let limit = 1_000;
+    let config = StructLogConfig {
+        limit: Some(limit),
+        ..StructLogConfig::default()
+    };
+    let result = Arc::new(OnceCell::new());
+    let struct_log_tracer = StructLogTracer::new(config, result.clone()).into_tracer_pointer();
+    vm.vm.push_transaction(tx);
+    let res = vm
+        .vm
+        .inspect(struct_log_tracer.into(), VmExecutionMode::OneTx);
+    assert!(!res.result.is_failed());
+
+    let logs = result.get().unwrap();
+    // The transaction executes many more opcodes than the limit.
+    assert_eq!(logs.len(), limit);
+    for log in logs {
+        assert!(!log.op.is_empty(), "{log:?}");
+        assert!(log.depth > 0, "{log:?}");
+        assert!(log.gas_cost <= log.gas, "{log:?}");
+        assert!(log.registers.is_some() && log.stack.is_some(), "{log:?}");
+        assert_eq!(log.memory, None);
+    }
+    // Ergs never increase within a frame.
+    for window in logs.windows(2) {
+        let (prev, next) = (&window[0], &window[1]);
+        if prev.depth == next.depth {
+            assert!(next.gas <= prev.gas, "{prev:?} -> {next:?}");
+        }
+    }
+    assert!(logs.iter().any(|log| log.pc > 0));
+    assert!(logs.iter().any(|log| log.gas_cost > 0));
+}
@@ -20,6 +20,7 @@ pub enum Bucket {
     SchedulerWitnessJobsFri,
     ProofsFri,
     StorageSnapshot,
+    MerkleTreeCheckpoints,
 }
 
 impl Bucket {
@@ -36,6 +37,7 @@ impl Bucket {
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
             Self::StorageSnapshot => "storage_logs_snapshots",
+            Self::MerkleTreeCheckpoints => "merkle_tree_checkpoints",
         }
     }
 }
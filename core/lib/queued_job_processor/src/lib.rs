@@ -18,6 +18,15 @@ struct JobProcessorMetrics {
     max_attempts_reached: LabeledFamily<(&'static str, String), Counter, 2>,
     #[metrics(labels = ["service_name"], buckets = ATTEMPT_BUCKETS)]
     attempts: LabeledFamily<&'static str, Histogram<usize>>,
+    /// Number of failed attempts to extend a job lease (e.g., because of DB errors).
+    #[metrics(labels = ["service_name"])]
+    lease_extension_errors: LabeledFamily<&'static str, Counter>,
+    /// Number of jobs abandoned because their lease was taken over by another worker.
+    #[metrics(labels = ["service_name"])]
+    lost_leases: LabeledFamily<&'static str, Counter>,
+    /// Number of in-progress jobs released because the processor was stopped.
+    #[metrics(labels = ["service_name"])]
+    released_jobs: LabeledFamily<&'static str, Counter>,
 }
 
 #[vise::register]
@@ -43,6 +52,33 @@ pub trait JobProcessor: Sync + Send {
     /// Should mark the job as failed
     async fn save_failure(&self, job_id: Self::JobId, started_at: Instant, error: String);
 
+    /// Duration of the lease acquired for a job once it's picked. If set, the lease is extended
+    /// via [`Self::extend_lease()`] while the job is processed, so that jobs picked by workers that died
+    /// (e.g., because of preemption) can be re-queued once their lease expires, without waiting
+    /// for the global processing timeout. Additionally, jobs in progress are released via [`Self::release_job()`]
+    /// when the processor is stopped.
+    ///
+    /// Leases are disabled by default.
+    fn lease_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Acquires or extends the lease for the job being processed. Returns `false` if the lease cannot be
+    /// extended because the job was taken over by another worker; in this case, processing the job is aborted.
+    async fn extend_lease(
+        &self,
+        _job_id: &Self::JobId,
+        _lease_duration: Duration,
+    ) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
+    /// Releases the job being processed so that it can be picked by another worker. Invoked if the processor
+    /// is stopped while processing a job and [leases](Self::lease_duration()) are enabled.
+    async fn release_job(&self, _job_id: Self::JobId) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// Function that processes a job
     async fn process_job(
         &self,
@@ -85,7 +121,7 @@ pub trait JobProcessor: Sync + Send {
                 );
                 let task = self.process_job(job, started_at).await;
 
-                self.wait_for_task(job_id, started_at, task)
+                self.wait_for_task(job_id, started_at, task, &stop_receiver)
                     .await
                     .context("wait_for_task")?;
             } else if iterations_left.is_some() {
@@ -101,12 +137,14 @@ pub trait JobProcessor: Sync + Send {
         Ok(())
     }
 
-    /// Polls task handle, saving its outcome.
+    /// Polls task handle, saving its outcome. If [leases](Self::lease_duration()) are enabled,
+    /// extends the job lease while the task is running.
     async fn wait_for_task(
         &self,
         job_id: Self::JobId,
         started_at: Instant,
         task: JoinHandle<anyhow::Result<Self::JobArtifacts>>,
+        stop_receiver: &watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
         let attempts = self.get_job_attempts(&job_id).await?;
         let max_attempts = self.max_attempts();
@@ -119,6 +157,8 @@ pub trait JobProcessor: Sync + Send {
             );
         }
 
+        let lease_duration = self.lease_duration();
+        let mut lease_extended_at = None::<Instant>;
         let result = loop {
             tracing::trace!(
                 "Polling {} task with id {:?}. Is finished: {}",
@@ -129,6 +169,50 @@ pub trait JobProcessor: Sync + Send {
             if task.is_finished() {
                 break task.await;
             }
+
+            if let Some(lease_duration) = lease_duration {
+                if *stop_receiver.borrow() {
+                    tracing::warn!(
+                        "Stop signal received, releasing {} job {:?}",
+                        Self::SERVICE_NAME,
+                        job_id
+                    );
+                    task.abort();
+                    METRICS.released_jobs[&Self::SERVICE_NAME].inc();
+                    return self.release_job(job_id).await.context("release_job()");
+                }
+
+                // Extend the lease several times per its duration, so that a single failed extension
+                // doesn't lead to the lease expiring.
+                let should_extend = lease_extended_at.map_or(true, |extended_at| {
+                    extended_at.elapsed() >= lease_duration / 3
+                });
+                if should_extend {
+                    match self.extend_lease(&job_id, lease_duration).await {
+                        Ok(true) => {
+                            lease_extended_at = Some(Instant::now());
+                        }
+                        Ok(false) => {
+                            tracing::warn!(
+                                "Lease for {} job {:?} was lost; aborting processing",
+                                Self::SERVICE_NAME,
+                                job_id
+                            );
+                            task.abort();
+                            METRICS.lost_leases[&Self::SERVICE_NAME].inc();
+                            return Ok(());
+                        }
+                        Err(err) => {
+                            tracing::warn!(
+                                "Failed extending lease for {} job {:?}: {err:#}",
+                                Self::SERVICE_NAME,
+                                job_id
+                            );
+                            METRICS.lease_extension_errors[&Self::SERVICE_NAME].inc();
+                        }
+                    }
+                }
+            }
             sleep(Duration::from_millis(Self::POLLING_INTERVAL_MS)).await;
         };
         let error_message = match result {
@@ -171,3 +255,121 @@ pub trait JobProcessor: Sync + Send {
     /// Invoked in `wait_for_task` for in-progress job.
     async fn get_job_attempts(&self, job_id: &Self::JobId) -> anyhow::Result<u32>;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MockProcessor {
+        lease_extensions: AtomicUsize,
+        max_lease_extensions: Option<usize>,
+        saved_result: AtomicBool,
+        released: AtomicBool,
+    }
+
+    #[async_trait]
+    impl JobProcessor for Arc<MockProcessor> {
+        type Job = Duration;
+        type JobId = u32;
+        type JobArtifacts = ();
+
+        const POLLING_INTERVAL_MS: u64 = 10;
+        const SERVICE_NAME: &'static str = "mock";
+
+        async fn get_next_job(&self) -> anyhow::Result<Option<(u32, Duration)>> {
+            Ok(Some((1, Duration::from_millis(200))))
+        }
+
+        async fn save_failure(&self, _job_id: u32, _started_at: Instant, error: String) {
+            panic!("unexpected failure: {error}");
+        }
+
+        fn lease_duration(&self) -> Option<Duration> {
+            Some(Duration::from_millis(60))
+        }
+
+        async fn extend_lease(&self, _job_id: &u32, _duration: Duration) -> anyhow::Result<bool> {
+            let extensions = self.lease_extensions.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(self
+                .max_lease_extensions
+                .map_or(true, |max_extensions| extensions <= max_extensions))
+        }
+
+        async fn release_job(&self, _job_id: u32) -> anyhow::Result<()> {
+            self.released.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn process_job(
+            &self,
+            job: Duration,
+            _started_at: Instant,
+        ) -> JoinHandle<anyhow::Result<()>> {
+            tokio::spawn(async move {
+                sleep(job).await;
+                Ok(())
+            })
+        }
+
+        async fn save_result(
+            &self,
+            _job_id: u32,
+            _started_at: Instant,
+            _: (),
+        ) -> anyhow::Result<()> {
+            self.saved_result.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn max_attempts(&self) -> u32 {
+            1
+        }
+
+        async fn get_job_attempts(&self, _job_id: &u32) -> anyhow::Result<u32> {
+            Ok(1)
+        }
+    }
+
+    #[tokio::test]
+    async fn lease_is_extended_while_job_is_processed() {
+        let processor = Arc::new(MockProcessor::default());
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        processor.clone().run(stop_receiver, Some(1)).await.unwrap();
+
+        assert!(processor.saved_result.load(Ordering::SeqCst));
+        assert!(processor.lease_extensions.load(Ordering::SeqCst) > 1);
+        assert!(!processor.released.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn job_is_abandoned_if_lease_is_lost() {
+        let processor = Arc::new(MockProcessor {
+            max_lease_extensions: Some(1),
+            ..MockProcessor::default()
+        });
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        processor.clone().run(stop_receiver, Some(1)).await.unwrap();
+
+        assert!(!processor.saved_result.load(Ordering::SeqCst));
+        assert_eq!(processor.lease_extensions.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn job_is_released_on_stop() {
+        let processor = Arc::new(MockProcessor::default());
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let task = tokio::spawn(processor.clone().run(stop_receiver, Some(1)));
+        sleep(Duration::from_millis(50)).await;
+        stop_sender.send_replace(true);
+        task.await.unwrap().unwrap();
+
+        assert!(!processor.saved_result.load(Ordering::SeqCst));
+        assert!(processor.released.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,119 @@
+//! Cache storing serialized values by their content digest.
+
+use std::{hash::Hash, sync::Arc};
+
+use zksync_types::{web3::signing::keccak256, H256};
+
+use super::{
+    metrics::{Method, RequestOutcome, METRICS},
+    MokaBase,
+};
+
+/// Maximum number of keys tracked by a [`ContentAddressedCache`]. Keys only map to 32-byte digests,
+/// so the key cache is small compared to the values.
+const MAX_KEYS: u64 = 1 << 16;
+
+/// Cache of serialized values addressed by their content: values are stored by their keccak256 digest,
+/// and keys are mapped to digests. Thus, equal values inserted under different keys occupy memory once.
+///
+/// Both maps use LRU eviction policy; the capacity is the total byte size of stored values.
+#[derive(Debug, Clone)]
+pub struct ContentAddressedCache<K: Eq + Hash> {
+    name: &'static str,
+    inner: Option<(MokaBase<K, H256>, MokaBase<H256, Arc<[u8]>>)>,
+}
+
+impl<K> ContentAddressedCache<K>
+where
+    K: Eq + Hash + Send + Sync + 'static,
+{
+    /// Creates a new cache. A cache with zero capacity doesn't store anything.
+    pub fn new(name: &'static str, capacity: u64) -> Self {
+        let inner = (capacity > 0).then(|| {
+            let digests = MokaBase::<K, H256>::builder()
+                .max_capacity(MAX_KEYS)
+                .build();
+            let values = MokaBase::<H256, Arc<[u8]>>::builder()
+                .weigher(|_, value: &Arc<[u8]>| u32::try_from(value.len()).unwrap_or(u32::MAX))
+                .max_capacity(capacity)
+                .build();
+            (digests, values)
+        });
+        Self { name, inner }
+    }
+
+    /// Gets the value stored for the specified key.
+    pub fn get(&self, key: &K) -> Option<Arc<[u8]>> {
+        let (digests, values) = self.inner.as_ref()?;
+        let latency = METRICS.latency[&(self.name, Method::Get)].start();
+        let value = digests.get(key).and_then(|digest| values.get(&digest));
+        latency.observe();
+
+        let request_outcome = if value.is_some() {
+            RequestOutcome::Hit
+        } else {
+            RequestOutcome::Miss
+        };
+        METRICS.requests[&(self.name, request_outcome)].inc();
+        value
+    }
+
+    /// Inserts a value for the specified key. If an equal value is already stored
+    /// (e.g., for another key), it is reused.
+    pub fn insert(&self, key: K, value: Vec<u8>) {
+        let Some((digests, values)) = &self.inner else {
+            return;
+        };
+        let latency = METRICS.latency[&(self.name, Method::Insert)].start();
+        let digest = H256(keccak256(&value));
+        if values.contains_key(&digest) {
+            METRICS.deduplicated_values[&self.name].inc();
+        } else {
+            values.insert(digest, value.into());
+        }
+        digests.insert(key, digest);
+        latency.observe();
+
+        METRICS.len[&self.name].set(values.entry_count());
+        METRICS.used_memory[&self.name].set(values.weighted_size());
+    }
+
+    /// Removes the specified key from this cache. The value is retained while it's referenced
+    /// by other keys and is not evicted.
+    pub fn remove(&self, key: &K) {
+        if let Some((digests, _)) = &self.inner {
+            digests.invalidate(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_values_are_deduplicated() {
+        let cache = ContentAddressedCache::<u32>::new("test", 1 << 20);
+        cache.insert(1, vec![1, 2, 3]);
+        cache.insert(2, vec![1, 2, 3]);
+        cache.insert(3, vec![4, 5]);
+
+        let first = cache.get(&1).unwrap();
+        let second = cache.get(&2).unwrap();
+        assert_eq!(*first, [1, 2, 3]);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(*cache.get(&3).unwrap(), [4, 5]);
+        assert_eq!(cache.get(&4), None);
+
+        cache.remove(&1);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(*cache.get(&2).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn cache_with_zero_capacity() {
+        let cache = ContentAddressedCache::<u32>::new("test", 0);
+        cache.insert(1, vec![1, 2, 3]);
+        assert_eq!(cache.get(&1), None);
+    }
+}
@@ -38,6 +38,9 @@ pub(super) struct CacheMetrics {
     /// Approximate memory usage of the cache.
     #[metrics(labels = ["name"])]
     pub used_memory: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Number of values inserted into a content-addressed cache that were already stored in it.
+    #[metrics(labels = ["name"])]
+    pub deduplicated_values: LabeledFamily<&'static str, Counter>,
 }
 
 #[vise::register]
@@ -2,8 +2,10 @@
 
 use std::hash::Hash;
 
+mod content_addressed;
 mod metrics;
 
+pub use self::content_addressed::ContentAddressedCache;
 use self::metrics::{Method, RequestOutcome, METRICS};
 
 type MokaBase<K, V> = mini_moka::sync::Cache<K, V>;
@@ -28,9 +28,10 @@ mod test_utils;
 mod witness;
 
 pub use self::{
+    cache::ContentAddressedCache,
     in_memory::{InMemoryStorage, IN_MEMORY_STORAGE_DEFAULT_NETWORK_ID},
     postgres::{PostgresStorage, PostgresStorageCaches},
-    rocksdb::RocksdbStorage,
+    rocksdb::{RocksdbStorage, RocksdbStorageSnapshot},
     shadow_storage::ShadowStorage,
     storage_view::{StorageView, StorageViewMetrics},
     witness::WitnessStorage,
@@ -19,11 +19,11 @@
 //! | Contracts    | address (20 bytes)              | `Vec<u8>`                       | Contract contents                         |
 //! | Factory deps | hash (32 bytes)                 | `Vec<u8>`                       | Bytecodes for new contracts that a certain contract may deploy. |
 
-use std::{collections::HashMap, convert::TryInto, mem, path::Path, time::Instant};
+use std::{collections::HashMap, convert::TryInto, mem, path::Path, sync::Arc, time::Instant};
 
 use itertools::{Either, Itertools};
 use zksync_dal::StorageProcessor;
-use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_storage::{db::NamedColumnFamily, RocksDB, RocksDBSnapshot};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256, U256};
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
@@ -97,7 +97,10 @@ impl StateValue {
 }
 
 /// [`ReadStorage`] implementation backed by RocksDB.
-#[derive(Debug)]
+///
+/// Cloned storages share the underlying RocksDB instance, so that updates saved via one of them
+/// are visible to all others.
+#[derive(Debug, Clone)]
 pub struct RocksdbStorage {
     db: RocksDB<StateKeeperColumnFamily>,
     pending_patch: InMemoryStorage,
@@ -440,6 +443,14 @@ impl RocksdbStorage {
         save_task.await.unwrap();
     }
 
+    /// Creates a consistent read-only view of the storage. Updates saved after the snapshot
+    /// is created are not visible via it.
+    pub fn snapshot(&self) -> RocksdbStorageSnapshot {
+        RocksdbStorageSnapshot {
+            db: Arc::new(self.db.snapshot()),
+        }
+    }
+
     /// Returns the last processed l1 batch number + 1
     /// # Panics
     /// Panics on RocksDB errors.
@@ -503,6 +514,65 @@ impl ReadStorage for RocksdbStorage {
     }
 }
 
+/// Point-in-time view of [`RocksdbStorage`] created via [`RocksdbStorage::snapshot()`].
+/// Cloned views share the underlying RocksDB snapshot.
+#[derive(Debug, Clone)]
+pub struct RocksdbStorageSnapshot {
+    db: Arc<RocksDBSnapshot<StateKeeperColumnFamily>>,
+}
+
+impl RocksdbStorageSnapshot {
+    /// Returns the last processed L1 batch number + 1 as of the snapshot creation.
+    ///
+    /// # Panics
+    ///
+    /// Panics on RocksDB errors.
+    pub fn l1_batch_number(&self) -> L1BatchNumber {
+        let block_number = self
+            .db
+            .get_cf(
+                StateKeeperColumnFamily::State,
+                RocksdbStorage::BLOCK_NUMBER_KEY,
+            )
+            .expect("failed to fetch block number");
+        let block_number = block_number.map_or(0, |bytes| deserialize_block_number(&bytes));
+        L1BatchNumber(block_number)
+    }
+
+    fn read_state_value(&self, key: &StorageKey) -> Option<StateValue> {
+        self.db
+            .get_cf(
+                StateKeeperColumnFamily::State,
+                &RocksdbStorage::serialize_state_key(key),
+            )
+            .expect("failed to read rocksdb state value")
+            .map(|value| StateValue::deserialize(&value))
+    }
+}
+
+impl ReadStorage for RocksdbStorageSnapshot {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        self.read_state_value(key)
+            .map_or_else(H256::zero, |state_value| state_value.value)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.read_state_value(key).is_none()
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        self.db
+            .get_cf(StateKeeperColumnFamily::FactoryDeps, hash.as_bytes())
+            .expect("failed to read RocksDB state value")
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        // See `RocksdbStorage::get_enumeration_index()` for why `unwrap()` is safe.
+        self.read_state_value(key)
+            .map(|state_value| state_value.enum_index.unwrap())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
@@ -534,6 +604,9 @@ mod tests {
             }
         }
 
+        let mut snapshot = storage.snapshot();
+        let old_storage_logs = storage_logs.clone();
+
         // Overwrite some of the logs.
         for log in storage_logs.values_mut().step_by(2) {
             *log = StorageValue::zero();
@@ -548,6 +621,12 @@ mod tests {
             assert!(!storage.is_write_initial(key));
             assert_eq!(storage.read_value(key), *value);
         }
+        // The snapshot doesn't observe the update.
+        assert_eq!(snapshot.l1_batch_number(), L1BatchNumber(0));
+        for (key, value) in &old_storage_logs {
+            assert!(!snapshot.is_write_initial(key));
+            assert_eq!(snapshot.read_value(key), *value);
+        }
     }
 
     #[tokio::test]
@@ -3,7 +3,7 @@ use std::{
     ffi::CStr,
     fmt, iter,
     marker::PhantomData,
-    ops,
+    mem, ops,
     path::Path,
     sync::{Arc, Condvar, Mutex},
     thread,
@@ -11,8 +11,9 @@ use std::{
 };
 
 use rocksdb::{
-    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice,
-    Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
+    checkpoint::Checkpoint, properties, BlockBasedOptions, Cache, ColumnFamily,
+    ColumnFamilyDescriptor, DBPinnableSlice, Direction, IteratorMode, Options, PrefixRange,
+    ReadOptions, WriteOptions, DB,
 };
 
 use crate::metrics::{RocksdbLabels, RocksdbSizeMetrics, METRICS};
@@ -535,6 +536,31 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
         // Thus, `unwrap()` should be safe.
     }
 
+    /// Creates a consistent checkpoint of this database in the specified directory. The directory
+    /// must not exist; it will be created by RocksDB. SST files are hard-linked if the checkpoint
+    /// is created on the same filesystem as the database, so checkpoints are cheap to create.
+    ///
+    /// Only changes written to the database (e.g., via [`Self::write()`]) are included
+    /// in the checkpoint; memtables are flushed as a part of the checkpoint creation.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        let checkpoint = Checkpoint::new(&self.inner.db)?;
+        checkpoint.create_checkpoint(path)
+    }
+
+    /// Creates a consistent point-in-time view of this database. Writes made after the snapshot
+    /// is created are not visible via it.
+    pub fn snapshot(&self) -> RocksDBSnapshot<CF> {
+        let db = self.clone();
+        let snapshot = db.inner.db.snapshot();
+        // SAFETY: The snapshot refers to the `DB` instance owned by `RocksDBInner`, which has
+        // a stable address since it's allocated in an `Arc`. This `Arc` is kept alive by
+        // `RocksDBSnapshot.db`, and the snapshot is dropped before it (see the field order).
+        let snapshot = unsafe {
+            mem::transmute::<rocksdb::Snapshot<'_>, rocksdb::Snapshot<'static>>(snapshot)
+        };
+        RocksDBSnapshot { snapshot, db }
+    }
+
     /// Iterates over key-value pairs in the specified column family `cf` in the lexical
     /// key order starting from the given `key_from`.
     pub fn from_iterator_cf(
@@ -552,6 +578,33 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
     }
 }
 
+/// Consistent point-in-time view of a [`RocksDB`] instance created via [`RocksDB::snapshot()`].
+///
+/// The snapshot is cheap to create, but it prevents RocksDB from removing data overwritten
+/// after its creation, so it shouldn't be held for a long time.
+pub struct RocksDBSnapshot<CF> {
+    // Importantly, the snapshot must be dropped before the DB it refers to, so it's declared first
+    // (fields in a struct are dropped in the declaration order).
+    snapshot: rocksdb::Snapshot<'static>,
+    db: RocksDB<CF>,
+}
+
+impl<CF: NamedColumnFamily> fmt::Debug for RocksDBSnapshot<CF> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RocksDBSnapshot")
+            .field("db_name", &CF::DB_NAME)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<CF: NamedColumnFamily> RocksDBSnapshot<CF> {
+    pub fn get_cf(&self, cf: CF, key: &[u8]) -> Result<Option<Vec<u8>>, rocksdb::Error> {
+        let cf = self.db.column_family(cf);
+        self.snapshot.get_cf(cf, key)
+    }
+}
+
 impl RocksDB<()> {
     /// Awaits termination of all running RocksDB instances.
     ///
@@ -702,6 +755,56 @@ mod tests {
         assert_eq!(value.unwrap(), b"value");
     }
 
+    #[test]
+    fn reading_from_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::<JunkColumnFamily>::new(temp_dir.path());
+        let mut batch = db.new_write_batch();
+        batch.put_cf(JunkColumnFamily, b"test", b"value");
+        db.write(batch).unwrap();
+
+        let snapshot = db.snapshot();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(JunkColumnFamily, b"test", b"new_value");
+        batch.put_cf(JunkColumnFamily, b"other", b"value");
+        db.write(batch).unwrap();
+        // The snapshot must remain usable after the original handle is dropped.
+        drop(db);
+
+        let value = snapshot.get_cf(JunkColumnFamily, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"value");
+        let value = snapshot.get_cf(JunkColumnFamily, b"other").unwrap();
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn creating_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::<NewColumnFamilies>::new(&temp_dir.path().join("db"));
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Default, b"test", b"value");
+        batch.put_cf(NewColumnFamilies::Other, b"other", b"value2");
+        db.write(batch).unwrap();
+
+        let checkpoint_path = temp_dir.path().join("checkpoint");
+        db.create_checkpoint(&checkpoint_path).unwrap();
+        // Changes after the checkpoint must not be visible in it.
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Default, b"test", b"new_value");
+        db.write(batch).unwrap();
+        drop(db);
+
+        let checkpoint = RocksDB::<NewColumnFamilies>::new(&checkpoint_path);
+        let value = checkpoint
+            .get_cf(NewColumnFamilies::Default, b"test")
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value");
+        let value = checkpoint
+            .get_cf(NewColumnFamilies::Other, b"other")
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value2");
+    }
+
     #[test]
     fn write_batch_can_be_restored_from_bytes() {
         let temp_dir = TempDir::new().unwrap();
@@ -60,6 +60,14 @@ pub struct L1BatchProofForL1 {
     pub scheduler_proof: Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
 }
 
+impl L1BatchProofForL1 {
+    /// Returns public inputs of the scheduler proof.
+    pub fn public_inputs(&self) -> Vec<U256> {
+        let (inputs, _) = serialize_proof(&self.scheduler_proof);
+        inputs
+    }
+}
+
 impl fmt::Debug for L1BatchProofForL1 {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter
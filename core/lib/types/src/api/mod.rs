@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
@@ -12,12 +14,15 @@ pub use crate::transaction_request::{
 };
 use crate::{
     protocol_version::L1VerifierConfig,
-    vm_trace::{Call, CallType},
+    vm_trace::{Call, CallType, GasProfile, StructLog},
     web3::types::{AccessList, Index, H2048},
     Address, MiniblockNumber, ProtocolVersionId,
 };
 
 pub mod en;
+pub mod state_override;
+
+pub use self::state_override::{OverrideAccount, StateOverride};
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq, Display)]
@@ -626,20 +631,105 @@ pub struct ProtocolVersion {
 #[serde(rename_all = "camelCase")]
 pub enum SupportedTracers {
     CallTracer,
+    PrestateTracer,
+    StructLogger,
+    GasProfiler,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CallTracerConfig {
+    #[serde(default)]
     pub only_top_call: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerConfig {
+    /// Maximum number of recorded steps. If not specified, a server-side default is used.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Whether to record heap memory for each step.
+    #[serde(default)]
+    pub enable_memory: bool,
+    /// Whether to omit the stack for each step.
+    #[serde(default)]
+    pub disable_stack: bool,
+    /// Whether to omit registers for each step.
+    #[serde(default)]
+    pub disable_registers: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PrestateTracerConfig {
+    /// If set, the tracer returns both pre- and post-execution state of the modified accounts
+    /// instead of the prestate of all touched accounts.
+    #[serde(default)]
+    pub diff_mode: bool,
+}
+
+/// Options in `tracerConfig`. Options of all tracers share the same object; each tracer only uses its own ones.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TracerSpecificConfig {
+    #[serde(flatten)]
+    pub call: CallTracerConfig,
+    #[serde(flatten)]
+    pub prestate: PrestateTracerConfig,
+    #[serde(flatten)]
+    pub struct_logger: StructLoggerConfig,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TracerConfig {
     pub tracer: SupportedTracers,
     #[serde(default)]
-    pub tracer_config: CallTracerConfig,
+    pub tracer_config: TracerSpecificConfig,
+    /// State override applied before tracing. Only supported by `debug_traceCall`.
+    #[serde(default)]
+    pub state_overrides: Option<StateOverride>,
+}
+
+/// Account state in the `prestateTracer` output. Unlike Ethereum, balances, nonces and bytecode hashes
+/// are stored in the storage of system contracts, so only storage slots are reported.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrestateAccount {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// Output of `prestateTracer` in the default mode: state of all accounts touched by the transaction.
+pub type PrestateTrace = BTreeMap<Address, PrestateAccount>;
+
+/// Output of `prestateTracer` in the diff mode.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrestateDiff {
+    /// Values of modified slots before the transaction.
+    pub pre: PrestateTrace,
+    /// Values of modified slots after the transaction. Slots cleared by the transaction are omitted.
+    pub post: PrestateTrace,
+}
+
+/// Output of `structLogger`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugStructLogs {
+    pub gas: u32,
+    pub failed: bool,
+    pub return_value: Bytes,
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// Result of `debug_traceCall` / `debug_traceTransaction` depending on the requested tracer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugTrace {
+    Call(DebugCall),
+    StructLogs(DebugStructLogs),
+    GasProfile(GasProfile),
+    PrestateDiff(PrestateDiff),
+    Prestate(PrestateTrace),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -700,5 +790,103 @@ pub struct StorageProof {
 #[serde(rename_all = "camelCase")]
 pub struct Proof {
     pub address: Address,
+    /// Root hash of the Merkle tree at the requested L1 batch. All `storage_proof`s are relative
+    /// to this hash. Analogous to `storageHash` in `eth_getProof`; since zkSync uses a single tree
+    /// for all accounts, the hash is the same for all addresses.
+    #[serde(default)]
+    pub storage_hash: H256,
     pub storage_proof: Vec<StorageProof>,
 }
+
+/// Overrides of the block context used by `zks_simulateBundle`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    /// Number of the L2 block the calls are executed in.
+    pub number: Option<U64>,
+    /// Timestamp of the L2 block (and the L1 batch) the calls are executed in.
+    pub timestamp: Option<U64>,
+    /// L1 gas price used to derive the base fee and the pubdata price.
+    pub l1_gas_price: Option<U64>,
+}
+
+/// Change of a single storage slot caused by a simulated call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiff {
+    pub address: Address,
+    pub key: H256,
+    pub before: H256,
+    pub after: H256,
+}
+
+/// Result of a single call executed by `zks_simulateBundle`. Calls are executed sequentially,
+/// with each call observing the state changes made by the previous ones. If a call is halted,
+/// it is the last call in the returned list, since the following calls cannot be executed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCall {
+    pub success: bool,
+    pub return_data: Bytes,
+    pub revert_reason: Option<String>,
+    /// Reason the call was halted by the VM (e.g., because of failed validation or running out of gas).
+    #[serde(default)]
+    pub halt_reason: Option<String>,
+    pub gas_used: U256,
+    pub logs: Vec<Log>,
+    pub storage_diffs: Vec<StorageDiff>,
+}
+
+/// Class of data pruned from the node storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PrunedData {
+    /// Historical state, i.e., storage logs overwritten by later writes.
+    StorageLogs,
+    /// Events and L2-to-L1 logs.
+    Events,
+    /// Executed L2 transactions and their call traces.
+    Transactions,
+    /// Completed prover jobs and witness inputs.
+    ProverJobs,
+}
+
+impl PrunedData {
+    pub const ALL: [Self; 4] = [
+        Self::StorageLogs,
+        Self::Events,
+        Self::Transactions,
+        Self::ProverJobs,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::StorageLogs => "storage_logs",
+            Self::Events => "events",
+            Self::Transactions => "transactions",
+            Self::ProverJobs => "prover_jobs",
+        }
+    }
+}
+
+/// Range of pruned data of a certain class. Data is pruned from genesis up to and including the specified
+/// L1 batch / miniblock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrunedRange {
+    pub data: PrunedData,
+    pub last_pruned_l1_batch: L1BatchNumber,
+    pub last_pruned_miniblock: MiniblockNumber,
+}
+
+impl PrunedRange {
+    /// Returns the first miniblock for which the pruned data is fully available. For storage logs,
+    /// this is the last pruned miniblock itself since pruning retains the latest value of each slot
+    /// in the pruned range.
+    pub fn first_available_miniblock(&self) -> MiniblockNumber {
+        match self.data {
+            PrunedData::StorageLogs => self.last_pruned_miniblock,
+            _ => self.last_pruned_miniblock + 1,
+        }
+    }
+}
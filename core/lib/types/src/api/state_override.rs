@@ -0,0 +1,25 @@
+//! geth-style state overrides for call-like API methods (`eth_call`, `eth_estimateGas`, etc.).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{web3::types::Bytes, Address, H256, U256};
+
+/// Collection of overridden accounts, keyed by the account address.
+pub type StateOverride = HashMap<Address, OverrideAccount>;
+
+/// Overrides applied to a single account. Unlike Ethereum, balances, nonces and bytecode hashes are stored
+/// in the storage of system contracts; overrides are applied to the corresponding storage slots.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideAccount {
+    pub balance: Option<U256>,
+    /// Transaction nonce of the account. The deployment nonce is left intact.
+    pub nonce: Option<U256>,
+    /// EraVM bytecode of the account.
+    pub code: Option<Bytes>,
+    /// Replaces the entire account storage; slots not mentioned are treated as zero.
+    pub state: Option<HashMap<H256, H256>>,
+    /// Replaces the specified storage slots, leaving the other slots intact.
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
@@ -215,12 +215,65 @@ pub struct CompilationArtifacts {
     pub abi: serde_json::Value,
 }
 
+/// Level of matching between the compiled and deployed bytecode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchLevel {
+    /// Bytecodes are equal, including metadata.
+    #[default]
+    Full,
+    /// Bytecodes are equal after stripping metadata (e.g., because the contract was compiled
+    /// with different source paths or metadata hash settings).
+    Partial,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VerificationInfo {
     pub request: VerificationRequest,
     pub artifacts: CompilationArtifacts,
     pub verified_at: DateTime<Utc>,
+    /// Defaults to full match for verifications saved before partial matching was introduced.
+    #[serde(default)]
+    pub match_level: MatchLevel,
+}
+
+/// Verification info for a contract together with the information derived from other contracts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractVerificationInfo {
+    #[serde(flatten)]
+    pub info: VerificationInfo,
+    /// Address of the explicitly verified contract with the same bytecode, if the verification
+    /// was propagated from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub propagated_from: Option<Address>,
+    /// Information about the implementation if the contract is a proxy. Only set if proxy detection
+    /// was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyInfo>,
+}
+
+/// Proxy standard detected for a contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyKind {
+    /// EIP-1967 proxy (including transparent and UUPS proxies).
+    Eip1967,
+    /// EIP-1822 (UUPS) proxy using the `PROXIABLE` slot.
+    Eip1822,
+    /// Legacy OpenZeppelin proxy using the `org.zeppelinos.proxy.implementation` slot.
+    OpenZeppelin,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyInfo {
+    pub kind: ProxyKind,
+    pub implementation: Address,
+    /// ABI of the implementation contract if it is verified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implementation_abi: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -230,7 +283,83 @@ pub struct VerificationRequestStatus {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub compilation_errors: Option<Vec<String>>,
+    pub compilation_errors: Option<Vec<CompilationError>>,
+    /// Match level of the contract verification; only set for successful requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_level: Option<MatchLevel>,
+}
+
+/// Structured compilation error. Follows the format of errors in the standard JSON output of `solc`;
+/// failures of the compiler process itself (e.g., timeouts) are reported in the same format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilationError {
+    /// Error severity, e.g. `error` or `warning`.
+    pub severity: String,
+    /// Error type, e.g. `TypeError` or `Timeout`.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+    /// Component that has reported the error, e.g. `general` or the compiler name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    pub message: String,
+    /// Human-readable message including the source snippet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_location: Option<SourceLocation>,
+}
+
+impl CompilationError {
+    /// Creates an error that is not attributed to a particular source location.
+    pub fn new(
+        component: impl Into<String>,
+        error_type: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity: "error".to_owned(),
+            error_type: Some(error_type.into()),
+            component: Some(component.into()),
+            error_code: None,
+            message: message.into(),
+            formatted_message: None,
+            source_location: None,
+        }
+    }
+
+    /// Creates an error from a plain message. Used for errors persisted before errors were structured.
+    pub fn from_message(message: String) -> Self {
+        Self {
+            severity: "error".to_owned(),
+            error_type: None,
+            component: None,
+            error_code: None,
+            formatted_message: Some(message.clone()),
+            message,
+            source_location: None,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == "error"
+    }
+
+    /// Returns the most detailed available message.
+    pub fn full_message(&self) -> &str {
+        self.formatted_message.as_deref().unwrap_or(&self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: String,
+    /// Start byte offset; `-1` if unknown.
+    pub start: i64,
+    /// End byte offset; `-1` if unknown.
+    pub end: i64,
 }
 
 #[derive(Debug)]
@@ -241,7 +370,7 @@ pub enum DeployContractCalldata {
 
 #[cfg(test)]
 mod tests {
-    use super::SourceCodeData;
+    use super::{CompilationError, SourceCodeData, SourceLocation};
 
     #[test]
     fn source_code_deserialization() {
@@ -273,4 +402,33 @@ mod tests {
             serde_json::from_str::<SourceCodeData>(type_not_specified_object_str);
         assert!(type_not_specified_object_result.is_err());
     }
+
+    #[test]
+    fn compilation_error_deserialization() {
+        let error = r#"{
+            "component": "general",
+            "errorCode": "7576",
+            "formattedMessage": "DeclarationError: Undeclared identifier.",
+            "message": "Undeclared identifier.",
+            "severity": "error",
+            "sourceLocation": { "end": 161, "file": "contracts/Test.sol", "start": 158 },
+            "type": "DeclarationError"
+        }"#;
+        let error: CompilationError = serde_json::from_str(error).unwrap();
+
+        assert!(error.is_error());
+        assert_eq!(error.error_type.as_deref(), Some("DeclarationError"));
+        assert_eq!(
+            error.full_message(),
+            "DeclarationError: Undeclared identifier."
+        );
+        assert_eq!(
+            error.source_location,
+            Some(SourceLocation {
+                file: "contracts/Test.sol".to_owned(),
+                start: 158,
+                end: 161,
+            })
+        );
+    }
 }
@@ -278,6 +278,56 @@ pub struct FriProverJobMetadata {
     pub is_node_final_proof: bool,
 }
 
+/// Priority class of FRI prover jobs. Queued jobs with a higher priority are picked before jobs
+/// with a lower priority, regardless of their L1 batch number and aggregation round.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[repr(i16)]
+pub enum FriProverJobPriority {
+    /// Jobs that are only picked if there are no other queued jobs.
+    Deprioritized = -1,
+    #[default]
+    Normal = 0,
+    High = 1,
+    /// Jobs that must be proven as soon as possible, e.g. batches blocking a protocol upgrade.
+    Urgent = 2,
+}
+
+impl FriProverJobPriority {
+    pub const ALL: [Self; 4] = [Self::Deprioritized, Self::Normal, Self::High, Self::Urgent];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Deprioritized => "deprioritized",
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::Urgent => "urgent",
+        }
+    }
+}
+
+impl TryFrom<i16> for FriProverJobPriority {
+    type Error = i16;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|&priority| priority as i16 == value)
+            .ok_or(value)
+    }
+}
+
+/// Priority override for all FRI prover jobs of an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FriBatchPriority {
+    pub l1_batch_number: L1BatchNumber,
+    pub priority: FriProverJobPriority,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct LeafAggregationJobMetadata {
     pub id: u32,
@@ -496,4 +546,19 @@ mod tests {
         let logs_from_job: Vec<_> = job.into_merkle_paths().collect();
         assert_eq!(logs_from_job, logs);
     }
+
+    #[test]
+    fn fri_prover_job_priority_conversions() {
+        for priority in FriProverJobPriority::ALL {
+            assert_eq!(
+                FriProverJobPriority::try_from(priority as i16),
+                Ok(priority)
+            );
+            let serialized = serde_json::to_value(priority).unwrap();
+            assert_eq!(serialized, priority.as_str());
+        }
+        assert_eq!(FriProverJobPriority::try_from(3), Err(3));
+        assert!(FriProverJobPriority::Urgent > FriProverJobPriority::Normal);
+        assert!(FriProverJobPriority::Deprioritized < FriProverJobPriority::Normal);
+    }
 }
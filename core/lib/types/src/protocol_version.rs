@@ -18,7 +18,17 @@ use crate::{
 
 #[repr(u16)]
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, TryFromPrimitive, Serialize, Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    TryFromPrimitive,
+    Serialize,
+    Deserialize,
 )]
 pub enum ProtocolVersionId {
     Version0 = 0,
@@ -106,7 +116,17 @@ impl TryFrom<U256> for ProtocolVersionId {
 
 #[repr(u16)]
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, TryFromPrimitive, Serialize, Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    TryFromPrimitive,
+    Serialize,
+    Deserialize,
 )]
 pub enum FriProtocolVersionId {
     Version0 = 0,
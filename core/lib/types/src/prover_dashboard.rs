@@ -0,0 +1,232 @@
+//! Types used by the FRI prover dashboard API, which reports proving progress for L1 batches.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::L1BatchNumber;
+
+/// Stage of proving an L1 batch. Stages are ordered in the order they are executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FriProvingStage {
+    BasicWitnessGeneration,
+    BasicCircuitsProving,
+    LeafWitnessGeneration,
+    LeafAggregationProving,
+    NodeWitnessGeneration,
+    NodeAggregationProving,
+    SchedulerWitnessGeneration,
+    SchedulerProving,
+    Compression,
+}
+
+/// Aggregated state of all jobs in a proving stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FriStageState {
+    /// No jobs were created for the stage yet.
+    NotStarted,
+    /// Jobs are waiting for their dependencies.
+    Waiting,
+    Queued,
+    InProgress,
+    /// At least one job has failed; it may be retried.
+    Failed,
+    Successful,
+}
+
+/// Information about a single FRI job (a witness generation, proving or compression job).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FriJobInfo {
+    /// Job ID; for stages with a single job per batch, this is the L1 batch number.
+    pub id: u64,
+    pub circuit_id: Option<u8>,
+    pub depth: Option<u16>,
+    pub status: String,
+    pub attempts: u32,
+    pub error: Option<String>,
+    /// Prover instance (e.g., a pod name) that has picked the job.
+    pub picked_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub processing_started_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub time_taken_ms: Option<u64>,
+}
+
+impl FriJobInfo {
+    fn is_completed(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "successful" | "skipped" | "sent_to_server"
+        )
+    }
+}
+
+/// Information about a proving stage for an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FriStageInfo {
+    pub stage: FriProvingStage,
+    pub state: FriStageState,
+    pub job_count: usize,
+    pub completed_job_count: usize,
+    /// Maximum number of attempts among the stage jobs.
+    pub max_attempts: u32,
+    /// Errors of failed jobs.
+    pub errors: Vec<String>,
+    /// Time elapsed since the first stage job was created until the last job was completed
+    /// (or until now if the stage is not completed).
+    pub elapsed_ms: Option<u64>,
+    /// Prover instances that have picked the stage jobs.
+    pub picked_by: Vec<String>,
+    pub jobs: Vec<FriJobInfo>,
+}
+
+impl FriStageInfo {
+    pub fn new(stage: FriProvingStage, jobs: Vec<FriJobInfo>, now: DateTime<Utc>) -> Self {
+        let completed_job_count = jobs.iter().filter(|job| job.is_completed()).count();
+        let has_status = |status: &str| jobs.iter().any(|job| job.status == status);
+        let state = if jobs.is_empty() {
+            FriStageState::NotStarted
+        } else if completed_job_count == jobs.len() {
+            FriStageState::Successful
+        } else if has_status("failed") {
+            FriStageState::Failed
+        } else if has_status("in_progress") || has_status("in_gpu_proof") {
+            FriStageState::InProgress
+        } else if has_status("queued") {
+            FriStageState::Queued
+        } else {
+            FriStageState::Waiting
+        };
+
+        let started_at = jobs.iter().map(|job| job.created_at).min();
+        let finished_at = if state == FriStageState::Successful {
+            jobs.iter().map(|job| job.updated_at).max()
+        } else {
+            Some(now)
+        };
+        let elapsed_ms = started_at
+            .zip(finished_at)
+            .map(|(started_at, finished_at)| {
+                (finished_at - started_at).num_milliseconds().max(0) as u64
+            });
+
+        let errors = jobs
+            .iter()
+            .filter(|job| job.status == "failed")
+            .filter_map(|job| job.error.clone())
+            .collect();
+        let mut picked_by: Vec<_> = jobs
+            .iter()
+            .filter_map(|job| job.picked_by.clone())
+            .collect();
+        picked_by.sort_unstable();
+        picked_by.dedup();
+
+        Self {
+            stage,
+            state,
+            job_count: jobs.len(),
+            completed_job_count,
+            max_attempts: jobs.iter().map(|job| job.attempts).max().unwrap_or(0),
+            errors,
+            elapsed_ms,
+            picked_by,
+            jobs,
+        }
+    }
+}
+
+/// Proving progress for an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FriBatchProvingInfo {
+    pub l1_batch_number: L1BatchNumber,
+    pub stages: Vec<FriStageInfo>,
+}
+
+impl FriBatchProvingInfo {
+    pub fn new(
+        l1_batch_number: L1BatchNumber,
+        stage_jobs: Vec<(FriProvingStage, Vec<FriJobInfo>)>,
+    ) -> Self {
+        let now = Utc::now();
+        let stages = stage_jobs
+            .into_iter()
+            .map(|(stage, jobs)| FriStageInfo::new(stage, jobs, now))
+            .collect();
+        Self {
+            l1_batch_number,
+            stages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn job(status: &str, created_at: DateTime<Utc>, updated_at: DateTime<Utc>) -> FriJobInfo {
+        FriJobInfo {
+            id: 1,
+            circuit_id: Some(1),
+            depth: None,
+            status: status.to_owned(),
+            attempts: 1,
+            error: None,
+            picked_by: Some("prover-0".to_owned()),
+            created_at,
+            processing_started_at: None,
+            updated_at,
+            time_taken_ms: None,
+        }
+    }
+
+    #[test]
+    fn stage_info_aggregation() {
+        let now = Utc::now();
+        let start = now - Duration::seconds(100);
+        let stage = FriProvingStage::BasicCircuitsProving;
+
+        let info = FriStageInfo::new(stage, vec![], now);
+        assert_eq!(info.state, FriStageState::NotStarted);
+        assert_eq!(info.elapsed_ms, None);
+
+        let jobs = vec![
+            job("successful", start, start + Duration::seconds(10)),
+            job("successful", start, start + Duration::seconds(30)),
+        ];
+        let info = FriStageInfo::new(stage, jobs, now);
+        assert_eq!(info.state, FriStageState::Successful);
+        assert_eq!(info.completed_job_count, 2);
+        assert_eq!(info.elapsed_ms, Some(30_000));
+        assert_eq!(info.picked_by, ["prover-0"]);
+
+        let mut failed_job = job("failed", start, start);
+        failed_job.attempts = 3;
+        failed_job.error = Some("out of memory".to_owned());
+        failed_job.picked_by = Some("prover-1".to_owned());
+        let jobs = vec![
+            job("in_progress", start, start),
+            failed_job,
+            job("queued", start, start),
+        ];
+        let info = FriStageInfo::new(stage, jobs, now);
+        assert_eq!(info.state, FriStageState::Failed);
+        assert_eq!(info.max_attempts, 3);
+        assert_eq!(info.errors, ["out of memory"]);
+        assert_eq!(info.elapsed_ms, Some(100_000));
+        assert_eq!(info.picked_by, ["prover-0", "prover-1"]);
+
+        let jobs = vec![
+            job("in_gpu_proof", start, start),
+            job("queued", start, start),
+        ];
+        let info = FriStageInfo::new(stage, jobs, now);
+        assert_eq!(info.state, FriStageState::InProgress);
+
+        let jobs = vec![job("waiting_for_proofs", start, start)];
+        let info = FriStageInfo::new(stage, jobs, now);
+        assert_eq!(info.state, FriStageState::Waiting);
+    }
+}
@@ -5,7 +5,7 @@ use zkevm_test_harness::{
     bellman::{
         bn256::{Bn256, Fq, Fr, G1Affine},
         plonk::better_better_cs::setup::VerificationKey,
-        CurveAffine, PrimeField,
+        CurveAffine, PrimeField, PrimeFieldRepr,
     },
     ff::to_hex,
     witness::{
@@ -14,7 +14,7 @@ use zkevm_test_harness::{
     },
 };
 
-use crate::{ethabi::Token, H256};
+use crate::{ethabi::Token, web3::signing::keccak256, H256};
 
 /// Calculates commitment for vk from L1 verifier contract.
 pub fn l1_vk_commitment(token: Token) -> H256 {
@@ -30,6 +30,31 @@ pub fn generate_vk_commitment(
     H256::from_str(&scheduler_commitment_hex).expect("invalid scheduler commitment")
 }
 
+/// Calculates the hash of the SNARK wrapper verification key in the same way as the L1 verifier contract
+/// (`verificationKeyHash()`), i.e., as `keccak256` of the big-endian coordinates of the key commitments.
+pub fn snark_vk_hash(
+    vk: &VerificationKey<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
+) -> H256 {
+    let commitments = vk
+        .gate_setup_commitments
+        .iter()
+        .chain(&vk.gate_selectors_commitments)
+        .chain(&vk.permutation_commitments)
+        .chain(&vk.lookup_selector_commitment)
+        .chain(&vk.lookup_tables_commitments)
+        .chain(&vk.lookup_table_type_commitment);
+
+    let mut buffer = vec![];
+    for commitment in commitments {
+        let (x, y) = commitment.as_xy();
+        x.into_repr().write_be(&mut buffer).unwrap();
+        y.into_repr().write_be(&mut buffer).unwrap();
+    }
+    // Flag signalling that the proof has no recursive part.
+    buffer.extend_from_slice(&[0; 32]);
+    H256(keccak256(&buffer))
+}
+
 fn vk_from_token(
     vk_token: Token,
 ) -> VerificationKey<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>> {
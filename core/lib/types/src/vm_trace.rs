@@ -201,6 +201,62 @@ impl fmt::Debug for Call {
     }
 }
 
+/// Single step of the opcode-level execution trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: u16,
+    pub op: String,
+    /// Ergs remaining in the current frame before executing the opcode.
+    pub gas: u32,
+    /// Ergs spent on the opcode. Set to 0 for opcodes switching the call frame (calls and returns).
+    pub gas_cost: u32,
+    /// Depth of the call stack, including near calls.
+    pub depth: usize,
+    /// Address of the contract executing the opcode.
+    pub contract: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registers: Option<Vec<U256>>,
+    /// Topmost words of the stack of the current frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    /// Words of the heap page of the current frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<U256>>,
+}
+
+/// Ergs spent by a contract function identified by its selector.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionGasProfile {
+    /// Hex-encoded 4-byte function selector. `None` for calls with shorter calldata (e.g., plain transfers).
+    pub selector: Option<String>,
+    pub computation: u64,
+    pub pubdata: u64,
+}
+
+/// Ergs spent by a contract.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractGasProfile {
+    pub address: Address,
+    pub computation: u64,
+    pub pubdata: u64,
+    /// Functions sorted by the spent computational ergs in the descending order.
+    pub functions: Vec<FunctionGasProfile>,
+}
+
+/// Aggregated ergs usage of a transaction. Computational ergs of each opcode are attributed
+/// to the contract and function executing it; pubdata is attributed according to the VM pubdata counter.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfile {
+    pub computation: u64,
+    pub pubdata: u64,
+    /// Contracts sorted by the spent computational ergs in the descending order.
+    pub contracts: Vec<ContractGasProfile>,
+}
+
 #[derive(Debug, Clone)]
 pub enum ViolatedValidationRule {
     TouchedUnallowedStorageSlots(Address, U256),
@@ -1,7 +1,7 @@
 //! Definition of errors that can occur in the zkSync Web3 API.
 
 use thiserror::Error;
-use zksync_types::api::SerializationTransactionError;
+use zksync_types::{api::SerializationTransactionError, L1BatchNumber, MiniblockNumber};
 
 #[derive(Debug, Error)]
 pub enum Web3Error {
@@ -23,6 +23,10 @@ pub enum Web3Error {
     SerializationError(#[from] SerializationTransactionError),
     #[error("Invalid fee parameters: {0}")]
     InvalidFeeParams(String),
+    #[error("Invalid state override: {0}")]
+    InvalidStateOverride(String),
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
     #[error("More than four topics in filter")]
     TooManyTopics,
     #[error("Your connection time exceeded the limit")]
@@ -39,4 +43,12 @@ pub enum Web3Error {
     TooManyLogs(usize),
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error(
+        "L1 batch #{0} was pruned from the Merkle tree; proofs for it are no longer available"
+    )]
+    PrunedL1Batch(L1BatchNumber),
+    #[error("Data for block #{0} was pruned; the earliest block with available data is #{1}")]
+    PrunedBlock(MiniblockNumber, MiniblockNumber),
+    #[error("Struct logs limit {0} exceeds the maximum allowed value {1}")]
+    StructLogsLimitExceeded(usize, usize),
 }
@@ -1,6 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugTrace, ResultDebugCall, TracerConfig},
     transaction_request::CallRequest,
 };
 
@@ -37,11 +37,11 @@ pub trait DebugNamespace {
         request: CallRequest,
         block: Option<BlockId>,
         options: Option<TracerConfig>,
-    ) -> RpcResult<DebugCall>;
+    ) -> RpcResult<DebugTrace>;
     #[method(name = "traceTransaction")]
     async fn trace_transaction(
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Option<DebugCall>>;
+    ) -> RpcResult<Option<DebugTrace>>;
 }
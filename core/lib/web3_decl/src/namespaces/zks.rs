@@ -4,7 +4,8 @@ use bigdecimal::BigDecimal;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
+        BlockDetails, BlockIdVariant, BlockOverrides, BridgeAddresses, L1BatchDetails,
+        L2ToL1LogProof, Proof, ProtocolVersion, PrunedRange, SimulatedCall, StateOverride,
         TransactionDetails,
     },
     fee::Fee,
@@ -28,11 +29,23 @@ use crate::types::{Filter, Log, Token};
 )]
 pub trait ZksNamespace {
     #[method(name = "estimateFee")]
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
+    async fn estimate_fee(
+        &self,
+        req: CallRequest,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Fee>;
 
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
 
+    #[method(name = "simulateBundle")]
+    async fn simulate_bundle(
+        &self,
+        calls: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> RpcResult<Vec<SimulatedCall>>;
+
     #[method(name = "getMainContract")]
     async fn get_main_contract(&self) -> RpcResult<Address>;
 
@@ -117,4 +130,7 @@ pub trait ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Proof>;
+
+    #[method(name = "getPrunedRanges")]
+    async fn get_pruned_ranges(&self) -> RpcResult<Vec<PrunedRange>>;
 }
@@ -1,20 +1,24 @@
 use actix_web::web;
 use zksync_dal::connection::ConnectionPool;
+use zksync_types::L2ChainId;
 
 #[derive(Debug, Clone)]
 pub struct RestApi {
     pub(super) master_connection_pool: ConnectionPool,
     pub(super) replica_connection_pool: ConnectionPool,
+    pub(super) l2_chain_id: L2ChainId,
 }
 
 impl RestApi {
     pub fn new(
         master_connection_pool: ConnectionPool,
         replica_connection_pool: ConnectionPool,
+        l2_chain_id: L2ChainId,
     ) -> Self {
         Self {
             master_connection_pool,
             replica_connection_pool,
+            l2_chain_id,
         }
     }
 
@@ -47,5 +51,21 @@ impl RestApi {
                 "/contract_verification/info/{address}",
                 web::get().to(Self::verification_info),
             )
+            // Etherscan-compatible API
+            .route("/api", web::get().to(Self::etherscan_get))
+            .route("/api", web::post().to(Self::etherscan_post))
+            // Sourcify-compatible API
+            .route(
+                "/sourcify/v2/verify/{chain_id}/{address}",
+                web::post().to(Self::sourcify_verify),
+            )
+            .route(
+                "/sourcify/v2/verify/{verification_id}",
+                web::get().to(Self::sourcify_verification_status),
+            )
+            .route(
+                "/sourcify/v2/contract/{chain_id}/{address}",
+                web::get().to(Self::sourcify_contract),
+            )
     }
 }
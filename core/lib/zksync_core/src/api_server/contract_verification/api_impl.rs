@@ -1,11 +1,45 @@
+use std::fmt;
+
 use actix_web::{
     web::{self, Json},
     HttpResponse, Result as ActixResult,
 };
-use serde::Serialize;
-use zksync_types::{contract_verification_api::VerificationIncomingRequest, Address};
+use serde::{Deserialize, Serialize};
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    contract_verification_api::{
+        ContractVerificationInfo, MatchLevel, ProxyInfo, VerificationIncomingRequest,
+        VerificationInfo,
+    },
+    Address,
+};
+
+use super::{api_decl::RestApi, metrics::METRICS, proxy};
+
+/// Query parameters for the contract verification info endpoint.
+#[derive(Debug, Deserialize)]
+pub struct VerificationInfoQuery {
+    /// Whether to check if the contract is a proxy. Detection requires additional storage lookups,
+    /// so it's opt-in.
+    #[serde(default)]
+    detect_proxy: bool,
+}
 
-use super::{api_decl::RestApi, metrics::METRICS};
+/// Reason for rejecting a verification request before it is queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum VerificationRejection {
+    NotDeployed,
+    AlreadyVerified,
+}
+
+impl fmt::Display for VerificationRejection {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::NotDeployed => "There is no deployed contract on this address",
+            Self::AlreadyVerified => "This contract is already verified",
+        })
+    }
+}
 
 fn ok_json(data: impl Serialize) -> ActixResult<HttpResponse> {
     Ok(HttpResponse::Ok().json(data))
@@ -33,7 +67,22 @@ impl RestApi {
         if let Err(res) = Self::validate_contract_verification_query(&request) {
             return Ok(res);
         }
-        let mut storage = self_
+        let request_id = match self_.queue_verification_request(request).await {
+            Ok(request_id) => request_id,
+            Err(rejection) => return Ok(HttpResponse::BadRequest().body(rejection.to_string())),
+        };
+
+        method_latency.observe();
+        ok_json(request_id)
+    }
+
+    /// Adds a verification request to the queue. Shared by the native API and compatibility APIs
+    /// (Etherscan, Sourcify).
+    pub(super) async fn queue_verification_request(
+        &self,
+        request: VerificationIncomingRequest,
+    ) -> Result<usize, VerificationRejection> {
+        let mut storage = self
             .master_connection_pool
             .access_storage_tagged("api")
             .await
@@ -44,17 +93,16 @@ impl RestApi {
             .is_contract_deployed_at_address(request.contract_address)
             .await
         {
-            return Ok(
-                HttpResponse::BadRequest().body("There is no deployed contract on this address")
-            );
+            return Err(VerificationRejection::NotDeployed);
         }
-        if storage
+        let match_level = storage
             .contract_verification_dal()
-            .is_contract_verified(request.contract_address)
+            .get_contract_match_level(request.contract_address)
             .await
-            .unwrap()
-        {
-            return Ok(HttpResponse::BadRequest().body("This contract is already verified"));
+            .unwrap();
+        // A partially matched contract may be re-verified to upgrade it to a full match.
+        if match_level == Some(MatchLevel::Full) {
+            return Err(VerificationRejection::AlreadyVerified);
         }
 
         let request_id = storage
@@ -62,9 +110,7 @@ impl RestApi {
             .add_contract_verification_request(request)
             .await
             .unwrap();
-
-        method_latency.observe();
-        ok_json(request_id)
+        Ok(request_id)
     }
 
     #[tracing::instrument(skip(self_))]
@@ -162,18 +208,12 @@ impl RestApi {
     pub async fn verification_info(
         self_: web::Data<Self>,
         address: web::Path<Address>,
+        query: web::Query<VerificationInfoQuery>,
     ) -> ActixResult<HttpResponse> {
         let method_latency = METRICS.call[&"contract_verification_info"].start();
-
         let info = self_
-            .replica_connection_pool
-            .access_storage_tagged("api")
-            .await
-            .unwrap()
-            .contract_verification_dal()
-            .get_contract_verification_info(*address)
-            .await
-            .unwrap();
+            .contract_verification_info(*address, query.detect_proxy)
+            .await;
 
         method_latency.observe();
         match info {
@@ -181,4 +221,60 @@ impl RestApi {
             None => Ok(HttpResponse::NotFound().finish()),
         }
     }
+
+    /// Returns verification info for the contract at `address`, which is either verified explicitly,
+    /// or has the same bytecode as an explicitly verified contract. If `detect_proxy` is set and the contract
+    /// is a proxy, the info is augmented with the implementation ABI.
+    pub(super) async fn contract_verification_info(
+        &self,
+        address: Address,
+        detect_proxy: bool,
+    ) -> Option<ContractVerificationInfo> {
+        let mut storage = self
+            .replica_connection_pool
+            .access_storage_tagged("api")
+            .await
+            .unwrap();
+        let (info, propagated_from) =
+            Self::verification_info_with_propagation(&mut storage, address).await?;
+
+        let proxy = if !detect_proxy {
+            None
+        } else if let Some((kind, implementation)) =
+            proxy::detect_proxy(&mut storage, address).await
+        {
+            let implementation_info =
+                Self::verification_info_with_propagation(&mut storage, implementation).await;
+            Some(ProxyInfo {
+                kind,
+                implementation,
+                implementation_abi: implementation_info.map(|(info, _)| info.artifacts.abi),
+            })
+        } else {
+            None
+        };
+        Some(ContractVerificationInfo {
+            info,
+            propagated_from,
+            proxy,
+        })
+    }
+
+    /// Returns verification info for the contract together with the address of the contract
+    /// the verification was propagated from.
+    async fn verification_info_with_propagation(
+        storage: &mut StorageProcessor<'_>,
+        address: Address,
+    ) -> Option<(VerificationInfo, Option<Address>)> {
+        let mut dal = storage.contract_verification_dal();
+        if let Some(info) = dal.get_contract_verification_info(address).await.unwrap() {
+            return Some((info, None));
+        }
+        let info = dal
+            .get_contract_verification_info_by_bytecode(address)
+            .await
+            .unwrap()?;
+        let verified_address = info.request.req.contract_address;
+        Some((info, Some(verified_address)))
+    }
 }
@@ -0,0 +1,606 @@
+//! Etherscan-compatible contract verification API (`/api?module=contract&action=...`), which is used
+//! by Hardhat, Foundry and block explorer tooling.
+
+use std::collections::HashMap;
+
+use actix_web::{error::InternalError, web, HttpResponse, Result as ActixResult};
+use serde::Serialize;
+use zksync_types::{
+    contract_verification_api::{
+        CompilerVersions, ContractVerificationInfo, SourceCodeData, VerificationIncomingRequest,
+        VerificationRequestStatus,
+    },
+    Address,
+};
+
+use super::{api_decl::RestApi, metrics::METRICS};
+
+const NOT_VERIFIED_MESSAGE: &str = "Contract source code not verified";
+
+/// Parameters of an Etherscan API request. Etherscan clients are inconsistent w.r.t. parameter casing
+/// (e.g., `codeformat` vs `codeFormat`), so parameter names are compared case-insensitively.
+#[derive(Debug, Default)]
+struct EtherscanParams(HashMap<String, String>);
+
+impl EtherscanParams {
+    fn new(params: impl IntoIterator<Item = (String, String)>) -> Self {
+        let params = params
+            .into_iter()
+            .map(|(name, value)| (name.to_lowercase(), value));
+        Self(params.collect())
+    }
+
+    /// Returns a non-empty parameter value.
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .get(name)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn require(&self, name: &str) -> Result<&str, String> {
+        self.get(name)
+            .ok_or_else(|| format!("Missing or empty `{name}` parameter"))
+    }
+
+    fn get_bool(&self, name: &str) -> Result<bool, String> {
+        match self.get(name) {
+            None | Some("0" | "false") => Ok(false),
+            Some("1" | "true") => Ok(true),
+            Some(value) => Err(format!("Invalid `{name}` parameter: {value}")),
+        }
+    }
+
+    fn address(&self, name: &str) -> Result<Address, String> {
+        let address = self.require(name)?;
+        address
+            .parse()
+            .map_err(|_| format!("Invalid `{name}` parameter: {address}"))
+    }
+
+    /// Translates a `verifysourcecode` request into a native verification request.
+    fn to_verification_request(&self) -> Result<VerificationIncomingRequest, String> {
+        let source_code = self.require("sourcecode")?;
+        let compiler_version = self.require("compilerversion")?;
+        let code_format = self.get("codeformat").unwrap_or("solidity-single-file");
+        let (source_code_data, compiler_versions) = match code_format {
+            "solidity-single-file" | "solidity-standard-json-input" => {
+                let source_code_data = if code_format == "solidity-single-file" {
+                    SourceCodeData::SolSingleFile(source_code.to_owned())
+                } else {
+                    SourceCodeData::StandardJsonInput(parse_json_object(source_code)?)
+                };
+                let compiler_versions = CompilerVersions::Solc {
+                    compiler_zksolc_version: self.require("zksolcversion")?.to_owned(),
+                    compiler_solc_version: normalize_compiler_version(compiler_version),
+                };
+                (source_code_data, compiler_versions)
+            }
+            "vyper-json" => {
+                let input = parse_json_object(source_code)?;
+                let source_code_data = SourceCodeData::VyperMultiFile(vyper_sources(&input)?);
+                let compiler_versions = CompilerVersions::Vyper {
+                    compiler_zkvyper_version: self.require("zkvyperversion")?.to_owned(),
+                    compiler_vyper_version: normalize_compiler_version(compiler_version),
+                };
+                (source_code_data, compiler_versions)
+            }
+            _ => return Err(format!("Unsupported `codeformat`: {code_format}")),
+        };
+
+        let constructor_arguments = self
+            .get("constructorarguements") // sic; this is the parameter name used by Etherscan
+            .or_else(|| self.get("constructorarguments"))
+            .unwrap_or_default();
+        let constructor_arguments = hex::decode(constructor_arguments.trim_start_matches("0x"))
+            .map_err(|err| format!("Invalid constructor arguments: {err}"))?;
+
+        Ok(VerificationIncomingRequest {
+            contract_address: self.address("contractaddress")?,
+            source_code_data,
+            contract_name: self.require("contractname")?.to_owned(),
+            compiler_versions,
+            optimization_used: self.get_bool("optimizationused")?,
+            optimizer_mode: self.get("optimizermode").map(str::to_owned),
+            constructor_arguments: constructor_arguments.into(),
+            is_system: self.get_bool("issystem")?,
+        })
+    }
+}
+
+fn parse_json_object(source: &str) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    // Etherscan allows wrapping standard JSON input in double braces.
+    let source = source.trim();
+    let source = source
+        .strip_prefix('{')
+        .and_then(|source| source.strip_suffix('}'))
+        .filter(|source| source.starts_with('{') && source.ends_with('}'))
+        .unwrap_or(source);
+    serde_json::from_str(source).map_err(|err| format!("Invalid JSON source code: {err}"))
+}
+
+/// Extracts Vyper sources from a standard JSON input.
+fn vyper_sources(
+    input: &serde_json::Map<String, serde_json::Value>,
+) -> Result<HashMap<String, String>, String> {
+    let sources = input
+        .get("sources")
+        .and_then(serde_json::Value::as_object)
+        .ok_or("Missing `sources` in Vyper JSON input")?;
+    sources
+        .iter()
+        .map(|(path, source)| {
+            let content = source
+                .get("content")
+                .and_then(serde_json::Value::as_str)
+                .ok_or_else(|| format!("Missing content for source `{path}`"))?;
+            Ok((path.clone(), content.to_owned()))
+        })
+        .collect()
+}
+
+/// Converts an Etherscan compiler version (e.g., `v0.8.17+commit.8df45f5f` or `vyper:0.3.10`)
+/// to the format used by the contract verifier (e.g., `0.8.17`).
+fn normalize_compiler_version(version: &str) -> String {
+    let version = version.strip_prefix("vyper:").unwrap_or(version);
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let version = version
+        .split_once('+')
+        .map_or(version, |(version, _)| version);
+    version.to_owned()
+}
+
+/// Generic Etherscan API response. Etherscan always responds with HTTP 200 and signals errors
+/// via `status` / `message` fields.
+#[derive(Debug, Serialize)]
+struct EtherscanResponse {
+    status: &'static str,
+    message: &'static str,
+    result: serde_json::Value,
+}
+
+impl EtherscanResponse {
+    fn ok(result: impl Serialize) -> Self {
+        Self {
+            status: "1",
+            message: "OK",
+            result: serde_json::to_value(result).expect("failed serializing response"),
+        }
+    }
+
+    fn error(result: impl Into<String>) -> Self {
+        Self {
+            status: "0",
+            message: "NOTOK",
+            result: serde_json::Value::String(result.into()),
+        }
+    }
+
+    fn into_http(self) -> ActixResult<HttpResponse> {
+        Ok(HttpResponse::Ok().json(self))
+    }
+}
+
+impl From<Result<Self, String>> for EtherscanResponse {
+    fn from(result: Result<Self, String>) -> Self {
+        result.unwrap_or_else(Self::error)
+    }
+}
+
+/// Configures the URL-encoded body extractor used by `POST` requests. Extraction errors (e.g., a body
+/// exceeding `limit`) are returned in the Etherscan format rather than as plain-text HTTP errors.
+pub(super) fn form_config(limit: usize) -> web::FormConfig {
+    web::FormConfig::default()
+        .limit(limit)
+        .error_handler(|err, _req| {
+            let response = HttpResponse::Ok().json(EtherscanResponse::error(err.to_string()));
+            InternalError::from_response(err, response).into()
+        })
+}
+
+/// Maps the verification request status to the Etherscan format.
+fn verification_status_response(status: Option<VerificationRequestStatus>) -> EtherscanResponse {
+    let Some(status) = status else {
+        return EtherscanResponse::error("Unknown UID");
+    };
+    match status.status.as_str() {
+        "queued" | "in_progress" => EtherscanResponse::error("Pending in queue"),
+        "successful" => EtherscanResponse::ok("Pass - Verified"),
+        _ => {
+            let mut reason = status.error.unwrap_or_default();
+            let compilation_errors = status.compilation_errors.unwrap_or_default();
+            for compilation_error in compilation_errors.iter().filter(|err| err.is_error()) {
+                reason.push('\n');
+                reason.push_str(compilation_error.full_message());
+            }
+            EtherscanResponse::error(format!("Fail - Unable to verify. {reason}"))
+        }
+    }
+}
+
+/// Item returned by the `getsourcecode` action.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct EtherscanSourceCode {
+    source_code: String,
+    #[serde(rename = "ABI")]
+    abi: String,
+    contract_name: String,
+    compiler_version: String,
+    /// zkSync-specific field containing the `zksolc` / `zkvyper` version.
+    zk_compiler_version: String,
+    optimization_used: String,
+    runs: String,
+    constructor_arguments: String,
+    #[serde(rename = "EVMVersion")]
+    evm_version: String,
+    library: String,
+    license_type: String,
+    proxy: String,
+    implementation: String,
+    swarm_source: String,
+}
+
+impl EtherscanSourceCode {
+    fn not_verified() -> Self {
+        Self {
+            abi: NOT_VERIFIED_MESSAGE.to_owned(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<ContractVerificationInfo> for EtherscanSourceCode {
+    fn from(info: ContractVerificationInfo) -> Self {
+        let (proxy, implementation) = match &info.proxy {
+            Some(proxy) => ("1", format!("{:?}", proxy.implementation)),
+            None => ("0", String::new()),
+        };
+        let info = info.info;
+        let request = info.request.req;
+        let source_code = match request.source_code_data {
+            SourceCodeData::SolSingleFile(source) | SourceCodeData::YulSingleFile(source) => source,
+            // Etherscan wraps standard JSON inputs in double braces.
+            SourceCodeData::StandardJsonInput(input) => {
+                format!("{{{}}}", serde_json::Value::Object(input))
+            }
+            SourceCodeData::VyperMultiFile(sources) => {
+                let sources: serde_json::Map<_, _> = sources
+                    .into_iter()
+                    .map(|(path, content)| (path, serde_json::json!({ "content": content })))
+                    .collect();
+                let input = serde_json::json!({ "language": "Vyper", "sources": sources });
+                format!("{{{input}}}")
+            }
+        };
+        let compiler_version = match &request.compiler_versions {
+            CompilerVersions::Solc { .. } => {
+                format!("v{}", request.compiler_versions.compiler_version())
+            }
+            CompilerVersions::Vyper { .. } => {
+                format!("vyper:{}", request.compiler_versions.compiler_version())
+            }
+        };
+        let contract_name = request
+            .contract_name
+            .rsplit_once(':')
+            .map_or(request.contract_name.as_str(), |(_, name)| name);
+
+        Self {
+            source_code,
+            abi: info.artifacts.abi.to_string(),
+            contract_name: contract_name.to_owned(),
+            compiler_version,
+            zk_compiler_version: request.compiler_versions.zk_compiler_version(),
+            optimization_used: if request.optimization_used { "1" } else { "0" }.to_owned(),
+            constructor_arguments: hex::encode(&request.constructor_arguments.0),
+            evm_version: "Default".to_owned(),
+            proxy: proxy.to_owned(),
+            implementation,
+            ..Self::default()
+        }
+    }
+}
+
+impl RestApi {
+    /// Etherscan API endpoint accepting parameters in the query string (used for `GET` requests).
+    #[tracing::instrument(skip(self_, query))]
+    pub async fn etherscan_get(
+        self_: web::Data<Self>,
+        query: web::Query<HashMap<String, String>>,
+    ) -> ActixResult<HttpResponse> {
+        let params = EtherscanParams::new(query.into_inner());
+        self_.etherscan(params).await.into_http()
+    }
+
+    /// Etherscan API endpoint accepting parameters both in the query string and in the URL-encoded body
+    /// (used for `POST` requests).
+    #[tracing::instrument(skip(self_, query, form))]
+    pub async fn etherscan_post(
+        self_: web::Data<Self>,
+        query: web::Query<HashMap<String, String>>,
+        form: web::Form<HashMap<String, String>>,
+    ) -> ActixResult<HttpResponse> {
+        let params = query.into_inner().into_iter().chain(form.into_inner());
+        let params = EtherscanParams::new(params);
+        self_.etherscan(params).await.into_http()
+    }
+
+    async fn etherscan(&self, params: EtherscanParams) -> EtherscanResponse {
+        if params.get("module") != Some("contract") {
+            return EtherscanResponse::error("Unsupported module; only `contract` is supported");
+        }
+        match params.get("action") {
+            Some("verifysourcecode") => self.etherscan_verify(&params).await.into(),
+            Some("checkverifystatus") => self.etherscan_verification_status(&params).await.into(),
+            Some("getabi") => self.etherscan_abi(&params).await.into(),
+            Some("getsourcecode") => self.etherscan_source_code(&params).await.into(),
+            Some(action) => EtherscanResponse::error(format!("Unsupported action: {action}")),
+            None => EtherscanResponse::error("Missing `action` parameter"),
+        }
+    }
+
+    async fn etherscan_verify(
+        &self,
+        params: &EtherscanParams,
+    ) -> Result<EtherscanResponse, String> {
+        let method_latency = METRICS.call[&"etherscan_verify_source_code"].start();
+        let request = params.to_verification_request()?;
+        let request_id = self
+            .queue_verification_request(request)
+            .await
+            .map_err(|rejection| rejection.to_string())?;
+        method_latency.observe();
+        // The request ID is used as the GUID for status polling.
+        Ok(EtherscanResponse::ok(request_id.to_string()))
+    }
+
+    async fn etherscan_verification_status(
+        &self,
+        params: &EtherscanParams,
+    ) -> Result<EtherscanResponse, String> {
+        let method_latency = METRICS.call[&"etherscan_check_verify_status"].start();
+        let guid = params.require("guid")?;
+        let Ok(request_id) = guid.parse::<usize>() else {
+            return Ok(EtherscanResponse::error("Unknown UID"));
+        };
+        let status = self
+            .replica_connection_pool
+            .access_storage_tagged("api")
+            .await
+            .unwrap()
+            .contract_verification_dal()
+            .get_verification_request_status(request_id)
+            .await
+            .unwrap();
+
+        method_latency.observe();
+        Ok(verification_status_response(status))
+    }
+
+    async fn etherscan_abi(&self, params: &EtherscanParams) -> Result<EtherscanResponse, String> {
+        let method_latency = METRICS.call[&"etherscan_get_abi"].start();
+        let address = params.address("address")?;
+        let info = self.contract_verification_info(address, false).await;
+
+        method_latency.observe();
+        Ok(match info {
+            // Etherscan returns ABI as a JSON string rather than a JSON value.
+            Some(info) => EtherscanResponse::ok(info.info.artifacts.abi.to_string()),
+            None => EtherscanResponse::error(NOT_VERIFIED_MESSAGE),
+        })
+    }
+
+    async fn etherscan_source_code(
+        &self,
+        params: &EtherscanParams,
+    ) -> Result<EtherscanResponse, String> {
+        let method_latency = METRICS.call[&"etherscan_get_source_code"].start();
+        let address = params.address("address")?;
+        // Etherscan reports whether the contract is a proxy in the source code response.
+        let info = self.contract_verification_info(address, true).await;
+
+        method_latency.observe();
+        // Etherscan responds with a successful status and empty fields for unverified contracts.
+        let source_code = info.map_or_else(EtherscanSourceCode::not_verified, Into::into);
+        Ok(EtherscanResponse::ok([source_code]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        http::{header::ContentType, StatusCode},
+        test, App,
+    };
+    use zksync_dal::ConnectionPool;
+    use zksync_types::L2ChainId;
+
+    use super::*;
+    use crate::api_server::contract_verification::MAX_REQUEST_BODY_SIZE;
+
+    fn params(params: &[(&str, &str)]) -> EtherscanParams {
+        let params = params
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()));
+        EtherscanParams::new(params)
+    }
+
+    #[test]
+    fn normalizing_compiler_versions() {
+        assert_eq!(
+            normalize_compiler_version("v0.8.17+commit.8df45f5f"),
+            "0.8.17"
+        );
+        assert_eq!(normalize_compiler_version("0.8.17"), "0.8.17");
+        assert_eq!(normalize_compiler_version("vyper:0.3.10"), "0.3.10");
+    }
+
+    #[test]
+    fn translating_single_file_request() {
+        let params = params(&[
+            ("module", "contract"),
+            ("action", "verifysourcecode"),
+            (
+                "contractaddress",
+                "0x0000000000000000000000000000000000001234",
+            ),
+            ("sourceCode", "contract Test {}"),
+            ("contractname", "Test"),
+            ("compilerversion", "v0.8.17+commit.8df45f5f"),
+            ("zksolcVersion", "v1.3.14"),
+            ("optimizationUsed", "1"),
+            ("constructorArguements", "0x0102"),
+        ]);
+        let request = params.to_verification_request().unwrap();
+
+        assert_eq!(request.contract_address, Address::from_low_u64_be(0x1234));
+        assert!(
+            matches!(&request.source_code_data, SourceCodeData::SolSingleFile(code) if code == "contract Test {}")
+        );
+        assert_eq!(request.contract_name, "Test");
+        assert_eq!(request.compiler_versions.compiler_version(), "0.8.17");
+        assert_eq!(request.compiler_versions.zk_compiler_version(), "v1.3.14");
+        assert!(request.optimization_used);
+        assert_eq!(request.constructor_arguments.0, [1, 2]);
+        assert!(!request.is_system);
+    }
+
+    #[test]
+    fn translating_standard_json_request() {
+        let params = params(&[
+            (
+                "contractaddress",
+                "0x0000000000000000000000000000000000001234",
+            ),
+            ("codeformat", "solidity-standard-json-input"),
+            ("sourceCode", r#"{{"language": "Solidity", "sources": {}}}"#),
+            ("contractname", "contracts/Test.sol:Test"),
+            ("compilerversion", "v0.8.17+commit.8df45f5f"),
+            ("zksolcversion", "v1.3.14"),
+        ]);
+        let request = params.to_verification_request().unwrap();
+        let SourceCodeData::StandardJsonInput(input) = &request.source_code_data else {
+            panic!(
+                "unexpected source code data: {:?}",
+                request.source_code_data
+            );
+        };
+        assert_eq!(input["language"], "Solidity");
+        assert_eq!(request.contract_name, "contracts/Test.sol:Test");
+    }
+
+    #[test]
+    fn translating_vyper_request() {
+        let params = params(&[
+            (
+                "contractaddress",
+                "0x0000000000000000000000000000000000001234",
+            ),
+            ("codeformat", "vyper-json"),
+            (
+                "sourceCode",
+                r#"{"language": "Vyper", "sources": {"Test.vy": {"content": "x: uint256"}}}"#,
+            ),
+            ("contractname", "Test"),
+            ("compilerversion", "vyper:0.3.10"),
+            ("zkvyperVersion", "v1.3.13"),
+        ]);
+        let request = params.to_verification_request().unwrap();
+        let SourceCodeData::VyperMultiFile(sources) = &request.source_code_data else {
+            panic!(
+                "unexpected source code data: {:?}",
+                request.source_code_data
+            );
+        };
+        assert_eq!(sources["Test.vy"], "x: uint256");
+        assert!(matches!(
+            request.compiler_versions,
+            CompilerVersions::Vyper { .. }
+        ));
+        assert_eq!(request.compiler_versions.compiler_version(), "0.3.10");
+    }
+
+    #[test]
+    fn invalid_requests_are_rejected() {
+        let err = params(&[("contractname", "Test")])
+            .to_verification_request()
+            .unwrap_err();
+        assert!(err.contains("sourcecode"), "{err}");
+
+        let err = params(&[
+            ("sourcecode", "contract Test {}"),
+            ("compilerversion", "v0.8.17"),
+        ])
+        .to_verification_request()
+        .unwrap_err();
+        assert!(err.contains("zksolcversion"), "{err}");
+    }
+
+    #[test]
+    fn mapping_verification_status() {
+        let status = |status: &str| VerificationRequestStatus {
+            status: status.to_owned(),
+            error: Some("Bytecode mismatch".to_owned()),
+            compilation_errors: None,
+            match_level: None,
+        };
+
+        let response = verification_status_response(Some(status("queued")));
+        assert_eq!(
+            (response.status, response.result.as_str()),
+            ("0", Some("Pending in queue"))
+        );
+        let response = verification_status_response(Some(status("successful")));
+        assert_eq!(
+            (response.status, response.result.as_str()),
+            ("1", Some("Pass - Verified"))
+        );
+        let response = verification_status_response(Some(status("failed")));
+        assert_eq!(response.status, "0");
+        let result = response.result.as_str().unwrap();
+        assert!(result.starts_with("Fail - Unable to verify"), "{result}");
+        assert!(result.contains("Bytecode mismatch"), "{result}");
+        let response = verification_status_response(None);
+        assert_eq!(response.result.as_str(), Some("Unknown UID"));
+    }
+
+    async fn post_form(body: String) -> serde_json::Value {
+        let pool = ConnectionPool::test_pool().await;
+        let api = RestApi::new(pool.clone(), pool, L2ChainId::default());
+        let app = App::new()
+            .app_data(form_config(MAX_REQUEST_BODY_SIZE))
+            .service(api.into_scope());
+        let app = test::init_service(app).await;
+
+        let request = test::TestRequest::post()
+            .uri("/api")
+            .insert_header(ContentType::form_url_encoded())
+            .set_payload(body)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        test::read_body_json(response).await
+    }
+
+    #[actix_web::test]
+    async fn posting_large_form() {
+        // Larger than the default 16 KiB limit for URL-encoded bodies.
+        let padding = "0".repeat(64 * 1024);
+        let response = post_form(format!("module=account&sourceCode={padding}")).await;
+        assert_eq!(response["status"], "0");
+        assert_eq!(response["message"], "NOTOK");
+        let result = response["result"].as_str().unwrap();
+        assert!(result.contains("Unsupported module"), "{result}");
+    }
+
+    #[actix_web::test]
+    async fn posting_form_exceeding_limit() {
+        let padding = "0".repeat(MAX_REQUEST_BODY_SIZE);
+        let response = post_form(format!("module=contract&sourceCode={padding}")).await;
+        assert_eq!(response["status"], "0");
+        assert_eq!(response["message"], "NOTOK");
+        let result = response["result"].as_str().unwrap();
+        assert!(result.contains("larger"), "{result}");
+    }
+}
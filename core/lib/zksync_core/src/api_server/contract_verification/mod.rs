@@ -5,13 +5,20 @@ use actix_web::{dev::Server, web, App, HttpResponse, HttpServer};
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_config::configs::api::ContractVerificationApiConfig;
 use zksync_dal::connection::ConnectionPool;
+use zksync_types::L2ChainId;
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
 use self::api_decl::RestApi;
 
 mod api_decl;
 mod api_impl;
+mod etherscan;
 mod metrics;
+mod proxy;
+mod sourcify;
+
+/// Maximum size of JSON and URL-encoded request bodies.
+const MAX_REQUEST_BODY_SIZE: usize = 2 * 1024 * 1024;
 
 fn start_server(api: RestApi, bind_to: SocketAddr, threads: usize) -> Server {
     HttpServer::new(move || {
@@ -25,6 +32,8 @@ fn start_server(api: RestApi, bind_to: SocketAddr, threads: usize) -> Server {
                     .allow_any_header()
                     .allow_any_method(),
             )
+            .app_data(web::JsonConfig::default().limit(MAX_REQUEST_BODY_SIZE))
+            .app_data(etherscan::form_config(MAX_REQUEST_BODY_SIZE))
             .service(api.into_scope())
             // Endpoint needed for js isReachable
             .route(
@@ -47,6 +56,7 @@ pub fn start_server_thread_detached(
     master_connection_pool: ConnectionPool,
     replica_connection_pool: ConnectionPool,
     api_config: ContractVerificationApiConfig,
+    l2_chain_id: L2ChainId,
     mut stop_receiver: watch::Receiver<bool>,
 ) -> JoinHandle<anyhow::Result<()>> {
     let (handler, panic_sender) = spawn_panic_handler();
@@ -59,7 +69,8 @@ pub fn start_server_thread_detached(
             actix_rt::System::new().block_on(async move {
                 let bind_address = api_config.bind_addr();
                 let threads = api_config.threads_per_server as usize;
-                let api = RestApi::new(master_connection_pool, replica_connection_pool);
+                let api =
+                    RestApi::new(master_connection_pool, replica_connection_pool, l2_chain_id);
 
                 let server = start_server(api, bind_address, threads);
                 let close_handle = server.handle();
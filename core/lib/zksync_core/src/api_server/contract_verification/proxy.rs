@@ -0,0 +1,60 @@
+//! Detection of proxy contracts based on standardized implementation storage slots.
+
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    contract_verification_api::ProxyKind, web3::signing::keccak256, AccountTreeId, Address,
+    StorageKey, H256,
+};
+use zksync_utils::{h256_to_account_address, h256_to_u256, u256_to_h256};
+
+/// Returns storage slots holding the implementation address for supported proxy standards.
+fn implementation_slots() -> [(ProxyKind, H256); 3] {
+    // EIP-1967 slot is offset by 1 so that its preimage is unknown.
+    let eip1967_slot = h256_to_u256(H256(keccak256(b"eip1967.proxy.implementation"))) - 1;
+    [
+        (ProxyKind::Eip1967, u256_to_h256(eip1967_slot)),
+        (ProxyKind::Eip1822, H256(keccak256(b"PROXIABLE"))),
+        (
+            ProxyKind::OpenZeppelin,
+            H256(keccak256(b"org.zeppelinos.proxy.implementation")),
+        ),
+    ]
+}
+
+/// Checks whether the contract at `address` is a proxy, and returns its kind and the implementation address
+/// if it is.
+pub(super) async fn detect_proxy(
+    storage: &mut StorageProcessor<'_>,
+    address: Address,
+) -> Option<(ProxyKind, Address)> {
+    for (kind, slot) in implementation_slots() {
+        let key = StorageKey::new(AccountTreeId::new(address), slot);
+        let Some(value) = storage.storage_dal().get_by_key(&key).await else {
+            continue;
+        };
+        // Slot values are left-padded addresses; values with non-zero high bytes are not addresses.
+        let is_address = value.as_bytes()[..12].iter().all(|&byte| byte == 0);
+        if is_address && !value.is_zero() {
+            return Some((kind, h256_to_account_address(&value)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn implementation_slots_are_correct() {
+        let slots = implementation_slots();
+        let expected_slots = [
+            "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc",
+            "0xc5f16f0fcc639fa48a6947836d9850f504798523bf8c9a3a87d5876cf622bcf7",
+            "0x7050c9e0f4ca769c69bd3a8ef740bc37934f8e2c036e5a723fd8ee048ed3f8c3",
+        ];
+        for ((_, slot), expected) in slots.iter().zip(expected_slots) {
+            assert_eq!(*slot, expected.parse().unwrap());
+        }
+    }
+}
@@ -0,0 +1,534 @@
+//! Sourcify-compatible contract verification API (the asynchronous `/v2` flavor), mounted at `/sourcify`.
+
+use actix_web::{
+    web::{self, Json},
+    HttpResponse, Result as ActixResult,
+};
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    contract_verification_api::{
+        CompilerVersions, MatchLevel, SourceCodeData, VerificationIncomingRequest,
+        VerificationInfo, VerificationRequestStatus,
+    },
+    Address, Bytes,
+};
+
+use super::{api_decl::RestApi, api_impl::VerificationRejection, metrics::METRICS};
+
+type JsonObject = serde_json::Map<String, serde_json::Value>;
+
+/// Request body for `POST /v2/verify/{chain_id}/{address}`. The `creationTransactionHash` field
+/// is ignored; constructor arguments must be supplied explicitly instead.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SourcifyVerificationRequest {
+    std_json_input: JsonObject,
+    compiler_version: String,
+    /// Fully qualified contract name, e.g. `contracts/Token.sol:Token`.
+    contract_identifier: String,
+    // zkSync-specific fields.
+    #[serde(alias = "zksolcVersion", alias = "zkvyperVersion")]
+    zk_compiler_version: String,
+    #[serde(default)]
+    optimizer_mode: Option<String>,
+    #[serde(default)]
+    constructor_arguments: Bytes,
+    #[serde(default)]
+    is_system: bool,
+}
+
+impl SourcifyVerificationRequest {
+    fn into_verification_request(
+        self,
+        contract_address: Address,
+    ) -> Result<VerificationIncomingRequest, SourcifyError> {
+        let optimization_used = self
+            .std_json_input
+            .get("settings")
+            .and_then(|settings| settings.get("optimizer")?.get("enabled")?.as_bool())
+            .unwrap_or(false);
+        let compiler_version = self
+            .compiler_version
+            .strip_prefix('v')
+            .unwrap_or(&self.compiler_version);
+        let compiler_version = compiler_version
+            .split_once('+')
+            .map_or(compiler_version, |(version, _)| version)
+            .to_owned();
+
+        let language = self
+            .std_json_input
+            .get("language")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("Solidity")
+            .to_owned();
+        let (source_code_data, compiler_versions, contract_name) = match language.as_str() {
+            "Solidity" => {
+                let compiler_versions = CompilerVersions::Solc {
+                    compiler_zksolc_version: self.zk_compiler_version,
+                    compiler_solc_version: compiler_version,
+                };
+                let source_code_data = SourceCodeData::StandardJsonInput(self.std_json_input);
+                (
+                    source_code_data,
+                    compiler_versions,
+                    self.contract_identifier,
+                )
+            }
+            "Vyper" => {
+                let sources = sources(&self.std_json_input)
+                    .map(|(path, content)| (path.to_owned(), content.to_owned()))
+                    .collect();
+                let compiler_versions = CompilerVersions::Vyper {
+                    compiler_zkvyper_version: self.zk_compiler_version,
+                    compiler_vyper_version: compiler_version,
+                };
+                // The Vyper verifier identifies contracts by their file name.
+                let contract_name = self
+                    .contract_identifier
+                    .rsplit_once(':')
+                    .map_or(self.contract_identifier.as_str(), |(_, name)| name)
+                    .to_owned();
+                let source_code_data = SourceCodeData::VyperMultiFile(sources);
+                (source_code_data, compiler_versions, contract_name)
+            }
+            language => {
+                return Err(SourcifyError::invalid_parameter(format!(
+                    "Unsupported language: {language}"
+                )));
+            }
+        };
+
+        Ok(VerificationIncomingRequest {
+            contract_address,
+            source_code_data,
+            contract_name,
+            compiler_versions,
+            optimization_used,
+            optimizer_mode: self.optimizer_mode,
+            constructor_arguments: self.constructor_arguments,
+            is_system: self.is_system,
+        })
+    }
+}
+
+/// Iterates over `(path, content)` pairs in a standard JSON input.
+fn sources(input: &JsonObject) -> impl Iterator<Item = (&str, &str)> + '_ {
+    let sources = input.get("sources").and_then(serde_json::Value::as_object);
+    sources.into_iter().flatten().filter_map(|(path, source)| {
+        let content = source.get("content")?.as_str()?;
+        Some((path.as_str(), content))
+    })
+}
+
+/// Sourcify error response.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourcifyError {
+    #[serde(skip)]
+    http_status: u16,
+    custom_code: &'static str,
+    message: String,
+}
+
+impl SourcifyError {
+    fn invalid_parameter(message: String) -> Self {
+        Self {
+            http_status: 400,
+            custom_code: "invalid_parameter",
+            message,
+        }
+    }
+
+    fn not_found(message: String) -> Self {
+        Self {
+            http_status: 404,
+            custom_code: "not_found",
+            message,
+        }
+    }
+
+    fn into_http(self) -> ActixResult<HttpResponse> {
+        let status =
+            actix_web::http::StatusCode::from_u16(self.http_status).expect("invalid HTTP status");
+        Ok(HttpResponse::build(status).json(self))
+    }
+}
+
+impl From<VerificationRejection> for SourcifyError {
+    fn from(rejection: VerificationRejection) -> Self {
+        let (http_status, custom_code) = match rejection {
+            VerificationRejection::NotDeployed => (404, "contract_not_deployed"),
+            VerificationRejection::AlreadyVerified => (409, "already_verified"),
+        };
+        Self {
+            http_status,
+            custom_code,
+            message: rejection.to_string(),
+        }
+    }
+}
+
+/// Maps the match level to the Sourcify match status.
+fn sourcify_match(match_level: MatchLevel) -> &'static str {
+    match match_level {
+        MatchLevel::Full => "exact_match",
+        MatchLevel::Partial => "match",
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourcifyJobStatus {
+    is_job_completed: bool,
+    verification_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<SourcifyError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contract: Option<SourcifyMatch>,
+}
+
+impl SourcifyJobStatus {
+    fn new(verification_id: String, chain_id: u64, status: VerificationRequestStatus) -> Self {
+        let (is_job_completed, error, contract) = match status.status.as_str() {
+            "queued" | "in_progress" => (false, None, None),
+            "successful" => {
+                let match_level = status.match_level.unwrap_or_default();
+                let contract = SourcifyMatch {
+                    r#match: Some(sourcify_match(match_level)),
+                    chain_id: chain_id.to_string(),
+                    address: None,
+                };
+                (true, None, Some(contract))
+            }
+            _ => {
+                let mut message = status.error.unwrap_or_default();
+                let compilation_errors = status.compilation_errors.unwrap_or_default();
+                for compilation_error in compilation_errors.iter().filter(|err| err.is_error()) {
+                    message.push('\n');
+                    message.push_str(compilation_error.full_message());
+                }
+                let error = SourcifyError {
+                    http_status: 200,
+                    custom_code: "verification_failed",
+                    message,
+                };
+                (true, Some(error), None)
+            }
+        };
+        Self {
+            is_job_completed,
+            verification_id,
+            error,
+            contract,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourcifyMatch {
+    r#match: Option<&'static str>,
+    chain_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<Address>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourcifyContract {
+    #[serde(flatten)]
+    contract: SourcifyMatch,
+    abi: serde_json::Value,
+    compilation: SourcifyCompilation,
+    sources: JsonObject,
+    verified_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourcifyCompilation {
+    language: &'static str,
+    compiler_version: String,
+    /// zkSync-specific field containing the `zksolc` / `zkvyper` version.
+    zk_compiler_version: String,
+    name: String,
+    fully_qualified_name: String,
+    compiler_settings: serde_json::Value,
+}
+
+impl SourcifyContract {
+    fn new(chain_id: u64, address: Address, info: VerificationInfo) -> Self {
+        let request = info.request.req;
+        let (name, fully_qualified_name) = match request.contract_name.rsplit_once(':') {
+            Some((_, name)) => (name.to_owned(), request.contract_name.clone()),
+            None => (
+                request.contract_name.clone(),
+                format!("{0}.sol:{0}", request.contract_name),
+            ),
+        };
+        let language = match &request.compiler_versions {
+            CompilerVersions::Solc { .. } => "Solidity",
+            CompilerVersions::Vyper { .. } => "Vyper",
+        };
+
+        let mut compiler_settings = serde_json::json!({
+            "optimizer": { "enabled": request.optimization_used },
+        });
+        let sources: JsonObject = match &request.source_code_data {
+            SourceCodeData::SolSingleFile(content) | SourceCodeData::YulSingleFile(content) => {
+                let path = fully_qualified_name
+                    .rsplit_once(':')
+                    .map_or(fully_qualified_name.as_str(), |(path, _)| path);
+                [(path.to_owned(), serde_json::json!({ "content": content }))]
+                    .into_iter()
+                    .collect()
+            }
+            SourceCodeData::StandardJsonInput(input) => {
+                if let Some(settings) = input.get("settings") {
+                    compiler_settings = settings.clone();
+                }
+                sources(input)
+                    .map(|(path, content)| {
+                        (path.to_owned(), serde_json::json!({ "content": content }))
+                    })
+                    .collect()
+            }
+            SourceCodeData::VyperMultiFile(sources) => sources
+                .iter()
+                .map(|(path, content)| (path.clone(), serde_json::json!({ "content": content })))
+                .collect(),
+        };
+
+        Self {
+            contract: SourcifyMatch {
+                r#match: Some(sourcify_match(info.match_level)),
+                chain_id: chain_id.to_string(),
+                address: Some(address),
+            },
+            abi: info.artifacts.abi,
+            compilation: SourcifyCompilation {
+                language,
+                compiler_version: request.compiler_versions.compiler_version(),
+                zk_compiler_version: request.compiler_versions.zk_compiler_version(),
+                name,
+                fully_qualified_name,
+                compiler_settings,
+            },
+            sources,
+            verified_at: info.verified_at.to_rfc3339(),
+        }
+    }
+}
+
+impl RestApi {
+    fn check_sourcify_chain_id(&self, chain_id: &str) -> Result<u64, SourcifyError> {
+        let expected_chain_id = self.l2_chain_id.as_u64();
+        if chain_id.parse::<u64>() == Ok(expected_chain_id) {
+            Ok(expected_chain_id)
+        } else {
+            Err(SourcifyError {
+                http_status: 400,
+                custom_code: "unsupported_chain",
+                message: format!("Unsupported chain ID {chain_id}; expected {expected_chain_id}"),
+            })
+        }
+    }
+
+    /// Queues a verification request submitted in the Sourcify format.
+    #[tracing::instrument(skip(self_, request))]
+    pub async fn sourcify_verify(
+        self_: web::Data<Self>,
+        path: web::Path<(String, Address)>,
+        Json(request): Json<SourcifyVerificationRequest>,
+    ) -> ActixResult<HttpResponse> {
+        let method_latency = METRICS.call[&"sourcify_verify"].start();
+        let (chain_id, address) = path.into_inner();
+        if let Err(err) = self_.check_sourcify_chain_id(&chain_id) {
+            return err.into_http();
+        }
+        let request = match request.into_verification_request(address) {
+            Ok(request) => request,
+            Err(err) => return err.into_http(),
+        };
+        let request_id = match self_.queue_verification_request(request).await {
+            Ok(request_id) => request_id,
+            Err(rejection) => return SourcifyError::from(rejection).into_http(),
+        };
+
+        method_latency.observe();
+        Ok(HttpResponse::Accepted().json(serde_json::json!({
+            "verificationId": request_id.to_string(),
+        })))
+    }
+
+    /// Polls the status of a verification request submitted via [`Self::sourcify_verify()`].
+    #[tracing::instrument(skip(self_))]
+    pub async fn sourcify_verification_status(
+        self_: web::Data<Self>,
+        verification_id: web::Path<String>,
+    ) -> ActixResult<HttpResponse> {
+        let method_latency = METRICS.call[&"sourcify_verification_status"].start();
+        let verification_id = verification_id.into_inner();
+        let not_found = || {
+            let message = format!("Unknown verification job: {verification_id}");
+            SourcifyError::not_found(message).into_http()
+        };
+        let Ok(request_id) = verification_id.parse::<usize>() else {
+            return not_found();
+        };
+        let status = self_
+            .replica_connection_pool
+            .access_storage_tagged("api")
+            .await
+            .unwrap()
+            .contract_verification_dal()
+            .get_verification_request_status(request_id)
+            .await
+            .unwrap();
+
+        method_latency.observe();
+        let Some(status) = status else {
+            return not_found();
+        };
+        let chain_id = self_.l2_chain_id.as_u64();
+        let status = SourcifyJobStatus::new(verification_id, chain_id, status);
+        Ok(HttpResponse::Ok().json(status))
+    }
+
+    /// Returns the verified contract in the Sourcify format.
+    #[tracing::instrument(skip(self_))]
+    pub async fn sourcify_contract(
+        self_: web::Data<Self>,
+        path: web::Path<(String, Address)>,
+    ) -> ActixResult<HttpResponse> {
+        let method_latency = METRICS.call[&"sourcify_contract"].start();
+        let (chain_id, address) = path.into_inner();
+        let chain_id = match self_.check_sourcify_chain_id(&chain_id) {
+            Ok(chain_id) => chain_id,
+            Err(err) => return err.into_http(),
+        };
+        let info = self_.contract_verification_info(address, false).await;
+
+        method_latency.observe();
+        match info {
+            Some(info) => {
+                let contract = SourcifyContract::new(chain_id, address, info.info);
+                Ok(HttpResponse::Ok().json(contract))
+            }
+            None => {
+                let message = format!("Contract {address:?} is not verified");
+                SourcifyError::not_found(message).into_http()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translating_verification_request() {
+        let request = serde_json::json!({
+            "stdJsonInput": {
+                "language": "Solidity",
+                "sources": { "contracts/Test.sol": { "content": "contract Test {}" } },
+                "settings": { "optimizer": { "enabled": true } },
+            },
+            "compilerVersion": "0.8.17+commit.8df45f5f",
+            "contractIdentifier": "contracts/Test.sol:Test",
+            "zksolcVersion": "v1.3.14",
+        });
+        let request: SourcifyVerificationRequest = serde_json::from_value(request).unwrap();
+        let address = Address::repeat_byte(0x23);
+        let request = request.into_verification_request(address).unwrap();
+
+        assert_eq!(request.contract_address, address);
+        assert!(matches!(
+            request.source_code_data,
+            SourceCodeData::StandardJsonInput(_)
+        ));
+        assert_eq!(request.contract_name, "contracts/Test.sol:Test");
+        assert_eq!(request.compiler_versions.compiler_version(), "0.8.17");
+        assert_eq!(request.compiler_versions.zk_compiler_version(), "v1.3.14");
+        assert!(request.optimization_used);
+    }
+
+    #[test]
+    fn translating_vyper_verification_request() {
+        let request = serde_json::json!({
+            "stdJsonInput": {
+                "language": "Vyper",
+                "sources": { "contracts/Test.vy": { "content": "x: uint256" } },
+            },
+            "compilerVersion": "0.3.10",
+            "contractIdentifier": "contracts/Test.vy:Test",
+            "zkvyperVersion": "v1.3.13",
+        });
+        let request: SourcifyVerificationRequest = serde_json::from_value(request).unwrap();
+        let request = request
+            .into_verification_request(Address::repeat_byte(0x23))
+            .unwrap();
+
+        let SourceCodeData::VyperMultiFile(sources) = &request.source_code_data else {
+            panic!(
+                "unexpected source code data: {:?}",
+                request.source_code_data
+            );
+        };
+        assert_eq!(sources["contracts/Test.vy"], "x: uint256");
+        assert_eq!(request.contract_name, "Test");
+        assert!(!request.optimization_used);
+    }
+
+    #[test]
+    fn serializing_job_status() {
+        let status = VerificationRequestStatus {
+            status: "failed".to_owned(),
+            error: Some("Bytecode mismatch".to_owned()),
+            compilation_errors: None,
+            match_level: None,
+        };
+        let status = SourcifyJobStatus::new("1".to_owned(), 270, status);
+        let status = serde_json::to_value(status).unwrap();
+        assert_eq!(
+            status,
+            serde_json::json!({
+                "isJobCompleted": true,
+                "verificationId": "1",
+                "error": { "customCode": "verification_failed", "message": "Bytecode mismatch" },
+            })
+        );
+
+        let status = VerificationRequestStatus {
+            status: "queued".to_owned(),
+            error: None,
+            compilation_errors: None,
+            match_level: None,
+        };
+        let status = SourcifyJobStatus::new("2".to_owned(), 270, status);
+        let status = serde_json::to_value(status).unwrap();
+        assert_eq!(
+            status,
+            serde_json::json!({ "isJobCompleted": false, "verificationId": "2" })
+        );
+
+        let status = VerificationRequestStatus {
+            status: "successful".to_owned(),
+            error: None,
+            compilation_errors: None,
+            match_level: Some(MatchLevel::Partial),
+        };
+        let status = SourcifyJobStatus::new("3".to_owned(), 270, status);
+        let status = serde_json::to_value(status).unwrap();
+        assert_eq!(
+            status,
+            serde_json::json!({
+                "isJobCompleted": true,
+                "verificationId": "3",
+                "contract": { "match": "match", "chainId": "270" },
+            })
+        );
+    }
+}
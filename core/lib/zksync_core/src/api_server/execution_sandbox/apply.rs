@@ -6,18 +6,25 @@
 //!
 //! This module is intended to be blocking.
 
-use std::time::{Duration, Instant};
+use std::{
+    num::NonZeroU32,
+    time::{Duration, Instant},
+};
 
 use multivm::{
     interface::{L1BatchEnv, L2BlockEnv, SystemEnv, VmInterface},
     vm_latest::{constants::BLOCK_GAS_LIMIT, HistoryDisabled},
     VmInstance,
 };
-use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
+use zksync_dal::{
+    backend::{BlocksReader, StorageReader},
+    ConnectionPool, SqlxError, StorageProcessor,
+};
 use zksync_state::{PostgresStorage, ReadStorage, StorageView, WriteStorage};
 use zksync_system_constants::{
-    SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
-    SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION, ZKPORTER_IS_AVAILABLE,
+    SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION,
+    SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION, SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+    SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES, ZKPORTER_IS_AVAILABLE,
 };
 use zksync_types::{
     api,
@@ -25,13 +32,15 @@ use zksync_types::{
     get_nonce_key,
     utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
     AccountTreeId, L1BatchNumber, MiniblockNumber, Nonce, ProtocolVersionId, StorageKey,
-    Transaction, H256, U256,
+    StorageValue, Transaction, H256, U256,
 };
 use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{
-    vm_metrics::{self, SandboxStage, SANDBOX_METRICS},
-    BlockArgs, TxExecutionArgs, TxSharedArgs, VmPermit,
+    storage::StorageWithOverrides,
+    vm_metrics::{self, SandboxStage, SandboxStorageKind, SANDBOX_METRICS},
+    warm_state::{WarmStateSnapshot, WarmStorage},
+    BlockArgs, SandboxBlockOverrides, TxExecutionArgs, TxSharedArgs, VmPermit,
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -43,7 +52,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
     tx: Transaction,
     block_args: BlockArgs,
     apply: impl FnOnce(
-        &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
+        &mut VmInstance<StorageView<StorageWithOverrides<SandboxStorage<'_>>>, HistoryDisabled>,
         Transaction,
     ) -> T,
 ) -> T {
@@ -51,46 +60,74 @@ pub(super) fn apply_vm_in_sandbox<T>(
     let span = tracing::debug_span!("initialization").entered();
 
     let rt_handle = vm_permit.rt_handle();
-    let mut connection = rt_handle
-        .block_on(connection_pool.access_storage_tagged("api"))
-        .unwrap();
-    let connection_acquire_time = stage_started_at.elapsed();
-    // We don't want to emit too many logs.
-    if connection_acquire_time > Duration::from_millis(10) {
-        tracing::debug!(
-            "Obtained connection (took {:?})",
-            stage_started_at.elapsed()
-        );
-    }
-
-    let resolve_started_at = Instant::now();
-    let ResolvedBlockInfo {
-        state_l2_block_number,
-        vm_l1_batch_number,
-        l1_batch_timestamp,
-        protocol_version,
-    } = rt_handle
-        .block_on(block_args.resolve_block_info(&mut connection))
-        .expect("Failed resolving block numbers");
-    let resolve_time = resolve_started_at.elapsed();
-    // We don't want to emit too many logs.
-    if resolve_time > Duration::from_millis(10) {
-        tracing::debug!(
-            "Resolved block numbers (took {:?})",
-            resolve_started_at.elapsed()
-        );
-    }
+    let warm_storage = shared_args
+        .warm_state
+        .as_ref()
+        .and_then(|warm_state| warm_state.storage_for(&block_args));
+    let (storage_kind, storage, block_state) = if let Some(warm_storage) = warm_storage {
+        let block_state =
+            SandboxBlockState::from_warm_snapshot(warm_storage.snapshot(), &block_args);
+        if block_args.resolves_to_latest_sealed_miniblock() {
+            shared_args
+                .caches
+                .schedule_values_update(block_state.resolved.state_l2_block_number);
+        }
+        (
+            SandboxStorageKind::Warm,
+            SandboxStorage::Warm(warm_storage),
+            block_state,
+        )
+    } else {
+        let mut connection = rt_handle
+            .block_on(connection_pool.access_storage_tagged("api"))
+            .unwrap();
+        let connection_acquire_time = stage_started_at.elapsed();
+        // We don't want to emit too many logs.
+        if connection_acquire_time > Duration::from_millis(10) {
+            tracing::debug!(
+                "Obtained connection (took {:?})",
+                stage_started_at.elapsed()
+            );
+        }
 
-    if block_args.resolves_to_latest_sealed_miniblock() {
-        shared_args
-            .caches
-            .schedule_values_update(state_l2_block_number);
-    }
+        let resolve_started_at = Instant::now();
+        let block_state = rt_handle
+            .block_on(SandboxBlockState::load(&mut connection, &block_args))
+            .expect("Failed resolving block numbers");
+        let resolve_time = resolve_started_at.elapsed();
+        // We don't want to emit too many logs.
+        if resolve_time > Duration::from_millis(10) {
+            tracing::debug!("Resolved block numbers (took {resolve_time:?})");
+        }
+        let state_l2_block_number = block_state.resolved.state_l2_block_number;
+        if block_args.resolves_to_latest_sealed_miniblock() {
+            shared_args
+                .caches
+                .schedule_values_update(state_l2_block_number);
+        }
+        let storage =
+            PostgresStorage::new(rt_handle.clone(), connection, state_l2_block_number, false)
+                .with_caches(shared_args.caches);
+        (
+            SandboxStorageKind::Postgres,
+            SandboxStorage::Postgres(storage),
+            block_state,
+        )
+    };
+    let SandboxBlockState {
+        resolved:
+            ResolvedBlockInfo {
+                vm_l1_batch_number,
+                l1_batch_timestamp,
+                protocol_version,
+                ..
+            },
+        current_l2_block_info,
+        prev_l2_block_info,
+    } = block_state;
 
     let mut l2_block_info_to_reset = None;
-    let current_l2_block_info =
-        rt_handle.block_on(read_l2_block_info(&mut connection, state_l2_block_number));
-    let next_l2_block_info = if block_args.is_pending_miniblock() {
+    let mut next_l2_block_info = if block_args.is_pending_miniblock() {
         L2BlockEnv {
             number: current_l2_block_info.l2_block_number + 1,
             timestamp: l1_batch_timestamp,
@@ -113,10 +150,8 @@ pub(super) fn apply_vm_in_sandbox<T>(
     } else {
         // We need to reset L2 block info in storage to process transaction in the current block context.
         // Actual resetting will be done after `storage_view` is created.
-        let prev_l2_block_info = rt_handle.block_on(read_l2_block_info(
-            &mut connection,
-            state_l2_block_number - 1,
-        ));
+        let prev_l2_block_info = prev_l2_block_info
+            .expect("previous L2 block info must be loaded for non-genesis block");
         l2_block_info_to_reset = Some(prev_l2_block_info);
         L2BlockEnv {
             number: current_l2_block_info.l2_block_number,
@@ -126,8 +161,8 @@ pub(super) fn apply_vm_in_sandbox<T>(
         }
     };
 
-    let storage = PostgresStorage::new(rt_handle.clone(), connection, state_l2_block_number, false)
-        .with_caches(shared_args.caches);
+    let state_override = shared_args.state_override.unwrap_or_default();
+    let storage = StorageWithOverrides::new(storage, &state_override);
     let mut storage_view = StorageView::new(storage);
 
     let storage_view_setup_started_at = Instant::now();
@@ -167,6 +202,16 @@ pub(super) fn apply_vm_in_sandbox<T>(
         );
     }
 
+    let block_overrides = execution_args.block_overrides.unwrap_or_default();
+    if block_overrides.number.is_some() || block_overrides.timestamp.is_some() {
+        next_l2_block_info = apply_block_overrides(
+            &mut storage_view,
+            next_l2_block_info,
+            &block_overrides,
+            protocol_version,
+        );
+    }
+
     let storage_view_setup_time = storage_view_setup_started_at.elapsed();
     // We don't want to emit too many logs.
     if storage_view_setup_time > Duration::from_millis(10) {
@@ -175,13 +220,21 @@ pub(super) fn apply_vm_in_sandbox<T>(
 
     let TxSharedArgs {
         operator_account,
-        l1_gas_price,
+        mut l1_gas_price,
         fair_l2_gas_price,
         base_system_contracts,
         validation_computational_gas_limit,
         chain_id,
         ..
     } = shared_args;
+    if let Some(l1_gas_price_override) = block_overrides.l1_gas_price {
+        l1_gas_price = l1_gas_price_override;
+    }
+    let l1_batch_timestamp = if block_overrides.timestamp.is_some() {
+        next_l2_block_info.timestamp
+    } else {
+        l1_batch_timestamp
+    };
 
     let system_env = SystemEnv {
         zk_porter_available: ZKPORTER_IS_AVAILABLE,
@@ -224,6 +277,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
     let execution_latency = SANDBOX_METRICS.sandbox[&SandboxStage::Execution].start();
     let result = apply(&mut vm, tx);
     let vm_execution_took = execution_latency.observe();
+    SANDBOX_METRICS.sandbox_call[&storage_kind].observe(stage_started_at.elapsed());
 
     let memory_metrics = vm.record_vm_memory_metrics();
     vm_metrics::report_vm_memory_metrics(
@@ -237,24 +291,184 @@ pub(super) fn apply_vm_in_sandbox<T>(
     result
 }
 
+/// Sets up the VM storage so that the executed L2 block has the overridden number and / or timestamp.
+/// The previous L2 block is replaced with a synthetic one (without transactions and with the timestamp
+/// directly preceding the overridden one) so that the VM and the system context accept the new block.
+fn apply_block_overrides<S: ReadStorage>(
+    storage_view: &mut StorageView<S>,
+    l2_block: L2BlockEnv,
+    block_overrides: &SandboxBlockOverrides,
+    protocol_version: ProtocolVersionId,
+) -> L2BlockEnv {
+    let number = block_overrides
+        .number
+        .map_or(l2_block.number, NonZeroU32::get);
+    let timestamp = block_overrides.timestamp.unwrap_or(l2_block.timestamp);
+
+    // Overridden numbers are non-zero by construction, and the executed L2 block is never the genesis one
+    // (see the special case in `apply_vm_in_sandbox()`), so the subtraction cannot overflow.
+    let prev_number = number - 1;
+    let prev_timestamp = timestamp.saturating_sub(1);
+    let prev_block_hash = if prev_number == 0 {
+        MiniblockHasher::legacy_hash(MiniblockNumber(0))
+    } else {
+        let hash_position = h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+            + U256::from((prev_number - 1) % SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES);
+        let hash_key = StorageKey::new(
+            AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+            u256_to_h256(hash_position),
+        );
+        let prev_prev_block_hash = storage_view.read_value(&hash_key);
+        MiniblockHasher::new(
+            MiniblockNumber(prev_number),
+            prev_timestamp,
+            prev_prev_block_hash,
+        )
+        .finalize(protocol_version)
+    };
+
+    let l2_block_info_key = StorageKey::new(
+        AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+        SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+    );
+    let l2_block_info = pack_block_info(prev_number.into(), prev_timestamp);
+    storage_view.set_value(l2_block_info_key, u256_to_h256(l2_block_info));
+    let l2_block_txs_rolling_hash_key = StorageKey::new(
+        AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+        SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+    );
+    storage_view.set_value(l2_block_txs_rolling_hash_key, H256::zero());
+
+    L2BlockEnv {
+        number,
+        timestamp,
+        prev_block_hash,
+        max_virtual_blocks_to_create: 1,
+    }
+}
+
+/// Storage used for sandboxed VM execution.
+#[derive(Debug)]
+pub(super) enum SandboxStorage<'a> {
+    Postgres(PostgresStorage<'a>),
+    Warm(WarmStorage),
+}
+
+impl ReadStorage for SandboxStorage<'_> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        match self {
+            Self::Postgres(storage) => storage.read_value(key),
+            Self::Warm(storage) => storage.read_value(key),
+        }
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        match self {
+            Self::Postgres(storage) => storage.is_write_initial(key),
+            Self::Warm(storage) => storage.is_write_initial(key),
+        }
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        match self {
+            Self::Postgres(storage) => storage.load_factory_dep(hash),
+            Self::Warm(storage) => storage.load_factory_dep(hash),
+        }
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        match self {
+            Self::Postgres(storage) => storage.get_enumeration_index(key),
+            Self::Warm(storage) => storage.get_enumeration_index(key),
+        }
+    }
+}
+
+/// Block information necessary to initialize the VM.
+#[derive(Debug)]
+struct SandboxBlockState {
+    resolved: ResolvedBlockInfo,
+    current_l2_block_info: StoredL2BlockInfo,
+    /// Only loaded if the executed block is not pending or genesis.
+    prev_l2_block_info: Option<StoredL2BlockInfo>,
+}
+
+impl SandboxBlockState {
+    async fn load(
+        connection: &mut StorageProcessor<'_>,
+        block_args: &BlockArgs,
+    ) -> Result<Self, SqlxError> {
+        let resolved = block_args.resolve_block_info(connection).await?;
+        let state_l2_block_number = resolved.state_l2_block_number;
+        let current_l2_block_info = read_l2_block_info(connection, state_l2_block_number).await;
+        let prev_l2_block_info =
+            if block_args.is_pending_miniblock() || current_l2_block_info.l2_block_number == 0 {
+                None
+            } else {
+                Some(read_l2_block_info(connection, state_l2_block_number - 1).await)
+            };
+        Ok(Self {
+            resolved,
+            current_l2_block_info,
+            prev_l2_block_info,
+        })
+    }
+
+    /// Mirrors [`Self::load()`] for a snapshot matching `block_args` (i.e., for the latest sealed or the pending miniblock).
+    fn from_warm_snapshot(snapshot: &WarmStateSnapshot, block_args: &BlockArgs) -> Self {
+        let l1_batch_timestamp = if block_args.is_pending_miniblock() {
+            // Timestamp of the next L1 batch must be greater than the timestamp of the last miniblock.
+            seconds_since_epoch().max(snapshot.miniblock_timestamp + 1)
+        } else {
+            block_args.l1_batch_timestamp_s.unwrap_or_else(|| {
+                panic!(
+                    "L1 batch timestamp is `None`, `block_id`: {:?}, `resolved_block_number`: {}",
+                    block_args.block_id, block_args.resolved_block_number.0
+                );
+            })
+        };
+        // Same as in `resolve_block_info()`: the pending miniblock belongs to the pending L1 batch,
+        // while the latest sealed miniblock belongs to the batch it was sealed in.
+        let vm_l1_batch_number = if block_args.is_pending_miniblock() {
+            snapshot.sealed_l1_batch_number + 1
+        } else {
+            snapshot.miniblock_l1_batch_number
+        };
+        let resolved = ResolvedBlockInfo {
+            state_l2_block_number: snapshot.miniblock_number,
+            vm_l1_batch_number,
+            l1_batch_timestamp,
+            protocol_version: snapshot.protocol_version,
+        };
+        Self {
+            resolved,
+            current_l2_block_info: snapshot.current_l2_block_info,
+            prev_l2_block_info: snapshot.prev_l2_block_info,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
-struct StoredL2BlockInfo {
+pub(super) struct StoredL2BlockInfo {
     pub l2_block_number: u32,
     pub l2_block_timestamp: u64,
     pub l2_block_hash: H256,
     pub txs_rolling_hash: H256,
 }
 
-async fn read_l2_block_info(
-    connection: &mut StorageProcessor<'_>,
+/// Reads L2 block info stored in the system context as of the end of the specified miniblock.
+pub(super) async fn read_l2_block_info<S>(
+    storage: &mut S,
     miniblock_number: MiniblockNumber,
-) -> StoredL2BlockInfo {
+) -> StoredL2BlockInfo
+where
+    S: StorageReader + BlocksReader,
+{
     let l2_block_info_key = StorageKey::new(
         AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
         SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
     );
-    let l2_block_info = connection
-        .storage_web3_dal()
+    let l2_block_info = storage
         .get_historical_value_unchecked(&l2_block_info_key, miniblock_number)
         .await
         .unwrap();
@@ -264,14 +478,12 @@ async fn read_l2_block_info(
         AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
         SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
     );
-    let txs_rolling_hash = connection
-        .storage_web3_dal()
+    let txs_rolling_hash = storage
         .get_historical_value_unchecked(&l2_block_txs_rolling_hash_key, miniblock_number)
         .await
         .unwrap();
 
-    let l2_block_hash = connection
-        .blocks_web3_dal()
+    let l2_block_hash = storage
         .get_miniblock_hash(miniblock_number)
         .await
         .unwrap()
@@ -362,3 +574,134 @@ impl BlockArgs {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::backend::InMemoryBackend;
+    use zksync_state::InMemoryStorage;
+    use zksync_types::{block::MiniblockHeader, StorageLog};
+
+    use super::*;
+
+    fn create_miniblock(number: u32) -> MiniblockHeader {
+        MiniblockHeader {
+            number: MiniblockNumber(number),
+            timestamp: number.into(),
+            hash: H256::from_low_u64_be(number.into()),
+            l1_tx_count: 0,
+            l2_tx_count: 0,
+            base_fee_per_gas: 100,
+            l1_gas_price: 100,
+            l2_fair_gas_price: 100,
+            base_system_contracts_hashes: Default::default(),
+            protocol_version: Some(ProtocolVersionId::latest()),
+            virtual_blocks: 1,
+        }
+    }
+
+    fn l2_block_info_logs(number: u32, txs_rolling_hash: H256) -> [StorageLog; 2] {
+        let l2_block_info_key = StorageKey::new(
+            AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+            SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+        );
+        let l2_block_info = pack_block_info(number.into(), number.into());
+        let txs_rolling_hash_key = StorageKey::new(
+            AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+            SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+        );
+        [
+            StorageLog::new_write_log(l2_block_info_key, u256_to_h256(l2_block_info)),
+            StorageLog::new_write_log(txs_rolling_hash_key, txs_rolling_hash),
+        ]
+    }
+
+    #[tokio::test]
+    async fn reading_l2_block_info() {
+        let mut storage = InMemoryBackend::default();
+        storage.insert_miniblock(create_miniblock(0), &l2_block_info_logs(0, H256::zero()));
+        let rolling_hash = H256::repeat_byte(1);
+        storage.insert_miniblock(create_miniblock(1), &l2_block_info_logs(1, rolling_hash));
+        // Miniblock without storage logs inherits the L2 block info from the previous miniblock.
+        storage.insert_miniblock(create_miniblock(2), &[]);
+
+        let info = read_l2_block_info(&mut storage, MiniblockNumber(0)).await;
+        assert_eq!(info.l2_block_number, 0);
+        assert_eq!(info.l2_block_timestamp, 0);
+        assert_eq!(info.l2_block_hash, H256::from_low_u64_be(0));
+        assert_eq!(info.txs_rolling_hash, H256::zero());
+
+        let info = read_l2_block_info(&mut storage, MiniblockNumber(1)).await;
+        assert_eq!(info.l2_block_number, 1);
+        assert_eq!(info.l2_block_timestamp, 1);
+        assert_eq!(info.l2_block_hash, H256::from_low_u64_be(1));
+        assert_eq!(info.txs_rolling_hash, rolling_hash);
+
+        let info = read_l2_block_info(&mut storage, MiniblockNumber(2)).await;
+        assert_eq!(info.l2_block_number, 1);
+        assert_eq!(info.l2_block_hash, H256::from_low_u64_be(2));
+        assert_eq!(info.txs_rolling_hash, rolling_hash);
+    }
+
+    fn system_context_key(position: H256) -> StorageKey {
+        StorageKey::new(AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS), position)
+    }
+
+    #[test]
+    fn applying_block_overrides() {
+        let mut storage = InMemoryStorage::default();
+        // Hash of L2 block #98 is stored in the ring buffer of the system context.
+        let prev_prev_block_hash = H256::repeat_byte(0xaa);
+        let hash_position = h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+            + U256::from(98 % SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES);
+        storage.set_value(
+            system_context_key(u256_to_h256(hash_position)),
+            prev_prev_block_hash,
+        );
+        let mut storage_view = StorageView::new(storage);
+
+        let l2_block = L2BlockEnv {
+            number: 5,
+            timestamp: 10,
+            prev_block_hash: H256::repeat_byte(1),
+            max_virtual_blocks_to_create: 1,
+        };
+        let overrides = SandboxBlockOverrides {
+            number: NonZeroU32::new(100),
+            timestamp: Some(1_000),
+            l1_gas_price: None,
+        };
+        let protocol_version = ProtocolVersionId::latest();
+        let new_l2_block =
+            apply_block_overrides(&mut storage_view, l2_block, &overrides, protocol_version);
+
+        assert_eq!(new_l2_block.number, 100);
+        assert_eq!(new_l2_block.timestamp, 1_000);
+        let expected_prev_block_hash =
+            MiniblockHasher::new(MiniblockNumber(99), 999, prev_prev_block_hash)
+                .finalize(protocol_version);
+        assert_eq!(new_l2_block.prev_block_hash, expected_prev_block_hash);
+
+        // The synthetic previous block should be written to the system context.
+        let l2_block_info = storage_view.read_value(&system_context_key(
+            SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+        ));
+        assert_eq!(unpack_block_info(h256_to_u256(l2_block_info)), (99, 999));
+        let txs_rolling_hash = storage_view.read_value(&system_context_key(
+            SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+        ));
+        assert_eq!(txs_rolling_hash, H256::zero());
+
+        // If only the number is overridden, the timestamp should be retained.
+        let overrides = SandboxBlockOverrides {
+            number: NonZeroU32::new(1),
+            ..SandboxBlockOverrides::default()
+        };
+        let new_l2_block =
+            apply_block_overrides(&mut storage_view, l2_block, &overrides, protocol_version);
+        assert_eq!((new_l2_block.number, new_l2_block.timestamp), (1, 10));
+        assert_eq!(
+            new_l2_block.prev_block_hash,
+            MiniblockHasher::legacy_hash(MiniblockNumber(0))
+        );
+    }
+}
@@ -1,7 +1,11 @@
 //! Implementation of "executing" methods, e.g. `eth_call`.
 
+use std::{iter, num::NonZeroU32};
+
 use multivm::{
-    interface::{TxExecutionMode, VmExecutionMode, VmExecutionResultAndLogs, VmInterface},
+    interface::{
+        ExecutionResult, TxExecutionMode, VmExecutionMode, VmExecutionResultAndLogs, VmInterface,
+    },
     tracers::StorageInvocations,
     vm_latest::constants::ETH_CALL_GAS_LIMIT,
     MultiVMTracer,
@@ -9,7 +13,7 @@ use multivm::{
 use tracing::{span, Level};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Nonce,
+    api, fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Nonce,
     PackedEthSignature, Transaction, U256,
 };
 
@@ -22,6 +26,40 @@ pub(crate) struct TxExecutionArgs {
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
     pub missed_storage_invocation_limit: usize,
+    pub block_overrides: Option<SandboxBlockOverrides>,
+}
+
+/// Block overrides checked to be applicable to the sandboxed VM.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct SandboxBlockOverrides {
+    pub number: Option<NonZeroU32>,
+    pub timestamp: Option<u64>,
+    pub l1_gas_price: Option<u64>,
+}
+
+impl TryFrom<api::BlockOverrides> for SandboxBlockOverrides {
+    type Error = String;
+
+    fn try_from(overrides: api::BlockOverrides) -> Result<Self, Self::Error> {
+        let number = overrides
+            .number
+            .map(|number| {
+                u32::try_from(number.as_u64())
+                    .ok()
+                    .and_then(NonZeroU32::new)
+                    .ok_or_else(|| format!("block number {number} is out of range"))
+            })
+            .transpose()?;
+        let timestamp = overrides.timestamp.map(|timestamp| timestamp.as_u64());
+        if timestamp == Some(0) {
+            return Err("block timestamp must be positive".to_owned());
+        }
+        Ok(Self {
+            number,
+            timestamp,
+            l1_gas_price: overrides.l1_gas_price.map(|price| price.as_u64()),
+        })
+    }
 }
 
 impl TxExecutionArgs {
@@ -32,6 +70,7 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
             missed_storage_invocation_limit: usize::MAX,
+            block_overrides: None,
         }
     }
 
@@ -46,6 +85,18 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(enforced_base_fee),
             missed_storage_invocation_limit,
+            block_overrides: None,
+        }
+    }
+
+    fn for_replay(tx: &Transaction, base_fee: u64) -> Self {
+        Self {
+            execution_mode: TxExecutionMode::VerifyExecute,
+            enforced_nonce: tx.nonce(),
+            added_balance: U256::zero(),
+            enforced_base_fee: Some(base_fee),
+            missed_storage_invocation_limit: usize::MAX,
+            block_overrides: None,
         }
     }
 
@@ -69,6 +120,7 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            block_overrides: None,
         }
     }
 }
@@ -86,14 +138,7 @@ pub(crate) async fn execute_tx_eth_call(
     let execution_args =
         TxExecutionArgs::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit);
 
-    if tx.common_data.signature.is_empty() {
-        tx.common_data.signature = PackedEthSignature::default().serialize_packed().into();
-    }
-
-    // Protection against infinite-loop eth_calls and alike:
-    // limiting the amount of gas the call can use.
-    // We can't use BLOCK_ERGS_LIMIT here since the VM itself has some overhead.
-    tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
+    prepare_eth_call_tx(&mut tx);
     let (vm_result, _) = execute_tx_in_sandbox(
         vm_permit,
         shared_args,
@@ -108,6 +153,137 @@ pub(crate) async fn execute_tx_eth_call(
     vm_result
 }
 
+fn prepare_eth_call_tx(tx: &mut L2Tx) {
+    if tx.common_data.signature.is_empty() {
+        tx.common_data.signature = PackedEthSignature::default().serialize_packed().into();
+    }
+
+    // Protection against infinite-loop eth_calls and alike:
+    // limiting the amount of gas the call can use.
+    // We can't use BLOCK_ERGS_LIMIT here since the VM itself has some overhead.
+    tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
+}
+
+/// Executes a bundle of calls in a single VM session, so that each call observes the state changes
+/// made by the previous ones. Execution stops after the first halted call; its result is the last one
+/// in the returned list. `txs` must not be empty.
+pub(crate) async fn execute_bundle_eth_call(
+    vm_permit: VmPermit,
+    shared_args: TxSharedArgs,
+    connection_pool: ConnectionPool,
+    mut txs: Vec<L2Tx>,
+    block_args: BlockArgs,
+    block_overrides: Option<SandboxBlockOverrides>,
+    vm_execution_cache_misses_limit: Option<usize>,
+) -> Vec<VmExecutionResultAndLogs> {
+    // The base fee is shared by all calls in the bundle, so it must not exceed the fee of any call.
+    let enforced_base_fee = txs
+        .iter()
+        .map(|tx| tx.common_data.fee.max_fee_per_gas.as_u64())
+        .min()
+        .unwrap_or(0);
+    let mut execution_args =
+        TxExecutionArgs::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit);
+    execution_args.block_overrides = block_overrides;
+
+    for tx in &mut txs {
+        prepare_eth_call_tx(tx);
+    }
+    let mut txs = txs.into_iter().map(Transaction::from);
+    let first_tx = txs.next().expect("bundle must not be empty");
+
+    tokio::task::spawn_blocking(move || {
+        let span = span!(Level::DEBUG, "execute_bundle_in_sandbox").entered();
+        let results = apply::apply_vm_in_sandbox(
+            vm_permit,
+            shared_args,
+            &execution_args,
+            &connection_pool,
+            first_tx,
+            block_args,
+            |vm, first_tx| {
+                let mut results = vec![];
+                for tx in iter::once(first_tx).chain(txs) {
+                    vm.push_transaction(tx);
+                    let storage_invocation_tracer =
+                        StorageInvocations::new(execution_args.missed_storage_invocation_limit);
+                    let tracers = vec![storage_invocation_tracer.into_tracer_pointer()];
+                    let result = vm.inspect(tracers.into(), VmExecutionMode::OneTx);
+                    let is_halted = matches!(result.result, ExecutionResult::Halt { .. });
+                    results.push(result);
+                    if is_halted {
+                        // The VM state cannot be rolled back, so the following calls cannot be executed.
+                        break;
+                    }
+                }
+                results
+            },
+        );
+        span.exit();
+        results
+    })
+    .await
+    .unwrap()
+}
+
+/// Re-executes a transaction that was already included into a miniblock with the fee parameters
+/// of this miniblock. `block_args` must point to the state at the start of the miniblock, and `preceding_txs`
+/// must contain the transactions included into the miniblock before `tx`. These transactions are executed first
+/// without custom tracers, so that `tx` observes the same state as during its original execution.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn execute_tx_replay(
+    vm_permit: VmPermit,
+    shared_args: TxSharedArgs,
+    connection_pool: ConnectionPool,
+    preceding_txs: Vec<Transaction>,
+    tx: Transaction,
+    base_fee: u64,
+    block_args: BlockArgs,
+    custom_tracers: Vec<ApiTracer>,
+) -> VmExecutionResultAndLogs {
+    let preceding_tx_count = preceding_txs.len();
+    let mut txs = preceding_txs.into_iter().chain([tx]);
+    let first_tx = txs.next().unwrap(); // `unwrap()` is safe since `txs` contains at least `tx`
+    let execution_args = TxExecutionArgs::for_replay(&first_tx, base_fee);
+
+    tokio::task::spawn_blocking(move || {
+        let span = span!(Level::DEBUG, "execute_replay_in_sandbox").entered();
+        let result = apply::apply_vm_in_sandbox(
+            vm_permit,
+            shared_args,
+            &execution_args,
+            &connection_pool,
+            first_tx,
+            block_args,
+            |vm, first_tx| {
+                let mut txs = iter::once(first_tx).chain(txs);
+                for preceding_tx in txs.by_ref().take(preceding_tx_count) {
+                    let tx_hash = preceding_tx.hash();
+                    vm.push_transaction(preceding_tx);
+                    let result = vm.execute(VmExecutionMode::OneTx);
+                    if let ExecutionResult::Halt { reason } = &result.result {
+                        tracing::warn!(
+                            "Transaction {tx_hash:?} preceding the replayed one has halted ({reason}); \
+                             replay may diverge from the original execution"
+                        );
+                    }
+                }
+
+                vm.push_transaction(txs.next().unwrap());
+                let custom_tracers: Vec<_> = custom_tracers
+                    .into_iter()
+                    .map(|tracer| tracer.into_boxed())
+                    .collect();
+                vm.inspect(custom_tracers.into(), VmExecutionMode::OneTx)
+            },
+        );
+        span.exit();
+        result
+    })
+    .await
+    .unwrap()
+}
+
 #[tracing::instrument(skip_all)]
 pub(crate) async fn execute_tx_with_pending_state(
     vm_permit: VmPermit,
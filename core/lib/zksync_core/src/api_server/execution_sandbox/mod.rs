@@ -5,13 +5,21 @@ use tokio::runtime::Handle;
 use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
 use zksync_state::{PostgresStorage, PostgresStorageCaches, ReadStorage, StorageView};
 use zksync_system_constants::PUBLISH_BYTECODE_OVERHEAD;
-use zksync_types::{api, AccountTreeId, L2ChainId, MiniblockNumber, U256};
+use zksync_types::{
+    api::{self, PrunedData},
+    AccountTreeId, L2ChainId, MiniblockNumber, U256,
+};
 use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 
 use self::vm_metrics::SandboxStage;
+pub use self::warm_state::{WarmVmState, WarmVmStateUpdater};
 pub(super) use self::{
     error::SandboxExecutionError,
-    execute::{execute_tx_eth_call, execute_tx_with_pending_state, TxExecutionArgs},
+    execute::{
+        execute_bundle_eth_call, execute_tx_eth_call, execute_tx_replay,
+        execute_tx_with_pending_state, SandboxBlockOverrides, TxExecutionArgs,
+    },
+    storage::validate_state_override,
     tracers::ApiTracer,
     vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
 };
@@ -21,9 +29,13 @@ use super::tx_sender::MultiVMBaseSystemContracts;
 mod apply;
 mod error;
 mod execute;
+mod storage;
+#[cfg(test)]
+mod tests;
 mod tracers;
 mod validate;
 mod vm_metrics;
+mod warm_state;
 
 /// Permit to invoke VM code.
 ///
@@ -230,6 +242,23 @@ pub(crate) struct TxSharedArgs {
     pub caches: PostgresStorageCaches,
     pub validation_computational_gas_limit: u32,
     pub chain_id: L2ChainId,
+    /// State override applied on top of the storage for the executed block.
+    pub state_override: Option<api::StateOverride>,
+    /// Warm VM state used instead of Postgres for the latest sealed and pending miniblocks, if available.
+    pub warm_state: Option<WarmVmState>,
+}
+
+/// Errors that can occur when loading [`BlockArgs`].
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum BlockArgsError {
+    #[error("Block is missing")]
+    Missing,
+    #[error(
+        "State for miniblock #{0} was pruned; the earliest available state is for miniblock #{1}"
+    )]
+    Pruned(MiniblockNumber, MiniblockNumber),
+    #[error("Database error: {0}")]
+    Database(#[from] SqlxError),
 }
 
 /// Information about a block provided to VM.
@@ -250,22 +279,33 @@ impl BlockArgs {
         }
     }
 
-    /// Loads block information from DB.
+    /// Loads block information from DB. Returns an error if the block state was pruned.
     pub async fn new(
         connection: &mut StorageProcessor<'_>,
         block_id: api::BlockId,
-    ) -> Result<Option<Self>, SqlxError> {
+    ) -> Result<Self, BlockArgsError> {
         if block_id == api::BlockId::Number(api::BlockNumber::Pending) {
-            return Ok(Some(BlockArgs::pending(connection).await));
+            return Ok(BlockArgs::pending(connection).await);
         }
 
         let resolved_block_number = connection
             .blocks_web3_dal()
             .resolve_block_id(block_id)
+            .await?
+            .ok_or(BlockArgsError::Missing)?;
+        let pruned_state = connection
+            .pruning_dal()
+            .get_pruned_range(PrunedData::StorageLogs)
             .await?;
-        let Some(resolved_block_number) = resolved_block_number else {
-            return Ok(None);
-        };
+        if let Some(pruned_state) = pruned_state {
+            let first_available_miniblock = pruned_state.first_available_miniblock();
+            if resolved_block_number < first_available_miniblock {
+                return Err(BlockArgsError::Pruned(
+                    resolved_block_number,
+                    first_available_miniblock,
+                ));
+            }
+        }
 
         let l1_batch_number = connection
             .storage_web3_dal()
@@ -280,11 +320,11 @@ impl BlockArgs {
             l1_batch_timestamp_s.is_some(),
             "Missing batch timestamp for non-pending block"
         );
-        Ok(Some(Self {
+        Ok(Self {
             block_id,
             resolved_block_number,
             l1_batch_timestamp_s,
-        }))
+        })
     }
 
     pub fn resolved_block_number(&self) -> MiniblockNumber {
@@ -0,0 +1,209 @@
+//! Storage wrapper applying state overrides to the VM storage.
+
+use std::collections::{HashMap, HashSet};
+
+use zksync_state::ReadStorage;
+use zksync_types::{
+    api::StateOverride,
+    get_code_key, get_known_code_key, get_nonce_key,
+    utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
+    AccountTreeId, StorageKey, StorageValue, H256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode},
+    h256_to_u256, u256_to_h256,
+};
+
+/// Checks that the state override can be applied.
+pub(crate) fn validate_state_override(state_override: &StateOverride) -> Result<(), String> {
+    for (address, account) in state_override {
+        if account.state.is_some() && account.state_diff.is_some() {
+            return Err(format!(
+                "account {address:?} has both `state` and `stateDiff` overrides"
+            ));
+        }
+        if let Some(nonce) = account.nonce {
+            // Nonces are stored as `u32` and packed together with the deployment nonce.
+            if nonce > u32::MAX.into() {
+                return Err(format!(
+                    "nonce {nonce} for account {address:?} exceeds the maximum value {}",
+                    u32::MAX
+                ));
+            }
+        }
+        if let Some(code) = &account.code {
+            validate_bytecode(&code.0)
+                .map_err(|err| format!("invalid code for account {address:?}: {err}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// [`ReadStorage`] implementation applying a [`StateOverride`] on top of the wrapped storage.
+///
+/// Overrides of balances, nonces and code are translated to writes to the corresponding slots
+/// of system contracts.
+#[derive(Debug)]
+pub(crate) struct StorageWithOverrides<S> {
+    storage_handle: S,
+    overridden_slots: HashMap<StorageKey, H256>,
+    /// Accounts with the entire storage replaced.
+    overridden_accounts: HashSet<AccountTreeId>,
+    overridden_factory_deps: HashMap<H256, Vec<u8>>,
+}
+
+impl<S: ReadStorage> StorageWithOverrides<S> {
+    /// Creates a wrapper for the provided storage. The state override must be validated
+    /// with [`validate_state_override()`] beforehand.
+    pub fn new(mut storage_handle: S, state_override: &StateOverride) -> Self {
+        let mut overridden_slots = HashMap::new();
+        let mut overridden_accounts = HashSet::new();
+        let mut overridden_factory_deps = HashMap::new();
+
+        for (address, account) in state_override {
+            if let Some(balance) = account.balance {
+                let balance_key = storage_key_for_eth_balance(address);
+                overridden_slots.insert(balance_key, u256_to_h256(balance));
+            }
+
+            if let Some(nonce) = account.nonce {
+                let nonce_key = get_nonce_key(address);
+                let full_nonce = storage_handle.read_value(&nonce_key);
+                let (_, deployment_nonce) = decompose_full_nonce(h256_to_u256(full_nonce));
+                let new_full_nonce = nonces_to_full_nonce(nonce, deployment_nonce);
+                overridden_slots.insert(nonce_key, u256_to_h256(new_full_nonce));
+            }
+
+            if let Some(code) = &account.code {
+                let bytecode_hash = hash_bytecode(&code.0);
+                overridden_slots.insert(get_code_key(address), bytecode_hash);
+                overridden_slots
+                    .insert(get_known_code_key(&bytecode_hash), H256::from_low_u64_be(1));
+                overridden_factory_deps.insert(bytecode_hash, code.0.clone());
+            }
+
+            let account_id = AccountTreeId::new(*address);
+            if account.state.is_some() {
+                overridden_accounts.insert(account_id);
+            }
+            for (key, value) in account.state.iter().chain(&account.state_diff).flatten() {
+                overridden_slots.insert(StorageKey::new(account_id, *key), *value);
+            }
+        }
+
+        Self {
+            storage_handle,
+            overridden_slots,
+            overridden_accounts,
+            overridden_factory_deps,
+        }
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for StorageWithOverrides<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(value) = self.overridden_slots.get(key) {
+            return *value;
+        }
+        if self.overridden_accounts.contains(key.account()) {
+            return H256::zero();
+        }
+        self.storage_handle.read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.storage_handle.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(bytecode) = self.overridden_factory_deps.get(&hash) {
+            return Some(bytecode.clone());
+        }
+        self.storage_handle.load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.storage_handle.get_enumeration_index(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_state::InMemoryStorage;
+    use zksync_types::{api::OverrideAccount, Address, U256};
+
+    use super::*;
+
+    #[test]
+    fn state_overrides_are_applied() {
+        let address = Address::repeat_byte(1);
+        let slot =
+            |index: u64| StorageKey::new(AccountTreeId::new(address), H256::from_low_u64_be(index));
+        let nonce_key = get_nonce_key(&address);
+        let mut storage = InMemoryStorage::default();
+        storage.set_value(slot(1), H256::repeat_byte(1));
+        storage.set_value(slot(2), H256::repeat_byte(2));
+        // Deployment nonce 3, transaction nonce 5.
+        let full_nonce = nonces_to_full_nonce(5.into(), 3.into());
+        storage.set_value(nonce_key, u256_to_h256(full_nonce));
+
+        let mut account = OverrideAccount {
+            balance: Some(U256::from(100)),
+            nonce: Some(U256::from(10)),
+            state_diff: Some(HashMap::from([(*slot(1).key(), H256::repeat_byte(0xff))])),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride::from([(address, account.clone())]);
+        validate_state_override(&state_override).unwrap();
+        let mut storage_with_overrides =
+            StorageWithOverrides::new(storage.clone(), &state_override);
+
+        let balance = storage_with_overrides.read_value(&storage_key_for_eth_balance(&address));
+        assert_eq!(h256_to_u256(balance), 100.into());
+        let full_nonce = storage_with_overrides.read_value(&nonce_key);
+        let (tx_nonce, deployment_nonce) = decompose_full_nonce(h256_to_u256(full_nonce));
+        assert_eq!((tx_nonce, deployment_nonce), (10.into(), 3.into()));
+        assert_eq!(
+            storage_with_overrides.read_value(&slot(1)),
+            H256::repeat_byte(0xff)
+        );
+        assert_eq!(
+            storage_with_overrides.read_value(&slot(2)),
+            H256::repeat_byte(2)
+        );
+
+        account.state = account.state_diff.take();
+        let state_override = StateOverride::from([(address, account.clone())]);
+        let mut storage_with_overrides = StorageWithOverrides::new(storage, &state_override);
+        assert_eq!(
+            storage_with_overrides.read_value(&slot(1)),
+            H256::repeat_byte(0xff)
+        );
+        assert_eq!(storage_with_overrides.read_value(&slot(2)), H256::zero());
+
+        account.state_diff = account.state.clone();
+        let state_override = StateOverride::from([(address, account)]);
+        validate_state_override(&state_override).unwrap_err();
+    }
+
+    #[test]
+    fn overflowing_nonce_overrides_are_rejected() {
+        let address = Address::repeat_byte(1);
+        let account = OverrideAccount {
+            nonce: Some(u32::MAX.into()),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride::from([(address, account.clone())]);
+        validate_state_override(&state_override).unwrap();
+
+        for nonce in [U256::from(u32::MAX) + 1, U256::MAX] {
+            let account = OverrideAccount {
+                nonce: Some(nonce),
+                ..account.clone()
+            };
+            let state_override = StateOverride::from([(address, account)]);
+            let err = validate_state_override(&state_override).unwrap_err();
+            assert!(err.contains("nonce"), "{err}");
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Tests for the VM execution sandbox.
+
+use std::{collections::HashMap, num::NonZeroU32};
+
+use assert_matches::assert_matches;
+use multivm::interface::ExecutionResult;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_contracts::read_bytecode;
+use zksync_system_constants::SYSTEM_CONTEXT_ADDRESS;
+use zksync_types::{
+    ethabi::{self, ParamType, Token},
+    l2::L2Tx,
+    transaction_request::CallRequest,
+    Address,
+};
+
+use super::*;
+use crate::{
+    api_server::tx_sender::ApiContracts,
+    genesis::{ensure_genesis_state, GenesisParams},
+};
+
+const COUNTER_CONTRACT_PATH: &str =
+    "etc/contracts-test-data/artifacts-zk/contracts/counter/counter.sol/Counter.json";
+
+async fn prepare_pool() -> ConnectionPool {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+    pool
+}
+
+fn shared_args(state_override: api::StateOverride) -> TxSharedArgs {
+    TxSharedArgs {
+        operator_account: AccountTreeId::default(),
+        l1_gas_price: 1,
+        fair_l2_gas_price: StateKeeperConfig::for_tests().fair_l2_gas_price,
+        base_system_contracts: ApiContracts::load_from_disk().eth_call,
+        caches: PostgresStorageCaches::new(1, 1),
+        validation_computational_gas_limit: u32::MAX,
+        chain_id: L2ChainId::default(),
+        state_override: Some(state_override),
+        warm_state: None,
+    }
+}
+
+fn call(to: Address, function: &str, params: &[ParamType], args: &[Token]) -> L2Tx {
+    let mut calldata = ethabi::short_signature(function, params).to_vec();
+    calldata.extend(ethabi::encode(args));
+    let request = CallRequest {
+        from: Some(Address::repeat_byte(0x11)),
+        to: Some(to),
+        data: Some(calldata.into()),
+        ..CallRequest::default()
+    };
+    L2Tx::from_request(request.into(), usize::MAX).unwrap()
+}
+
+fn increment_call(counter_address: Address, value: u64, should_revert: bool) -> L2Tx {
+    call(
+        counter_address,
+        "incrementWithRevert",
+        &[ParamType::Uint(256), ParamType::Bool],
+        &[Token::Uint(value.into()), Token::Bool(should_revert)],
+    )
+}
+
+#[test]
+fn converting_block_overrides() {
+    let overrides = api::BlockOverrides {
+        number: Some(100.into()),
+        timestamp: Some(1_000.into()),
+        l1_gas_price: None,
+    };
+    let overrides = SandboxBlockOverrides::try_from(overrides).unwrap();
+    assert_eq!(
+        overrides,
+        SandboxBlockOverrides {
+            number: NonZeroU32::new(100),
+            timestamp: Some(1_000),
+            l1_gas_price: None,
+        }
+    );
+
+    let invalid_overrides = [
+        api::BlockOverrides {
+            number: Some(0.into()),
+            ..api::BlockOverrides::default()
+        },
+        api::BlockOverrides {
+            number: Some((u64::from(u32::MAX) + 1).into()),
+            ..api::BlockOverrides::default()
+        },
+        api::BlockOverrides {
+            timestamp: Some(0.into()),
+            ..api::BlockOverrides::default()
+        },
+    ];
+    for overrides in invalid_overrides {
+        SandboxBlockOverrides::try_from(overrides).unwrap_err();
+    }
+}
+
+#[tokio::test]
+async fn executing_bundle_with_block_overrides() {
+    let pool = prepare_pool().await;
+    let counter_address = Address::repeat_byte(0xc0);
+    let state_override = HashMap::from([(
+        counter_address,
+        api::OverrideAccount {
+            code: Some(read_bytecode(COUNTER_CONTRACT_PATH).into()),
+            ..api::OverrideAccount::default()
+        },
+    )]);
+
+    let txs = vec![
+        increment_call(counter_address, 5, false),
+        // Reverted call; its state changes must not be visible to the following calls.
+        increment_call(counter_address, 1, true),
+        call(counter_address, "get", &[], &[]),
+        call(
+            SYSTEM_CONTEXT_ADDRESS,
+            "getL2BlockNumberAndTimestamp",
+            &[],
+            &[],
+        ),
+    ];
+    let block_overrides = SandboxBlockOverrides {
+        number: NonZeroU32::new(100),
+        timestamp: Some(1_000),
+        l1_gas_price: None,
+    };
+
+    let block_args = BlockArgs::pending(&mut pool.access_storage().await.unwrap()).await;
+    let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(1);
+    let vm_permit = vm_concurrency_limiter.acquire().await.unwrap();
+    let results = execute_bundle_eth_call(
+        vm_permit,
+        shared_args(state_override),
+        pool,
+        txs,
+        block_args,
+        Some(block_overrides),
+        None,
+    )
+    .await;
+
+    assert_eq!(results.len(), 4);
+    assert_matches!(
+        &results[0].result,
+        ExecutionResult::Success { output } if U256::from_big_endian(output) == 5.into()
+    );
+    assert_matches!(&results[1].result, ExecutionResult::Revert { .. });
+    // The state is carried forward from the first call, but not from the reverted second one.
+    assert_matches!(
+        &results[2].result,
+        ExecutionResult::Success { output } if U256::from_big_endian(output) == 5.into()
+    );
+
+    let ExecutionResult::Success { output } = &results[3].result else {
+        panic!("Unexpected result: {:?}", results[3].result);
+    };
+    assert_eq!(output.len(), 64);
+    let block_number = U256::from_big_endian(&output[..32]);
+    let block_timestamp = U256::from_big_endian(&output[32..]);
+    assert_eq!(block_number, 100.into());
+    assert_eq!(block_timestamp, 1_000.into());
+}
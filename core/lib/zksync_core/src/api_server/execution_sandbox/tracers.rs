@@ -1,14 +1,27 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
-use multivm::{tracers::CallTracer, vm_latest::HistoryMode, MultiVMTracer, MultiVmTracerPointer};
+use multivm::{
+    tracers::{
+        prestate_tracer::StorageSlotValues, CallTracer, GasProfiler, PrestateTracer,
+        StructLogConfig, StructLogTracer,
+    },
+    vm_latest::HistoryMode,
+    MultiVMTracer, MultiVmTracerPointer,
+};
 use once_cell::sync::OnceCell;
 use zksync_state::WriteStorage;
-use zksync_types::vm_trace::Call;
+use zksync_types::{
+    vm_trace::{Call, GasProfile, StructLog},
+    StorageKey,
+};
 
 /// Custom tracers supported by our API
 #[derive(Debug)]
 pub(crate) enum ApiTracer {
     CallTracer(Arc<OnceCell<Vec<Call>>>),
+    PrestateTracer(Arc<OnceCell<BTreeMap<StorageKey, StorageSlotValues>>>),
+    StructLogTracer(StructLogConfig, Arc<OnceCell<Vec<StructLog>>>),
+    GasProfiler(Arc<OnceCell<GasProfile>>),
 }
 
 impl ApiTracer {
@@ -20,6 +33,15 @@ impl ApiTracer {
     ) -> MultiVmTracerPointer<S, H> {
         match self {
             ApiTracer::CallTracer(tracer) => CallTracer::new(tracer.clone()).into_tracer_pointer(),
+            ApiTracer::PrestateTracer(result) => {
+                PrestateTracer::new(result.clone()).into_tracer_pointer()
+            }
+            ApiTracer::StructLogTracer(config, result) => {
+                StructLogTracer::new(config, result.clone()).into_tracer_pointer()
+            }
+            ApiTracer::GasProfiler(result) => {
+                GasProfiler::new(result.clone()).into_tracer_pointer()
+            }
         }
     }
 }
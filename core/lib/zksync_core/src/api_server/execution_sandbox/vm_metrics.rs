@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use multivm::interface::{VmExecutionResultAndLogs, VmMemoryMetrics};
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
 use zksync_state::StorageViewMetrics;
 use zksync_types::{
     event::{extract_long_l2_to_l1_messages, extract_published_bytecodes},
@@ -81,6 +81,14 @@ pub(super) enum SandboxStage {
     Execution,
 }
 
+/// Storage backing a sandboxed VM call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "storage", rename_all = "snake_case")]
+pub(super) enum SandboxStorageKind {
+    Postgres,
+    Warm,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
 pub(in crate::api_server) enum SubmitTxStage {
@@ -101,6 +109,9 @@ pub(in crate::api_server) enum SubmitTxStage {
 pub(in crate::api_server) struct SandboxMetrics {
     #[metrics(buckets = Buckets::LATENCIES)]
     pub(super) sandbox: Family<SandboxStage, Histogram<Duration>>,
+    /// Total latency of a sandboxed VM call (initialization and execution) by the backing storage.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub(super) sandbox_call: Family<SandboxStorageKind, Histogram<Duration>>,
     #[metrics(buckets = Buckets::linear(0.0..=2_000.0, 200.0))]
     pub(super) sandbox_execution_permits: Histogram<usize>,
     #[metrics(buckets = Buckets::LATENCIES)]
@@ -113,6 +124,23 @@ pub(in crate::api_server) struct SandboxMetrics {
 pub(in crate::api_server) static SANDBOX_METRICS: vise::Global<SandboxMetrics> =
     vise::Global::new();
 
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_warm_vm_state")]
+pub(super) struct WarmVmStateMetrics {
+    /// Latency of updating the warm VM state to the latest sealed miniblock.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub update: Histogram<Duration>,
+    /// Latest sealed miniblock mirrored by the warm VM state.
+    pub miniblock_number: Gauge<u64>,
+    /// Number of storage values written in the pending L1 batch held in memory.
+    pub pending_values: Gauge<usize>,
+    /// Number of failed warm VM state updates.
+    pub update_errors: Counter,
+}
+
+#[vise::register]
+pub(super) static WARM_STATE_METRICS: vise::Global<WarmVmStateMetrics> = vise::Global::new();
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "api_execution")]
 pub(super) struct ExecutionMetrics {
@@ -0,0 +1,328 @@
+//! Warm VM state for sandboxed execution against the latest sealed miniblock.
+//!
+//! Instead of querying Postgres for every storage slot accessed by the VM, [`WarmVmState`] uses a local RocksDB
+//! instance (the same format as the state keeper cache) caught up to the last sealed L1 batch, together with
+//! an in-memory overlay of storage writes and factory deps from the sealed miniblocks of the pending L1 batch.
+//! Both are kept current by [`WarmVmStateUpdater`]. Block information required to initialize the VM is
+//! resolved once per update as well, so that calls for the latest / pending block don't touch Postgres at all.
+//!
+//! VM instances themselves are not pooled: each call mutates the VM state, and the L1 batch environment
+//! depends on per-call inputs (e.g., fee parameters), so a VM is still created for each call on top of
+//! the pre-initialized storage and block information.
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_state::{ReadStorage, RocksdbStorage, RocksdbStorageSnapshot};
+use zksync_types::{
+    L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, StorageValue, H256,
+};
+
+use super::{
+    apply::{read_l2_block_info, StoredL2BlockInfo},
+    vm_metrics::WARM_STATE_METRICS,
+    BlockArgs,
+};
+
+/// Snapshot of the VM state for the latest sealed miniblock.
+#[derive(Debug)]
+pub(super) struct WarmStateSnapshot {
+    /// Number of the latest sealed miniblock.
+    pub miniblock_number: MiniblockNumber,
+    pub miniblock_timestamp: u64,
+    /// Number of the last sealed L1 batch. RocksDB contains the state as of the end of this batch.
+    pub sealed_l1_batch_number: L1BatchNumber,
+    /// Number of the L1 batch the latest sealed miniblock belongs to. Equals `sealed_l1_batch_number`
+    /// if the miniblock is the last one in the sealed batch, and the pending batch number otherwise.
+    pub miniblock_l1_batch_number: L1BatchNumber,
+    pub protocol_version: ProtocolVersionId,
+    pub current_l2_block_info: StoredL2BlockInfo,
+    /// Info for the miniblock preceding the latest sealed one; `None` if the latest sealed miniblock is genesis.
+    pub prev_l2_block_info: Option<StoredL2BlockInfo>,
+    /// Latest storage values written in the miniblocks of the pending L1 batch.
+    pending_values: HashMap<StorageKey, StorageValue>,
+    /// Factory deps added in the miniblocks of the pending L1 batch.
+    pending_factory_deps: HashMap<H256, Vec<u8>>,
+    /// RocksDB snapshot taken when the snapshot was loaded, so that reads are not affected
+    /// by concurrent updates.
+    rocksdb: RocksdbStorageSnapshot,
+}
+
+/// Shared handle to the warm VM state. Cloning the handle is cheap.
+#[derive(Debug, Clone)]
+pub struct WarmVmState {
+    snapshot: watch::Receiver<Option<Arc<WarmStateSnapshot>>>,
+}
+
+impl WarmVmState {
+    /// Opens RocksDB at the specified path and creates a handle together with the updater task
+    /// that should be run in the background to keep the state current.
+    pub async fn new(
+        db_path: PathBuf,
+        pool: ConnectionPool,
+        poll_interval: Duration,
+    ) -> anyhow::Result<(Self, WarmVmStateUpdater)> {
+        let rocksdb = tokio::task::spawn_blocking(move || RocksdbStorage::new(&db_path))
+            .await
+            .context("failed opening RocksDB for warm VM state")?;
+        let (sender, snapshot) = watch::channel(None);
+        let this = Self { snapshot };
+        let updater = WarmVmStateUpdater {
+            rocksdb,
+            pool,
+            sender,
+            poll_interval,
+        };
+        Ok((this, updater))
+    }
+
+    /// Returns storage for the specified block if the warm state can be used for it, i.e., if the block
+    /// is the latest sealed miniblock or the pending miniblock following it.
+    pub(super) fn storage_for(&self, block_args: &BlockArgs) -> Option<WarmStorage> {
+        let snapshot = self.snapshot.borrow().clone()?;
+        let expected_block_number = if block_args.is_pending_miniblock() {
+            snapshot.miniblock_number + 1
+        } else {
+            snapshot.miniblock_number
+        };
+        if block_args.resolved_block_number() != expected_block_number {
+            return None;
+        }
+        Some(WarmStorage {
+            rocksdb: snapshot.rocksdb.clone(),
+            snapshot,
+        })
+    }
+}
+
+/// [`ReadStorage`] implementation combining RocksDB with the pending L1 batch overlay.
+#[derive(Debug)]
+pub(super) struct WarmStorage {
+    rocksdb: RocksdbStorageSnapshot,
+    snapshot: Arc<WarmStateSnapshot>,
+}
+
+impl WarmStorage {
+    pub fn snapshot(&self) -> &WarmStateSnapshot {
+        &self.snapshot
+    }
+}
+
+impl ReadStorage for WarmStorage {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(value) = self.snapshot.pending_values.get(key) {
+            return *value;
+        }
+        self.rocksdb.read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        // Writes in the pending L1 batch are not taken into account, which is consistent with `PostgresStorage`.
+        self.rocksdb.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(bytecode) = self.snapshot.pending_factory_deps.get(&hash) {
+            return Some(bytecode.clone());
+        }
+        self.rocksdb.load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.rocksdb.get_enumeration_index(key)
+    }
+}
+
+/// Task keeping [`WarmVmState`] current.
+#[derive(Debug)]
+pub struct WarmVmStateUpdater {
+    rocksdb: RocksdbStorage,
+    pool: ConnectionPool,
+    sender: watch::Sender<Option<Arc<WarmStateSnapshot>>>,
+    poll_interval: Duration,
+}
+
+impl WarmVmStateUpdater {
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, warm VM state updater is shutting down");
+                return Ok(());
+            }
+            if let Err(err) = self.update().await {
+                tracing::warn!("Failed updating warm VM state, will retry: {err:#}");
+                WARM_STATE_METRICS.update_errors.inc();
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn update(&mut self) -> anyhow::Result<()> {
+        let mut conn = self.pool.access_storage_tagged("api").await?;
+        let latest_miniblock_number = conn
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("get_sealed_miniblock_number()")?;
+        let current_miniblock_number = self
+            .sender
+            .borrow()
+            .as_ref()
+            .map(|snapshot| snapshot.miniblock_number);
+        if current_miniblock_number == Some(latest_miniblock_number) {
+            return Ok(());
+        }
+
+        let latency = WARM_STATE_METRICS.update.start();
+        self.rocksdb.update_from_postgres(&mut conn).await;
+        let Some(snapshot) = self.load_snapshot(&mut conn).await? else {
+            // An L1 batch was sealed concurrently with the update; retry on the next iteration.
+            return Ok(());
+        };
+        latency.observe();
+
+        tracing::debug!(
+            "Updated warm VM state to miniblock #{} with {} pending storage values",
+            snapshot.miniblock_number,
+            snapshot.pending_values.len()
+        );
+        WARM_STATE_METRICS
+            .miniblock_number
+            .set(snapshot.miniblock_number.0.into());
+        WARM_STATE_METRICS
+            .pending_values
+            .set(snapshot.pending_values.len());
+        self.sender.send_replace(Some(Arc::new(snapshot)));
+        Ok(())
+    }
+
+    async fn load_snapshot(
+        &self,
+        conn: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<WarmStateSnapshot>> {
+        let rocksdb = self.rocksdb.snapshot();
+        let sealed_l1_batch_number = rocksdb.l1_batch_number() - 1;
+        let (_, last_miniblock_in_batch) = conn
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(sealed_l1_batch_number)
+            .await
+            .context("get_miniblock_range_of_l1_batch()")?
+            .with_context(|| format!("L1 batch #{sealed_l1_batch_number} has no miniblocks"))?;
+        let miniblock_header = conn
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .context("get_last_sealed_miniblock_header()")?
+            .context("no sealed miniblocks in Postgres")?;
+        // Check that the loaded miniblock belongs to the pending L1 batch (or is the last miniblock
+        // in the sealed batch).
+        let current_sealed_l1_batch_number = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")?;
+        if current_sealed_l1_batch_number != sealed_l1_batch_number {
+            return Ok(None);
+        }
+
+        let miniblock_number = miniblock_header.number;
+        let pending_miniblocks = (last_miniblock_in_batch + 1)..=miniblock_number;
+        let miniblock_l1_batch_number = if pending_miniblocks.is_empty() {
+            sealed_l1_batch_number
+        } else {
+            sealed_l1_batch_number + 1
+        };
+        let (pending_values, pending_factory_deps) = if pending_miniblocks.is_empty() {
+            (HashMap::new(), HashMap::new())
+        } else {
+            let values = conn
+                .storage_logs_dal()
+                .get_touched_slots_for_miniblocks(pending_miniblocks.clone())
+                .await;
+            let factory_deps = conn
+                .storage_dal()
+                .get_factory_deps_for_miniblocks(pending_miniblocks)
+                .await;
+            (values, factory_deps)
+        };
+
+        // Blocks without version specified are considered to be of `Version9`.
+        let protocol_version = conn
+            .blocks_dal()
+            .get_miniblock_protocol_version_id(miniblock_number)
+            .await
+            .context("get_miniblock_protocol_version_id()")?
+            .unwrap_or(ProtocolVersionId::Version9);
+        let current_l2_block_info = read_l2_block_info(conn, miniblock_number).await;
+        let prev_l2_block_info = if miniblock_number.0 == 0 {
+            None
+        } else {
+            Some(read_l2_block_info(conn, miniblock_number - 1).await)
+        };
+
+        Ok(Some(WarmStateSnapshot {
+            miniblock_number,
+            miniblock_timestamp: miniblock_header.timestamp,
+            sealed_l1_batch_number,
+            miniblock_l1_batch_number,
+            protocol_version,
+            current_l2_block_info,
+            prev_l2_block_info,
+            pending_values,
+            pending_factory_deps,
+            rocksdb,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use zksync_types::{AccountTreeId, Address};
+
+    use super::*;
+
+    #[test]
+    fn warm_storage_prefers_pending_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let rocksdb = RocksdbStorage::new(temp_dir.path()).snapshot();
+        let block_info = StoredL2BlockInfo {
+            l2_block_number: 1,
+            l2_block_timestamp: 1,
+            l2_block_hash: H256::zero(),
+            txs_rolling_hash: H256::zero(),
+        };
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        let bytecode_hash = H256::repeat_byte(2);
+        let snapshot = WarmStateSnapshot {
+            miniblock_number: MiniblockNumber(1),
+            miniblock_timestamp: 1,
+            sealed_l1_batch_number: L1BatchNumber(0),
+            miniblock_l1_batch_number: L1BatchNumber(1),
+            protocol_version: ProtocolVersionId::latest(),
+            current_l2_block_info: block_info,
+            prev_l2_block_info: Some(block_info),
+            pending_values: HashMap::from([(key, H256::repeat_byte(3))]),
+            pending_factory_deps: HashMap::from([(bytecode_hash, vec![0; 32])]),
+            rocksdb: rocksdb.clone(),
+        };
+        let mut storage = WarmStorage {
+            rocksdb,
+            snapshot: Arc::new(snapshot),
+        };
+
+        assert_eq!(storage.read_value(&key), H256::repeat_byte(3));
+        // Pending writes are not considered when determining initial writes.
+        assert!(storage.is_write_initial(&key));
+        assert_eq!(storage.load_factory_dep(bytecode_hash), Some(vec![0; 32]));
+
+        let other_key = StorageKey::new(
+            AccountTreeId::new(Address::repeat_byte(1)),
+            H256::repeat_byte(1),
+        );
+        assert_eq!(storage.read_value(&other_key), H256::zero());
+        assert_eq!(storage.load_factory_dep(H256::zero()), None);
+    }
+}
@@ -1,7 +1,14 @@
-use std::{collections::HashSet, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
 
 use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use tokio::sync::watch;
+use zksync_config::configs::api::HealthCheckConfig;
+use zksync_dal::ActiveSlowQuery;
 use zksync_health_check::{AppHealth, CheckHealth};
 
 type SharedHealthchecks = Arc<[Box<dyn CheckHealth>]>;
@@ -16,9 +23,36 @@ async fn check_health(health_checks: State<SharedHealthchecks>) -> (StatusCode,
     (response_code, Json(response))
 }
 
+/// Visibility of slow DB queries currently being executed on the `/debug/slow_queries` endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlowQueriesVisibility {
+    /// The endpoint is not served.
+    Hidden,
+    /// Queries are served without bound arguments.
+    WithoutArgs,
+    /// Queries are served together with bound arguments, which may contain sensitive data.
+    WithArgs,
+}
+
+impl From<&HealthCheckConfig> for SlowQueriesVisibility {
+    fn from(config: &HealthCheckConfig) -> Self {
+        match (config.expose_slow_queries, config.expose_slow_query_args) {
+            (false, _) => Self::Hidden,
+            (true, false) => Self::WithoutArgs,
+            (true, true) => Self::WithArgs,
+        }
+    }
+}
+
+/// Returns slow DB queries currently being executed grouped by the connection tag.
+fn active_slow_queries(include_args: bool) -> Json<HashMap<&'static str, Vec<ActiveSlowQuery>>> {
+    Json(zksync_dal::active_slow_queries(include_args))
+}
+
 async fn run_server(
     bind_address: &SocketAddr,
     health_checks: Vec<Box<dyn CheckHealth>>,
+    slow_queries: SlowQueriesVisibility,
     mut stop_receiver: watch::Receiver<bool>,
 ) {
     let mut health_check_names = HashSet::with_capacity(health_checks.len());
@@ -36,9 +70,15 @@ async fn run_server(
     );
 
     let health_checks = SharedHealthchecks::from(health_checks);
-    let app = Router::new()
-        .route("/health", get(check_health))
-        .with_state(health_checks);
+    let mut app = Router::new().route("/health", get(check_health));
+    if slow_queries != SlowQueriesVisibility::Hidden {
+        let include_args = slow_queries == SlowQueriesVisibility::WithArgs;
+        app = app.route(
+            "/debug/slow_queries",
+            get(move || async move { active_slow_queries(include_args) }),
+        );
+    }
+    let app = app.with_state(health_checks);
 
     axum::Server::bind(bind_address)
         .serve(app.into_make_service())
@@ -60,10 +100,14 @@ pub struct HealthCheckHandle {
 }
 
 impl HealthCheckHandle {
-    pub fn spawn_server(addr: SocketAddr, healthchecks: Vec<Box<dyn CheckHealth>>) -> Self {
+    pub fn spawn_server(
+        addr: SocketAddr,
+        healthchecks: Vec<Box<dyn CheckHealth>>,
+        slow_queries: SlowQueriesVisibility,
+    ) -> Self {
         let (stop_sender, stop_receiver) = watch::channel(false);
         let server = tokio::spawn(async move {
-            run_server(&addr, healthchecks, stop_receiver).await;
+            run_server(&addr, healthchecks, slow_queries, stop_receiver).await;
         });
 
         Self {
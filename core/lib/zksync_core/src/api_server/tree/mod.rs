@@ -22,15 +22,27 @@ mod metrics;
 #[cfg(test)]
 mod tests;
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TreeProofsRequest {
+    pub l1_batch_number: L1BatchNumber,
+    pub hashed_keys: Vec<U256>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
-struct TreeProofsRequest {
-    l1_batch_number: L1BatchNumber,
-    hashed_keys: Vec<U256>,
+pub(crate) struct TreeProofsResponse {
+    /// Root hash of the tree at the requested L1 batch. All proofs in `entries` are relative to this hash.
+    pub root_hash: H256,
+    pub entries: Vec<TreeEntryWithProof>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeBatchProofsRequest {
+    requests: Vec<TreeProofsRequest>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct TreeProofsResponse {
-    entries: Vec<TreeEntryWithProof>,
+struct TreeBatchProofsResponse {
+    responses: Vec<TreeProofsResponse>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -63,27 +75,85 @@ impl TreeEntryWithProof {
 #[derive(Debug)]
 enum TreeApiError {
     NoTreeVersion(NoVersionError),
+    PrunedTreeVersion(NoVersionError),
+    TooManyKeys { requested: usize, limit: usize },
+}
+
+impl From<NoVersionError> for TreeApiError {
+    fn from(err: NoVersionError) -> Self {
+        if err.is_pruned() {
+            Self::PrunedTreeVersion(err)
+        } else {
+            Self::NoTreeVersion(err)
+        }
+    }
 }
 
 impl IntoResponse for TreeApiError {
     fn into_response(self) -> Response {
-        let (status, title, detail) = match self {
-            Self::NoTreeVersion(err) => {
-                (StatusCode::NOT_FOUND, "L1 batch not found", err.to_string())
-            }
+        let missing_version = match &self {
+            Self::NoTreeVersion(err) | Self::PrunedTreeVersion(err) => Some(err.missing_version()),
+            Self::TooManyKeys { .. } => None,
+        };
+        let (status, ty, title, detail) = match self {
+            Self::NoTreeVersion(err) => (
+                StatusCode::NOT_FOUND,
+                "l1-batch-not-found",
+                "L1 batch not found",
+                err.to_string(),
+            ),
+            Self::PrunedTreeVersion(err) => (
+                StatusCode::GONE,
+                "l1-batch-pruned",
+                "L1 batch pruned",
+                err.to_string(),
+            ),
+            Self::TooManyKeys { requested, limit } => (
+                StatusCode::BAD_REQUEST,
+                "too-many-keys",
+                "Too many keys requested",
+                format!("Requested proofs for {requested} keys, while at most {limit} are allowed"),
+            ),
         };
 
         // Loosely conforms to HTTP Problem Details RFC: https://datatracker.ietf.org/doc/html/rfc7807
-        let body = serde_json::json!({
-            "type": "/errors#l1-batch-not-found",
+        let mut body = serde_json::json!({
+            "type": format!("/errors#{ty}"),
             "title": title,
             "detail": detail,
         });
+        if let Some(version) = missing_version {
+            // Allows clients to determine the offending L1 batch for batched requests.
+            body["l1BatchNumber"] = version.into();
+        }
         let headers = [(header::CONTENT_TYPE, "application/problem+json")];
         (status, headers, Json(body)).into_response()
     }
 }
 
+/// Error returned by [`TreeApiClient`] methods if the requested L1 batch was pruned from the tree.
+#[derive(Debug, thiserror::Error)]
+#[error("L1 batch #{0} was pruned from the Merkle tree")]
+pub(crate) struct PrunedL1BatchError(pub L1BatchNumber);
+
+/// Extension members of the problem details returned by the server for missing tree versions.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MissingL1BatchProblem {
+    l1_batch_number: L1BatchNumber,
+}
+
+/// Converts an error returned by the in-memory tree reader into the client error.
+fn convert_no_version_error(err: NoVersionError) -> anyhow::Error {
+    if err.is_pruned() {
+        // Tree versions correspond to L1 batch numbers, so the conversion is safe.
+        let l1_batch_number = L1BatchNumber(err.missing_version() as u32);
+        PrunedL1BatchError(l1_batch_number).into()
+    } else {
+        err.into()
+    }
+}
+
 /// Client accessing Merkle tree API.
 #[async_trait]
 pub(crate) trait TreeApiClient {
@@ -95,7 +165,15 @@ pub(crate) trait TreeApiClient {
         &self,
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
-    ) -> anyhow::Result<Vec<TreeEntryWithProof>>;
+    ) -> anyhow::Result<TreeProofsResponse>;
+
+    /// Obtains proofs for multiple (L1 batch, keys) requests at once. Responses are returned
+    /// in the same order as requests. If any of the requested tree versions is missing, the entire
+    /// batch fails.
+    async fn get_proofs_batch(
+        &self,
+        requests: Vec<TreeProofsRequest>,
+    ) -> anyhow::Result<Vec<TreeProofsResponse>>;
 }
 
 /// In-memory client implementation.
@@ -109,10 +187,25 @@ impl TreeApiClient for AsyncTreeReader {
         &self,
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
-    ) -> anyhow::Result<Vec<TreeEntryWithProof>> {
+    ) -> anyhow::Result<TreeProofsResponse> {
         self.get_proofs_inner(l1_batch_number, hashed_keys)
             .await
-            .map_err(Into::into)
+            .map_err(convert_no_version_error)
+    }
+
+    async fn get_proofs_batch(
+        &self,
+        requests: Vec<TreeProofsRequest>,
+    ) -> anyhow::Result<Vec<TreeProofsResponse>> {
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            let response = self
+                .get_proofs_inner(request.l1_batch_number, request.hashed_keys)
+                .await
+                .map_err(convert_no_version_error)?;
+            responses.push(response);
+        }
+        Ok(responses)
     }
 }
 
@@ -122,6 +215,7 @@ pub struct TreeApiHttpClient {
     inner: reqwest::Client,
     info_url: String,
     proofs_url: String,
+    batch_proofs_url: String,
 }
 
 impl TreeApiHttpClient {
@@ -130,6 +224,7 @@ impl TreeApiHttpClient {
             inner: reqwest::Client::new(),
             info_url: url_base.to_owned(),
             proofs_url: format!("{url_base}/proofs"),
+            batch_proofs_url: format!("{url_base}/proofs/batch"),
         }
     }
 }
@@ -156,7 +251,7 @@ impl TreeApiClient for TreeApiHttpClient {
         &self,
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
-    ) -> anyhow::Result<Vec<TreeEntryWithProof>> {
+    ) -> anyhow::Result<TreeProofsResponse> {
         let response = self
             .inner
             .post(&self.proofs_url)
@@ -167,17 +262,57 @@ impl TreeApiClient for TreeApiHttpClient {
             .send()
             .await
             .with_context(|| format!("Failed requesting proofs for L1 batch #{l1_batch_number}"))?;
+        if response.status() == StatusCode::GONE {
+            return Err(PrunedL1BatchError(l1_batch_number).into());
+        }
         let response = response.error_for_status().with_context(|| {
             format!("Requesting proofs for L1 batch #{l1_batch_number} returned non-OK response")
         })?;
-        let response: TreeProofsResponse = response.json().await.with_context(|| {
-            format!("Failed deserializing proofs for L1 batch #{l1_batch_number}")
-        })?;
-        Ok(response.entries)
+        response
+            .json()
+            .await
+            .with_context(|| format!("Failed deserializing proofs for L1 batch #{l1_batch_number}"))
+    }
+
+    async fn get_proofs_batch(
+        &self,
+        requests: Vec<TreeProofsRequest>,
+    ) -> anyhow::Result<Vec<TreeProofsResponse>> {
+        let request_count = requests.len();
+        let response = self
+            .inner
+            .post(&self.batch_proofs_url)
+            .json(&TreeBatchProofsRequest { requests })
+            .send()
+            .await
+            .context("Failed requesting batched proofs")?;
+        if response.status() == StatusCode::GONE {
+            let problem: MissingL1BatchProblem = response
+                .json()
+                .await
+                .context("Failed deserializing error for batched proofs")?;
+            return Err(PrunedL1BatchError(problem.l1_batch_number).into());
+        }
+        let response = response
+            .error_for_status()
+            .context("Requesting batched proofs returned non-OK response")?;
+        let response: TreeBatchProofsResponse = response
+            .json()
+            .await
+            .context("Failed deserializing batched proofs")?;
+        anyhow::ensure!(
+            response.responses.len() == request_count,
+            "Unexpected number of responses for batched proofs: expected {request_count}, got {}",
+            response.responses.len()
+        );
+        Ok(response.responses)
     }
 }
 
 impl AsyncTreeReader {
+    /// Maximum total number of keys in a single proofs request (including batched requests).
+    const MAX_REQUESTED_KEYS: usize = 10_000;
+
     async fn info_handler(State(this): State<Self>) -> Json<MerkleTreeInfo> {
         let latency = API_METRICS.latency[&MerkleTreeApiMethod::Info].start();
         let info = this.info().await;
@@ -189,12 +324,24 @@ impl AsyncTreeReader {
         &self,
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
-    ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
-        let proofs = self
+    ) -> Result<TreeProofsResponse, NoVersionError> {
+        let (root_hash, proofs) = self
             .clone()
             .entries_with_proofs(l1_batch_number, hashed_keys)
             .await?;
-        Ok(proofs.into_iter().map(TreeEntryWithProof::new).collect())
+        Ok(TreeProofsResponse {
+            root_hash,
+            entries: proofs.into_iter().map(TreeEntryWithProof::new).collect(),
+        })
+    }
+
+    fn check_requested_keys(requested: usize) -> Result<(), TreeApiError> {
+        let limit = Self::MAX_REQUESTED_KEYS;
+        if requested > limit {
+            Err(TreeApiError::TooManyKeys { requested, limit })
+        } else {
+            Ok(())
+        }
     }
 
     async fn get_proofs_handler(
@@ -202,15 +349,37 @@ impl AsyncTreeReader {
         Json(request): Json<TreeProofsRequest>,
     ) -> Result<Json<TreeProofsResponse>, TreeApiError> {
         let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetProofs].start();
-        let entries = this
+        Self::check_requested_keys(request.hashed_keys.len())?;
+        let response = this
             .get_proofs_inner(request.l1_batch_number, request.hashed_keys)
-            .await
-            .map_err(TreeApiError::NoTreeVersion)?;
-        let response = TreeProofsResponse { entries };
+            .await?;
         latency.observe();
         Ok(Json(response))
     }
 
+    async fn get_proofs_batch_handler(
+        State(this): State<Self>,
+        Json(request): Json<TreeBatchProofsRequest>,
+    ) -> Result<Json<TreeBatchProofsResponse>, TreeApiError> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetProofsBatch].start();
+        let requested_keys = request
+            .requests
+            .iter()
+            .map(|request| request.hashed_keys.len())
+            .sum();
+        Self::check_requested_keys(requested_keys)?;
+
+        let mut responses = Vec::with_capacity(request.requests.len());
+        for request in request.requests {
+            let response = this
+                .get_proofs_inner(request.l1_batch_number, request.hashed_keys)
+                .await?;
+            responses.push(response);
+        }
+        latency.observe();
+        Ok(Json(TreeBatchProofsResponse { responses }))
+    }
+
     fn create_api_server(
         self,
         bind_address: &SocketAddr,
@@ -221,6 +390,10 @@ impl AsyncTreeReader {
         let app = Router::new()
             .route("/", routing::get(Self::info_handler))
             .route("/proofs", routing::post(Self::get_proofs_handler))
+            .route(
+                "/proofs/batch",
+                routing::post(Self::get_proofs_batch_handler),
+            )
             .with_state(self);
 
         let server = axum::Server::try_bind(bind_address)
@@ -42,16 +42,47 @@ async fn merkle_tree_api() {
     hashed_keys.extend((0_u8..10).map(|byte| U256::from_big_endian(&[byte; 32])));
 
     let proofs = api_client
-        .get_proofs(L1BatchNumber(5), hashed_keys)
+        .get_proofs(L1BatchNumber(5), hashed_keys.clone())
         .await
         .unwrap();
-    assert_eq!(proofs.len(), 20);
-    for (i, proof) in proofs.into_iter().enumerate() {
+    assert_eq!(proofs.root_hash, tree_info.root_hash);
+    assert_eq!(proofs.entries.len(), 20);
+    for (i, proof) in proofs.entries.iter().enumerate() {
         let should_be_present = i < 10;
         assert_eq!(proof.index == 0, !should_be_present);
         assert!(!proof.merkle_path.is_empty());
     }
 
+    let requests = vec![
+        TreeProofsRequest {
+            l1_batch_number: L1BatchNumber(5),
+            hashed_keys: hashed_keys[..10].to_vec(),
+        },
+        TreeProofsRequest {
+            l1_batch_number: L1BatchNumber(0),
+            hashed_keys: hashed_keys.clone(),
+        },
+    ];
+    let batch_proofs = api_client.get_proofs_batch(requests).await.unwrap();
+    assert_eq!(batch_proofs.len(), 2);
+    assert_eq!(batch_proofs[0].root_hash, tree_info.root_hash);
+    assert_eq!(batch_proofs[0].entries.len(), 10);
+    for (batch_proof, proof) in batch_proofs[0].entries.iter().zip(&proofs.entries) {
+        assert_eq!(batch_proof.value, proof.value);
+        assert_eq!(batch_proof.index, proof.index);
+        assert_eq!(batch_proof.merkle_path, proof.merkle_path);
+    }
+    assert_ne!(batch_proofs[1].root_hash, tree_info.root_hash);
+    // Keys were not present in the genesis L1 batch.
+    assert!(batch_proofs[1].entries.iter().all(|proof| proof.index == 0));
+
+    let too_many_keys = vec![U256::zero(); AsyncTreeReader::MAX_REQUESTED_KEYS + 1];
+    let err = api_client
+        .get_proofs(L1BatchNumber(5), too_many_keys)
+        .await
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("400 Bad Request"), "{err:?}");
+
     let err = api_client
         .get_proofs(L1BatchNumber(10), vec![])
         .await
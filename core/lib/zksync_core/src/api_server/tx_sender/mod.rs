@@ -23,6 +23,7 @@ use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
+    api::StateOverride,
     fee::{Fee, TransactionExecutionMetrics},
     get_code_key, get_intrinsic_constants,
     l2::{error::TxCheckError::TxDuplication, L2Tx},
@@ -31,15 +32,16 @@ use zksync_types::{
     ProtocolVersionId, Transaction, H160, H256, MAX_GAS_PER_PUBDATA_BYTE, MAX_L2_TX_GAS_LIMIT,
     MAX_NEW_FACTORY_DEPS, U256,
 };
-use zksync_utils::h256_to_u256;
+use zksync_utils::{bytecode::hash_bytecode, h256_to_u256};
 
 pub(super) use self::{proxy::TxProxy, result::SubmitTxError};
 use crate::{
     api_server::{
         execution_sandbox::{
-            adjust_l1_gas_price_for_tx, execute_tx_eth_call, execute_tx_with_pending_state,
-            get_pubdata_for_factory_deps, BlockArgs, SubmitTxStage, TxExecutionArgs, TxSharedArgs,
-            VmConcurrencyLimiter, VmPermit, SANDBOX_METRICS,
+            adjust_l1_gas_price_for_tx, execute_bundle_eth_call, execute_tx_eth_call,
+            execute_tx_with_pending_state, get_pubdata_for_factory_deps, BlockArgs,
+            SandboxBlockOverrides, SubmitTxStage, TxExecutionArgs, TxSharedArgs,
+            VmConcurrencyLimiter, VmPermit, WarmVmState, SANDBOX_METRICS,
         },
         tx_sender::result::ApiCallResult,
     },
@@ -147,6 +149,9 @@ pub struct TxSenderBuilder {
     /// Actual state keeper configuration, required for tx verification.
     /// If not set, transactions would not be checked against seal criteria.
     state_keeper_config: Option<StateKeeperConfig>,
+    /// Warm VM state used for sandboxed execution against the latest miniblock.
+    /// If not set, all storage reads during sandboxed execution are served by Postgres.
+    warm_vm_state: Option<WarmVmState>,
 }
 
 impl TxSenderBuilder {
@@ -158,6 +163,7 @@ impl TxSenderBuilder {
             rate_limiter: None,
             proxy: None,
             state_keeper_config: None,
+            warm_vm_state: None,
         }
     }
 
@@ -187,6 +193,11 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_warm_vm_state(mut self, warm_vm_state: WarmVmState) -> Self {
+        self.warm_vm_state = Some(warm_vm_state);
+        self
+    }
+
     pub async fn build<G: L1GasPriceProvider>(
         self,
         l1_gas_price_source: Arc<G>,
@@ -210,6 +221,7 @@ impl TxSenderBuilder {
             state_keeper_config: self.state_keeper_config,
             vm_concurrency_limiter,
             storage_caches,
+            warm_vm_state: self.warm_vm_state,
         }))
     }
 }
@@ -269,6 +281,7 @@ pub struct TxSenderInner<G> {
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Caches used in VM execution.
     storage_caches: PostgresStorageCaches,
+    warm_vm_state: Option<WarmVmState>,
 }
 
 pub struct TxSender<G>(pub(super) Arc<TxSenderInner<G>>);
@@ -297,6 +310,10 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         self.0.storage_caches.clone()
     }
 
+    pub(crate) fn warm_vm_state(&self) -> Option<WarmVmState> {
+        self.0.warm_vm_state.clone()
+    }
+
     #[tracing::instrument(skip(self, tx))]
     pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
         if let Some(rate_limiter) = &self.0.rate_limiter {
@@ -413,6 +430,8 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                 .sender_config
                 .validation_computational_gas_limit,
             chain_id: self.0.sender_config.chain_id,
+            state_override: None,
+            warm_state: self.warm_vm_state(),
         }
     }
 
@@ -586,6 +605,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         tx_gas_limit: u32,
         l1_gas_price: u64,
         base_fee: u64,
+        state_override: Option<&StateOverride>,
     ) -> (VmExecutionResultAndLogs, TransactionExecutionMetrics) {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
@@ -615,7 +635,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             }
         }
 
-        let shared_args = self.shared_args_for_gas_estimate(l1_gas_price);
+        let shared_args = self.shared_args_for_gas_estimate(l1_gas_price, state_override);
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
         let execution_args =
             TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
@@ -631,7 +651,11 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         (exec_result, tx_metrics)
     }
 
-    fn shared_args_for_gas_estimate(&self, l1_gas_price: u64) -> TxSharedArgs {
+    fn shared_args_for_gas_estimate(
+        &self,
+        l1_gas_price: u64,
+        state_override: Option<&StateOverride>,
+    ) -> TxSharedArgs {
         let config = &self.0.sender_config;
         TxSharedArgs {
             operator_account: AccountTreeId::new(config.fee_account_addr),
@@ -642,6 +666,8 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             base_system_contracts: self.0.api_contracts.estimate_gas.clone(),
             caches: self.storage_caches(),
             chain_id: config.chain_id,
+            state_override: state_override.cloned(),
+            warm_state: self.warm_vm_state(),
         }
     }
 
@@ -650,6 +676,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         mut tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
+        state_override: Option<StateOverride>,
     ) -> Result<Fee, SubmitTxError> {
         let estimation_started_at = Instant::now();
         let l1_gas_price = {
@@ -683,24 +710,35 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             }
         }
 
+        let initiator_override = state_override
+            .as_ref()
+            .and_then(|state_override| state_override.get(&tx.initiator_account()));
         let hashed_key = get_code_key(&tx.initiator_account());
         // if the default account does not have enough funds
         // for transferring tx.value, without taking into account the fee,
         // there is no sense to estimate the fee
-        let account_code_hash = self
-            .0
-            .replica_connection_pool
-            .access_storage_tagged("api")
-            .await
-            .unwrap()
-            .storage_dal()
-            .get_by_key(&hashed_key)
-            .await
-            .unwrap_or_default();
+        let account_code_hash =
+            if let Some(code) = initiator_override.and_then(|account| account.code.as_ref()) {
+                hash_bytecode(&code.0)
+            } else {
+                self.0
+                    .replica_connection_pool
+                    .access_storage_tagged("api")
+                    .await
+                    .unwrap()
+                    .storage_dal()
+                    .get_by_key(&hashed_key)
+                    .await
+                    .unwrap_or_default()
+            };
+        let initiator_balance =
+            if let Some(balance) = initiator_override.and_then(|account| account.balance) {
+                balance
+            } else {
+                self.get_balance(&tx.initiator_account()).await
+            };
 
-        if !tx.is_l1()
-            && account_code_hash == H256::zero()
-            && tx.execute.value > self.get_balance(&tx.initiator_account()).await
+        if !tx.is_l1() && account_code_hash == H256::zero() && tx.execute.value > initiator_balance
         {
             tracing::info!(
                 "fee estimation failed on validation step.
@@ -777,6 +815,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                     try_gas_limit,
                     l1_gas_price,
                     base_fee,
+                    state_override.as_ref(),
                 )
                 .await;
 
@@ -814,6 +853,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                 suggested_gas_limit,
                 l1_gas_price,
                 base_fee,
+                state_override.as_ref(),
             )
             .await;
 
@@ -850,14 +890,19 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         &self,
         block_args: BlockArgs,
         tx: L2Tx,
+        state_override: Option<StateOverride>,
     ) -> Result<Vec<u8>, SubmitTxError> {
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
 
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let shared_args = TxSharedArgs {
+            state_override,
+            ..self.shared_args()
+        };
         execute_tx_eth_call(
             vm_permit,
-            self.shared_args(),
+            shared_args,
             self.0.replica_connection_pool.clone(),
             tx,
             block_args,
@@ -868,6 +913,30 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         .into_api_call_result()
     }
 
+    /// Executes calls sequentially in a single VM session. Unlike with [`Self::eth_call()`],
+    /// the returned results are not checked for reverts.
+    pub(super) async fn simulate_bundle(
+        &self,
+        block_args: BlockArgs,
+        txs: Vec<L2Tx>,
+        block_overrides: Option<SandboxBlockOverrides>,
+    ) -> Result<Vec<VmExecutionResultAndLogs>, SubmitTxError> {
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        Ok(execute_bundle_eth_call(
+            vm_permit,
+            self.shared_args(),
+            self.0.replica_connection_pool.clone(),
+            txs,
+            block_args,
+            block_overrides,
+            vm_execution_cache_misses_limit,
+        )
+        .await)
+    }
+
     pub fn gas_price(&self) -> u64 {
         let gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
         let l1_gas_price = (gas_price as f64 * self.0.sender_config.gas_price_scale_factor).round();
@@ -16,9 +16,14 @@ pub fn into_jsrpc_error(err: Web3Error) -> Error {
             | Web3Error::TooManyTopics
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
+            | Web3Error::InvalidStateOverride(_)
+            | Web3Error::InvalidBundle(_)
             | Web3Error::LogsLimitExceeded(_, _, _)
             | Web3Error::TooManyLogs(_)
-            | Web3Error::InvalidFilterBlockHash => ErrorCode::InvalidParams,
+            | Web3Error::InvalidFilterBlockHash
+            | Web3Error::PrunedL1Batch(_)
+            | Web3Error::PrunedBlock(..)
+            | Web3Error::StructLogsLimitExceeded(..) => ErrorCode::InvalidParams,
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3.into(),
             Web3Error::PubSubTimeout => 4.into(),
             Web3Error::RequestTimeout => 5.into(),
@@ -2,8 +2,8 @@ use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 use zksync_types::{
     api::{
-        BlockId, BlockIdVariant, BlockNumber, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        BlockId, BlockIdVariant, BlockNumber, StateOverride, Transaction, TransactionId,
+        TransactionReceipt, TransactionVariant,
     },
     transaction_request::CallRequest,
     web3::types::{FeeHistory, Index, SyncState},
@@ -25,13 +25,19 @@ pub trait EthNamespaceT {
     fn chain_id(&self) -> BoxFuture<Result<U64>>;
 
     #[rpc(name = "eth_call")]
-    fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> BoxFuture<Result<Bytes>>;
+    fn call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Result<Bytes>>;
 
     #[rpc(name = "eth_estimateGas")]
     fn estimate_gas(
         &self,
         req: CallRequest,
         _block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
     ) -> BoxFuture<Result<U256>>;
 
     #[rpc(name = "eth_gasPrice")]
@@ -188,11 +194,16 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceT for EthNamespa
         Box::pin(async move { Ok(self_.chain_id_impl()) })
     }
 
-    fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> BoxFuture<Result<Bytes>> {
+    fn call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Result<Bytes>> {
         let self_ = self.clone();
         Box::pin(async move {
             self_
-                .call_impl(req, block.map(Into::into))
+                .call_impl(req, block.map(Into::into), state_override)
                 .await
                 .map_err(into_jsrpc_error)
         })
@@ -202,11 +213,12 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceT for EthNamespa
         &self,
         req: CallRequest,
         block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
     ) -> BoxFuture<Result<U256>> {
         let self_ = self.clone();
         Box::pin(async move {
             self_
-                .estimate_gas_impl(req, block)
+                .estimate_gas_impl(req, block, state_override)
                 .await
                 .map_err(into_jsrpc_error)
         })
@@ -5,7 +5,8 @@ use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
+        BlockDetails, BlockIdVariant, BlockOverrides, BridgeAddresses, L1BatchDetails,
+        L2ToL1LogProof, Proof, ProtocolVersion, PrunedRange, SimulatedCall, StateOverride,
         TransactionDetails,
     },
     fee::Fee,
@@ -22,11 +23,23 @@ use crate::{
 #[rpc]
 pub trait ZksNamespaceT {
     #[rpc(name = "zks_estimateFee")]
-    fn estimate_fee(&self, req: CallRequest) -> BoxFuture<Result<Fee>>;
+    fn estimate_fee(
+        &self,
+        req: CallRequest,
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Result<Fee>>;
 
     #[rpc(name = "zks_estimateGasL1ToL2")]
     fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> BoxFuture<Result<U256>>;
 
+    #[rpc(name = "zks_simulateBundle")]
+    fn simulate_bundle(
+        &self,
+        calls: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> BoxFuture<Result<Vec<SimulatedCall>>>;
+
     #[rpc(name = "zks_getMainContract")]
     fn get_main_contract(&self) -> BoxFuture<Result<Address>>;
 
@@ -116,12 +129,24 @@ pub trait ZksNamespaceT {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> BoxFuture<Result<Proof>>;
+
+    #[rpc(name = "zks_getPrunedRanges")]
+    fn get_pruned_ranges(&self) -> BoxFuture<Result<Vec<PrunedRange>>>;
 }
 
 impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespace<G> {
-    fn estimate_fee(&self, req: CallRequest) -> BoxFuture<Result<Fee>> {
+    fn estimate_fee(
+        &self,
+        req: CallRequest,
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Result<Fee>> {
         let self_ = self.clone();
-        Box::pin(async move { self_.estimate_fee_impl(req).await.map_err(into_jsrpc_error) })
+        Box::pin(async move {
+            self_
+                .estimate_fee_impl(req, state_override)
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
     fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> BoxFuture<Result<U256>> {
@@ -134,6 +159,21 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         })
     }
 
+    fn simulate_bundle(
+        &self,
+        calls: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> BoxFuture<Result<Vec<SimulatedCall>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .simulate_bundle_impl(calls, block.map(Into::into), block_overrides)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
     fn get_main_contract(&self) -> BoxFuture<Result<Address>> {
         let self_ = self.clone();
         Box::pin(async move { Ok(self_.get_main_contract_impl()) })
@@ -328,4 +368,14 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
                 .map_err(into_jsrpc_error)
         })
     }
+
+    fn get_pruned_ranges(&self) -> BoxFuture<Result<Vec<PrunedRange>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_pruned_ranges_impl()
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
 }
@@ -26,9 +26,14 @@ pub fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::TooManyTopics
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
+            | Web3Error::InvalidStateOverride(_)
+            | Web3Error::InvalidBundle(_)
             | Web3Error::InvalidFilterBlockHash
             | Web3Error::LogsLimitExceeded(_, _, _)
-            | Web3Error::TooManyLogs(_) => ErrorCode::InvalidParams.code(),
+            | Web3Error::TooManyLogs(_)
+            | Web3Error::PrunedL1Batch(_)
+            | Web3Error::PrunedBlock(..)
+            | Web3Error::StructLogsLimitExceeded(..) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
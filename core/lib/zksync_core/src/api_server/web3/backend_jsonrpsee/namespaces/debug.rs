@@ -1,5 +1,5 @@
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugTrace, ResultDebugCall, TracerConfig},
     transaction_request::CallRequest,
     H256,
 };
@@ -35,7 +35,7 @@ impl DebugNamespaceServer for DebugNamespace {
         request: CallRequest,
         block: Option<BlockId>,
         options: Option<TracerConfig>,
-    ) -> RpcResult<DebugCall> {
+    ) -> RpcResult<DebugTrace> {
         self.debug_trace_call_impl(request, block, options)
             .await
             .map_err(into_jsrpc_error)
@@ -44,7 +44,9 @@ impl DebugNamespaceServer for DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Option<DebugCall>> {
-        Ok(self.debug_trace_transaction_impl(tx_hash, options).await)
+    ) -> RpcResult<Option<DebugTrace>> {
+        self.debug_trace_transaction_impl(tx_hash, options)
+            .await
+            .map_err(into_jsrpc_error)
     }
 }
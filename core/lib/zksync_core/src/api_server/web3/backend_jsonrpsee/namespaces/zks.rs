@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use bigdecimal::BigDecimal;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
+        BlockDetails, BlockIdVariant, BlockOverrides, BridgeAddresses, L1BatchDetails,
+        L2ToL1LogProof, Proof, ProtocolVersion, PrunedRange, SimulatedCall, StateOverride,
         TransactionDetails,
     },
     fee::Fee,
@@ -23,8 +24,14 @@ use crate::{
 
 #[async_trait]
 impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNamespace<G> {
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee> {
-        self.estimate_fee_impl(req).await.map_err(into_jsrpc_error)
+    async fn estimate_fee(
+        &self,
+        req: CallRequest,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Fee> {
+        self.estimate_fee_impl(req, state_override)
+            .await
+            .map_err(into_jsrpc_error)
     }
 
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256> {
@@ -33,6 +40,17 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn simulate_bundle(
+        &self,
+        calls: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> RpcResult<Vec<SimulatedCall>> {
+        self.simulate_bundle_impl(calls, block.map(Into::into), block_overrides)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_main_contract(&self) -> RpcResult<Address> {
         Ok(self.get_main_contract_impl())
     }
@@ -168,4 +186,10 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .await
             .map_err(into_jsrpc_error)
     }
+
+    async fn get_pruned_ranges(&self) -> RpcResult<Vec<PrunedRange>> {
+        self.get_pruned_ranges_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
 }
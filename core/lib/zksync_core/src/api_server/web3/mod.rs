@@ -14,7 +14,10 @@ use tokio::{
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_types::{api, MiniblockNumber};
+use zksync_types::{
+    api::{self, PrunedData},
+    MiniblockNumber,
+};
 use zksync_web3_decl::{
     error::Web3Error,
     jsonrpsee::{
@@ -48,7 +51,9 @@ use self::{
 };
 use crate::{
     api_server::{
-        execution_sandbox::VmConcurrencyBarrier, tree::TreeApiHttpClient, tx_sender::TxSender,
+        execution_sandbox::{BlockArgsError, VmConcurrencyBarrier},
+        tree::TreeApiHttpClient,
+        tx_sender::TxSender,
         web3::backend_jsonrpc::batch_limiter_middleware::RateLimitMetadata,
     },
     l1_gas_price::L1GasPriceProvider,
@@ -807,3 +812,51 @@ async fn resolve_block(
         .map_err(|err| internal_error(method_name, err))?
         .ok_or(Web3Error::NoBlock)
 }
+
+/// Returns an error if `data` for the specified miniblock was pruned from Postgres.
+async fn ensure_not_pruned(
+    connection: &mut StorageProcessor<'_>,
+    data: PrunedData,
+    block_number: MiniblockNumber,
+    method_name: &'static str,
+) -> Result<(), Web3Error> {
+    let pruned_range = connection.pruning_dal().get_pruned_range(data).await;
+    let pruned_range = pruned_range.map_err(|err| internal_error(method_name, err))?;
+    if let Some(pruned_range) = pruned_range {
+        let first_available_miniblock = pruned_range.first_available_miniblock();
+        if block_number < first_available_miniblock {
+            return Err(Web3Error::PrunedBlock(
+                block_number,
+                first_available_miniblock,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Resolves a block with its state, returning an error if the state was pruned.
+async fn resolve_block_with_state(
+    connection: &mut StorageProcessor<'_>,
+    block: api::BlockId,
+    method_name: &'static str,
+) -> Result<MiniblockNumber, Web3Error> {
+    let block_number = resolve_block(connection, block, method_name).await?;
+    ensure_not_pruned(
+        connection,
+        PrunedData::StorageLogs,
+        block_number,
+        method_name,
+    )
+    .await?;
+    Ok(block_number)
+}
+
+fn block_args_error(method_name: &'static str, err: BlockArgsError) -> Web3Error {
+    match err {
+        BlockArgsError::Missing => Web3Error::NoBlock,
+        BlockArgsError::Pruned(block_number, first_available_miniblock) => {
+            Web3Error::PrunedBlock(block_number, first_available_miniblock)
+        }
+        BlockArgsError::Database(err) => internal_error(method_name, err),
+    }
+}
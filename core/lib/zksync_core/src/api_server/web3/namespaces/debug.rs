@@ -1,26 +1,36 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
-use multivm::{interface::ExecutionResult, vm_latest::constants::BLOCK_GAS_LIMIT};
+use multivm::{
+    interface::{ExecutionResult, VmExecutionResultAndLogs},
+    tracers::{prestate_tracer::StorageSlotValues, StructLogConfig},
+    vm_latest::constants::BLOCK_GAS_LIMIT,
+};
 use once_cell::sync::OnceCell;
 use zksync_dal::ConnectionPool;
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, DebugCall, DebugTrace, PrestateDiff, PrestateTrace, PrunedData,
+        ResultDebugCall, StructLoggerConfig, SupportedTracers, TracerConfig, TracerSpecificConfig,
+        TransactionId,
+    },
     l2::L2Tx,
     transaction_request::CallRequest,
-    vm_trace::Call,
-    AccountTreeId, L2ChainId, H256, USED_BOOTLOADER_MEMORY_BYTES,
+    vm_trace::{Call, GasProfile, StructLog},
+    AccountTreeId, L2ChainId, MiniblockNumber, StorageKey, H256, U64, USED_BOOTLOADER_MEMORY_BYTES,
 };
 use zksync_web3_decl::error::Web3Error;
 
 use crate::{
     api_server::{
         execution_sandbox::{
-            execute_tx_eth_call, ApiTracer, BlockArgs, TxSharedArgs, VmConcurrencyLimiter,
+            execute_tx_eth_call, execute_tx_replay, validate_state_override, ApiTracer, BlockArgs,
+            TxSharedArgs, VmConcurrencyLimiter, WarmVmState,
         },
         tx_sender::ApiContracts,
         web3::{
             backend_jsonrpc::error::internal_error,
+            block_args_error, ensure_not_pruned,
             metrics::API_METRICS,
             resolve_block,
             state::{RpcState, SealedMiniblockNumber},
@@ -29,6 +39,12 @@ use crate::{
     l1_gas_price::L1GasPriceProvider,
 };
 
+/// Number of steps recorded by `structLogger` if the limit is not specified by the caller.
+const DEFAULT_STRUCT_LOGS_LIMIT: usize = 10_000;
+/// Maximum number of steps that can be requested from `structLogger`. Each step may include
+/// the stack, registers and heap memory, so unbounded traces could exhaust the node memory.
+const MAX_STRUCT_LOGS_LIMIT: usize = 100_000;
+
 #[derive(Debug, Clone)]
 pub struct DebugNamespace {
     connection_pool: ConnectionPool,
@@ -37,6 +53,7 @@ pub struct DebugNamespace {
     vm_execution_cache_misses_limit: Option<usize>,
     vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     storage_caches: PostgresStorageCaches,
+    warm_vm_state: Option<WarmVmState>,
     last_sealed_miniblock: SealedMiniblockNumber,
     chain_id: L2ChainId,
 }
@@ -53,6 +70,7 @@ impl DebugNamespace {
             vm_execution_cache_misses_limit: sender_config.vm_execution_cache_misses_limit,
             vm_concurrency_limiter: state.tx_sender.vm_concurrency_limiter(),
             storage_caches: state.tx_sender.storage_caches(),
+            warm_vm_state: state.tx_sender.warm_vm_state(),
             last_sealed_miniblock: state.last_sealed_miniblock,
             chain_id: sender_config.chain_id,
         }
@@ -68,7 +86,7 @@ impl DebugNamespace {
 
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
         let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
+            .map(|options| options.tracer_config.call.only_top_call)
             .unwrap_or(false);
         let mut connection = self
             .connection_pool
@@ -76,6 +94,13 @@ impl DebugNamespace {
             .await
             .unwrap();
         let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        ensure_not_pruned(
+            &mut connection,
+            PrunedData::Transactions,
+            block_number,
+            METHOD_NAME,
+        )
+        .await?;
         let call_trace = connection
             .blocks_web3_dal()
             .get_trace_for_miniblock(block_number)
@@ -102,10 +127,14 @@ impl DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> Option<DebugCall> {
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+    ) -> Result<Option<DebugTrace>, Web3Error> {
+        let (tracer, tracer_config) = split_options(options);
+        if !matches!(tracer, SupportedTracers::CallTracer) {
+            return self
+                .trace_transaction_by_replay(tx_hash, tracer, tracer_config)
+                .await;
+        }
+
         let call_trace = self
             .connection_pool
             .access_storage_tagged("api")
@@ -114,13 +143,85 @@ impl DebugNamespace {
             .transactions_dal()
             .get_call_trace(tx_hash)
             .await;
-        call_trace.map(|call_trace| {
+        Ok(call_trace.map(|call_trace| {
             let mut result: DebugCall = call_trace.into();
-            if only_top_call {
+            if tracer_config.call.only_top_call {
                 result.calls = vec![];
             }
-            result
-        })
+            DebugTrace::Call(result)
+        }))
+    }
+
+    /// Re-executes a stored transaction with the specified tracer. Only call traces are persisted,
+    /// so the transaction is executed on top of the state of the previous miniblock with the fee parameters
+    /// of its own miniblock, after the transactions preceding it in the same miniblock.
+    async fn trace_transaction_by_replay(
+        &self,
+        tx_hash: H256,
+        tracer: SupportedTracers,
+        tracer_config: TracerSpecificConfig,
+    ) -> Result<Option<DebugTrace>, Web3Error> {
+        const METHOD_NAME: &str = "debug_trace_transaction";
+
+        let mut connection = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .unwrap();
+        let api_tx = connection
+            .transactions_web3_dal()
+            .get_transaction(TransactionId::Hash(tx_hash), self.chain_id)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(block_number) = api_tx.and_then(|tx| tx.block_number) else {
+            return Ok(None);
+        };
+        let miniblock_number = MiniblockNumber(block_number.as_u32());
+        let miniblock_header = connection
+            .blocks_dal()
+            .get_miniblock_header(miniblock_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .ok_or(Web3Error::NoBlock)?;
+        let mut preceding_txs = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(miniblock_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(tx_index) = preceding_txs.iter().position(|tx| tx.hash() == tx_hash) else {
+            return Ok(None);
+        };
+        let tx = preceding_txs.remove(tx_index);
+        preceding_txs.truncate(tx_index);
+        let tracer_results = TracerResults::default();
+        let custom_tracers = tracer_results.api_tracers(&tracer, &tracer_config)?;
+        let prev_block_id = BlockId::Number(BlockNumber::Number(U64::from(
+            miniblock_number.0.saturating_sub(1),
+        )));
+        let block_args = BlockArgs::new(&mut connection, prev_block_id)
+            .await
+            .map_err(|err| block_args_error(METHOD_NAME, err))?;
+        drop(connection);
+
+        let mut shared_args = self.shared_args();
+        shared_args.l1_gas_price = miniblock_header.l1_gas_price;
+        shared_args.fair_l2_gas_price = miniblock_header.l2_fair_gas_price;
+        let vm_permit = self.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(Web3Error::InternalError)?;
+
+        let result = execute_tx_replay(
+            vm_permit,
+            shared_args,
+            self.connection_pool.clone(),
+            preceding_txs,
+            tx,
+            miniblock_header.base_fee_per_gas,
+            block_args,
+            custom_tracers,
+        )
+        .await;
+        let trace = tracer_results.into_trace(&tracer, &tracer_config, &result, METHOD_NAME)?;
+        Ok(Some(trace))
     }
 
     #[tracing::instrument(skip(self, request, block_id))]
@@ -129,14 +230,20 @@ impl DebugNamespace {
         request: CallRequest,
         block_id: Option<BlockId>,
         options: Option<TracerConfig>,
-    ) -> Result<DebugCall, Web3Error> {
+    ) -> Result<DebugTrace, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_call";
 
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+        let state_override = options
+            .as_ref()
+            .and_then(|options| options.state_overrides.clone());
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override).map_err(Web3Error::InvalidStateOverride)?;
+        }
+        let (tracer, tracer_config) = split_options(options);
+        let tracer_results = TracerResults::default();
+        let custom_tracers = tracer_results.api_tracers(&tracer, &tracer_config)?;
 
         let mut connection = self
             .connection_pool
@@ -145,24 +252,18 @@ impl DebugNamespace {
             .unwrap();
         let block_args = BlockArgs::new(&mut connection, block_id)
             .await
-            .map_err(|err| internal_error("debug_trace_call", err))?
-            .ok_or(Web3Error::NoBlock)?;
+            .map_err(|err| block_args_error("debug_trace_call", err))?;
         drop(connection);
 
         let tx = L2Tx::from_request(request.into(), USED_BOOTLOADER_MEMORY_BYTES)?;
 
-        let shared_args = self.shared_args();
+        let shared_args = TxSharedArgs {
+            state_override,
+            ..self.shared_args()
+        };
         let vm_permit = self.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(Web3Error::InternalError)?;
 
-        // We don't need properly trace if we only need top call
-        let call_tracer_result = Arc::new(OnceCell::default());
-        let custom_tracers = if only_top_call {
-            vec![]
-        } else {
-            vec![ApiTracer::CallTracer(call_tracer_result.clone())]
-        };
-
         let result = execute_tx_eth_call(
             vm_permit,
             shared_args,
@@ -174,22 +275,24 @@ impl DebugNamespace {
         )
         .await;
 
+        if let ExecutionResult::Halt { reason } = &result.result {
+            return Err(Web3Error::SubmitTransactionError(
+                reason.to_string(),
+                vec![],
+            ));
+        }
+        let block_diff = self.last_sealed_miniblock.diff_with_block_args(&block_args);
+        if !matches!(tracer, SupportedTracers::CallTracer) {
+            method_latency.observe(block_diff);
+            return tracer_results.into_trace(&tracer, &tracer_config, &result, METHOD_NAME);
+        }
+
         let (output, revert_reason) = match result.result {
             ExecutionResult::Success { output, .. } => (output, None),
             ExecutionResult::Revert { output } => (vec![], Some(output.to_string())),
-            ExecutionResult::Halt { reason } => {
-                return Err(Web3Error::SubmitTransactionError(
-                    reason.to_string(),
-                    vec![],
-                ))
-            }
+            ExecutionResult::Halt { .. } => unreachable!("halted execution is handled above"),
         };
-
-        // We had only one copy of Arc this arc is already dropped it's safe to unwrap
-        let trace = Arc::try_unwrap(call_tracer_result)
-            .unwrap()
-            .take()
-            .unwrap_or_default();
+        let trace = take_result(tracer_results.call_trace, METHOD_NAME)?;
         let call = Call::new_high_level(
             tx.common_data.fee.gas_limit.as_u32(),
             result.statistics.gas_used,
@@ -200,9 +303,8 @@ impl DebugNamespace {
             trace,
         );
 
-        let block_diff = self.last_sealed_miniblock.diff_with_block_args(&block_args);
         method_latency.observe(block_diff);
-        Ok(call.into())
+        Ok(DebugTrace::Call(call.into()))
     }
 
     fn shared_args(&self) -> TxSharedArgs {
@@ -214,6 +316,243 @@ impl DebugNamespace {
             caches: self.storage_caches.clone(),
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
             chain_id: self.chain_id,
+            state_override: None,
+            warm_state: self.warm_vm_state.clone(),
+        }
+    }
+}
+
+/// Results of VM tracers used by debug methods.
+#[derive(Debug, Default)]
+struct TracerResults {
+    call_trace: Arc<OnceCell<Vec<Call>>>,
+    prestate: Arc<OnceCell<BTreeMap<StorageKey, StorageSlotValues>>>,
+    struct_logs: Arc<OnceCell<Vec<StructLog>>>,
+    gas_profile: Arc<OnceCell<GasProfile>>,
+}
+
+impl TracerResults {
+    fn api_tracers(
+        &self,
+        tracer: &SupportedTracers,
+        config: &TracerSpecificConfig,
+    ) -> Result<Vec<ApiTracer>, Web3Error> {
+        Ok(match tracer {
+            // We don't need properly trace if we only need top call
+            SupportedTracers::CallTracer if config.call.only_top_call => vec![],
+            SupportedTracers::CallTracer => vec![ApiTracer::CallTracer(self.call_trace.clone())],
+            SupportedTracers::PrestateTracer => {
+                vec![ApiTracer::PrestateTracer(self.prestate.clone())]
+            }
+            SupportedTracers::StructLogger => {
+                let struct_log_config = struct_log_config(&config.struct_logger)?;
+                vec![ApiTracer::StructLogTracer(
+                    struct_log_config,
+                    self.struct_logs.clone(),
+                )]
+            }
+            SupportedTracers::GasProfiler => vec![ApiTracer::GasProfiler(self.gas_profile.clone())],
+        })
+    }
+
+    /// Converts tracer results into the RPC output. Call traces are built separately since they depend
+    /// on the executed transaction.
+    fn into_trace(
+        self,
+        tracer: &SupportedTracers,
+        config: &TracerSpecificConfig,
+        result: &VmExecutionResultAndLogs,
+        method_name: &'static str,
+    ) -> Result<DebugTrace, Web3Error> {
+        Ok(match tracer {
+            SupportedTracers::CallTracer => unreachable!("call traces are built separately"),
+            SupportedTracers::PrestateTracer => prestate_trace(
+                take_result(self.prestate, method_name)?,
+                config.prestate.diff_mode,
+            ),
+            SupportedTracers::StructLogger => {
+                let return_value = match &result.result {
+                    ExecutionResult::Success { output } => output.clone(),
+                    ExecutionResult::Revert { .. } | ExecutionResult::Halt { .. } => vec![],
+                };
+                DebugTrace::StructLogs(DebugStructLogs {
+                    gas: result.statistics.gas_used,
+                    failed: result.result.is_failed(),
+                    return_value: return_value.into(),
+                    struct_logs: take_result(self.struct_logs, method_name)?,
+                })
+            }
+            SupportedTracers::GasProfiler => {
+                DebugTrace::GasProfile(take_result(self.gas_profile, method_name)?)
+            }
+        })
+    }
+}
+
+/// Converts the caller-provided `structLogger` options into the tracer config, applying the server-side
+/// default for the number of recorded steps and checking that the requested number doesn't exceed the cap.
+fn struct_log_config(config: &StructLoggerConfig) -> Result<StructLogConfig, Web3Error> {
+    let limit = config.limit.unwrap_or(DEFAULT_STRUCT_LOGS_LIMIT);
+    if limit > MAX_STRUCT_LOGS_LIMIT {
+        return Err(Web3Error::StructLogsLimitExceeded(
+            limit,
+            MAX_STRUCT_LOGS_LIMIT,
+        ));
+    }
+    Ok(StructLogConfig {
+        limit: Some(limit),
+        disable_registers: config.disable_registers,
+        disable_stack: config.disable_stack,
+        enable_memory: config.enable_memory,
+        ..StructLogConfig::default()
+    })
+}
+
+fn take_result<T: Default>(
+    cell: Arc<OnceCell<T>>,
+    method_name: &'static str,
+) -> Result<T, Web3Error> {
+    // Tracers are dropped together with the VM, so we should hold the only copy of the `Arc`.
+    let cell = Arc::try_unwrap(cell).map_err(|_| {
+        internal_error(
+            method_name,
+            "tracer result is referenced after VM execution",
+        )
+    })?;
+    Ok(cell.into_inner().unwrap_or_default())
+}
+
+fn split_options(options: Option<TracerConfig>) -> (SupportedTracers, TracerSpecificConfig) {
+    options.map_or(
+        (
+            SupportedTracers::CallTracer,
+            TracerSpecificConfig::default(),
+        ),
+        |options| (options.tracer, options.tracer_config),
+    )
+}
+
+/// Converts storage slots touched during execution into the geth `prestateTracer` output.
+/// In the default mode, all touched slots are returned with their values before execution.
+/// In the diff mode, only modified slots are returned; cleared slots are omitted from the post-state.
+fn prestate_trace(slots: BTreeMap<StorageKey, StorageSlotValues>, diff_mode: bool) -> DebugTrace {
+    if !diff_mode {
+        let mut trace = PrestateTrace::new();
+        for (key, values) in slots {
+            let account = trace.entry(*key.address()).or_default();
+            account.storage.insert(*key.key(), values.before);
+        }
+        return DebugTrace::Prestate(trace);
+    }
+
+    let mut diff = PrestateDiff::default();
+    for (key, values) in slots {
+        if !values.is_modified() {
+            continue;
+        }
+        let address = *key.address();
+        let pre_account = diff.pre.entry(address).or_default();
+        pre_account.storage.insert(*key.key(), values.before);
+        if !values.after.is_zero() {
+            let post_account = diff.post.entry(address).or_default();
+            post_account.storage.insert(*key.key(), values.after);
         }
     }
+    DebugTrace::PrestateDiff(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{AccountTreeId, Address};
+
+    use super::*;
+
+    #[test]
+    fn prestate_trace_modes() {
+        let address = Address::repeat_byte(1);
+        let slot = |index: u64| H256::from_low_u64_be(index);
+        let values = |before: u64, after: u64| StorageSlotValues {
+            before: slot(before),
+            after: slot(after),
+        };
+        let slots = BTreeMap::from([
+            (
+                StorageKey::new(AccountTreeId::new(address), slot(1)),
+                values(1, 1),
+            ),
+            (
+                StorageKey::new(AccountTreeId::new(address), slot(2)),
+                values(0, 5),
+            ),
+            (
+                StorageKey::new(AccountTreeId::new(address), slot(3)),
+                values(7, 0),
+            ),
+        ]);
+
+        let DebugTrace::Prestate(trace) = prestate_trace(slots.clone(), false) else {
+            panic!("unexpected trace");
+        };
+        let storage = &trace[&address].storage;
+        assert_eq!(storage.len(), 3);
+        assert_eq!(storage[&slot(2)], slot(0));
+
+        let DebugTrace::PrestateDiff(diff) = prestate_trace(slots, true) else {
+            panic!("unexpected trace");
+        };
+        let pre_storage = &diff.pre[&address].storage;
+        assert_eq!(
+            pre_storage.keys().copied().collect::<Vec<_>>(),
+            [slot(2), slot(3)]
+        );
+        let post_storage = &diff.post[&address].storage;
+        assert_eq!(post_storage.len(), 1);
+        assert_eq!(post_storage[&slot(2)], slot(5));
+    }
+
+    #[test]
+    fn parsing_tracer_specific_config() {
+        let options: TracerConfig = serde_json::from_value(serde_json::json!({
+            "tracer": "prestateTracer",
+            "tracerConfig": { "diffMode": true },
+        }))
+        .unwrap();
+        let (tracer, config) = split_options(Some(options));
+        assert!(matches!(tracer, SupportedTracers::PrestateTracer));
+        assert!(config.prestate.diff_mode);
+        assert!(!config.call.only_top_call);
+
+        let options: TracerConfig = serde_json::from_value(serde_json::json!({
+            "tracer": "callTracer",
+            "tracerConfig": { "onlyTopCall": true },
+        }))
+        .unwrap();
+        assert!(options.tracer_config.call.only_top_call);
+        assert!(!options.tracer_config.prestate.diff_mode);
+    }
+
+    #[test]
+    fn struct_logger_limits() {
+        let options: TracerConfig = serde_json::from_value(serde_json::json!({
+            "tracer": "structLogger",
+            "tracerConfig": { "enableMemory": true },
+        }))
+        .unwrap();
+        let config = &options.tracer_config.struct_logger;
+        assert!(config.enable_memory);
+        let struct_log_config = struct_log_config(config).unwrap();
+        assert_eq!(struct_log_config.limit, Some(DEFAULT_STRUCT_LOGS_LIMIT));
+        assert!(struct_log_config.enable_memory);
+
+        let options: TracerConfig = serde_json::from_value(serde_json::json!({
+            "tracer": "structLogger",
+            "tracerConfig": { "limit": MAX_STRUCT_LOGS_LIMIT + 1 },
+        }))
+        .unwrap();
+        let err = struct_log_config(&options.tracer_config.struct_logger).unwrap_err();
+        assert!(
+            matches!(err, Web3Error::StructLogsLimitExceeded(limit, MAX_STRUCT_LOGS_LIMIT) if limit == MAX_STRUCT_LOGS_LIMIT + 1),
+            "{err:?}"
+        );
+    }
 }
@@ -1,7 +1,7 @@
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        BlockId, BlockNumber, GetLogsFilter, PrunedData, StateOverride, Transaction, TransactionId,
+        TransactionReceipt, TransactionVariant,
     },
     l2::{L2Tx, TransactionType},
     transaction_request::CallRequest,
@@ -19,11 +19,12 @@ use zksync_web3_decl::{
 
 use crate::{
     api_server::{
-        execution_sandbox::BlockArgs,
+        execution_sandbox::{validate_state_override, BlockArgs},
         web3::{
             backend_jsonrpc::error::internal_error,
+            block_args_error, ensure_not_pruned,
             metrics::{BlockCallObserver, API_METRICS},
-            resolve_block,
+            resolve_block, resolve_block_with_state,
             state::RpcState,
             TypedFilter,
         },
@@ -73,14 +74,19 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         block_number
     }
 
-    #[tracing::instrument(skip(self, request, block_id))]
+    #[tracing::instrument(skip(self, request, block_id, state_override))]
     pub async fn call_impl(
         &self,
         request: CallRequest,
         block_id: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> Result<Bytes, Web3Error> {
         const METHOD_NAME: &str = "call";
 
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override).map_err(Web3Error::InvalidStateOverride)?;
+        }
+
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
         let mut connection = self
@@ -91,13 +97,16 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             .unwrap();
         let block_args = BlockArgs::new(&mut connection, block_id)
             .await
-            .map_err(|err| internal_error("eth_call", err))?
-            .ok_or(Web3Error::NoBlock)?;
+            .map_err(|err| block_args_error("eth_call", err))?;
         drop(connection);
 
         let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
 
-        let call_result = self.state.tx_sender.eth_call(block_args, tx).await;
+        let call_result = self
+            .state
+            .tx_sender
+            .eth_call(block_args, tx, state_override)
+            .await;
         let res_bytes = call_result
             .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
 
@@ -109,18 +118,25 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         Ok(res_bytes.into())
     }
 
-    #[tracing::instrument(skip(self, request, _block))]
+    #[tracing::instrument(skip(self, request, _block, state_override))]
     pub async fn estimate_gas_impl(
         &self,
         request: CallRequest,
         _block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
     ) -> Result<U256, Web3Error> {
         const METHOD_NAME: &str = "estimate_gas";
 
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override).map_err(Web3Error::InvalidStateOverride)?;
+        }
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let mut request_with_gas_per_pubdata_overridden = request;
         self.state
-            .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
+            .set_nonce_for_call_request(
+                &mut request_with_gas_per_pubdata_overridden,
+                state_override.as_ref(),
+            )
             .await?;
 
         if let Some(ref mut eip712_meta) = request_with_gas_per_pubdata_overridden.eip712_meta {
@@ -158,7 +174,12 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(
+                tx.into(),
+                scale_factor,
+                acceptable_overestimation,
+                state_override,
+            )
             .await
             .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
 
@@ -192,7 +213,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             .access_storage_tagged("api")
             .await
             .unwrap();
-        let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        let block_number = resolve_block_with_state(&mut connection, block_id, METHOD_NAME).await?;
         let balance = connection
             .storage_web3_dal()
             .standard_token_historical_balance(
@@ -283,12 +304,13 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         };
         let method_latency = API_METRICS.start_block_call(method_name, block_id);
 
-        let block = self
+        let mut connection = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        let block = connection
             .blocks_web3_dal()
             .get_block_by_web3_block_id(
                 block_id,
@@ -296,15 +318,22 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                 self.state.api_config.l2_chain_id,
             )
             .await
-            .map_err(|err| internal_error(method_name, err));
+            .map_err(|err| internal_error(method_name, err))?;
 
-        if let Ok(Some(block)) = &block {
-            let block_number = MiniblockNumber(block.number.as_u32());
-            self.report_latency_with_block_id(method_latency, block_number);
-        } else {
+        let Some(block) = block else {
             method_latency.observe_without_diff();
-        }
-        block
+            return Ok(None);
+        };
+        let block_number = MiniblockNumber(block.number.as_u32());
+        ensure_not_pruned(
+            &mut connection,
+            PrunedData::Transactions,
+            block_number,
+            method_name,
+        )
+        .await?;
+        self.report_latency_with_block_id(method_latency, block_number);
+        Ok(Some(block))
     }
 
     #[tracing::instrument(skip(self))]
@@ -315,23 +344,31 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_block_transaction_count";
 
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
-        let tx_count = self
+        let mut connection = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        let tx_count = connection
             .blocks_web3_dal()
             .get_block_tx_count(block_id)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
 
-        if let Ok(Some((block_number, _))) = &tx_count {
-            self.report_latency_with_block_id(method_latency, *block_number);
-        } else {
+        let Some((block_number, tx_count)) = tx_count else {
             method_latency.observe_without_diff();
-        }
-        Ok(tx_count?.map(|(_, count)| count))
+            return Ok(None);
+        };
+        ensure_not_pruned(
+            &mut connection,
+            PrunedData::Transactions,
+            block_number,
+            METHOD_NAME,
+        )
+        .await?;
+        self.report_latency_with_block_id(method_latency, block_number);
+        Ok(Some(tx_count))
     }
 
     #[tracing::instrument(skip(self))]
@@ -350,7 +387,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             .access_storage_tagged("api")
             .await
             .unwrap();
-        let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        let block_number = resolve_block_with_state(&mut connection, block_id, METHOD_NAME).await?;
         let contract_code = connection
             .storage_web3_dal()
             .get_contract_code_unchecked(address, block_number)
@@ -384,7 +421,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             .access_storage_tagged("api")
             .await
             .unwrap();
-        let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        let block_number = resolve_block_with_state(&mut connection, block_id, METHOD_NAME).await?;
         let value = connection
             .storage_web3_dal()
             .get_historical_value_unchecked(&storage_key, block_number)
@@ -426,7 +463,8 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                 (nonce, None)
             }
             _ => {
-                let block_number = resolve_block(&mut connection, block_id, method_name).await?;
+                let block_number =
+                    resolve_block_with_state(&mut connection, block_id, method_name).await?;
                 let nonce = connection
                     .storage_web3_dal()
                     .get_address_historical_nonce(address, block_number)
@@ -455,17 +493,37 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_transaction";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let mut transaction = self
+        let mut connection = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        let mut transaction = connection
             .transactions_web3_dal()
             .get_transaction(id, self.state.api_config.l2_chain_id)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err));
 
+        if let (Ok(None), TransactionId::Block(block_id, _)) = (&transaction, id) {
+            // Distinguish a missing transaction from a pruned one.
+            let block_number = connection
+                .blocks_web3_dal()
+                .resolve_block_id(block_id)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            if let Some(block_number) = block_number {
+                ensure_not_pruned(
+                    &mut connection,
+                    PrunedData::Transactions,
+                    block_number,
+                    METHOD_NAME,
+                )
+                .await?;
+            }
+        }
+        drop(connection);
+
         if let Some(proxy) = &self.state.tx_sender.0.proxy {
             // We're running an external node - check the proxy cache in
             // case the transaction was proxied but not yet synced back to us
@@ -850,6 +908,8 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                     .access_storage_tagged("api")
                     .await
                     .map_err(|err| internal_error(METHOD_NAME, err))?;
+                ensure_not_pruned(&mut storage, PrunedData::Events, *from_block, METHOD_NAME)
+                    .await?;
 
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
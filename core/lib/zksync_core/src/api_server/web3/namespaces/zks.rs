@@ -1,12 +1,17 @@
-use std::{collections::HashMap, convert::TryInto};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryInto,
+};
 
 use bigdecimal::{BigDecimal, Zero};
+use multivm::interface::ExecutionResult;
 use zksync_dal::StorageProcessor;
 use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        BlockDetails, BlockId, BlockNumber, BlockOverrides, BridgeAddresses, GetLogsFilter,
+        L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion, PrunedData, PrunedRange,
+        SimulatedCall, StateOverride, StorageDiff, StorageProof, TransactionDetails,
     },
     fee::Fee,
     l1::L1Tx,
@@ -14,11 +19,11 @@ use zksync_types::{
     l2_to_l1_log::L2ToL1Log,
     tokens::ETHEREUM_ADDRESS,
     transaction_request::CallRequest,
-    AccountTreeId, L1BatchNumber, MiniblockNumber, StorageKey, Transaction, L1_MESSENGER_ADDRESS,
-    L2_ETH_TOKEN_ADDRESS, MAX_GAS_PER_PUBDATA_BYTE, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
-    U64,
+    AccountTreeId, L1BatchNumber, MiniblockNumber, StorageKey, StorageLogQuery, Transaction,
+    L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, MAX_GAS_PER_PUBDATA_BYTE,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
-use zksync_utils::{address_to_h256, ratio_to_big_decimal_normalized};
+use zksync_utils::{address_to_h256, ratio_to_big_decimal_normalized, u256_to_h256};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Filter, Log, Token, H256},
@@ -26,12 +31,19 @@ use zksync_web3_decl::{
 
 use crate::{
     api_server::{
-        tree::TreeApiClient,
-        web3::{backend_jsonrpc::error::internal_error, metrics::API_METRICS, RpcState},
+        execution_sandbox::{validate_state_override, BlockArgs, SandboxBlockOverrides},
+        tree::{PrunedL1BatchError, TreeApiClient},
+        web3::{
+            backend_jsonrpc::error::internal_error, block_args_error, ensure_not_pruned,
+            metrics::API_METRICS, RpcState,
+        },
     },
     l1_gas_price::L1GasPriceProvider,
 };
 
+/// Maximum number of calls in a bundle executed by `zks_simulateBundle`.
+const MAX_SIMULATED_BUNDLE_CALLS: usize = 64;
+
 #[derive(Debug)]
 pub struct ZksNamespace<G> {
     pub state: RpcState<G>,
@@ -50,15 +62,25 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         Self { state }
     }
 
-    #[tracing::instrument(skip(self, request))]
-    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
+    #[tracing::instrument(skip(self, request, state_override))]
+    pub async fn estimate_fee_impl(
+        &self,
+        request: CallRequest,
+        state_override: Option<StateOverride>,
+    ) -> Result<Fee, Web3Error> {
         const METHOD_NAME: &str = "estimate_fee";
 
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override).map_err(Web3Error::InvalidStateOverride)?;
+        }
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let mut request_with_gas_per_pubdata_overridden = request;
 
         self.state
-            .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
+            .set_nonce_for_call_request(
+                &mut request_with_gas_per_pubdata_overridden,
+                state_override.as_ref(),
+            )
             .await?;
 
         if let Some(ref mut eip712_meta) = request_with_gas_per_pubdata_overridden.eip712_meta {
@@ -75,11 +97,108 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = MAX_GAS_PER_PUBDATA_BYTE.into();
 
-        let fee = self.estimate_fee(tx.into()).await?;
+        let fee = self.estimate_fee(tx.into(), state_override).await?;
         method_latency.observe();
         Ok(fee)
     }
 
+    #[tracing::instrument(skip(self, calls, block_overrides))]
+    pub async fn simulate_bundle_impl(
+        &self,
+        calls: Vec<CallRequest>,
+        block_id: Option<BlockId>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<Vec<SimulatedCall>, Web3Error> {
+        const METHOD_NAME: &str = "simulate_bundle";
+
+        if calls.is_empty() {
+            return Err(Web3Error::InvalidBundle("bundle has no calls".to_owned()));
+        }
+        if calls.len() > MAX_SIMULATED_BUNDLE_CALLS {
+            return Err(Web3Error::InvalidBundle(format!(
+                "bundle has {} calls, while at most {MAX_SIMULATED_BUNDLE_CALLS} are allowed",
+                calls.len()
+            )));
+        }
+        let block_overrides = block_overrides
+            .map(SandboxBlockOverrides::try_from)
+            .transpose()
+            .map_err(Web3Error::InvalidBundle)?;
+
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .unwrap();
+        let block_args = BlockArgs::new(&mut connection, block_id)
+            .await
+            .map_err(|err| block_args_error(METHOD_NAME, err))?;
+        drop(connection);
+
+        let txs = calls
+            .into_iter()
+            .map(|call| L2Tx::from_request(call.into(), self.state.api_config.max_tx_size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let results = self
+            .state
+            .tx_sender
+            .simulate_bundle(block_args, txs, block_overrides)
+            .await
+            .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
+
+        let mut simulated_calls = Vec::with_capacity(results.len());
+        for (call_index, result) in results.into_iter().enumerate() {
+            let (success, return_data, revert_reason, halt_reason) = match result.result {
+                ExecutionResult::Success { output } => (true, output, None, None),
+                ExecutionResult::Revert { output } => {
+                    (false, output.encoded_data(), Some(output.to_string()), None)
+                }
+                // A halted call is always the last one in `results`.
+                ExecutionResult::Halt { reason } => (false, vec![], None, Some(reason.to_string())),
+            };
+            let logs = result
+                .logs
+                .events
+                .into_iter()
+                .enumerate()
+                .map(|(log_index, event)| Log {
+                    address: event.address,
+                    topics: event.indexed_topics,
+                    data: event.value.into(),
+                    block_hash: None,
+                    block_number: None,
+                    l1_batch_number: None,
+                    transaction_hash: None,
+                    transaction_index: Some((call_index as u64).into()),
+                    log_index: Some(log_index.into()),
+                    transaction_log_index: Some(log_index.into()),
+                    log_type: None,
+                    removed: None,
+                })
+                .collect();
+
+            simulated_calls.push(SimulatedCall {
+                success,
+                return_data: return_data.into(),
+                revert_reason,
+                halt_reason,
+                gas_used: result.statistics.gas_used.into(),
+                logs,
+                storage_diffs: storage_diffs(&result.logs.storage_logs),
+            });
+        }
+
+        let block_diff = self
+            .state
+            .last_sealed_miniblock
+            .diff_with_block_args(&block_args);
+        method_latency.observe(block_diff);
+        Ok(simulated_calls)
+    }
+
     #[tracing::instrument(skip(self, request))]
     pub async fn estimate_l1_to_l2_gas_impl(
         &self,
@@ -101,12 +220,16 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .try_into()
             .map_err(Web3Error::SerializationError)?;
 
-        let fee = self.estimate_fee(tx.into()).await?;
+        let fee = self.estimate_fee(tx.into(), None).await?;
         method_latency.observe();
         Ok(fee.gas_limit)
     }
 
-    async fn estimate_fee(&self, tx: Transaction) -> Result<Fee, Web3Error> {
+    async fn estimate_fee(
+        &self,
+        tx: Transaction,
+        state_override: Option<StateOverride>,
+    ) -> Result<Fee, Web3Error> {
         let scale_factor = self.state.api_config.estimate_gas_scale_factor;
         let acceptable_overestimation =
             self.state.api_config.estimate_gas_acceptable_overestimation;
@@ -114,7 +237,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation, state_override)
             .await
             .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
 
@@ -492,12 +615,20 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         const METHOD_NAME: &str = "get_raw_block_transactions";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let transactions = self
+        let mut connection = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        ensure_not_pruned(
+            &mut connection,
+            PrunedData::Transactions,
+            block_number,
+            METHOD_NAME,
+        )
+        .await?;
+        let transactions = connection
             .transactions_web3_dal()
             .get_raw_miniblock_transactions(block_number)
             .await
@@ -657,14 +788,22 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
             .collect();
 
-        let storage_proof = self
+        let proofs = self
             .state
             .tree_api
             .as_ref()
             .ok_or(Web3Error::TreeApiUnavailable)?
             .get_proofs(l1_batch_number, hashed_keys)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| {
+                if err.downcast_ref::<PrunedL1BatchError>().is_some() {
+                    Web3Error::PrunedL1Batch(l1_batch_number)
+                } else {
+                    internal_error(METHOD_NAME, err)
+                }
+            })?;
+        let storage_proof = proofs
+            .entries
             .into_iter()
             .zip(keys)
             .map(|(proof, key)| StorageProof {
@@ -677,7 +816,117 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
 
         Ok(Proof {
             address,
+            storage_hash: proofs.root_hash,
             storage_proof,
         })
     }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_pruned_ranges_impl(&self) -> Result<Vec<PrunedRange>, Web3Error> {
+        const METHOD_NAME: &str = "get_pruned_ranges";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let ranges = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .unwrap()
+            .pruning_dal()
+            .get_pruned_ranges()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err));
+
+        method_latency.observe();
+        ranges
+    }
+}
+
+/// Returns storage slots modified by a call, taking rolled back writes into account.
+fn storage_diffs(storage_logs: &[StorageLogQuery]) -> Vec<StorageDiff> {
+    let mut values = BTreeMap::new();
+    for log in storage_logs.iter().filter(|log| log.log_query.rw_flag) {
+        let query = &log.log_query;
+        let value = if query.rollback {
+            query.read_value
+        } else {
+            query.written_value
+        };
+        values
+            .entry((query.address, query.key))
+            .and_modify(|(_, after)| *after = value)
+            .or_insert((query.read_value, value));
+    }
+
+    values
+        .into_iter()
+        .filter(|(_, (before, after))| before != after)
+        .map(|((address, key), (before, after))| StorageDiff {
+            address,
+            key: u256_to_h256(key),
+            before: u256_to_h256(before),
+            after: u256_to_h256(after),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{LogQuery, StorageLogQueryType, Timestamp};
+
+    use super::*;
+
+    fn write_query(
+        address: Address,
+        key: u64,
+        values: (u64, u64),
+        rollback: bool,
+    ) -> StorageLogQuery {
+        StorageLogQuery {
+            log_query: LogQuery {
+                timestamp: Timestamp(0),
+                tx_number_in_block: 0,
+                aux_byte: 0,
+                shard_id: 0,
+                address,
+                key: key.into(),
+                read_value: values.0.into(),
+                written_value: values.1.into(),
+                rw_flag: true,
+                rollback,
+                is_service: false,
+            },
+            log_type: StorageLogQueryType::RepeatedWrite,
+        }
+    }
+
+    #[test]
+    fn storage_diffs_with_rollbacks() {
+        let address = Address::repeat_byte(1);
+        let other_address = Address::repeat_byte(2);
+        let mut read_query = write_query(address, 3, (7, 0), false);
+        read_query.log_query.rw_flag = false;
+        let storage_logs = [
+            write_query(address, 1, (1, 2), false),
+            write_query(address, 1, (2, 3), false),
+            // Write rolled back by a reverted subcall.
+            write_query(address, 2, (0, 5), false),
+            write_query(address, 2, (0, 5), true),
+            // Write restoring the original value.
+            write_query(other_address, 1, (4, 0), false),
+            write_query(other_address, 1, (0, 4), false),
+            read_query,
+        ];
+
+        let diffs = storage_diffs(&storage_logs);
+        assert_eq!(
+            diffs,
+            [StorageDiff {
+                address,
+                key: H256::from_low_u64_be(1),
+                before: H256::from_low_u64_be(1),
+                after: H256::from_low_u64_be(3),
+            }]
+        );
+    }
 }
@@ -14,7 +14,7 @@ use vise::GaugeGuard;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    api::{self, BlockId, BlockNumber, GetLogsFilter},
+    api::{self, BlockId, BlockNumber, GetLogsFilter, PrunedData},
     block::unpack_block_upgrade_info,
     l2::L2Tx,
     transaction_request::CallRequest,
@@ -34,8 +34,8 @@ use crate::{
         tree::TreeApiHttpClient,
         tx_sender::TxSender,
         web3::{
-            backend_jsonrpc::error::internal_error, namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
-            resolve_block, TypedFilter,
+            backend_jsonrpc::error::internal_error, ensure_not_pruned,
+            namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT, resolve_block, TypedFilter,
         },
     },
     sync_layer::SyncState,
@@ -306,11 +306,20 @@ impl<E> RpcState<E> {
     pub(crate) async fn set_nonce_for_call_request(
         &self,
         call_request: &mut CallRequest,
+        state_override: Option<&api::StateOverride>,
     ) -> Result<(), Web3Error> {
         const METHOD_NAME: &str = "set_nonce_for_call_request";
 
         if call_request.nonce.is_none() {
             let from = call_request.from.unwrap_or_default();
+            let overridden_nonce = state_override
+                .and_then(|state_override| state_override.get(&from))
+                .and_then(|account| account.nonce);
+            if let Some(nonce) = overridden_nonce {
+                call_request.nonce = Some(nonce);
+                return Ok(());
+            }
+
             let block_id = api::BlockId::Number(api::BlockNumber::Latest);
             let mut connection = self
                 .connection_pool
@@ -413,6 +422,13 @@ impl<E> RpcState<E> {
         if to_miniblock_number < from_miniblock_number {
             return Ok(vec![]);
         }
+        ensure_not_pruned(
+            &mut conn,
+            PrunedData::Events,
+            MiniblockNumber(from_miniblock_number),
+            METHOD_NAME,
+        )
+        .await?;
 
         let block_filter = Filter {
             from_block: Some(from_miniblock_number.into()),
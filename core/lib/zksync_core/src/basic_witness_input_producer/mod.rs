@@ -4,10 +4,14 @@ use anyhow::Context;
 use async_trait::async_trait;
 use multivm::interface::{L2BlockEnv, VmInterface};
 use tokio::{runtime::Handle, task::JoinHandle};
+use zksync_config::configs::BasicWitnessInputProducerConfig;
 use zksync_dal::{basic_witness_input_producer_dal::JOB_MAX_ATTEMPT, ConnectionPool};
-use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_object_store::{ObjectStore, ObjectStoreFactory, StoredObject};
 use zksync_queued_job_processor::JobProcessor;
-use zksync_types::{witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId};
+use zksync_state::ContentAddressedCache;
+use zksync_types::{
+    witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId, ProtocolVersionId,
+};
 
 use self::{
     metrics::METRICS,
@@ -17,6 +21,8 @@ use self::{
 mod metrics;
 mod vm_interactions;
 
+type WitnessInputCache = ContentAddressedCache<(L1BatchNumber, ProtocolVersionId)>;
+
 /// Component that extracts all data (from DB) necessary to run a Basic Witness Generator.
 /// Does this by rerunning an entire L1Batch and extracting information from both the VM run and DB.
 /// This component will upload Witness Inputs to the object store.
@@ -27,6 +33,8 @@ pub struct BasicWitnessInputProducer {
     connection_pool: ConnectionPool,
     l2_chain_id: L2ChainId,
     object_store: Arc<dyn ObjectStore>,
+    /// Witness inputs produced by this instance, so that retried jobs don't re-execute the batch.
+    witness_input_cache: WitnessInputCache,
 }
 
 impl BasicWitnessInputProducer {
@@ -34,11 +42,16 @@ impl BasicWitnessInputProducer {
         connection_pool: ConnectionPool,
         store_factory: &ObjectStoreFactory,
         l2_chain_id: L2ChainId,
+        config: &BasicWitnessInputProducerConfig,
     ) -> anyhow::Result<Self> {
         Ok(BasicWitnessInputProducer {
             connection_pool,
             object_store: store_factory.create_store().await.into(),
             l2_chain_id,
+            witness_input_cache: ContentAddressedCache::new(
+                "basic_witness_inputs",
+                config.witness_input_cache_capacity(),
+            ),
         })
     }
 
@@ -48,11 +61,34 @@ impl BasicWitnessInputProducer {
         started_at: Instant,
         connection_pool: ConnectionPool,
         l2_chain_id: L2ChainId,
+        witness_input_cache: WitnessInputCache,
     ) -> anyhow::Result<WitnessBlockState> {
         let mut connection = rt_handle
             .block_on(connection_pool.access_storage())
             .context("failed to get connection for BasicWitnessInputProducer")?;
 
+        let protocol_version = rt_handle
+            .block_on(connection.blocks_dal().get_l1_batch_header(l1_batch_number))?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not in the storage"))?
+            .protocol_version;
+        // Batches without a protocol version (e.g., ones created before versions were tracked) are not cached.
+        let cache_key = protocol_version.map(|version| (l1_batch_number, version));
+        let cached_bytes = cache_key.and_then(|key| witness_input_cache.get(&key));
+        if let (Some(cache_key), Some(bytes)) = (cache_key, cached_bytes) {
+            match WitnessBlockState::deserialize(bytes.to_vec()) {
+                Ok(witness_block_state) => {
+                    tracing::info!(
+                        "Reusing cached witness inputs for l1_batch: {l1_batch_number:?}"
+                    );
+                    return Ok(witness_block_state);
+                }
+                Err(err) => {
+                    tracing::warn!("Failed deserializing cached witness inputs: {err}");
+                    witness_input_cache.remove(&cache_key);
+                }
+            }
+        }
+
         let miniblocks_execution_data = rt_handle.block_on(
             connection
                 .transactions_dal()
@@ -104,6 +140,12 @@ impl BasicWitnessInputProducer {
         );
 
         let witness_block_state = (*storage_view).borrow().witness_block_state();
+        if let Some(cache_key) = cache_key {
+            match witness_block_state.serialize() {
+                Ok(bytes) => witness_input_cache.insert(cache_key, bytes),
+                Err(err) => tracing::warn!("Failed serializing witness inputs: {err}"),
+            }
+        }
         Ok(witness_block_state)
     }
 }
@@ -149,6 +191,7 @@ impl JobProcessor for BasicWitnessInputProducer {
     ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
         let l2_chain_id = self.l2_chain_id;
         let connection_pool = self.connection_pool.clone();
+        let witness_input_cache = self.witness_input_cache.clone();
         tokio::task::spawn_blocking(move || {
             let rt_handle = Handle::current();
             Self::process_job_impl(
@@ -157,6 +200,7 @@ impl JobProcessor for BasicWitnessInputProducer {
                 started_at,
                 connection_pool.clone(),
                 l2_chain_id,
+                witness_input_cache,
             )
         })
     }
@@ -0,0 +1,22 @@
+//! Metrics for the Postgres pruner.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, LabeledFamily, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_db_pruner")]
+pub(super) struct DbPrunerMetrics {
+    /// Latency of pruning a single chunk of L1 batches.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["data"])]
+    pub chunk_latency: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of rows removed by the pruner.
+    #[metrics(labels = ["data"])]
+    pub removed_rows: LabeledFamily<&'static str, Counter>,
+    /// Last pruned L1 batch.
+    #[metrics(labels = ["data"])]
+    pub last_pruned_l1_batch: LabeledFamily<&'static str, Gauge<u64>>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DbPrunerMetrics> = vise::Global::new();
@@ -0,0 +1,254 @@
+//! Postgres pruning component.
+//!
+//! Periodically removes data that is no longer needed by the node from Postgres. Data is separated
+//! into classes ([`PrunedData`]), each with its own retention and its own persistent cursor stored
+//! in the `pruning_log` table. Data is pruned only for L1 batches that are both executed on L1
+//! and processed by the Merkle tree, so the state keeper and the tree never observe missing data
+//! they rely on. The API checks pruning cursors and returns an error for pruned blocks.
+//! Note that rebuilding the Merkle tree or the state keeper cache from scratch requires
+//! unpruned storage logs.
+
+use std::{cmp, ops};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::PruningConfig;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{api::PrunedData, L1BatchNumber};
+
+use self::metrics::METRICS;
+
+mod metrics;
+
+/// Maximum number of storage logs removed by a single query.
+const STORAGE_LOGS_DELETE_BATCH_SIZE: u32 = 10_000;
+
+/// Component removing outdated data from Postgres in bounded chunks.
+#[derive(Debug)]
+pub struct DbPruner {
+    pool: ConnectionPool,
+    /// Pool for the prover database, which stores FRI prover jobs and witness inputs.
+    prover_pool: ConnectionPool,
+    config: PruningConfig,
+}
+
+impl DbPruner {
+    pub fn new(pool: ConnectionPool, prover_pool: ConnectionPool, config: PruningConfig) -> Self {
+        Self {
+            pool,
+            prover_pool,
+            config,
+        }
+    }
+
+    fn retention(&self, data: PrunedData) -> Option<u32> {
+        match data {
+            PrunedData::StorageLogs => self.config.storage_logs_retention_l1_batches,
+            PrunedData::Events => self.config.events_retention_l1_batches,
+            PrunedData::Transactions => self.config.transactions_retention_l1_batches,
+            PrunedData::ProverJobs => self.config.prover_jobs_retention_l1_batches,
+        }
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, DB pruner is shutting down");
+                return Ok(());
+            }
+            self.prune(&stop_receiver).await?;
+            tokio::time::sleep(self.config.poll_interval()).await;
+        }
+    }
+
+    async fn prune(&self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("db_pruner").await?;
+        let Some(last_prunable_l1_batch) = Self::last_prunable_l1_batch(&mut storage).await? else {
+            return Ok(());
+        };
+
+        for data in PrunedData::ALL {
+            let Some(retention) = self.retention(data) else {
+                continue;
+            };
+            let Some(target_l1_batch) = last_prunable_l1_batch.0.checked_sub(retention) else {
+                continue;
+            };
+            let last_pruned_l1_batch = storage
+                .pruning_dal()
+                .get_last_pruned_l1_batch(data)
+                .await
+                .context("get_last_pruned_l1_batch()")?;
+
+            let chunks = pruning_chunks(
+                last_pruned_l1_batch,
+                L1BatchNumber(target_l1_batch),
+                self.config.chunk_size,
+            );
+            for chunk in chunks {
+                if *stop_receiver.borrow() {
+                    return Ok(());
+                }
+                self.prune_chunk(&mut storage, data, chunk).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the last L1 batch that is executed on L1 and processed by the Merkle tree.
+    async fn last_prunable_l1_batch(
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        let Some(last_executed_l1_batch) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_executed_on_eth()")?
+        else {
+            return Ok(None);
+        };
+        let last_l1_batch_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .context("get_last_l1_batch_number_with_metadata()")?;
+        Ok(Some(cmp::min(
+            last_executed_l1_batch,
+            last_l1_batch_with_metadata,
+        )))
+    }
+
+    async fn prune_chunk(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        data: PrunedData,
+        l1_batches: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<()> {
+        let latency = METRICS.chunk_latency[&data.as_str()].start();
+        let (first_miniblock, _) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(*l1_batches.start())
+            .await
+            .context("get_miniblock_range_of_l1_batch()")?
+            .with_context(|| format!("L1 batch #{} has no miniblocks", l1_batches.start()))?;
+        let (_, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(*l1_batches.end())
+            .await
+            .context("get_miniblock_range_of_l1_batch()")?
+            .with_context(|| format!("L1 batch #{} has no miniblocks", l1_batches.end()))?;
+        let miniblocks = first_miniblock..=last_miniblock;
+
+        let removed_rows = match data {
+            PrunedData::StorageLogs => {
+                // The cursor is advanced before removing logs, so that the API never serves state
+                // for miniblocks with partially removed logs. Logs left after an interruption
+                // are overwritten ones, so they don't influence the retained state.
+                storage
+                    .pruning_dal()
+                    .set_pruned(data, *l1_batches.end(), last_miniblock)
+                    .await
+                    .context("set_pruned()")?;
+                let mut removed_rows = 0;
+                loop {
+                    let removed_batch = storage
+                        .pruning_dal()
+                        .prune_storage_logs(miniblocks.clone(), STORAGE_LOGS_DELETE_BATCH_SIZE)
+                        .await
+                        .context("prune_storage_logs()")?;
+                    removed_rows += removed_batch;
+                    if removed_batch < u64::from(STORAGE_LOGS_DELETE_BATCH_SIZE) {
+                        break removed_rows;
+                    }
+                }
+            }
+            PrunedData::Events | PrunedData::Transactions => {
+                let mut transaction = storage.start_transaction().await?;
+                let mut pruning_dal = transaction.pruning_dal();
+                let removed_rows = if data == PrunedData::Events {
+                    pruning_dal.prune_events(miniblocks).await
+                } else {
+                    pruning_dal.prune_transactions(miniblocks).await
+                };
+                let removed_rows =
+                    removed_rows.with_context(|| format!("failed pruning {data:?}"))?;
+                pruning_dal
+                    .set_pruned(data, *l1_batches.end(), last_miniblock)
+                    .await
+                    .context("set_pruned()")?;
+                transaction.commit().await?;
+                removed_rows
+            }
+            PrunedData::ProverJobs => {
+                // Prover jobs may reside in a separate database, so they cannot be removed
+                // atomically with advancing the cursor. Removal is idempotent, so it's safe
+                // to repeat it if the pruner is interrupted before advancing the cursor.
+                let mut prover_storage =
+                    self.prover_pool.access_storage_tagged("db_pruner").await?;
+                let removed_rows = prover_storage
+                    .pruning_dal()
+                    .prune_prover_jobs(l1_batches.clone())
+                    .await
+                    .context("prune_prover_jobs()")?;
+                storage
+                    .pruning_dal()
+                    .set_pruned(data, *l1_batches.end(), last_miniblock)
+                    .await
+                    .context("set_pruned()")?;
+                removed_rows
+            }
+        };
+        let latency = latency.observe();
+
+        tracing::info!(
+            "Pruned {removed_rows} rows of {data:?} for L1 batches {l1_batches:?} in {latency:?}"
+        );
+        METRICS.removed_rows[&data.as_str()].inc_by(removed_rows);
+        METRICS.last_pruned_l1_batch[&data.as_str()].set(l1_batches.end().0.into());
+        Ok(())
+    }
+}
+
+/// Splits L1 batches after `last_pruned_l1_batch` up to and including `target_l1_batch`
+/// into chunks of at most `chunk_size` batches.
+fn pruning_chunks(
+    last_pruned_l1_batch: Option<L1BatchNumber>,
+    target_l1_batch: L1BatchNumber,
+    chunk_size: u32,
+) -> impl Iterator<Item = ops::RangeInclusive<L1BatchNumber>> {
+    let first_l1_batch = last_pruned_l1_batch.map_or(0, |number| number.0 + 1);
+    let chunk_size = chunk_size.max(1);
+    (first_l1_batch..=target_l1_batch.0)
+        .step_by(chunk_size as usize)
+        .map(move |start| {
+            let end = cmp::min(start + chunk_size - 1, target_l1_batch.0);
+            L1BatchNumber(start)..=L1BatchNumber(end)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitting_l1_batches_into_chunks() {
+        let chunks: Vec<_> = pruning_chunks(None, L1BatchNumber(4), 2).collect();
+        assert_eq!(
+            chunks,
+            [
+                L1BatchNumber(0)..=L1BatchNumber(1),
+                L1BatchNumber(2)..=L1BatchNumber(3),
+                L1BatchNumber(4)..=L1BatchNumber(4),
+            ]
+        );
+
+        let chunks: Vec<_> =
+            pruning_chunks(Some(L1BatchNumber(4)), L1BatchNumber(10), 10).collect();
+        assert_eq!(chunks, [L1BatchNumber(5)..=L1BatchNumber(10)]);
+
+        let chunks: Vec<_> = pruning_chunks(Some(L1BatchNumber(4)), L1BatchNumber(4), 10).collect();
+        assert!(chunks.is_empty());
+        let chunks: Vec<_> = pruning_chunks(Some(L1BatchNumber(5)), L1BatchNumber(4), 10).collect();
+        assert!(chunks.is_empty());
+    }
+}
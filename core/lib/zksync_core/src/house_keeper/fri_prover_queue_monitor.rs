@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use zksync_config::configs::fri_prover_group::FriProverGroupConfig;
 use zksync_dal::ConnectionPool;
 use zksync_prover_utils::periodic_job::PeriodicJob;
+use zksync_types::proofs::FriProverJobPriority;
 
 #[derive(Debug)]
 pub struct FriProverStatsReporter {
@@ -74,6 +75,33 @@ impl PeriodicJob for FriProverStatsReporter {
             );
         }
 
+        let queued_jobs_by_priority = conn
+            .fri_prover_jobs_dal()
+            .get_queued_jobs_count_by_priority()
+            .await;
+        for priority in FriProverJobPriority::ALL {
+            let queued = queued_jobs_by_priority.get(&priority).copied().unwrap_or(0);
+            metrics::gauge!(
+              "fri_prover.prover.jobs_by_priority",
+              queued as f64,
+              "type" => "queued",
+              "priority" => priority.as_str(),
+            );
+        }
+
+        let prioritized_batches = conn.fri_prover_jobs_dal().get_batch_priorities().await?;
+        for priority in FriProverJobPriority::ALL {
+            let batch_count = prioritized_batches
+                .iter()
+                .filter(|batch| batch.priority == priority)
+                .count();
+            metrics::gauge!(
+              "fri_prover.prioritized_batches",
+              batch_count as f64,
+              "priority" => priority.as_str(),
+            );
+        }
+
         let lag_by_circuit_type = conn
             .fri_prover_jobs_dal()
             .min_unproved_l1_batch_number()
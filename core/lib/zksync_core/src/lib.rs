@@ -1,6 +1,11 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
-use std::{net::Ipv4Addr, str::FromStr, sync::Arc, time::Instant};
+use std::{
+    net::Ipv4Addr,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use futures::channel::oneshot;
@@ -20,6 +25,7 @@ use zksync_config::{
         },
         contracts::ProverAtGenesis,
         database::MerkleTreeMode,
+        BasicWitnessInputProducerConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, PostgresConfig,
 };
@@ -43,7 +49,7 @@ use zksync_types::{
 use crate::{
     api_server::{
         contract_verification,
-        execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
+        execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter, WarmVmState},
         healthcheck::HealthCheckHandle,
         tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
         web3,
@@ -51,6 +57,7 @@ use crate::{
     },
     basic_witness_input_producer::BasicWitnessInputProducer,
     data_fetchers::run_data_fetchers,
+    db_pruner::DbPruner,
     eth_sender::{Aggregator, EthTxAggregator, EthTxManager},
     eth_watch::start_eth_watch,
     house_keeper::{
@@ -80,6 +87,7 @@ pub mod block_reverter;
 mod consensus;
 pub mod consistency_checker;
 pub mod data_fetchers;
+pub mod db_pruner;
 pub mod eth_sender;
 pub mod eth_watch;
 pub mod gas_tracker;
@@ -93,6 +101,7 @@ pub mod reorg_detector;
 pub mod state_keeper;
 pub mod sync_layer;
 pub mod temp_config_store;
+pub mod vm_replay;
 
 /// Inserts the initial information about zkSync tokens into the database.
 pub async fn genesis_init(
@@ -240,6 +249,8 @@ pub enum Component {
     Housekeeper,
     /// Component for exposing APIs to prover for providing proof generation data and accepting proofs.
     ProofDataHandler,
+    /// Component removing outdated data from Postgres.
+    DbPruner,
 }
 
 #[derive(Debug)]
@@ -280,11 +291,17 @@ impl FromStr for Components {
             "eth_tx_aggregator" => Ok(Components(vec![Component::EthTxAggregator])),
             "eth_tx_manager" => Ok(Components(vec![Component::EthTxManager])),
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
+            "db_pruner" => Ok(Components(vec![Component::DbPruner])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
 }
 
+/// Maximum replication lag of a read replica used by the API servers if not specified in the config.
+const DEFAULT_MAX_REPLICATION_LAG_SEC: u32 = 10;
+/// Default size of the master DB pool used as a fallback for read replicas by the API servers.
+const DEFAULT_REPLICA_FALLBACK_POOL_SIZE: u32 = 10;
+
 pub async fn initialize_components(
     configs: &TempConfigStore,
     components: Vec<Component>,
@@ -305,12 +322,35 @@ pub async fn initialize_components(
         .build()
         .await
         .context("failed to build connection_pool")?;
-    let replica_connection_pool =
+    let replica_connection_pool = if postgres_config.read_replica_urls.is_empty() {
         ConnectionPool::builder(postgres_config.replica_url()?, pool_size)
             .set_statement_timeout(statement_timeout)
             .build()
             .await
-            .context("failed to build replica_connection_pool")?;
+            .context("failed to build replica_connection_pool")?
+    } else {
+        // Reads are routed to replicas with the same lag limit as used by the replication lag circuit breaker.
+        let max_replication_lag = configs
+            .circuit_breaker_config
+            .as_ref()
+            .and_then(|config| config.replication_lag_limit_sec)
+            .unwrap_or(DEFAULT_MAX_REPLICATION_LAG_SEC);
+        let read_replica_urls = postgres_config.read_replica_urls.iter().map(String::as_str);
+        let fallback_pool_size = postgres_config
+            .read_replica_fallback_pool_size
+            .unwrap_or(DEFAULT_REPLICA_FALLBACK_POOL_SIZE)
+            .min(pool_size);
+        ConnectionPool::builder(postgres_config.master_url()?, pool_size)
+            .set_statement_timeout(statement_timeout)
+            .set_read_replicas(
+                read_replica_urls,
+                Duration::from_secs(max_replication_lag.into()),
+            )
+            .set_fallback_max_size(fallback_pool_size)
+            .build()
+            .await
+            .context("failed to build replica_connection_pool")?
+    };
 
     let mut healthchecks: Vec<Box<dyn CheckHealth>> = Vec::new();
     let contracts_config = configs
@@ -394,6 +434,20 @@ pub async fn initialize_components(
         // terminate immediately if storage caches are dropped, which will lead to the (unexpected)
         // program termination.
         let mut storage_caches = None;
+        // The warm VM state is shared among HTTP and WS APIs, so that it's synced with Postgres only once.
+        let warm_vm_state =
+            if components.contains(&Component::HttpApi) || components.contains(&Component::WsApi) {
+                build_warm_vm_state(
+                    configs,
+                    &replica_connection_pool,
+                    &stop_receiver,
+                    &mut task_futures,
+                )
+                .await
+                .context("build_warm_vm_state()")?
+            } else {
+                None
+            };
 
         if components.contains(&Component::HttpApi) {
             storage_caches = Some(
@@ -420,6 +474,7 @@ pub async fn initialize_components(
                 state_keeper_config.save_call_traces,
                 components.contains(&Component::ApiTranslator),
                 storage_caches.clone().unwrap(),
+                warm_vm_state.clone(),
             )
             .await
             .context("run_http_api")?;
@@ -458,6 +513,7 @@ pub async fn initialize_components(
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 storage_caches,
+                warm_vm_state,
                 components.contains(&Component::ApiTranslator),
             )
             .await
@@ -480,6 +536,7 @@ pub async fn initialize_components(
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
                 api_config.contract_verification.clone(),
+                network_config.zksync_network_id,
                 stop_receiver.clone(),
             ));
             let elapsed = started_at.elapsed();
@@ -657,11 +714,16 @@ pub async fn initialize_components(
             .await
             .context("failed to build singleton connection_pool")?;
         let network_config = configs.network_config.clone().context("network_config")?;
+        let basic_witness_input_producer_config = configs
+            .basic_witness_input_producer_config
+            .clone()
+            .unwrap_or_default();
         add_basic_witness_input_producer_to_task_futures(
             &mut task_futures,
             &singleton_connection_pool,
             &store_factory,
             network_config.zksync_network_id,
+            basic_witness_input_producer_config,
             stop_receiver.clone(),
         )
         .await
@@ -690,6 +752,16 @@ pub async fn initialize_components(
         )));
     }
 
+    if components.contains(&Component::DbPruner) {
+        let pruning_config = configs.pruning_config.clone().context("pruning_config")?;
+        let pruner_prover_pool = ConnectionPool::singleton(postgres_config.prover_url()?)
+            .build()
+            .await
+            .context("failed to build pruner_prover_pool")?;
+        let pruner = DbPruner::new(connection_pool.clone(), pruner_prover_pool, pruning_config);
+        task_futures.push(tokio::spawn(pruner.run(stop_receiver.clone())));
+    }
+
     // Run healthcheck server for all components.
     healthchecks.push(Box::new(ConnectionPoolHealthCheck::new(
         replica_connection_pool,
@@ -699,8 +771,11 @@ pub async fn initialize_components(
         .health_check_config
         .clone()
         .context("health_check_config")?;
-    let health_check_handle =
-        HealthCheckHandle::spawn_server(healtcheck_api_config.bind_addr(), healthchecks);
+    let health_check_handle = HealthCheckHandle::spawn_server(
+        healtcheck_api_config.bind_addr(),
+        healthchecks,
+        (&healtcheck_api_config).into(),
+    );
 
     if let Some(task) = gas_adjuster.run_if_initialized(stop_receiver.clone()) {
         task_futures.push(task);
@@ -894,6 +969,7 @@ async fn add_basic_witness_input_producer_to_task_futures(
     connection_pool: &ConnectionPool,
     store_factory: &ObjectStoreFactory,
     l2_chain_id: L2ChainId,
+    config: BasicWitnessInputProducerConfig,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     // Witness Generator won't be spawned with `ZKSYNC_LOCAL_SETUP` running.
@@ -903,8 +979,13 @@ async fn add_basic_witness_input_producer_to_task_futures(
     }
     let started_at = Instant::now();
     tracing::info!("initializing BasicWitnessInputProducer");
-    let producer =
-        BasicWitnessInputProducer::new(connection_pool.clone(), store_factory, l2_chain_id).await?;
+    let producer = BasicWitnessInputProducer::new(
+        connection_pool.clone(),
+        store_factory,
+        l2_chain_id,
+        &config,
+    )
+    .await?;
     task_futures.push(tokio::spawn(producer.run(stop_receiver, None)));
     tracing::info!(
         "Initialized BasicWitnessInputProducer in {:?}",
@@ -1071,6 +1152,32 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+async fn build_warm_vm_state(
+    configs: &TempConfigStore,
+    replica_connection_pool: &ConnectionPool,
+    stop_receiver: &watch::Receiver<bool>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+) -> anyhow::Result<Option<WarmVmState>> {
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    let rpc_config = configs
+        .web3_json_rpc_config
+        .as_ref()
+        .context("web3_json_rpc_config")?;
+    let Some(db_path) = rpc_config.warm_vm_state_db_path() else {
+        return Ok(None);
+    };
+    tracing::info!("Initializing warm VM state at `{db_path}`");
+    let (warm_vm_state, updater) = WarmVmState::new(
+        db_path.into(),
+        replica_connection_pool.clone(),
+        POLL_INTERVAL,
+    )
+    .await?;
+    task_futures.push(tokio::spawn(updater.run(stop_receiver.clone())));
+    Ok(Some(warm_vm_state))
+}
+
 async fn build_tx_sender<G: L1GasPriceProvider>(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
@@ -1079,6 +1186,7 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
     master_pool: ConnectionPool,
     l1_gas_price_provider: Arc<G>,
     storage_caches: PostgresStorageCaches,
+    warm_vm_state: Option<WarmVmState>,
 ) -> (TxSender<G>, VmConcurrencyBarrier) {
     let mut tx_sender_builder = TxSenderBuilder::new(tx_sender_config.clone(), replica_pool)
         .with_main_connection_pool(master_pool)
@@ -1088,6 +1196,9 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
     if let Some(transactions_per_sec_limit) = web3_json_config.transactions_per_sec_limit {
         tx_sender_builder = tx_sender_builder.with_rate_limiter(transactions_per_sec_limit);
     };
+    if let Some(warm_vm_state) = warm_vm_state {
+        tx_sender_builder = tx_sender_builder.with_warm_vm_state(warm_vm_state);
+    }
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
@@ -1117,6 +1228,7 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     with_debug_namespace: bool,
     with_logs_request_translator_enabled: bool,
     storage_caches: PostgresStorageCaches,
+    warm_vm_state: Option<WarmVmState>,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1126,6 +1238,7 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         master_connection_pool,
         gas_adjuster,
         storage_caches,
+        warm_vm_state,
     )
     .await;
 
@@ -1169,6 +1282,7 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
+    warm_vm_state: Option<WarmVmState>,
     with_logs_request_translator_enabled: bool,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
@@ -1179,6 +1293,7 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         master_connection_pool,
         gas_adjuster,
         storage_caches,
+        warm_vm_state,
     )
     .await;
     let last_miniblock_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
@@ -0,0 +1,378 @@
+//! Merkle tree checkpoints. A checkpoint is a consistent RocksDB snapshot of the tree truncated
+//! to a certain L1 batch. Checkpoints are uploaded to an [`ObjectStore`] file by file, together
+//! with a [manifest](TreeCheckpointManifest) describing the checkpoint contents. Restoring a tree
+//! from a checkpoint is much faster than rebuilding it from Postgres.
+//!
+//! Files are uploaded in parts of bounded size, so that neither uploading nor downloading
+//! a checkpoint needs to hold entire (potentially large) SST files in memory.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt},
+};
+use zksync_dal::StorageProcessor;
+use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_object_store::{serialize_using_bincode, Bucket, ObjectStore, StoredObject};
+use zksync_storage::RocksDB;
+use zksync_types::{web3::signing::keccak256, L1BatchNumber, H256};
+
+/// Information about a single file in a Merkle tree checkpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeCheckpointFile {
+    /// File name relative to the checkpoint directory.
+    pub name: String,
+    /// File size in bytes.
+    pub size: u64,
+    /// Keccak-256 digests of the file parts, in the order the parts are stored in the file.
+    pub part_hashes: Vec<H256>,
+}
+
+/// Manifest of a Merkle tree checkpoint stored in an [`ObjectStore`]. The manifest is uploaded
+/// after all checkpoint files, so its presence means that the checkpoint is complete.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeCheckpointManifest {
+    /// Latest L1 batch in the checkpointed tree.
+    pub l1_batch_number: L1BatchNumber,
+    /// Root hash of the tree after `l1_batch_number`.
+    pub root_hash: H256,
+    /// Files comprising the checkpoint.
+    pub files: Vec<TreeCheckpointFile>,
+}
+
+impl StoredObject for TreeCheckpointManifest {
+    const BUCKET: Bucket = Bucket::MerkleTreeCheckpoints;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("checkpoint_l1_batch_{key}_manifest.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+impl TreeCheckpointManifest {
+    fn file_part_key(l1_batch_number: L1BatchNumber, file_name: &str, part: usize) -> String {
+        format!("checkpoint_l1_batch_{l1_batch_number}_{file_name}_part{part}")
+    }
+}
+
+/// Maximum size of a single checkpoint file part uploaded to the object store.
+const FILE_PART_SIZE: u64 = 64 << 20;
+
+/// Creates a checkpoint of the Merkle tree stored at `db_path` for the specified L1 batch
+/// and uploads it to the `object_store`. The tree must not be concurrently used by other components
+/// (e.g., the metadata calculator) since RocksDB is opened in the read-write mode.
+pub async fn create_tree_checkpoint(
+    db_path: &Path,
+    l1_batch_number: L1BatchNumber,
+    object_store: &dyn ObjectStore,
+) -> anyhow::Result<TreeCheckpointManifest> {
+    let checkpoint_path = PathBuf::from(format!(
+        "{}_checkpoint_{l1_batch_number}",
+        db_path.display()
+    ));
+    anyhow::ensure!(
+        !checkpoint_path.exists(),
+        "Checkpoint directory `{}` already exists; remove it before creating a checkpoint",
+        checkpoint_path.display()
+    );
+
+    let db_path = db_path.to_owned();
+    let tree_checkpoint_path = checkpoint_path.clone();
+    let root_hash = tokio::task::spawn_blocking(move || {
+        let tree = ZkSyncTree::new_lightweight(RocksDB::new(&db_path));
+        anyhow::ensure!(
+            l1_batch_number < tree.next_l1_batch_number(),
+            "L1 batch #{l1_batch_number} is not processed by the tree at `{}` yet",
+            db_path.display()
+        );
+        tree.create_checkpoint(&tree_checkpoint_path, l1_batch_number)
+            .context("failed creating RocksDB checkpoint")
+    })
+    .await
+    .context("panicked creating RocksDB checkpoint")?;
+
+    let upload_result = match root_hash {
+        Ok(root_hash) => {
+            upload_checkpoint(
+                &checkpoint_path,
+                l1_batch_number,
+                root_hash,
+                object_store,
+                FILE_PART_SIZE,
+            )
+            .await
+        }
+        // The checkpoint directory may be created even if checkpoint creation fails.
+        Err(err) if checkpoint_path.exists() => Err(err),
+        Err(err) => return Err(err),
+    };
+    fs::remove_dir_all(&checkpoint_path)
+        .await
+        .with_context(|| {
+            format!(
+                "failed removing checkpoint directory `{}`",
+                checkpoint_path.display()
+            )
+        })?;
+    upload_result
+}
+
+async fn upload_checkpoint(
+    checkpoint_path: &Path,
+    l1_batch_number: L1BatchNumber,
+    root_hash: H256,
+    object_store: &dyn ObjectStore,
+    part_size: u64,
+) -> anyhow::Result<TreeCheckpointManifest> {
+    let mut files = vec![];
+    let mut dir_entries = fs::read_dir(checkpoint_path).await?;
+    while let Some(entry) = dir_entries.next_entry().await? {
+        let name = entry
+            .file_name()
+            .into_string()
+            .map_err(|name| anyhow::anyhow!("non-UTF8 file name in checkpoint: {name:?}"))?;
+        let file = upload_file(
+            &entry.path(),
+            name,
+            l1_batch_number,
+            object_store,
+            part_size,
+        )
+        .await?;
+        tracing::debug!("Uploaded checkpoint file {file:?}");
+        files.push(file);
+    }
+    files.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let manifest = TreeCheckpointManifest {
+        l1_batch_number,
+        root_hash,
+        files,
+    };
+    object_store
+        .put(l1_batch_number, &manifest)
+        .await
+        .context("failed uploading checkpoint manifest")?;
+    tracing::info!(
+        "Uploaded Merkle tree checkpoint for L1 batch #{l1_batch_number} consisting of {} files",
+        manifest.files.len()
+    );
+    Ok(manifest)
+}
+
+/// Uploads a single checkpoint file part by part. An empty file is uploaded as a single empty part.
+async fn upload_file(
+    path: &Path,
+    name: String,
+    l1_batch_number: L1BatchNumber,
+    object_store: &dyn ObjectStore,
+    part_size: u64,
+) -> anyhow::Result<TreeCheckpointFile> {
+    let mut reader = fs::File::open(path)
+        .await
+        .with_context(|| format!("failed opening checkpoint file `{name}`"))?;
+    let mut size = 0;
+    let mut part_hashes = vec![];
+    loop {
+        let mut part = vec![];
+        (&mut reader)
+            .take(part_size)
+            .read_to_end(&mut part)
+            .await
+            .with_context(|| format!("failed reading checkpoint file `{name}`"))?;
+        let part_len = part.len() as u64;
+        if part_len == 0 && !part_hashes.is_empty() {
+            break;
+        }
+
+        let key = TreeCheckpointManifest::file_part_key(l1_batch_number, &name, part_hashes.len());
+        part_hashes.push(H256(keccak256(&part)));
+        object_store
+            .put_raw(Bucket::MerkleTreeCheckpoints, &key, part)
+            .await
+            .with_context(|| format!("failed uploading checkpoint file `{name}`"))?;
+        size += part_len;
+        if part_len < part_size {
+            break;
+        }
+    }
+    Ok(TreeCheckpointFile {
+        name,
+        size,
+        part_hashes,
+    })
+}
+
+/// Restores the Merkle tree at `db_path` from a checkpoint for the specified L1 batch previously
+/// uploaded to the `object_store`. `db_path` must not exist.
+///
+/// The root hash of the checkpoint is verified against the root hash of the L1 batch stored
+/// in Postgres both before downloading the checkpoint, and after the tree is restored. Thus,
+/// once this method returns successfully, the metadata calculator can safely resume from the next
+/// L1 batch.
+pub async fn restore_tree_checkpoint(
+    storage: &mut StorageProcessor<'_>,
+    object_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+    db_path: &Path,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !db_path.exists(),
+        "Merkle tree directory `{}` already exists; remove it before restoring a checkpoint",
+        db_path.display()
+    );
+
+    let manifest: TreeCheckpointManifest =
+        object_store.get(l1_batch_number).await.with_context(|| {
+            format!("failed getting checkpoint manifest for L1 batch #{l1_batch_number}")
+        })?;
+    let expected_root_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(l1_batch_number)
+        .await
+        .context("failed getting L1 batch root hash from Postgres")?
+        .with_context(|| {
+            format!("L1 batch #{l1_batch_number} doesn't have a root hash in Postgres")
+        })?;
+    anyhow::ensure!(
+        manifest.root_hash == expected_root_hash,
+        "Root hash mismatch for L1 batch #{l1_batch_number}: checkpoint has {:?}, Postgres has {expected_root_hash:?}",
+        manifest.root_hash
+    );
+
+    fs::create_dir_all(db_path).await?;
+    if let Err(err) = download_checkpoint(&manifest, db_path, object_store).await {
+        fs::remove_dir_all(db_path).await.ok();
+        return Err(err);
+    }
+
+    let tree_db_path = db_path.to_owned();
+    let (next_l1_batch_number, root_hash) = tokio::task::spawn_blocking(move || {
+        let tree = ZkSyncTree::new_lightweight(RocksDB::new(&tree_db_path));
+        (tree.next_l1_batch_number(), tree.root_hash())
+    })
+    .await
+    .context("panicked opening restored Merkle tree")?;
+
+    anyhow::ensure!(
+        next_l1_batch_number == l1_batch_number + 1,
+        "Unexpected next L1 batch for the restored tree: expected {}, got {next_l1_batch_number}",
+        l1_batch_number + 1
+    );
+    anyhow::ensure!(
+        root_hash == expected_root_hash,
+        "Root hash mismatch for the restored tree: expected {expected_root_hash:?}, got {root_hash:?}"
+    );
+    tracing::info!(
+        "Restored Merkle tree at `{}` from checkpoint for L1 batch #{l1_batch_number}",
+        db_path.display()
+    );
+    Ok(())
+}
+
+async fn download_checkpoint(
+    manifest: &TreeCheckpointManifest,
+    db_path: &Path,
+    object_store: &dyn ObjectStore,
+) -> anyhow::Result<()> {
+    for file in &manifest.files {
+        let mut writer = fs::File::create(db_path.join(&file.name))
+            .await
+            .with_context(|| format!("failed creating checkpoint file `{}`", file.name))?;
+        let mut size = 0;
+        for (i, &expected_hash) in file.part_hashes.iter().enumerate() {
+            let key =
+                TreeCheckpointManifest::file_part_key(manifest.l1_batch_number, &file.name, i);
+            let part = object_store
+                .get_raw(Bucket::MerkleTreeCheckpoints, &key)
+                .await
+                .with_context(|| format!("failed downloading checkpoint file `{}`", file.name))?;
+            anyhow::ensure!(
+                H256(keccak256(&part)) == expected_hash,
+                "Checkpoint file `{}` is corrupted (part #{i})",
+                file.name
+            );
+            size += part.len() as u64;
+            writer
+                .write_all(&part)
+                .await
+                .with_context(|| format!("failed writing checkpoint file `{}`", file.name))?;
+        }
+        anyhow::ensure!(
+            size == file.size,
+            "Checkpoint file `{}` is corrupted (expected {} bytes, got {size})",
+            file.name,
+            file.size
+        );
+        writer
+            .sync_all()
+            .await
+            .with_context(|| format!("failed writing checkpoint file `{}`", file.name))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use zksync_object_store::ObjectStoreFactory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn uploading_and_downloading_files_in_parts() {
+        let temp_dir = TempDir::new().expect("failed creating temporary directory");
+        let checkpoint_path = temp_dir.path().join("checkpoint");
+        fs::create_dir(&checkpoint_path).await.unwrap();
+        let contents: Vec<u8> = (0..=u8::MAX).collect();
+        let files = [
+            ("empty", &[][..]),
+            ("short", &contents[..10]),
+            ("aligned", &contents[..64]),
+            ("long", &contents[..]),
+        ];
+        for (name, data) in files {
+            fs::write(checkpoint_path.join(name), data).await.unwrap();
+        }
+
+        let object_store = ObjectStoreFactory::mock().create_store().await;
+        let manifest = upload_checkpoint(
+            &checkpoint_path,
+            L1BatchNumber(1),
+            H256::zero(),
+            &*object_store,
+            32,
+        )
+        .await
+        .unwrap();
+        let part_counts: Vec<_> = manifest
+            .files
+            .iter()
+            .map(|file| (file.name.as_str(), file.size, file.part_hashes.len()))
+            .collect();
+        assert_eq!(
+            part_counts,
+            [
+                ("aligned", 64, 2),
+                ("empty", 0, 1),
+                ("long", 256, 8),
+                ("short", 10, 1)
+            ]
+        );
+
+        let restored_path = temp_dir.path().join("restored");
+        fs::create_dir(&restored_path).await.unwrap();
+        download_checkpoint(&manifest, &restored_path, &*object_store)
+            .await
+            .unwrap();
+        for (name, data) in files {
+            let restored_data = fs::read(restored_path.join(name)).await.unwrap();
+            assert_eq!(restored_data, data, "{name}");
+        }
+    }
+}
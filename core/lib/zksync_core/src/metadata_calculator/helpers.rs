@@ -15,7 +15,8 @@ use zksync_dal::StorageProcessor;
 use zksync_health_check::{Health, HealthStatus};
 use zksync_merkle_tree::{
     domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
-    Key, MerkleTreeColumnFamily, NoVersionError, TreeEntryWithProof, TreeInstruction,
+    Key, MerkleTreeColumnFamily, MerkleTreePruner, MerkleTreePrunerHandle, NoVersionError,
+    RocksDBWrapper, TreeEntryWithProof, TreeInstruction,
 };
 use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries};
 use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
@@ -134,6 +135,14 @@ impl AsyncTree {
         }
     }
 
+    /// Creates a pruner for this tree; see [`ZkSyncTree::pruner()`] for details.
+    pub fn pruner(
+        &self,
+        retained_l1_batches: u64,
+    ) -> (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle) {
+        self.as_ref().pruner(retained_l1_batches)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.as_ref().is_empty()
     }
@@ -198,14 +207,22 @@ impl AsyncTreeReader {
         .unwrap()
     }
 
+    /// Returns entries with proofs for the specified `keys` together with the root hash of the tree
+    /// at the specified L1 batch.
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
         keys: Vec<Key>,
-    ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
-        tokio::task::spawn_blocking(move || self.inner.entries_with_proofs(l1_batch_number, &keys))
-            .await
-            .unwrap()
+    ) -> Result<(H256, Vec<TreeEntryWithProof>), NoVersionError> {
+        tokio::task::spawn_blocking(move || {
+            let entries = self.inner.entries_with_proofs(l1_batch_number, &keys)?;
+            // The tree version was present when reading entries, so it can only be missing now
+            // if it was pruned in the meantime.
+            let root_hash = self.inner.try_l1_batch_root_hash(l1_batch_number)?;
+            Ok((root_hash, entries))
+        })
+        .await
+        .unwrap()
     }
 }
 
@@ -18,6 +18,9 @@ use zksync_types::{
     H256,
 };
 
+pub use self::checkpoint::{
+    create_tree_checkpoint, restore_tree_checkpoint, TreeCheckpointFile, TreeCheckpointManifest,
+};
 pub(crate) use self::helpers::{AsyncTreeReader, L1BatchWithLogs, MerkleTreeInfo};
 use self::{
     helpers::Delayer,
@@ -26,6 +29,7 @@ use self::{
 };
 use crate::gas_tracker::commit_gas_count_for_l1_batch;
 
+mod checkpoint;
 mod helpers;
 mod metrics;
 #[cfg(test)]
@@ -76,6 +80,9 @@ pub struct MetadataCalculatorConfig<'a> {
     pub memtable_capacity: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// Number of past L1 batches retained in the tree besides the latest one. If `None`, the tree
+    /// is not pruned.
+    pub pruning_retained_l1_batches: Option<u64>,
 }
 
 impl<'a> MetadataCalculatorConfig<'a> {
@@ -93,6 +100,7 @@ impl<'a> MetadataCalculatorConfig<'a> {
             block_cache_capacity: merkle_tree_config.block_cache_size(),
             memtable_capacity: merkle_tree_config.memtable_capacity(),
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            pruning_retained_l1_batches: merkle_tree_config.pruning_retained_l1_batches,
         }
     }
 }
@@ -9,7 +9,7 @@ use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_health_check::{CheckHealth, HealthStatus};
 use zksync_merkle_tree::domain::ZkSyncTree;
-use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreFactory};
 use zksync_types::{
     block::{BlockGasCount, L1BatchHeader, MiniblockHasher, MiniblockHeader},
     proofs::PrepareBasicCircuitsJob,
@@ -19,7 +19,8 @@ use zksync_types::{
 use zksync_utils::u32_to_h256;
 
 use super::{
-    L1BatchWithLogs, MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig,
+    create_tree_checkpoint, restore_tree_checkpoint, L1BatchWithLogs, MetadataCalculator,
+    MetadataCalculatorConfig, MetadataCalculatorModeConfig,
 };
 use crate::genesis::{ensure_genesis_state, GenesisParams};
 
@@ -181,6 +182,89 @@ async fn multi_l1_batch_workflow() {
     }
 }
 
+#[tokio::test]
+async fn creating_and_restoring_tree_checkpoint() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, object_store) = setup_calculator(temp_dir.path(), &pool).await;
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool.clone()).await;
+
+    let db_path = temp_dir.path().join("new");
+    let manifest = create_tree_checkpoint(&db_path, L1BatchNumber(3), &*object_store)
+        .await
+        .unwrap();
+    assert_eq!(manifest.l1_batch_number, L1BatchNumber(3));
+    assert!(!manifest.files.is_empty());
+    let mut storage = pool.access_storage().await.unwrap();
+    let expected_root_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(L1BatchNumber(3))
+        .await
+        .unwrap();
+    assert_eq!(Some(manifest.root_hash), expected_root_hash);
+
+    let restored_path = temp_dir.path().join("restored");
+    restore_tree_checkpoint(
+        &mut storage,
+        &*object_store,
+        L1BatchNumber(3),
+        &restored_path,
+    )
+    .await
+    .unwrap();
+    // Restoring into an existing directory should fail.
+    let err = restore_tree_checkpoint(
+        &mut storage,
+        &*object_store,
+        L1BatchNumber(3),
+        &restored_path,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("already exists"), "{err}");
+
+    // Corrupt the checkpoint and check that it's not restored.
+    let corrupted_file = &manifest.files[0].name;
+    let key = format!("checkpoint_l1_batch_3_{corrupted_file}_part0");
+    object_store
+        .put_raw(Bucket::MerkleTreeCheckpoints, &key, b"garbage".to_vec())
+        .await
+        .unwrap();
+    let corrupted_path = temp_dir.path().join("corrupted");
+    let err = restore_tree_checkpoint(
+        &mut storage,
+        &*object_store,
+        L1BatchNumber(3),
+        &corrupted_path,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("is corrupted"), "{err}");
+    assert!(!corrupted_path.exists());
+    drop(storage);
+
+    // The metadata calculator should resume from the restored tree.
+    let restored_config = MerkleTreeConfig {
+        path: path_to_string(&restored_path),
+        ..Default::default()
+    };
+    let operation_config = OperationsManagerConfig { delay_interval: 50 };
+    let calculator = setup_calculator_with_options(
+        &restored_config,
+        &operation_config,
+        &pool,
+        MetadataCalculatorModeConfig::Lightweight,
+    )
+    .await;
+    assert_eq!(
+        calculator.updater.tree().next_l1_batch_number(),
+        L1BatchNumber(4)
+    );
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+    assert_eq!(root_hash, expected_tree_hash(&pool).await);
+}
+
 #[tokio::test]
 async fn running_metadata_calculator_with_additional_blocks() {
     let pool = ConnectionPool::test_pool().await;
@@ -1,6 +1,6 @@
 //! Tree updater trait and its implementations.
 
-use std::{ops, time::Instant};
+use std::{ops, thread, time::Instant};
 
 use anyhow::Context as _;
 use futures::{future, FutureExt};
@@ -23,6 +23,7 @@ use super::{
 pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
+    pruning_retained_l1_batches: Option<u64>,
     object_store: Option<Box<dyn ObjectStore>>,
 }
 
@@ -50,6 +51,7 @@ impl TreeUpdater {
         Self {
             tree,
             max_l1_batches_per_iter: config.max_l1_batches_per_iter,
+            pruning_retained_l1_batches: config.pruning_retained_l1_batches,
             object_store,
         }
     }
@@ -348,6 +350,21 @@ impl TreeUpdater {
             health_updater.update(tree_info.into());
         }
 
+        let pruner_handle = self
+            .pruning_retained_l1_batches
+            .map(|retained_l1_batches| {
+                tracing::info!(
+                    "Starting Merkle tree pruner retaining {retained_l1_batches} past L1 batches"
+                );
+                let (pruner, handle) = self.tree.pruner(retained_l1_batches);
+                thread::Builder::new()
+                    .name("merkle-tree-pruner".to_owned())
+                    .spawn(|| pruner.run())
+                    .context("failed spawning Merkle tree pruner thread")?;
+                anyhow::Ok(handle)
+            })
+            .transpose()?;
+
         loop {
             if *stop_receiver.borrow_and_update() {
                 tracing::info!("Stop signal received, metadata_calculator is shutting down");
@@ -386,6 +403,9 @@ impl TreeUpdater {
                 () = delay => { /* The delay has passed */ }
             }
         }
+        if let Some(handle) = pruner_handle {
+            handle.abort();
+        }
         drop(health_updater); // Explicitly mark where the updater should be dropped
         Ok(())
     }
@@ -0,0 +1,227 @@
+//! Authentication of prover clusters using the proof data handler API.
+
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use axum::http::{header, HeaderMap, HeaderName};
+use zksync_config::configs::ProofDataHandlerConfig;
+use zksync_types::{web3::signing::keccak256, H256};
+
+/// Name of the prover cluster all requests are attributed to if authentication is disabled.
+pub(crate) const DEFAULT_PROVER_CLUSTER: &str = "default";
+
+fn parse_cluster_entries(entries: &[String], kind: &str) -> anyhow::Result<Vec<(String, String)>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let (cluster, value) = entry.split_once(':').with_context(|| {
+                format!("invalid prover cluster {kind}: expected `<cluster_name>:<{kind}>`")
+            })?;
+            anyhow::ensure!(
+                !cluster.is_empty() && !value.is_empty(),
+                "prover cluster name and {kind} must be non-empty"
+            );
+            Ok((cluster.to_owned(), value.to_owned()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AuthError {
+    MissingCredentials,
+    InvalidCredentials,
+}
+
+/// Maps credentials presented by prover clusters to cluster names.
+#[derive(Debug, Clone)]
+pub(crate) struct ProverClusterAuth {
+    /// Cluster names keyed by API key digests, so that keys are not compared directly.
+    api_keys: HashMap<H256, String>,
+    identity_header: Option<HeaderName>,
+    identities: HashMap<String, String>,
+}
+
+impl ProverClusterAuth {
+    pub fn new(config: &ProofDataHandlerConfig) -> anyhow::Result<Self> {
+        let api_keys = parse_cluster_entries(&config.prover_cluster_api_keys, "api_key")?;
+        let api_keys = api_keys
+            .into_iter()
+            .map(|(cluster, key)| (H256(keccak256(key.as_bytes())), cluster))
+            .collect();
+        let identities = parse_cluster_entries(&config.prover_cluster_identities, "identity")?;
+        let identities = identities
+            .into_iter()
+            .map(|(cluster, identity)| (identity, cluster))
+            .collect();
+
+        let identity_header = config
+            .client_identity_header
+            .as_deref()
+            .map(HeaderName::try_from)
+            .transpose()
+            .context("invalid client identity header name")?;
+        anyhow::ensure!(
+            identity_header.is_some() || config.prover_cluster_identities.is_empty(),
+            "prover cluster identities are configured without the client identity header"
+        );
+        // The identity header can be spoofed unless the proxy in front of the API strips it,
+        // so trusting it must be explicitly opted into.
+        anyhow::ensure!(
+            identity_header.is_none() || config.trust_client_identity_header,
+            "client identity header is configured, but `trust_client_identity_header` is not set"
+        );
+
+        Ok(Self {
+            api_keys,
+            identity_header,
+            identities,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.api_keys.is_empty() || !self.identities.is_empty()
+    }
+
+    /// Returns the name of the prover cluster that has sent a request with the specified headers.
+    /// The API key is expected in the `Authorization: Bearer <api_key>` header; the client identity
+    /// is taken from the configured header if it is explicitly trusted.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<String, AuthError> {
+        if !self.is_enabled() {
+            return Ok(DEFAULT_PROVER_CLUSTER.to_owned());
+        }
+
+        if let Some(identity_header) = &self.identity_header {
+            if let Some(identity) = headers.get(identity_header) {
+                let identity = identity
+                    .to_str()
+                    .map_err(|_| AuthError::InvalidCredentials)?;
+                return self
+                    .identities
+                    .get(identity)
+                    .cloned()
+                    .ok_or(AuthError::InvalidCredentials);
+            }
+        }
+
+        let authorization = headers
+            .get(header::AUTHORIZATION)
+            .ok_or(AuthError::MissingCredentials)?;
+        let api_key = authorization
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(AuthError::InvalidCredentials)?;
+        let digest = H256(keccak256(api_key.trim().as_bytes()));
+        self.api_keys
+            .get(&digest)
+            .cloned()
+            .ok_or(AuthError::InvalidCredentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use zksync_config::configs::proof_data_handler::ProtocolVersionLoadingMode;
+
+    use super::*;
+
+    fn base_config() -> ProofDataHandlerConfig {
+        ProofDataHandlerConfig {
+            http_port: 3320,
+            proof_generation_timeout_in_secs: 18000,
+            protocol_version_loading_mode: ProtocolVersionLoadingMode::FromEnvVar,
+            fri_protocol_version_id: 2,
+            prover_cluster_api_keys: vec![],
+            client_identity_header: None,
+            trust_client_identity_header: false,
+            prover_cluster_identities: vec![],
+            max_provers_per_batch: None,
+            snark_wrapper_vk_path: None,
+        }
+    }
+
+    fn headers(entries: &[(&'static str, &'static str)]) -> HeaderMap {
+        entries
+            .iter()
+            .map(|&(name, value)| {
+                (
+                    HeaderName::from_static(name),
+                    HeaderValue::from_static(value),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn authentication_is_disabled_by_default() {
+        let auth = ProverClusterAuth::new(&base_config()).unwrap();
+        assert!(!auth.is_enabled());
+        assert_eq!(
+            auth.authenticate(&HeaderMap::new()).unwrap(),
+            DEFAULT_PROVER_CLUSTER
+        );
+    }
+
+    #[test]
+    fn authenticating_prover_clusters() {
+        let config = ProofDataHandlerConfig {
+            prover_cluster_api_keys: vec!["a:secret_a".to_owned(), "b:secret_b".to_owned()],
+            client_identity_header: Some("x-client-identity".to_owned()),
+            trust_client_identity_header: true,
+            prover_cluster_identities: vec!["c:CN=prover-c".to_owned()],
+            ..base_config()
+        };
+        let auth = ProverClusterAuth::new(&config).unwrap();
+        assert!(auth.is_enabled());
+
+        let cluster = auth
+            .authenticate(&headers(&[("authorization", "Bearer secret_b")]))
+            .unwrap();
+        assert_eq!(cluster, "b");
+        let cluster = auth
+            .authenticate(&headers(&[("x-client-identity", "CN=prover-c")]))
+            .unwrap();
+        assert_eq!(cluster, "c");
+
+        assert_eq!(
+            auth.authenticate(&HeaderMap::new()).unwrap_err(),
+            AuthError::MissingCredentials
+        );
+        let err = auth
+            .authenticate(&headers(&[("authorization", "Bearer secret_c")]))
+            .unwrap_err();
+        assert_eq!(err, AuthError::InvalidCredentials);
+        let err = auth
+            .authenticate(&headers(&[("authorization", "secret_a")]))
+            .unwrap_err();
+        assert_eq!(err, AuthError::InvalidCredentials);
+        let err = auth
+            .authenticate(&headers(&[("x-client-identity", "CN=unknown")]))
+            .unwrap_err();
+        assert_eq!(err, AuthError::InvalidCredentials);
+    }
+
+    #[test]
+    fn invalid_auth_config() {
+        let config = ProofDataHandlerConfig {
+            prover_cluster_api_keys: vec!["secret".to_owned()],
+            ..base_config()
+        };
+        ProverClusterAuth::new(&config).unwrap_err();
+
+        let config = ProofDataHandlerConfig {
+            prover_cluster_identities: vec!["c:CN=prover-c".to_owned()],
+            ..base_config()
+        };
+        ProverClusterAuth::new(&config).unwrap_err();
+
+        let config = ProofDataHandlerConfig {
+            client_identity_header: Some("x-client-identity".to_owned()),
+            prover_cluster_identities: vec!["c:CN=prover-c".to_owned()],
+            ..base_config()
+        };
+        let err = ProverClusterAuth::new(&config).unwrap_err().to_string();
+        assert!(err.contains("trust_client_identity_header"), "{err}");
+    }
+}
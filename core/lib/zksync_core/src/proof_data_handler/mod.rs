@@ -1,7 +1,7 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::Path};
 
 use anyhow::Context as _;
-use axum::{extract::Path, routing::post, Json, Router};
+use axum::{extract::Path, http::HeaderMap, routing::post, Json, Router};
 use tokio::sync::watch;
 use zksync_config::{
     configs::{proof_data_handler::ProtocolVersionLoadingMode, ProofDataHandlerConfig},
@@ -15,9 +15,13 @@ use zksync_types::{
     H256,
 };
 
-use crate::proof_data_handler::request_processor::RequestProcessor;
+use crate::proof_data_handler::{
+    auth::ProverClusterAuth, request_processor::RequestProcessor, snark_verifier::SnarkVerifier,
+};
 
+mod auth;
 mod request_processor;
+mod snark_verifier;
 
 fn fri_l1_verifier_config(contracts_config: &ContractsConfig) -> L1VerifierConfig {
     L1VerifierConfig {
@@ -44,8 +48,32 @@ pub(crate) async fn run_server(
         ProtocolVersionLoadingMode::FromDb => None,
         ProtocolVersionLoadingMode::FromEnvVar => Some(fri_l1_verifier_config(&contracts_config)),
     };
-    let get_proof_gen_processor =
-        RequestProcessor::new(blob_store, pool, config, l1_verifier_config);
+    let snark_verifier = match (&config.snark_wrapper_vk_path, &l1_verifier_config) {
+        (Some(vk_path), Some(l1_verifier_config)) => {
+            let vk_hash = l1_verifier_config.recursion_scheduler_level_vk_hash;
+            let verifier = SnarkVerifier::load(Path::new(vk_path), vk_hash)
+                .context("failed loading SNARK verification key")?;
+            Some(verifier)
+        }
+        _ => {
+            tracing::warn!(
+                "SNARK verification key is not configured for proof data handler; submitted proofs will be rejected"
+            );
+            None
+        }
+    };
+    let auth = ProverClusterAuth::new(&config).context("invalid prover cluster credentials")?;
+    if !auth.is_enabled() {
+        tracing::warn!("Prover cluster authentication is disabled for proof data handler");
+    }
+    let get_proof_gen_processor = RequestProcessor::new(
+        blob_store,
+        pool,
+        config,
+        l1_verifier_config,
+        snark_verifier,
+        auth,
+    );
     let submit_proof_processor = get_proof_gen_processor.clone();
     let app = Router::new()
         .route(
@@ -53,9 +81,9 @@ pub(crate) async fn run_server(
             post(
                 // we use post method because the returned data is not idempotent,
                 // i.e we return different result on each call.
-                move |payload: Json<ProofGenerationDataRequest>| async move {
+                move |headers: HeaderMap, payload: Json<ProofGenerationDataRequest>| async move {
                     get_proof_gen_processor
-                        .get_proof_generation_data(payload)
+                        .get_proof_generation_data(headers, payload)
                         .await
                 },
             ),
@@ -63,9 +91,11 @@ pub(crate) async fn run_server(
         .route(
             "/submit_proof/:l1_batch_number",
             post(
-                move |l1_batch_number: Path<u32>, payload: Json<SubmitProofRequest>| async move {
+                move |headers: HeaderMap,
+                      l1_batch_number: Path<u32>,
+                      payload: Json<SubmitProofRequest>| async move {
                     submit_proof_processor
-                        .submit_proof(l1_batch_number, payload)
+                        .submit_proof(headers, l1_batch_number, payload)
                         .await
                 },
             ),
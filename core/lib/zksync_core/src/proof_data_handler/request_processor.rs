@@ -2,16 +2,20 @@ use std::{convert::TryFrom, sync::Arc};
 
 use axum::{
     extract::Path,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use zksync_config::configs::{
     proof_data_handler::ProtocolVersionLoadingMode, ProofDataHandlerConfig,
 };
-use zksync_dal::{ConnectionPool, SqlxError};
+use zksync_dal::{
+    proof_generation_dal::ProofGenerationAssignmentStatus, ConnectionPool, SqlxError,
+    StorageProcessor,
+};
 use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_types::{
+    aggregated_operations::L1BatchProofForL1,
     commitment::serialize_commitments,
     protocol_version::{FriProtocolVersionId, L1VerifierConfig},
     prover_server_api::{
@@ -19,21 +23,37 @@ use zksync_types::{
         SubmitProofRequest, SubmitProofResponse,
     },
     web3::signing::keccak256,
-    L1BatchNumber, H256,
+    L1BatchNumber, H256, U256,
 };
 use zksync_utils::u256_to_h256;
 
+use super::{
+    auth::{AuthError, ProverClusterAuth},
+    snark_verifier::SnarkVerifier,
+};
+
+/// Number of low-order bits dropped from the batch commitments hash to get the proof public input
+/// (the same as in the L1 `Executor` contract).
+const PUBLIC_INPUT_SHIFT: usize = 32;
+
 #[derive(Clone)]
 pub(crate) struct RequestProcessor {
     blob_store: Arc<dyn ObjectStore>,
     pool: ConnectionPool,
     config: ProofDataHandlerConfig,
     l1_verifier_config: Option<L1VerifierConfig>,
+    snark_verifier: Option<Arc<SnarkVerifier>>,
+    auth: ProverClusterAuth,
 }
 
 pub(crate) enum RequestProcessorError {
     ObjectStore(ObjectStoreError),
     Sqlx(SqlxError),
+    Unauthorized(AuthError),
+    BatchNotAssigned(L1BatchNumber),
+    AssignmentExpired(L1BatchNumber),
+    ProofVerificationUnavailable,
+    InvalidProof(String),
 }
 
 impl IntoResponse for RequestProcessorError {
@@ -58,6 +78,30 @@ impl IntoResponse for RequestProcessorError {
                     ),
                 }
             }
+            RequestProcessorError::Unauthorized(err) => {
+                let message = match err {
+                    AuthError::MissingCredentials => "Missing prover cluster credentials",
+                    AuthError::InvalidCredentials => "Invalid prover cluster credentials",
+                };
+                (StatusCode::UNAUTHORIZED, message.to_owned())
+            }
+            RequestProcessorError::BatchNotAssigned(l1_batch_number) => (
+                StatusCode::FORBIDDEN,
+                format!("L1 batch #{l1_batch_number} is not assigned to the prover cluster"),
+            ),
+            RequestProcessorError::AssignmentExpired(l1_batch_number) => (
+                StatusCode::FORBIDDEN,
+                format!(
+                    "Assignment of L1 batch #{l1_batch_number} to the prover cluster has expired"
+                ),
+            ),
+            RequestProcessorError::ProofVerificationUnavailable => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "SNARK verification key is not configured; proofs cannot be verified".to_owned(),
+            ),
+            RequestProcessorError::InvalidProof(message) => {
+                (StatusCode::BAD_REQUEST, format!("Invalid proof: {message}"))
+            }
         };
         (status_code, message).into_response()
     }
@@ -69,20 +113,34 @@ impl RequestProcessor {
         pool: ConnectionPool,
         config: ProofDataHandlerConfig,
         l1_verifier_config: Option<L1VerifierConfig>,
+        snark_verifier: Option<SnarkVerifier>,
+        auth: ProverClusterAuth,
     ) -> Self {
         Self {
             blob_store: Arc::from(blob_store),
             pool,
             config,
             l1_verifier_config,
+            snark_verifier: snark_verifier.map(Arc::new),
+            auth,
         }
     }
 
+    fn authenticate(&self, headers: &HeaderMap) -> Result<String, RequestProcessorError> {
+        self.auth
+            .authenticate(headers)
+            .map_err(RequestProcessorError::Unauthorized)
+    }
+
     pub(crate) async fn get_proof_generation_data(
         &self,
+        headers: HeaderMap,
         request: Json<ProofGenerationDataRequest>,
     ) -> Result<Json<ProofGenerationDataResponse>, RequestProcessorError> {
-        tracing::info!("Received request for proof generation data: {:?}", request);
+        let prover_cluster = self.authenticate(&headers)?;
+        tracing::info!(
+            "Received request for proof generation data from prover cluster `{prover_cluster}`: {request:?}"
+        );
 
         let l1_batch_number_result = self
             .pool
@@ -90,13 +148,19 @@ impl RequestProcessor {
             .await
             .unwrap()
             .proof_generation_dal()
-            .get_next_block_to_be_proven(self.config.proof_generation_timeout())
-            .await;
+            .get_next_block_to_be_proven(
+                &prover_cluster,
+                self.config.proof_generation_timeout(),
+                self.config.max_provers_per_batch(),
+            )
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
 
         let l1_batch_number = match l1_batch_number_result {
             Some(number) => number,
             None => return Ok(Json(ProofGenerationDataResponse::Success(None))), // no batches pending to be proven
         };
+        tracing::info!("Assigned L1 batch #{l1_batch_number} to prover cluster `{prover_cluster}`");
 
         let blob = self
             .blob_store
@@ -132,107 +196,223 @@ impl RequestProcessor {
 
     pub(crate) async fn submit_proof(
         &self,
+        headers: HeaderMap,
         Path(l1_batch_number): Path<u32>,
         Json(payload): Json<SubmitProofRequest>,
     ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
-        tracing::info!("Received proof for block number: {:?}", l1_batch_number);
+        let prover_cluster = self.authenticate(&headers)?;
+        tracing::info!(
+            "Received proof for block number {l1_batch_number:?} from prover cluster `{prover_cluster}`"
+        );
         let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let mut storage = self.pool.access_storage().await.unwrap();
+        let assignment = storage
+            .proof_generation_dal()
+            .get_assignment(l1_batch_number, &prover_cluster)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        let Some(assignment) = assignment else {
+            return Err(RequestProcessorError::BatchNotAssigned(l1_batch_number));
+        };
+        match assignment.status {
+            ProofGenerationAssignmentStatus::Assigned if assignment.is_expired => {
+                return Err(RequestProcessorError::AssignmentExpired(l1_batch_number));
+            }
+            ProofGenerationAssignmentStatus::Assigned => { /* the assignment is active */ }
+            ProofGenerationAssignmentStatus::Submitted => {
+                tracing::info!(
+                    "Prover cluster `{prover_cluster}` has already submitted proof for L1 batch #{l1_batch_number}; \
+                     ignoring resubmission"
+                );
+                return Ok(Json(SubmitProofResponse::Success));
+            }
+            ProofGenerationAssignmentStatus::Rejected => {
+                return Err(RequestProcessorError::BatchNotAssigned(l1_batch_number));
+            }
+        }
+
         match payload {
             SubmitProofRequest::Proof(proof) => {
-                let blob_url = self
-                    .blob_store
-                    .put(l1_batch_number, &*proof)
-                    .await
-                    .map_err(RequestProcessorError::ObjectStore)?;
-
-                let system_logs_hash_from_prover =
-                    H256::from_slice(&proof.aggregation_result_coords[0]);
-                let state_diff_hash_from_prover =
-                    H256::from_slice(&proof.aggregation_result_coords[1]);
-                let bootloader_heap_initial_content_from_prover =
-                    H256::from_slice(&proof.aggregation_result_coords[2]);
-                let events_queue_state_from_prover =
-                    H256::from_slice(&proof.aggregation_result_coords[3]);
-
-                let mut storage = self.pool.access_storage().await.unwrap();
-
-                let l1_batch = storage
-                    .blocks_dal()
-                    .get_l1_batch_metadata(l1_batch_number)
-                    .await
-                    .unwrap()
-                    .expect("Proved block without metadata");
-
-                let is_pre_boojum = l1_batch
-                    .header
-                    .protocol_version
-                    .map(|v| v.is_pre_boojum())
-                    .unwrap_or(true);
-                if !is_pre_boojum {
-                    let events_queue_state = l1_batch
-                        .metadata
-                        .events_queue_commitment
-                        .expect("No events_queue_commitment");
-                    let bootloader_heap_initial_content = l1_batch
-                        .metadata
-                        .bootloader_initial_content_commitment
-                        .expect("No bootloader_initial_content_commitment");
-
-                    if events_queue_state != events_queue_state_from_prover
-                        || bootloader_heap_initial_content
-                            != bootloader_heap_initial_content_from_prover
-                    {
-                        let server_values = format!("events_queue_state = {events_queue_state}, bootloader_heap_initial_content = {bootloader_heap_initial_content}");
-                        let prover_values = format!("events_queue_state = {events_queue_state_from_prover}, bootloader_heap_initial_content = {bootloader_heap_initial_content_from_prover}");
-                        panic!(
-                            "Auxilary output doesn't match, server values: {} prover values: {}",
-                            server_values, prover_values
-                        );
-                    }
+                let snark_verifier = self
+                    .snark_verifier
+                    .clone()
+                    .ok_or(RequestProcessorError::ProofVerificationUnavailable)?;
+                if let Err(message) =
+                    Self::verify_proof(&mut storage, snark_verifier, l1_batch_number, &proof).await
+                {
+                    tracing::warn!(
+                        "Rejected proof for L1 batch #{l1_batch_number} from prover cluster `{prover_cluster}`: {message}"
+                    );
+                    storage
+                        .proof_generation_dal()
+                        .update_assignment_status(
+                            l1_batch_number,
+                            &prover_cluster,
+                            ProofGenerationAssignmentStatus::Rejected,
+                            Some(&message),
+                        )
+                        .await
+                        .map_err(RequestProcessorError::Sqlx)?;
+                    return Err(RequestProcessorError::InvalidProof(message));
                 }
 
-                let system_logs = serialize_commitments(&l1_batch.header.system_logs);
-                let system_logs_hash = H256(keccak256(&system_logs));
-
-                if !is_pre_boojum {
-                    let state_diff_hash = l1_batch
-                        .header
-                        .system_logs
-                        .into_iter()
-                        .find(|elem| elem.0.key == u256_to_h256(2.into()))
-                        .expect("No state diff hash key")
-                        .0
-                        .value;
-
-                    if state_diff_hash != state_diff_hash_from_prover
-                        || system_logs_hash != system_logs_hash_from_prover
-                    {
-                        let server_values = format!("system_logs_hash = {system_logs_hash}, state_diff_hash = {state_diff_hash}");
-                        let prover_values = format!("system_logs_hash = {system_logs_hash_from_prover}, state_diff_hash = {state_diff_hash_from_prover}");
-                        panic!(
-                            "Auxilary output doesn't match, server values: {} prover values: {}",
-                            server_values, prover_values
-                        );
-                    }
-                }
-                storage
+                // If the batch is proven redundantly, only the first submitted proof is stored.
+                let is_generated = storage
                     .proof_generation_dal()
-                    .save_proof_artifacts_metadata(l1_batch_number, &blob_url)
+                    .is_proof_generated(l1_batch_number)
                     .await
                     .map_err(RequestProcessorError::Sqlx)?;
+                if is_generated {
+                    tracing::info!(
+                        "L1 batch #{l1_batch_number} is already proven; not storing proof from prover cluster `{prover_cluster}`"
+                    );
+                } else {
+                    let blob_url = self
+                        .blob_store
+                        .put(l1_batch_number, &*proof)
+                        .await
+                        .map_err(RequestProcessorError::ObjectStore)?;
+                    storage
+                        .proof_generation_dal()
+                        .save_proof_artifacts_metadata(l1_batch_number, &blob_url)
+                        .await
+                        .map_err(RequestProcessorError::Sqlx)?;
+                }
             }
             SubmitProofRequest::SkippedProofGeneration => {
-                self.pool
-                    .access_storage()
-                    .await
-                    .unwrap()
+                let is_generated = storage
                     .proof_generation_dal()
-                    .mark_proof_generation_job_as_skipped(l1_batch_number)
+                    .is_proof_generated(l1_batch_number)
                     .await
                     .map_err(RequestProcessorError::Sqlx)?;
+                if !is_generated {
+                    storage
+                        .proof_generation_dal()
+                        .mark_proof_generation_job_as_skipped(l1_batch_number)
+                        .await
+                        .map_err(RequestProcessorError::Sqlx)?;
+                }
             }
         }
+        storage
+            .proof_generation_dal()
+            .update_assignment_status(
+                l1_batch_number,
+                &prover_cluster,
+                ProofGenerationAssignmentStatus::Submitted,
+                None,
+            )
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
 
         Ok(Json(SubmitProofResponse::Success))
     }
+
+    /// Verifies the proof for the specified L1 batch. Checks that its auxiliary outputs match the batch
+    /// metadata, that its public input matches the batch commitments, and that the SNARK itself is valid
+    /// for the verification key of the current protocol version.
+    async fn verify_proof(
+        storage: &mut StorageProcessor<'_>,
+        snark_verifier: Arc<SnarkVerifier>,
+        l1_batch_number: L1BatchNumber,
+        proof: &L1BatchProofForL1,
+    ) -> Result<(), String> {
+        let system_logs_hash_from_prover = H256::from_slice(&proof.aggregation_result_coords[0]);
+        let state_diff_hash_from_prover = H256::from_slice(&proof.aggregation_result_coords[1]);
+        let bootloader_heap_initial_content_from_prover =
+            H256::from_slice(&proof.aggregation_result_coords[2]);
+        let events_queue_state_from_prover = H256::from_slice(&proof.aggregation_result_coords[3]);
+
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("Proved block without metadata");
+
+        let is_pre_boojum = l1_batch
+            .header
+            .protocol_version
+            .map(|v| v.is_pre_boojum())
+            .unwrap_or(true);
+        if is_pre_boojum {
+            return Err(format!(
+                "L1 batch #{l1_batch_number} has pre-boojum protocol version; its proofs cannot be verified"
+            ));
+        }
+
+        let events_queue_state = l1_batch
+            .metadata
+            .events_queue_commitment
+            .expect("No events_queue_commitment");
+        let bootloader_heap_initial_content = l1_batch
+            .metadata
+            .bootloader_initial_content_commitment
+            .expect("No bootloader_initial_content_commitment");
+        if events_queue_state != events_queue_state_from_prover
+            || bootloader_heap_initial_content != bootloader_heap_initial_content_from_prover
+        {
+            let server_values = format!("events_queue_state = {events_queue_state}, bootloader_heap_initial_content = {bootloader_heap_initial_content}");
+            let prover_values = format!("events_queue_state = {events_queue_state_from_prover}, bootloader_heap_initial_content = {bootloader_heap_initial_content_from_prover}");
+            return Err(format!(
+                "Auxilary output doesn't match, server values: {server_values} prover values: {prover_values}"
+            ));
+        }
+
+        let system_logs = serialize_commitments(&l1_batch.header.system_logs);
+        let system_logs_hash = H256(keccak256(&system_logs));
+        let state_diff_hash = l1_batch
+            .header
+            .system_logs
+            .iter()
+            .find(|elem| elem.0.key == u256_to_h256(2.into()))
+            .expect("No state diff hash key")
+            .0
+            .value;
+        if state_diff_hash != state_diff_hash_from_prover
+            || system_logs_hash != system_logs_hash_from_prover
+        {
+            let server_values = format!(
+                "system_logs_hash = {system_logs_hash}, state_diff_hash = {state_diff_hash}"
+            );
+            let prover_values = format!("system_logs_hash = {system_logs_hash_from_prover}, state_diff_hash = {state_diff_hash_from_prover}");
+            return Err(format!(
+                "Auxilary output doesn't match, server values: {server_values} prover values: {prover_values}"
+            ));
+        }
+
+        let prev_l1_batch_number = l1_batch_number
+            .0
+            .checked_sub(1)
+            .expect("Genesis L1 batch is not proven");
+        let prev_l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(prev_l1_batch_number))
+            .await
+            .unwrap()
+            .expect("Previous L1 batch without metadata");
+        let expected_public_input = batch_proof_public_input(
+            prev_l1_batch.metadata.commitment,
+            l1_batch.metadata.commitment,
+        );
+        let public_inputs = proof.public_inputs();
+        if public_inputs != [expected_public_input] {
+            return Err(format!(
+                "Public input doesn't match, expected: {expected_public_input}, got: {public_inputs:?}"
+            ));
+        }
+
+        let proof = proof.clone();
+        tokio::task::spawn_blocking(move || snark_verifier.verify(&proof))
+            .await
+            .map_err(|err| format!("SNARK verification panicked: {err}"))?
+    }
+}
+
+/// Computes the scheduler proof public input for an L1 batch in the same way as the L1 contract.
+fn batch_proof_public_input(prev_commitment: H256, commitment: H256) -> U256 {
+    let mut buffer = [0_u8; 64];
+    buffer[..32].copy_from_slice(prev_commitment.as_bytes());
+    buffer[32..].copy_from_slice(commitment.as_bytes());
+    U256::from_big_endian(&keccak256(&buffer)) >> PUBLIC_INPUT_SHIFT
 }
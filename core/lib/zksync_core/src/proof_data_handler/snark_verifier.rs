@@ -0,0 +1,94 @@
+//! Verification of SNARK-wrapped proofs submitted by prover clusters.
+
+use std::{fmt, fs, path::Path};
+
+use anyhow::Context as _;
+use zksync_types::{
+    aggregated_operations::L1BatchProofForL1,
+    vk_transform::snark_vk_hash,
+    zkevm_test_harness::{
+        abstract_zksync_circuit::concrete_circuits::ZkSyncCircuit,
+        bellman::{
+            bn256::{Bn256, Fr},
+            plonk::{
+                better_better_cs::{setup::VerificationKey, verifier::verify},
+                commitments::transcript::keccak_transcript::RollingKeccakTranscript,
+            },
+        },
+        witness::oracle::VmWitnessOracle,
+    },
+    H256,
+};
+
+type SnarkWrapperCircuit = ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>;
+
+/// Verifies proofs against the SNARK wrapper verification key used by the L1 verifier contract.
+pub(crate) struct SnarkVerifier {
+    vk: VerificationKey<Bn256, SnarkWrapperCircuit>,
+    vk_hash: H256,
+}
+
+impl fmt::Debug for SnarkVerifier {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SnarkVerifier")
+            .field("vk_hash", &self.vk_hash)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SnarkVerifier {
+    /// Loads the verification key from the specified JSON file and checks that its hash matches
+    /// the expected one (i.e., the scheduler verification key hash for the current protocol version).
+    pub fn load(path: &Path, expected_vk_hash: H256) -> anyhow::Result<Self> {
+        let vk = fs::read_to_string(path)
+            .with_context(|| format!("failed reading SNARK verification key from {path:?}"))?;
+        let vk: VerificationKey<Bn256, SnarkWrapperCircuit> =
+            serde_json::from_str(&vk).context("failed deserializing SNARK verification key")?;
+        let vk_hash = snark_vk_hash(&vk);
+        anyhow::ensure!(
+            vk_hash == expected_vk_hash,
+            "SNARK verification key at {path:?} has hash {vk_hash:?}, while the protocol version \
+             expects {expected_vk_hash:?}"
+        );
+        Ok(Self { vk, vk_hash })
+    }
+
+    /// Verifies the scheduler proof. This is a CPU-bound operation and should not be run
+    /// on an async runtime thread.
+    pub fn verify(&self, proof: &L1BatchProofForL1) -> Result<(), String> {
+        let is_valid =
+            verify::<_, _, RollingKeccakTranscript<Fr>>(&self.vk, &proof.scheduler_proof, None)
+                .map_err(|err| format!("SNARK verification failed: {err:?}"))?;
+        if is_valid {
+            Ok(())
+        } else {
+            Err("SNARK proof is invalid".to_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const VK_PATH: &str =
+        "../../../prover/vk_setup_data_generator_server_fri/data/snark_verification_scheduler_key.json";
+    /// Value of `CONTRACTS_SNARK_WRAPPER_VK_HASH` in the base config.
+    const VK_HASH: &str = "0x750d8e21be7555a6841472a5cacd24c75a7ceb34261aea61e72bb7423a7d30fc";
+
+    #[test]
+    fn loading_snark_verification_key() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(VK_PATH);
+        let expected_vk_hash = H256::from_str(VK_HASH).unwrap();
+        let verifier = SnarkVerifier::load(&path, expected_vk_hash).unwrap();
+        assert_eq!(verifier.vk_hash, expected_vk_hash);
+
+        let err = SnarkVerifier::load(&path, H256::repeat_byte(1))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expects"), "{err}");
+    }
+}
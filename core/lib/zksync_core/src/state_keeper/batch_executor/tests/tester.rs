@@ -12,18 +12,16 @@ use zksync_dal::ConnectionPool;
 use zksync_state::RocksdbStorage;
 use zksync_test_account::{Account, DeployContractsTx, TxType};
 use zksync_types::{
-    ethabi::Token, fee::Fee, system_contracts::get_system_smart_contracts,
-    utils::storage_key_for_standard_token_balance, AccountTreeId, Address, Execute, L1BatchNumber,
-    L2ChainId, MiniblockNumber, PriorityOpId, ProtocolVersionId, StorageLog, Transaction, H256,
-    L2_ETH_TOKEN_ADDRESS, SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
+    ethabi::Token, fee::Fee, system_contracts::get_system_smart_contracts, Address, Execute,
+    L1BatchNumber, L2ChainId, PriorityOpId, ProtocolVersionId, Transaction, H256,
+    SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
 };
-use zksync_utils::u256_to_h256;
 
 use crate::{
     genesis::create_genesis_l1_batch,
     state_keeper::{
         batch_executor::BatchExecutorHandle,
-        tests::{default_l1_batch_env, default_system_env, BASE_SYSTEM_CONTRACTS},
+        tests::{default_l1_batch_env, default_system_env, fund, BASE_SYSTEM_CONTRACTS},
     },
 };
 
@@ -163,37 +161,7 @@ impl Tester {
             .access_storage_tagged("state_keeper")
             .await
             .unwrap();
-
-        let eth_amount = U256::from(10u32).pow(U256::from(32)); //10^32 wei
-
-        for address in addresses {
-            let key = storage_key_for_standard_token_balance(
-                AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
-                address,
-            );
-            let value = u256_to_h256(eth_amount);
-            let storage_log = StorageLog::new_write_log(key, value);
-
-            storage
-                .storage_logs_dal()
-                .append_storage_logs(MiniblockNumber(0), &[(H256::zero(), vec![storage_log])])
-                .await;
-            storage
-                .storage_dal()
-                .apply_storage_logs(&[(H256::zero(), vec![storage_log])])
-                .await;
-            if storage
-                .storage_logs_dedup_dal()
-                .filter_written_slots(&[storage_log.key.hashed_key()])
-                .await
-                .is_empty()
-            {
-                storage
-                    .storage_logs_dedup_dal()
-                    .insert_initial_writes(L1BatchNumber(0), &[storage_log.key])
-                    .await
-            }
-        }
+        fund(&mut storage, addresses).await;
     }
 }
 
@@ -5,10 +5,10 @@ use multivm::{
     vm_latest::constants::BLOCK_GAS_LIMIT,
 };
 use zksync_contracts::BaseSystemContracts;
-use zksync_dal::StorageProcessor;
+use zksync_dal::{backend::BlocksReader, SqlxError, StorageProcessor};
 use zksync_types::{
-    Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, H256, U256,
-    ZKPORTER_IS_AVAILABLE,
+    block::MiniblockHeader, Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId,
+    H256, U256, ZKPORTER_IS_AVAILABLE,
 };
 use zksync_utils::u256_to_h256;
 
@@ -69,6 +69,33 @@ pub(crate) fn poll_iters(delay_interval: Duration, max_wait: Duration) -> usize
     ((max_wait_millis + delay_interval_millis - 1) / delay_interval_millis).max(1) as usize
 }
 
+/// Loads the header of the first miniblock in the pending L1 batch together with the hash
+/// of the preceding miniblock. Returns `None` if there are no pending miniblocks.
+pub(crate) async fn load_pending_miniblock_header<S: BlocksReader>(
+    storage: &mut S,
+    current_l1_batch_number: L1BatchNumber,
+) -> Result<Option<(MiniblockHeader, H256)>, SqlxError> {
+    // If miniblock doesn't exist (for instance if it's pending), it means that there is no unsynced state (i.e. no transactions
+    // were executed after the last sealed batch).
+    let (_, last_miniblock_number_included_in_l1_batch) = storage
+        .get_miniblock_range_of_l1_batch(current_l1_batch_number - 1)
+        .await?
+        .expect("Previous L1 batch must be sealed");
+    let pending_miniblock_number = last_miniblock_number_included_in_l1_batch + 1;
+    let Some(pending_miniblock_header) = storage
+        .get_miniblock_header(pending_miniblock_number)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let prev_miniblock_hash = storage
+        .get_miniblock_hash(pending_miniblock_number - 1)
+        .await?
+        .expect("Last miniblock in the previous L1 batch must be sealed");
+    Ok(Some((pending_miniblock_header, prev_miniblock_hash)))
+}
+
 pub(crate) async fn load_l1_batch_params(
     storage: &mut StorageProcessor<'_>,
     current_l1_batch_number: L1BatchNumber,
@@ -76,36 +103,16 @@ pub(crate) async fn load_l1_batch_params(
     validation_computational_gas_limit: u32,
     chain_id: L2ChainId,
 ) -> Option<(SystemEnv, L1BatchEnv)> {
-    // If miniblock doesn't exist (for instance if it's pending), it means that there is no unsynced state (i.e. no transactions
-    // were executed after the last sealed batch).
-    let pending_miniblock_number = {
-        let (_, last_miniblock_number_included_in_l1_batch) = storage
-            .blocks_dal()
-            .get_miniblock_range_of_l1_batch(current_l1_batch_number - 1)
+    let (pending_miniblock_header, prev_miniblock_hash) =
+        load_pending_miniblock_header(storage, current_l1_batch_number)
             .await
-            .unwrap()
-            .unwrap();
-        last_miniblock_number_included_in_l1_batch + 1
-    };
-    let pending_miniblock_header = storage
-        .blocks_dal()
-        .get_miniblock_header(pending_miniblock_number)
-        .await
-        .unwrap()?;
+            .unwrap()?;
+    let pending_miniblock_number = pending_miniblock_header.number;
 
     tracing::info!("Getting previous batch hash");
     let (previous_l1_batch_hash, _) =
         extractors::wait_for_prev_l1_batch_params(storage, current_l1_batch_number).await;
 
-    tracing::info!("Getting previous miniblock hash");
-    let prev_miniblock_hash = storage
-        .blocks_dal()
-        .get_miniblock_header(pending_miniblock_number - 1)
-        .await
-        .unwrap()
-        .unwrap()
-        .hash;
-
     let base_system_contracts = storage
         .storage_dal()
         .get_base_system_contracts(
@@ -170,8 +177,37 @@ pub(crate) async fn load_pending_batch(
 
 #[cfg(test)]
 mod tests {
+    use zksync_dal::backend::InMemoryBackend;
+    use zksync_types::block::L1BatchHeader;
+
     use super::*;
 
+    fn create_miniblock(number: u32) -> MiniblockHeader {
+        MiniblockHeader {
+            number: MiniblockNumber(number),
+            timestamp: number.into(),
+            hash: H256::from_low_u64_be(number.into()),
+            l1_tx_count: 0,
+            l2_tx_count: 0,
+            base_fee_per_gas: 100,
+            l1_gas_price: 100,
+            l2_fair_gas_price: 100,
+            base_system_contracts_hashes: Default::default(),
+            protocol_version: Some(ProtocolVersionId::latest()),
+            virtual_blocks: 1,
+        }
+    }
+
+    fn create_l1_batch(number: u32) -> L1BatchHeader {
+        L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            Address::default(),
+            Default::default(),
+            ProtocolVersionId::latest(),
+        )
+    }
+
     #[test]
     #[rustfmt::skip] // One-line formatting looks better here.
     fn test_poll_iters() {
@@ -181,4 +217,35 @@ mod tests {
         assert_eq!(poll_iters(Duration::from_millis(100), Duration::from_millis(200)), 2);
         assert_eq!(poll_iters(Duration::from_millis(100), Duration::from_millis(201)), 3);
     }
+
+    #[tokio::test]
+    async fn loading_pending_miniblock_header() {
+        let mut storage = InMemoryBackend::default();
+        storage.insert_miniblock(create_miniblock(0), &[]);
+        storage.insert_l1_batch(create_l1_batch(0));
+        let pending = load_pending_miniblock_header(&mut storage, L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(pending, None);
+
+        storage.insert_miniblock(create_miniblock(1), &[]);
+        storage.insert_miniblock(create_miniblock(2), &[]);
+        let (header, prev_miniblock_hash) =
+            load_pending_miniblock_header(&mut storage, L1BatchNumber(1))
+                .await
+                .unwrap()
+                .expect("no pending miniblock");
+        assert_eq!(header.number, MiniblockNumber(1));
+        assert_eq!(prev_miniblock_hash, H256::from_low_u64_be(0));
+
+        storage.insert_l1_batch(create_l1_batch(1));
+        storage.insert_miniblock(create_miniblock(3), &[]);
+        let (header, prev_miniblock_hash) =
+            load_pending_miniblock_header(&mut storage, L1BatchNumber(2))
+                .await
+                .unwrap()
+                .expect("no pending miniblock");
+        assert_eq!(header.number, MiniblockNumber(3));
+        assert_eq!(prev_miniblock_hash, H256::from_low_u64_be(2));
+    }
 }
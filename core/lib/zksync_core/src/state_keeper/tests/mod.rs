@@ -14,8 +14,11 @@ use multivm::{
     vm_latest::{constants::BLOCK_GAS_LIMIT, VmExecutionLogs},
 };
 use once_cell::sync::Lazy;
+use tempfile::TempDir;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_state::RocksdbStorage;
 use zksync_system_constants::ZKPORTER_IS_AVAILABLE;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
@@ -25,9 +28,12 @@ use zksync_types::{
     l2::L2Tx,
     transaction_request::PaymasterParams,
     tx::tx_execution_info::ExecutionMetrics,
-    Address, L1BatchNumber, L2ChainId, LogQuery, MiniblockNumber, Nonce, ProtocolVersionId,
-    StorageLogQuery, StorageLogQueryType, Timestamp, Transaction, H256, U256,
+    utils::storage_key_for_standard_token_balance,
+    AccountTreeId, Address, L1BatchNumber, L2ChainId, LogQuery, MiniblockNumber, Nonce,
+    ProtocolVersionId, StorageLog, StorageLogQuery, StorageLogQueryType, Timestamp, Transaction,
+    H256, L2_ETH_TOKEN_ADDRESS, U256,
 };
+use zksync_utils::u256_to_h256;
 
 pub(crate) use self::tester::TestBatchExecutorBuilder;
 use self::tester::{
@@ -37,6 +43,9 @@ use self::tester::{
 use crate::{
     gas_tracker::l1_batch_base_cost,
     state_keeper::{
+        batch_executor::{BatchExecutorHandle, TxExecutionResult},
+        extractors,
+        io::{common::l1_batch_params, MiniblockParams},
         keeper::POLL_WAIT_DURATION,
         seal_criteria::{
             criteria::{GasCriterion, SlotsCriterion},
@@ -133,6 +142,158 @@ pub(super) fn default_vm_block_result() -> FinishedL1Batch {
     }
 }
 
+/// Adds 10^32 wei to the balances of the specified accounts. Expects genesis to be performed.
+pub(crate) async fn fund(storage: &mut StorageProcessor<'_>, addresses: &[Address]) {
+    let eth_amount = U256::from(10u32).pow(U256::from(32)); //10^32 wei
+
+    for address in addresses {
+        let key = storage_key_for_standard_token_balance(
+            AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
+            address,
+        );
+        let value = u256_to_h256(eth_amount);
+        let storage_log = StorageLog::new_write_log(key, value);
+
+        storage
+            .storage_logs_dal()
+            .append_storage_logs(MiniblockNumber(0), &[(H256::zero(), vec![storage_log])])
+            .await;
+        storage
+            .storage_dal()
+            .apply_storage_logs(&[(H256::zero(), vec![storage_log])])
+            .await;
+        if storage
+            .storage_logs_dedup_dal()
+            .filter_written_slots(&[storage_log.key.hashed_key()])
+            .await
+            .is_empty()
+        {
+            storage
+                .storage_logs_dedup_dal()
+                .insert_initial_writes(L1BatchNumber(0), &[storage_log.key])
+                .await
+        }
+    }
+}
+
+/// Executes the provided transactions in a new L1 batch using the real batch executor and seals
+/// the batch in the same way as the state keeper does. All transactions are put into a single miniblock,
+/// which is followed by the fictive one. Returns the number of the sealed batch.
+pub(crate) async fn execute_and_seal_l1_batch(
+    pool: &ConnectionPool,
+    fee_account: Address,
+    txs: Vec<Transaction>,
+) -> L1BatchNumber {
+    let mut storage = pool.access_storage_tagged("state_keeper").await.unwrap();
+    let l1_batch_number = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap()
+        + 1;
+    let (prev_l1_batch_hash, _) =
+        extractors::wait_for_prev_l1_batch_params(&mut storage, l1_batch_number).await;
+    let prev_miniblock = storage
+        .blocks_dal()
+        .get_last_sealed_miniblock_header()
+        .await
+        .unwrap()
+        .expect("no sealed miniblocks");
+    let timestamp = prev_miniblock.timestamp + 1;
+    let (base_system_contracts, protocol_version) = storage
+        .protocol_versions_dal()
+        .base_system_contracts_by_timestamp(timestamp)
+        .await;
+
+    let (system_env, l1_batch_env) = l1_batch_params(
+        l1_batch_number,
+        fee_account,
+        timestamp,
+        prev_l1_batch_hash,
+        1,
+        1,
+        prev_miniblock.number + 1,
+        prev_miniblock.hash,
+        base_system_contracts,
+        BLOCK_GAS_LIMIT,
+        protocol_version,
+        1,
+        L2ChainId::default(),
+    );
+
+    let db_dir = TempDir::new().unwrap();
+    let mut secondary_storage = RocksdbStorage::new(db_dir.path());
+    secondary_storage.update_from_postgres(&mut storage).await;
+    let batch_executor = BatchExecutorHandle::new(
+        false,
+        StateKeeperConfig::for_tests()
+            .max_allowed_l2_tx_gas_limit
+            .into(),
+        secondary_storage,
+        l1_batch_env.clone(),
+        system_env.clone(),
+        false,
+    );
+    let mut updates_manager = UpdatesManager::new(
+        l1_batch_env.clone(),
+        system_env.base_system_smart_contracts.hashes(),
+        protocol_version,
+    );
+
+    for tx in txs {
+        let TxExecutionResult::Success {
+            tx_result,
+            tx_metrics,
+            compressed_bytecodes,
+            call_tracer_result,
+            ..
+        } = batch_executor.execute_tx(tx.clone()).await
+        else {
+            panic!("transaction {:?} was not executed successfully", tx.hash());
+        };
+        updates_manager.extend_from_executed_transaction(
+            tx,
+            *tx_result,
+            compressed_bytecodes,
+            tx_metrics.l1_gas,
+            tx_metrics.execution_metrics,
+            call_tracer_result,
+        );
+    }
+
+    let miniblock_number = MiniblockNumber(updates_manager.miniblock.number);
+    updates_manager
+        .seal_miniblock_command(
+            l1_batch_number,
+            miniblock_number,
+            Address::default(),
+            None,
+            true,
+        )
+        .seal(&mut storage)
+        .await;
+    updates_manager.push_miniblock(MiniblockParams {
+        timestamp: timestamp + 1,
+        virtual_blocks: 1,
+    });
+    batch_executor
+        .start_next_miniblock(updates_manager.miniblock.get_miniblock_env())
+        .await;
+
+    let (finished_batch, _) = batch_executor.finish_batch().await;
+    updates_manager
+        .seal_l1_batch(
+            &mut storage,
+            miniblock_number + 1,
+            &l1_batch_env,
+            finished_batch,
+            Address::default(),
+            None,
+        )
+        .await;
+    l1_batch_number
+}
+
 pub(super) fn create_updates_manager() -> UpdatesManager {
     let l1_batch_env = default_l1_batch_env(1, 1, Address::default());
     UpdatesManager::new(
@@ -1,6 +1,7 @@
 use zksync_config::{
     configs::{
         api::{HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig},
+        basic_witness_input_producer::BasicWitnessInputProducerConfig,
         chain::{
             CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
             StateKeeperConfig,
@@ -8,7 +9,7 @@ use zksync_config::{
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
         FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig,
-        ProofDataHandlerConfig, ProverGroupConfig, WitnessGeneratorConfig,
+        ProofDataHandlerConfig, ProverGroupConfig, PruningConfig, WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     FetcherConfig, GasAdjusterConfig, ObjectStoreConfig, PostgresConfig, ProverConfigs,
@@ -36,6 +37,8 @@ pub struct TempConfigStore {
     pub prometheus_config: Option<PrometheusConfig>,
     pub proof_data_handler_config: Option<ProofDataHandlerConfig>,
     pub prover_group_config: Option<ProverGroupConfig>,
+    pub pruning_config: Option<PruningConfig>,
+    pub basic_witness_input_producer_config: Option<BasicWitnessInputProducerConfig>,
     pub witness_generator_config: Option<WitnessGeneratorConfig>,
     pub api_config: Option<ApiConfig>,
     pub contracts_config: Option<ContractsConfig>,
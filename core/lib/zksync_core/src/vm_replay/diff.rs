@@ -0,0 +1,246 @@
+//! Comparison of L1 batch execution outputs.
+
+use std::{collections::HashSet, fmt};
+
+use serde::Serialize;
+
+use super::L1BatchOutputs;
+
+/// Kind of L1 batch execution outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputKind {
+    StorageWrites,
+    Events,
+    StorageRefunds,
+    TxRefunds,
+    UserL2ToL1Logs,
+    SystemLogs,
+    UsedContractHashes,
+    BootloaderMemory,
+}
+
+/// Single mismatch between expected and actual outputs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Mismatch {
+    pub kind: OutputKind,
+    pub message: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "[{:?}] {}", self.kind, self.message)
+    }
+}
+
+/// Differences between two sets of L1 batch execution outputs.
+#[derive(Debug, Default, Serialize)]
+pub struct L1BatchDiff {
+    pub mismatches: Vec<Mismatch>,
+    /// Outputs that weren't compared because they are missing from either side.
+    pub skipped: Vec<OutputKind>,
+}
+
+impl L1BatchDiff {
+    /// Compares `actual` outputs against the `expected` ones.
+    pub fn new(expected: &L1BatchOutputs, actual: &L1BatchOutputs) -> Self {
+        let mut diff = Self::default();
+        diff.compare_storage_writes(expected, actual);
+        diff.compare_optional_seqs(
+            OutputKind::Events,
+            expected.events_queue.as_deref(),
+            actual.events_queue.as_deref(),
+        );
+        diff.compare_optional_seqs(
+            OutputKind::StorageRefunds,
+            expected.storage_refunds.as_deref(),
+            actual.storage_refunds.as_deref(),
+        );
+        diff.compare_seqs(
+            OutputKind::TxRefunds,
+            &expected.tx_refunds,
+            &actual.tx_refunds,
+        );
+        diff.compare_seqs(
+            OutputKind::UserL2ToL1Logs,
+            &expected.user_l2_to_l1_logs,
+            &actual.user_l2_to_l1_logs,
+        );
+        diff.compare_seqs(
+            OutputKind::SystemLogs,
+            &expected.system_logs,
+            &actual.system_logs,
+        );
+        diff.compare_seqs(
+            OutputKind::UsedContractHashes,
+            &expected.used_contract_hashes,
+            &actual.used_contract_hashes,
+        );
+        diff.compare_optional_seqs(
+            OutputKind::BootloaderMemory,
+            expected.bootloader_memory.as_deref(),
+            actual.bootloader_memory.as_deref(),
+        );
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    fn push(&mut self, kind: OutputKind, message: String) {
+        self.mismatches.push(Mismatch { kind, message });
+    }
+
+    fn compare_storage_writes(&mut self, expected: &L1BatchOutputs, actual: &L1BatchOutputs) {
+        let modified_keys: HashSet<_> = expected
+            .storage_writes
+            .keys()
+            .chain(actual.storage_writes.keys())
+            .collect();
+        let mut modified_keys: Vec<_> = modified_keys.into_iter().collect();
+        modified_keys.sort_unstable_by_key(|key| key.hashed_key());
+
+        for key in modified_keys {
+            let expected_value = expected
+                .storage_writes
+                .get(key)
+                .or_else(|| expected.unchanged_slots.get(key));
+            let actual_value = actual
+                .storage_writes
+                .get(key)
+                .or_else(|| actual.unchanged_slots.get(key));
+            if expected_value != actual_value {
+                self.push(
+                    OutputKind::StorageWrites,
+                    format!(
+                        "slot {:?} of {:?}: expected {expected_value:?}, got {actual_value:?}",
+                        key.key(),
+                        key.address()
+                    ),
+                );
+            }
+        }
+    }
+
+    fn compare_optional_seqs<T: PartialEq + fmt::Debug>(
+        &mut self,
+        kind: OutputKind,
+        expected: Option<&[T]>,
+        actual: Option<&[T]>,
+    ) {
+        if let (Some(expected), Some(actual)) = (expected, actual) {
+            self.compare_seqs(kind, expected, actual);
+        } else {
+            self.skipped.push(kind);
+        }
+    }
+
+    fn compare_seqs<T: PartialEq + fmt::Debug>(
+        &mut self,
+        kind: OutputKind,
+        expected: &[T],
+        actual: &[T],
+    ) {
+        if expected.len() != actual.len() {
+            self.push(
+                kind,
+                format!("expected {} entries, got {}", expected.len(), actual.len()),
+            );
+        }
+        let first_mismatch = expected
+            .iter()
+            .zip(actual)
+            .position(|(expected, actual)| expected != actual);
+        if let Some(idx) = first_mismatch {
+            self.push(
+                kind,
+                format!(
+                    "first mismatch at entry #{idx}: expected {:?}, got {:?}",
+                    expected[idx], actual[idx]
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zksync_types::{AccountTreeId, Address, StorageKey, H256};
+
+    use super::*;
+
+    fn storage_key(index: u64) -> StorageKey {
+        StorageKey::new(
+            AccountTreeId::new(Address::repeat_byte(1)),
+            H256::from_low_u64_be(index),
+        )
+    }
+
+    #[test]
+    fn storage_writes_are_compared_with_unchanged_slots() {
+        let expected = L1BatchOutputs {
+            storage_writes: HashMap::from([
+                (storage_key(1), H256::repeat_byte(1)),
+                (storage_key(2), H256::zero()),
+            ]),
+            ..L1BatchOutputs::default()
+        };
+        let mut actual = L1BatchOutputs {
+            storage_writes: HashMap::from([(storage_key(1), H256::repeat_byte(1))]),
+            unchanged_slots: HashMap::from([
+                (storage_key(2), H256::zero()),
+                (storage_key(3), H256::zero()),
+            ]),
+            ..L1BatchOutputs::default()
+        };
+        let diff = L1BatchDiff::new(&expected, &actual);
+        assert!(diff.is_empty(), "{diff:?}");
+        assert_eq!(
+            diff.skipped,
+            [
+                OutputKind::Events,
+                OutputKind::StorageRefunds,
+                OutputKind::BootloaderMemory
+            ]
+        );
+
+        actual
+            .storage_writes
+            .insert(storage_key(4), H256::repeat_byte(4));
+        actual.unchanged_slots.remove(&storage_key(2));
+        let diff = L1BatchDiff::new(&expected, &actual);
+        assert_eq!(diff.mismatches.len(), 2, "{diff:?}");
+        assert!(diff
+            .mismatches
+            .iter()
+            .all(|mismatch| mismatch.kind == OutputKind::StorageWrites));
+    }
+
+    #[test]
+    fn sequences_are_compared() {
+        let expected = L1BatchOutputs {
+            storage_refunds: Some(vec![1, 2, 3]),
+            tx_refunds: vec![(H256::zero(), 100)],
+            ..L1BatchOutputs::default()
+        };
+        let actual = L1BatchOutputs {
+            storage_refunds: Some(vec![1, 5]),
+            tx_refunds: vec![(H256::zero(), 100)],
+            ..L1BatchOutputs::default()
+        };
+        let diff = L1BatchDiff::new(&expected, &actual);
+        let kinds: Vec<_> = diff
+            .mismatches
+            .iter()
+            .map(|mismatch| mismatch.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [OutputKind::StorageRefunds, OutputKind::StorageRefunds]
+        );
+        assert!(diff.mismatches[1].message.contains("entry #1"));
+    }
+}
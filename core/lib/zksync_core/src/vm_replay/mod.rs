@@ -0,0 +1,342 @@
+//! Re-execution of sealed L1 batches in the VM, used to check that the VM produces the same outputs
+//! as the ones persisted in Postgres, or to compare behavior of different VM versions.
+
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use multivm::{
+    interface::{
+        BootloaderMemory, FinishedL1Batch, L1BatchEnv, L2BlockEnv, SystemEnv, VmInterface,
+        VmInterfaceHistoryEnabled,
+    },
+    vm_latest::HistoryEnabled,
+    VmInstance,
+};
+use tokio::runtime::Handle;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_state::{PostgresStorage, StorageView, WriteStorage};
+use zksync_types::{
+    l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
+    AccountTreeId, L1BatchNumber, L2ChainId, LogQuery, MiniblockNumber, StorageKey,
+    StorageLogQuery, Transaction, VmVersion, H256, U256,
+};
+use zksync_utils::u256_to_h256;
+
+pub use self::diff::{L1BatchDiff, Mismatch, OutputKind};
+use crate::state_keeper::io::common::load_l1_batch_params;
+
+mod diff;
+#[cfg(test)]
+mod tests;
+
+/// Outputs of L1 batch execution compared by [`VmReplayer`].
+#[derive(Debug, Clone, Default)]
+pub struct L1BatchOutputs {
+    /// Final values of storage slots modified in the batch.
+    pub storage_writes: HashMap<StorageKey, H256>,
+    /// Values of storage slots that were written to in the batch, but have the same values
+    /// as before the batch. Such slots may or may not be persisted in storage logs.
+    pub unchanged_slots: HashMap<StorageKey, H256>,
+    /// Sorted and deduplicated events queue. `None` if it's not persisted for the batch.
+    pub events_queue: Option<Vec<LogQuery>>,
+    /// Refunds for storage writes. `None` if they are not persisted for the batch.
+    pub storage_refunds: Option<Vec<u32>>,
+    /// Refunded gas for each transaction in the batch in the execution order.
+    pub tx_refunds: Vec<(H256, u64)>,
+    pub user_l2_to_l1_logs: Vec<UserL2ToL1Log>,
+    pub system_logs: Vec<SystemL2ToL1Log>,
+    pub used_contract_hashes: Vec<U256>,
+    /// Final bootloader memory. `None` for old VM versions.
+    pub bootloader_memory: Option<BootloaderMemory>,
+}
+
+impl L1BatchOutputs {
+    fn from_vm(finished_batch: FinishedL1Batch, tx_refunds: Vec<(H256, u64)>) -> Self {
+        let state = finished_batch.final_execution_state;
+        let (storage_writes, unchanged_slots) = final_storage_values(&state.storage_log_queries);
+        Self {
+            storage_writes,
+            unchanged_slots,
+            events_queue: Some(state.deduplicated_events_logs),
+            storage_refunds: Some(state.storage_refunds),
+            tx_refunds,
+            user_l2_to_l1_logs: state.user_l2_to_l1_logs,
+            system_logs: state.system_logs,
+            used_contract_hashes: state.used_contract_hashes,
+            bootloader_memory: finished_batch.final_bootloader_memory,
+        }
+    }
+}
+
+/// Splits storage slots written to by the VM into modified and unchanged ones, taking rollbacks
+/// into account.
+fn final_storage_values(
+    storage_logs: &[StorageLogQuery],
+) -> (HashMap<StorageKey, H256>, HashMap<StorageKey, H256>) {
+    let mut initial_values = HashMap::new();
+    let mut final_values = HashMap::new();
+    for log in storage_logs.iter().filter(|log| log.log_query.rw_flag) {
+        let query = &log.log_query;
+        let key = StorageKey::new(AccountTreeId::new(query.address), u256_to_h256(query.key));
+        initial_values.entry(key).or_insert(query.read_value);
+        let value = if query.rollback {
+            query.read_value
+        } else {
+            query.written_value
+        };
+        final_values.insert(key, value);
+    }
+
+    final_values
+        .into_iter()
+        .map(|(key, value)| (key, u256_to_h256(value), initial_values[&key] == value))
+        .fold(
+            (HashMap::new(), HashMap::new()),
+            |(mut modified, mut unchanged), (key, value, is_unchanged)| {
+                if is_unchanged {
+                    unchanged.insert(key, value);
+                } else {
+                    modified.insert(key, value);
+                }
+                (modified, unchanged)
+            },
+        )
+}
+
+/// Data necessary to re-execute an L1 batch.
+#[derive(Debug)]
+struct L1BatchReplayData {
+    system_env: SystemEnv,
+    l1_batch_env: L1BatchEnv,
+    /// Environments of miniblocks after the first one (including the fictive miniblock).
+    next_miniblocks: Vec<L2BlockEnv>,
+    txs_by_miniblock: HashMap<MiniblockNumber, Vec<Transaction>>,
+    /// Last miniblock of the previous L1 batch; the VM storage is a snapshot at this miniblock.
+    prev_miniblock_number: MiniblockNumber,
+}
+
+/// Re-executes sealed L1 batches on top of the Postgres storage.
+#[derive(Debug)]
+pub struct VmReplayer {
+    pool: ConnectionPool,
+    l2_chain_id: L2ChainId,
+}
+
+impl VmReplayer {
+    pub fn new(pool: ConnectionPool, l2_chain_id: L2ChainId) -> Self {
+        Self { pool, l2_chain_id }
+    }
+
+    /// Loads outputs of the specified L1 batch persisted in Postgres.
+    pub async fn load_persisted_outputs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<L1BatchOutputs> {
+        let mut storage = self.pool.access_storage_tagged("vm_replay").await?;
+        let header = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not sealed"))?;
+        let storage_writes = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_l1_batch(l1_batch_number)
+            .await;
+        let events_queue = storage
+            .blocks_dal()
+            .get_events_queue(l1_batch_number)
+            .await?;
+        let storage_refunds = storage
+            .blocks_dal()
+            .get_storage_refunds(l1_batch_number)
+            .await?;
+        let tx_refunds = storage
+            .transactions_dal()
+            .get_refunded_gas_for_l1_batch(l1_batch_number)
+            .await?;
+        let bootloader_memory = storage
+            .blocks_dal()
+            .get_initial_bootloader_heap(l1_batch_number)
+            .await?;
+
+        Ok(L1BatchOutputs {
+            storage_writes,
+            unchanged_slots: HashMap::new(),
+            events_queue,
+            storage_refunds,
+            tx_refunds,
+            user_l2_to_l1_logs: header.l2_to_l1_logs,
+            system_logs: header.system_logs,
+            used_contract_hashes: header.used_contract_hashes,
+            bootloader_memory,
+        })
+    }
+
+    /// Re-executes the specified L1 batch. If `vm_version` is not specified, the batch is executed
+    /// using the VM version corresponding to its protocol version.
+    pub async fn replay(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        vm_version: Option<VmVersion>,
+    ) -> anyhow::Result<L1BatchOutputs> {
+        anyhow::ensure!(
+            l1_batch_number > L1BatchNumber(0),
+            "genesis L1 batch cannot be replayed"
+        );
+        let mut storage = self.pool.access_storage_tagged("vm_replay").await?;
+        let replay_data = self
+            .load_replay_data(&mut storage, l1_batch_number)
+            .await
+            .context("failed loading L1 batch data")?;
+        drop(storage);
+
+        let vm_version = vm_version.unwrap_or_else(|| replay_data.system_env.version.into());
+        tracing::info!("Replaying L1 batch #{l1_batch_number} using VM version {vm_version:?}");
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || Self::execute(pool, replay_data, vm_version))
+            .await
+            .context("VM execution panicked")?
+    }
+
+    async fn load_replay_data(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<L1BatchReplayData> {
+        let (_, prev_miniblock_number) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number - 1)
+            .await?
+            .with_context(|| format!("L1 batch #{} has no miniblocks", l1_batch_number - 1))?;
+        let (first_miniblock_number, last_miniblock_number) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} has no miniblocks"))?;
+        let fee_account = storage
+            .blocks_dal()
+            .get_fee_address_for_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} has no fee account"))?;
+
+        // The batch was already executed by the state keeper, so we don't want to reject
+        // any transactions based on the computational gas limit.
+        let (system_env, l1_batch_env) = load_l1_batch_params(
+            storage,
+            l1_batch_number,
+            fee_account,
+            u32::MAX,
+            self.l2_chain_id,
+        )
+        .await
+        .with_context(|| format!("failed loading params for L1 batch #{l1_batch_number}"))?;
+
+        let mut next_miniblocks = vec![];
+        let mut prev_block_hash = l1_batch_env.first_l2_block.prev_block_hash;
+        for number in first_miniblock_number.0..=last_miniblock_number.0 {
+            let header = storage
+                .blocks_dal()
+                .get_miniblock_header(MiniblockNumber(number))
+                .await?
+                .with_context(|| format!("miniblock #{number} is missing"))?;
+            if number > first_miniblock_number.0 {
+                next_miniblocks.push(L2BlockEnv {
+                    number,
+                    timestamp: header.timestamp,
+                    prev_block_hash,
+                    max_virtual_blocks_to_create: header.virtual_blocks,
+                });
+            }
+            prev_block_hash = header.hash;
+        }
+
+        let txs_by_miniblock = storage
+            .transactions_dal()
+            .get_miniblocks_to_execute_for_l1_batch(l1_batch_number)
+            .await?
+            .into_iter()
+            .map(|miniblock| (miniblock.number, miniblock.txs))
+            .collect();
+
+        Ok(L1BatchReplayData {
+            system_env,
+            l1_batch_env,
+            next_miniblocks,
+            txs_by_miniblock,
+            prev_miniblock_number,
+        })
+    }
+
+    fn execute(
+        pool: ConnectionPool,
+        mut replay_data: L1BatchReplayData,
+        vm_version: VmVersion,
+    ) -> anyhow::Result<L1BatchOutputs> {
+        let rt_handle = Handle::current();
+        let connection = rt_handle.block_on(pool.access_storage_tagged("vm_replay"))?;
+        let storage = PostgresStorage::new(
+            rt_handle,
+            connection,
+            replay_data.prev_miniblock_number,
+            true,
+        );
+        let storage_view = StorageView::new(storage).to_rc_ptr();
+
+        let first_miniblock_number =
+            MiniblockNumber(replay_data.l1_batch_env.first_l2_block.number);
+        let mut vm = VmInstance::new_with_specific_version(
+            replay_data.l1_batch_env,
+            replay_data.system_env,
+            storage_view,
+            vm_version,
+        );
+
+        let mut tx_refunds = vec![];
+        let mut miniblock_number = first_miniblock_number;
+        let mut next_miniblocks = replay_data.next_miniblocks.into_iter();
+        loop {
+            let txs = replay_data
+                .txs_by_miniblock
+                .remove(&miniblock_number)
+                .unwrap_or_default();
+            tracing::debug!(
+                "Replaying {} transactions in miniblock #{miniblock_number}",
+                txs.len()
+            );
+            for tx in txs {
+                let tx_hash = tx.hash();
+                let gas_refunded = execute_tx(tx, &mut vm)
+                    .with_context(|| format!("failed executing transaction {tx_hash:?}"))?;
+                tx_refunds.push((tx_hash, gas_refunded.into()));
+            }
+
+            let Some(next_miniblock) = next_miniblocks.next() else {
+                break;
+            };
+            miniblock_number = MiniblockNumber(next_miniblock.number);
+            vm.start_new_l2_block(next_miniblock);
+        }
+
+        let finished_batch = vm.finish_batch();
+        Ok(L1BatchOutputs::from_vm(finished_batch, tx_refunds))
+    }
+}
+
+/// Executes a transaction in the same way as the state keeper does, i.e., falling back
+/// to execution without bytecode compression if compression fails. Returns the refunded gas.
+fn execute_tx<S: WriteStorage>(
+    tx: Transaction,
+    vm: &mut VmInstance<S, HistoryEnabled>,
+) -> anyhow::Result<u32> {
+    vm.make_snapshot();
+    if let Ok(result) = vm.execute_transaction_with_bytecode_compression(tx.clone(), true) {
+        vm.pop_snapshot_no_rollback();
+        return Ok(result.refunds.gas_refunded);
+    }
+
+    vm.rollback_to_the_latest_snapshot();
+    let result = vm
+        .execute_transaction_with_bytecode_compression(tx, false)
+        .map_err(|err| anyhow::anyhow!("compression can't fail if we don't apply it: {err}"))?;
+    Ok(result.refunds.gas_refunded)
+}
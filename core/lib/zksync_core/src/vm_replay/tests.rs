@@ -0,0 +1,59 @@
+//! Tests for L1 batch replaying.
+
+use zksync_test_account::Account;
+use zksync_types::{fee::Fee, Address, Execute, SYSTEM_CONTEXT_MINIMAL_BASE_FEE};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    state_keeper::tests::{execute_and_seal_l1_batch, fund},
+};
+
+fn transfer(account: &mut Account) -> Transaction {
+    let fee = Fee {
+        gas_limit: U256::from(1_000_000),
+        max_fee_per_gas: SYSTEM_CONTEXT_MINIMAL_BASE_FEE.into(),
+        max_priority_fee_per_gas: U256::zero(),
+        gas_per_pubdata_limit: U256::from(100),
+    };
+    account.get_l2_tx_for_execute(
+        Execute {
+            contract_address: Address::random(),
+            calldata: vec![],
+            value: 1_000.into(),
+            factory_deps: None,
+        },
+        Some(fee),
+    )
+}
+
+#[tokio::test]
+async fn replaying_sealed_l1_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let mut account = Account::random();
+    fund(&mut storage, &[account.address]).await;
+    drop(storage);
+
+    let txs = (0..3).map(|_| transfer(&mut account)).collect();
+    let l1_batch_number = execute_and_seal_l1_batch(&pool, Address::repeat_byte(1), txs).await;
+    assert_eq!(l1_batch_number, L1BatchNumber(1));
+
+    let replayer = VmReplayer::new(pool, L2ChainId::default());
+    let persisted = replayer
+        .load_persisted_outputs(l1_batch_number)
+        .await
+        .unwrap();
+    assert_eq!(persisted.tx_refunds.len(), 3);
+    let replayed = replayer.replay(l1_batch_number, None).await.unwrap();
+
+    let diff = L1BatchDiff::new(&persisted, &replayed);
+    assert!(diff.mismatches.is_empty(), "{:?}", diff.mismatches);
+    assert!(diff.skipped.is_empty(), "{:?}", diff.skipped);
+
+    let err = replayer.replay(L1BatchNumber(0), None).await.unwrap_err();
+    assert!(err.to_string().contains("genesis"), "{err}");
+}
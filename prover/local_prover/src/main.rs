@@ -0,0 +1,113 @@
+#![feature(generic_const_exprs)]
+
+use std::env;
+
+use anyhow::Context as _;
+use structopt::StructOpt;
+use tokio::sync::watch;
+use zksync_config::{
+    configs::{
+        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, PostgresConfig,
+    },
+    ObjectStoreConfig,
+};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::{
+    proofs::PrepareBasicCircuitsJob, protocol_version::FriProtocolVersionId, L1BatchNumber,
+};
+use zksync_vk_setup_data_server_fri::commitment_utils::get_cached_commitments;
+
+use crate::pipeline::LocalProvingPipeline;
+
+mod pipeline;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "zksync_local_prover",
+    about = "CPU-only prover running the whole FRI proving pipeline for an L1 batch in-process"
+)]
+struct Opt {
+    /// Number of the L1 batch to prove.
+    #[structopt(long = "l1_batch_number")]
+    l1_batch_number: u32,
+    /// Stop after the scheduler proof is generated instead of compressing it.
+    #[structopt(long = "skip_compression")]
+    skip_compression: bool,
+    /// Maximum number of passes over the proving pipeline.
+    #[structopt(long = "max_passes", default_value = "32")]
+    max_passes: usize,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
+    let log_format = vlog::log_format_from_env();
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let opt = Opt::from_args();
+    let l1_batch_number = L1BatchNumber(opt.l1_batch_number);
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let connection_pool = ConnectionPool::builder(
+        postgres_config.master_url()?,
+        postgres_config.max_connections()?,
+    )
+    .build()
+    .await
+    .context("failed to build a connection_pool")?;
+    let prover_connection_pool = ConnectionPool::builder(
+        postgres_config.prover_url()?,
+        postgres_config.max_connections()?,
+    )
+    .build()
+    .await
+    .context("failed to build a prover_connection_pool")?;
+
+    // Witness inputs are copied from the object store written by the server to the in-memory store
+    // used by all components.
+    let object_store_config =
+        ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+    let input: PrepareBasicCircuitsJob = ObjectStoreFactory::new(object_store_config)
+        .create_store()
+        .await
+        .get(l1_batch_number)
+        .await
+        .with_context(|| format!("failed loading witness inputs for L1 batch {l1_batch_number}"))?;
+
+    let compressor_config = if opt.skip_compression {
+        None
+    } else {
+        let config =
+            FriProofCompressorConfig::from_env().context("FriProofCompressorConfig::from_env()")?;
+        zksync_prover_utils::ensure_initial_setup_keys_present(
+            &config.universal_setup_path,
+            &config.universal_setup_download_url,
+        );
+        env::set_var("CRS_FILE", config.universal_setup_path.clone());
+        Some(config)
+    };
+    let pipeline = LocalProvingPipeline::new(
+        l1_batch_number,
+        ObjectStoreFactory::mock(),
+        connection_pool,
+        prover_connection_pool,
+        FriWitnessGeneratorConfig::from_env().context("FriWitnessGeneratorConfig::from_env()")?,
+        FriProverConfig::from_env().context("FriProverConfig::from_env()")?,
+        compressor_config,
+        FriProtocolVersionId::latest(),
+        get_cached_commitments(),
+    );
+    pipeline.register_batch(&input).await?;
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    ctrlc::set_handler(move || {
+        stop_sender.send(true).ok();
+    })
+    .expect("Error setting Ctrl+C handler"); // Setting handler should always succeed.
+
+    tracing::info!("Starting local prover for L1 batch {l1_batch_number}");
+    pipeline.run(stop_receiver, opt.max_passes).await
+}
@@ -0,0 +1,500 @@
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::{
+    FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig,
+};
+use zksync_dal::ConnectionPool;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_proof_fri_compressor::compressor::ProofCompressor;
+use zksync_prover_fri::prover_job_processor::{GeneratedSetupDataCache, Prover, SetupLoadMode};
+use zksync_queued_job_processor::JobProcessor;
+use zksync_types::{
+    proofs::{AggregationRound, PrepareBasicCircuitsJob},
+    protocol_version::{FriProtocolVersionId, L1VerifierConfig},
+    prover_dashboard::FriJobInfo,
+    L1BatchNumber,
+};
+use zksync_witness_generator::{
+    basic_circuits::BasicWitnessGenerator, leaf_aggregation::LeafAggregationWitnessGenerator,
+    node_aggregation::NodeAggregationWitnessGenerator, scheduler::SchedulerWitnessGenerator,
+};
+
+/// Drives all FRI proving components in a single process until an L1 batch is proven.
+///
+/// Each pass over the pipeline runs every component until it has no more jobs, and then moves
+/// jobs whose dependencies are satisfied to the queue, which is normally done by the house keeper.
+/// All components share an in-memory object store, so artifacts are lost once the process exits.
+pub(crate) struct LocalProvingPipeline {
+    l1_batch_number: L1BatchNumber,
+    store_factory: ObjectStoreFactory,
+    connection_pool: ConnectionPool,
+    prover_connection_pool: ConnectionPool,
+    witness_generator_config: FriWitnessGeneratorConfig,
+    prover_config: FriProverConfig,
+    /// `None` means that proofs are not compressed.
+    compressor_config: Option<FriProofCompressorConfig>,
+    protocol_version: FriProtocolVersionId,
+    vk_commitments: L1VerifierConfig,
+    setup_data_cache: GeneratedSetupDataCache,
+}
+
+impl LocalProvingPipeline {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        l1_batch_number: L1BatchNumber,
+        store_factory: ObjectStoreFactory,
+        connection_pool: ConnectionPool,
+        prover_connection_pool: ConnectionPool,
+        mut witness_generator_config: FriWitnessGeneratorConfig,
+        mut prover_config: FriProverConfig,
+        compressor_config: Option<FriProofCompressorConfig>,
+        protocol_version: FriProtocolVersionId,
+        vk_commitments: L1VerifierConfig,
+    ) -> Self {
+        // Sampling would make the witness generator skip the batch.
+        witness_generator_config.blocks_proving_percentage = None;
+        witness_generator_config.shall_save_to_public_bucket = false;
+        prover_config.shall_save_to_public_bucket = false;
+
+        Self {
+            l1_batch_number,
+            store_factory,
+            connection_pool,
+            prover_connection_pool,
+            witness_generator_config,
+            prover_config,
+            compressor_config,
+            protocol_version,
+            vk_commitments,
+            setup_data_cache: Arc::default(),
+        }
+    }
+
+    /// Saves witness inputs for the batch to the in-memory object store and queues the batch
+    /// for basic witness generation. This is what the prover gateway does in the regular setup.
+    pub async fn register_batch(&self, input: &PrepareBasicCircuitsJob) -> anyhow::Result<()> {
+        let blob_url = self
+            .store_factory
+            .create_store()
+            .await
+            .put(self.l1_batch_number, input)
+            .await
+            .context("failed saving witness inputs")?;
+
+        let mut storage = self
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection")?;
+        storage
+            .fri_protocol_versions_dal()
+            .save_prover_protocol_version(self.protocol_version, self.vk_commitments)
+            .await;
+        storage
+            .fri_witness_generator_dal()
+            .save_witness_inputs(self.l1_batch_number, &blob_url, self.protocol_version)
+            .await;
+        Ok(())
+    }
+
+    /// Runs the pipeline until the batch is proven, making at most `max_passes` passes over it.
+    /// Fails on the first failed job, since retrying is unlikely to help in a local setup.
+    pub async fn run(
+        &self,
+        stop_receiver: watch::Receiver<bool>,
+        max_passes: usize,
+    ) -> anyhow::Result<()> {
+        for pass in 1..=max_passes {
+            tracing::info!(
+                "Starting pass #{pass} over the proving pipeline for L1 batch {}",
+                self.l1_batch_number
+            );
+            self.advance_queues().await;
+            self.run_pass(stop_receiver.clone()).await?;
+            if *stop_receiver.borrow() {
+                anyhow::bail!("stop signal received before the batch was proven");
+            }
+
+            let jobs = self.load_jobs().await.context("load_jobs()")?;
+            if let Some((stage, job)) = jobs.iter().find(|(_, job)| job.status == "failed") {
+                anyhow::bail!(
+                    "{stage} job {} has failed: {}",
+                    job.id,
+                    job.error.as_deref().unwrap_or("unknown error")
+                );
+            }
+            if self.is_batch_proven(&jobs) {
+                tracing::info!(
+                    "L1 batch {} is proven after {pass} passes",
+                    self.l1_batch_number
+                );
+                return Ok(());
+            }
+        }
+        anyhow::bail!(
+            "L1 batch {} is not proven after {max_passes} passes over the proving pipeline",
+            self.l1_batch_number
+        )
+    }
+
+    async fn run_pass(&self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        // Setting the number of iterations makes the components exit once there are no jobs left.
+        const ALL_JOBS: Option<usize> = Some(usize::MAX);
+
+        let config = &self.witness_generator_config;
+        BasicWitnessGenerator::new(
+            config.clone(),
+            &self.store_factory,
+            None,
+            self.connection_pool.clone(),
+            self.prover_connection_pool.clone(),
+            vec![self.protocol_version],
+        )
+        .await
+        .run(stop_receiver.clone(), ALL_JOBS)
+        .await
+        .context("basic circuits witness generator")?;
+        LeafAggregationWitnessGenerator::new(
+            config.clone(),
+            &self.store_factory,
+            self.prover_connection_pool.clone(),
+            vec![self.protocol_version],
+        )
+        .await
+        .run(stop_receiver.clone(), ALL_JOBS)
+        .await
+        .context("leaf aggregation witness generator")?;
+        NodeAggregationWitnessGenerator::new(
+            config.clone(),
+            &self.store_factory,
+            self.prover_connection_pool.clone(),
+            vec![self.protocol_version],
+        )
+        .await
+        .run(stop_receiver.clone(), ALL_JOBS)
+        .await
+        .context("node aggregation witness generator")?;
+        SchedulerWitnessGenerator::new(
+            config.clone(),
+            &self.store_factory,
+            self.prover_connection_pool.clone(),
+            vec![self.protocol_version],
+        )
+        .await
+        .run(stop_receiver.clone(), ALL_JOBS)
+        .await
+        .context("scheduler witness generator")?;
+
+        let prover = Prover::new(
+            self.store_factory.create_store().await,
+            None,
+            self.prover_config.clone(),
+            self.prover_connection_pool.clone(),
+            SetupLoadMode::Generated(self.setup_data_cache.clone()),
+            vec![],
+            self.vk_commitments,
+        );
+        prover
+            .run(stop_receiver.clone(), ALL_JOBS)
+            .await
+            .context("prover")?;
+
+        if let Some(config) = &self.compressor_config {
+            let compressor = ProofCompressor::new(
+                self.store_factory.create_store().await,
+                self.prover_connection_pool.clone(),
+                config.compression_mode,
+                config.verify_wrapper_proof,
+                config.max_attempts,
+            );
+            compressor
+                .run(stop_receiver, ALL_JOBS)
+                .await
+                .context("proof compressor")?;
+        }
+        Ok(())
+    }
+
+    /// Performs the same queue transitions as the house keeper.
+    async fn advance_queues(&self) {
+        let mut storage = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut witness_dal = storage.fri_witness_generator_dal();
+        witness_dal
+            .move_leaf_aggregation_jobs_from_waiting_to_queued()
+            .await;
+        witness_dal.move_depth_zero_node_aggregation_jobs().await;
+        witness_dal
+            .move_depth_non_zero_node_aggregation_jobs()
+            .await;
+
+        let l1_batch_numbers = storage
+            .fri_scheduler_dependency_tracker_dal()
+            .get_l1_batches_ready_for_queuing()
+            .await;
+        for &l1_batch_number in &l1_batch_numbers {
+            storage
+                .fri_witness_generator_dal()
+                .mark_scheduler_jobs_as_queued(l1_batch_number)
+                .await;
+        }
+        storage
+            .fri_scheduler_dependency_tracker_dal()
+            .mark_l1_batches_queued(l1_batch_numbers)
+            .await;
+    }
+
+    async fn load_jobs(&self) -> anyhow::Result<Vec<(&'static str, FriJobInfo)>> {
+        let mut storage = self
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection")?;
+        let mut jobs = vec![];
+        for (stage, round) in [
+            ("basic witness generation", AggregationRound::BasicCircuits),
+            ("leaf witness generation", AggregationRound::LeafAggregation),
+            ("node witness generation", AggregationRound::NodeAggregation),
+            ("scheduler witness generation", AggregationRound::Scheduler),
+        ] {
+            let round_jobs = storage
+                .fri_witness_generator_dal()
+                .get_witness_jobs_for_batch(round, self.l1_batch_number)
+                .await?;
+            jobs.extend(round_jobs.into_iter().map(|job| (stage, job)));
+        }
+
+        let prover_jobs = storage
+            .fri_prover_jobs_dal()
+            .get_prover_jobs_for_batch(self.l1_batch_number)
+            .await?;
+        jobs.extend(prover_jobs.into_iter().map(|(round, job)| {
+            let stage = if round == AggregationRound::Scheduler {
+                "scheduler proving"
+            } else {
+                "proving"
+            };
+            (stage, job)
+        }));
+
+        let compression_job = storage
+            .fri_proof_compressor_dal()
+            .get_proof_compression_job_for_batch(self.l1_batch_number)
+            .await?;
+        jobs.extend(compression_job.map(|job| ("compression", job)));
+        Ok(jobs)
+    }
+
+    fn is_batch_proven(&self, jobs: &[(&'static str, FriJobInfo)]) -> bool {
+        let final_stage = if self.compressor_config.is_some() {
+            "compression"
+        } else {
+            "scheduler proving"
+        };
+        jobs.iter()
+            .any(|(stage, job)| *stage == final_stage && job.status == "successful")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use zksync_config::configs::fri_prover::SetupLoadMode as SetupLoadModeConfig;
+
+    use super::*;
+
+    const L1_BATCH_NUMBER: L1BatchNumber = L1BatchNumber(1);
+
+    fn witness_generator_config() -> FriWitnessGeneratorConfig {
+        FriWitnessGeneratorConfig {
+            generation_timeout_in_secs: 60,
+            max_attempts: 1,
+            blocks_proving_percentage: Some(0),
+            dump_arguments_for_blocks: vec![],
+            last_l1_batch_to_process: None,
+            force_process_block: None,
+            shall_save_to_public_bucket: true,
+            input_cache_size_mb: Some(0),
+        }
+    }
+
+    fn prover_config() -> FriProverConfig {
+        FriProverConfig {
+            setup_data_path: String::new(),
+            prometheus_port: 0,
+            max_attempts: 1,
+            generation_timeout_in_secs: 60,
+            base_layer_circuit_ids_to_be_verified: vec![],
+            recursive_layer_circuit_ids_to_be_verified: vec![],
+            setup_load_mode: SetupLoadModeConfig::FromDisk,
+            specialized_group_id: 0,
+            witness_vector_generator_thread_count: None,
+            queue_capacity: 1,
+            witness_vector_receiver_port: 0,
+            job_lease_duration_in_secs: None,
+            shall_save_to_public_bucket: true,
+        }
+    }
+
+    async fn create_pipeline() -> LocalProvingPipeline {
+        // The master and prover databases are the same in the test setup.
+        let pool = ConnectionPool::test_pool().await;
+        let pipeline = LocalProvingPipeline::new(
+            L1_BATCH_NUMBER,
+            ObjectStoreFactory::mock(),
+            pool.clone(),
+            pool,
+            witness_generator_config(),
+            prover_config(),
+            None,
+            FriProtocolVersionId::latest(),
+            L1VerifierConfig::default(),
+        );
+        pipeline
+            .register_batch(&PrepareBasicCircuitsJob::new(1))
+            .await
+            .unwrap();
+        pipeline
+    }
+
+    #[tokio::test]
+    async fn registering_batch() {
+        let pipeline = create_pipeline().await;
+        // Settings that would prevent the batch from being proven are overridden.
+        assert_eq!(
+            pipeline.witness_generator_config.blocks_proving_percentage,
+            None
+        );
+        assert!(
+            !pipeline
+                .witness_generator_config
+                .shall_save_to_public_bucket
+        );
+        assert!(!pipeline.prover_config.shall_save_to_public_bucket);
+
+        let input: PrepareBasicCircuitsJob = pipeline
+            .store_factory
+            .create_store()
+            .await
+            .get(L1_BATCH_NUMBER)
+            .await
+            .unwrap();
+        assert_eq!(input.next_enumeration_index(), 1);
+
+        let jobs = pipeline.load_jobs().await.unwrap();
+        assert_eq!(jobs.len(), 1, "{jobs:?}");
+        let (stage, job) = &jobs[0];
+        assert_eq!(*stage, "basic witness generation");
+        assert_eq!(job.id, u64::from(L1_BATCH_NUMBER.0));
+        assert_eq!(job.status, "queued");
+        assert!(!pipeline.is_batch_proven(&jobs));
+
+        // Registering the batch again must not create duplicate jobs.
+        pipeline
+            .register_batch(&PrepareBasicCircuitsJob::new(1))
+            .await
+            .unwrap();
+        assert_eq!(pipeline.load_jobs().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pipeline_fails_on_failed_job() {
+        let pipeline = create_pipeline().await;
+        let mut storage = pipeline
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .unwrap();
+        storage
+            .fri_witness_generator_dal()
+            .mark_witness_job_failed("oops", L1_BATCH_NUMBER)
+            .await;
+        drop(storage);
+
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let err = pipeline.run(stop_receiver, 1).await.unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("basic witness generation"), "{err}");
+        assert!(err.contains("oops"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn pipeline_completes_once_scheduler_proof_is_generated() {
+        let pipeline = create_pipeline().await;
+        let mut storage = pipeline
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .unwrap();
+        storage
+            .fri_witness_generator_dal()
+            .mark_witness_job_as_successful(L1_BATCH_NUMBER, Duration::from_secs(1))
+            .await;
+        storage
+            .fri_prover_jobs_dal()
+            .insert_prover_job(
+                L1_BATCH_NUMBER,
+                1,
+                0,
+                0,
+                AggregationRound::Scheduler,
+                "scheduler_circuit",
+                false,
+                pipeline.protocol_version,
+            )
+            .await;
+        let jobs = pipeline.load_jobs().await.unwrap();
+        let (_, scheduler_job) = jobs
+            .iter()
+            .find(|(stage, _)| *stage == "scheduler proving")
+            .unwrap();
+        storage
+            .fri_prover_jobs_dal()
+            .save_proof(
+                scheduler_job.id as u32,
+                Duration::from_secs(1),
+                "scheduler_proof",
+            )
+            .await;
+        drop(storage);
+
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        pipeline.run(stop_receiver, 1).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn pipeline_gives_up_after_max_passes() {
+        let pipeline = create_pipeline().await;
+        // Emulate a job picked by another witness generator, so that the pipeline makes no progress.
+        let mut storage = pipeline
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .unwrap();
+        let job = storage
+            .fri_witness_generator_dal()
+            .get_next_basic_circuit_witness_job(u32::MAX, &[pipeline.protocol_version], "test")
+            .await;
+        assert_eq!(job, Some(L1_BATCH_NUMBER));
+        drop(storage);
+
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let err = pipeline.run(stop_receiver, 2).await.unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("not proven after 2 passes"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn pipeline_stops_on_signal() {
+        let pipeline = create_pipeline().await;
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        stop_sender.send_replace(true);
+        let err = pipeline.run(stop_receiver, 1).await.unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("stop signal"), "{err}");
+
+        // The queued job must not be picked after the stop signal.
+        let jobs = pipeline.load_jobs().await.unwrap();
+        assert_eq!(jobs[0].1.status, "queued");
+    }
+}
@@ -0,0 +1,2 @@
+pub mod compressor;
+mod metrics;
@@ -8,14 +8,10 @@ use zksync_config::configs::{FriProofCompressorConfig, PostgresConfig};
 use zksync_dal::ConnectionPool;
 use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
 use zksync_object_store::ObjectStoreFactory;
+use zksync_proof_fri_compressor::compressor::ProofCompressor;
 use zksync_queued_job_processor::JobProcessor;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
-use crate::compressor::ProofCompressor;
-
-mod compressor;
-mod metrics;
-
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "zksync_proof_fri_compressor",
@@ -1,6 +1,10 @@
 #[cfg(feature = "gpu")]
 pub mod gpu_prover {
-    use std::{collections::HashMap, sync::Arc, time::Instant};
+    use std::{
+        collections::HashMap,
+        sync::Arc,
+        time::{Duration, Instant},
+    };
 
     use anyhow::Context as _;
     use shivini::{gpu_prove_from_external_witness_data, ProverContext};
@@ -35,7 +39,7 @@ pub mod gpu_prover {
         metrics::METRICS,
         utils::{
             get_setup_data_key, save_proof, setup_metadata_to_setup_data_key, verify_proof,
-            GpuProverJob, ProverArtifacts, SharedWitnessVectorQueue,
+            GpuJobLeases, GpuProverJob, ProverArtifacts, SharedWitnessVectorQueue,
         },
     };
 
@@ -61,6 +65,7 @@ pub mod gpu_prover {
         prover_context: ProverContext,
         address: SocketAddress,
         zone: String,
+        leases: GpuJobLeases,
     }
 
     impl Prover {
@@ -88,6 +93,7 @@ pub mod gpu_prover {
                     .expect("failed initializing gpu prover context"),
                 address,
                 zone,
+                leases: GpuJobLeases::default(),
             }
         }
 
@@ -127,6 +133,7 @@ pub mod gpu_prover {
             let WitnessVectorArtifacts {
                 witness_vector,
                 prover_job,
+                ..
             } = witness_vector_artifacts;
 
             let (proof_config, circuit_id) = match &prover_job.circuit_wrapper {
@@ -215,19 +222,17 @@ pub mod gpu_prover {
                             )
                             .await;
                     }
-                    tracing::info!(
-                        "Started GPU proving for job: {:?}",
-                        item.witness_vector_artifacts.prover_job.job_id
-                    );
-                    Ok(Some((
-                        item.witness_vector_artifacts.prover_job.job_id,
-                        item,
-                    )))
+                    let job_id = item.witness_vector_artifacts.prover_job.job_id;
+                    tracing::info!("Started GPU proving for job: {job_id:?}");
+                    self.leases
+                        .insert(job_id, item.witness_vector_artifacts.picked_by.clone());
+                    Ok(Some((job_id, item)))
                 }
             }
         }
 
         async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
+            self.leases.remove(job_id);
             self.prover_connection_pool
                 .access_storage()
                 .await
@@ -260,6 +265,7 @@ pub mod gpu_prover {
             artifacts: Self::JobArtifacts,
         ) -> anyhow::Result<()> {
             METRICS.gpu_total_proving_time.observe(started_at.elapsed());
+            self.leases.remove(job_id);
 
             let mut storage_processor = self.prover_connection_pool.access_storage().await.unwrap();
             save_proof(
@@ -292,6 +298,34 @@ pub mod gpu_prover {
                 .map(|attempts| attempts.unwrap_or(0))
                 .context("failed to get job attempts for Prover")
         }
+
+        fn lease_duration(&self) -> Option<Duration> {
+            self.config.job_lease_duration()
+        }
+
+        async fn extend_lease(
+            &self,
+            job_id: &u32,
+            lease_duration: Duration,
+        ) -> anyhow::Result<bool> {
+            let mut storage = self
+                .prover_connection_pool
+                .access_storage()
+                .await
+                .context("failed to acquire DB connection for Prover")?;
+            self.leases
+                .extend(&mut storage, *job_id, lease_duration)
+                .await
+        }
+
+        async fn release_job(&self, job_id: u32) -> anyhow::Result<()> {
+            let mut storage = self
+                .prover_connection_pool
+                .access_storage()
+                .await
+                .context("failed to acquire DB connection for Prover")?;
+            self.leases.release(&mut storage, job_id).await
+        }
     }
 
     pub fn load_setup_data_cache(config: &FriProverConfig) -> anyhow::Result<SetupLoadMode> {
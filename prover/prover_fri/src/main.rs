@@ -1,11 +1,11 @@
 #![feature(generic_const_exprs)]
-use std::future::Future;
+use std::{future::Future, time::Duration};
 
 use anyhow::Context as _;
 use local_ip_address::local_ip;
 use prometheus_exporter::PrometheusExporterConfig;
 use tokio::{
-    sync::{oneshot, watch::Receiver},
+    sync::{mpsc, oneshot, watch::Receiver},
     task::JoinHandle,
 };
 use zksync_config::configs::{
@@ -32,6 +32,9 @@ mod prover_job_processor;
 mod socket_listener;
 mod utils;
 
+/// Maximum time given to the prover to release the job in progress on shutdown.
+const JOB_RELEASE_TIMEOUT: Duration = Duration::from_secs(5);
+
 async fn graceful_shutdown(port: u16) -> anyhow::Result<impl Future<Output = ()>> {
     let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
     let pool = ConnectionPool::singleton(postgres_config.prover_url()?)
@@ -127,9 +130,14 @@ async fn main() -> anyhow::Result<()> {
     .await
     .context("failed to build a connection pool")?;
     let port = prover_config.witness_vector_receiver_port;
+    let job_lease_duration = prover_config.job_lease_duration();
+    // Every job processor holds a sender until it returns, so the channel closes once all of them
+    // have stopped (and released their jobs, if leases are enabled).
+    let (processors_stopped_sender, mut processors_stopped) = mpsc::channel::<()>(1);
     let prover_tasks = get_prover_tasks(
         prover_config,
         stop_receiver.clone(),
+        processors_stopped_sender,
         object_store_factory,
         public_blob_store,
         pool,
@@ -159,13 +167,37 @@ async fn main() -> anyhow::Result<()> {
     }
 
     stop_sender.send(true).ok();
+    if job_lease_duration.is_some() {
+        // Wait for the prover to release the job in progress, so that it's re-queued immediately.
+        let stopped = tokio::time::timeout(JOB_RELEASE_TIMEOUT, processors_stopped.recv()).await;
+        if stopped.is_err() {
+            tracing::warn!(
+                "Job processors didn't stop in {JOB_RELEASE_TIMEOUT:?}; jobs in progress will be \
+                 re-queued once their leases expire"
+            );
+        }
+    }
     Ok(())
 }
 
+/// Spawns a job processor that drops `stopped_sender` once it returns.
+fn spawn_job_processor<P: JobProcessor + 'static>(
+    processor: P,
+    stop_receiver: Receiver<bool>,
+    stopped_sender: mpsc::Sender<()>,
+) -> JoinHandle<anyhow::Result<()>> {
+    tokio::spawn(async move {
+        let result = processor.run(stop_receiver, None).await;
+        drop(stopped_sender);
+        result
+    })
+}
+
 #[cfg(not(feature = "gpu"))]
 async fn get_prover_tasks(
     prover_config: FriProverConfig,
     stop_receiver: Receiver<bool>,
+    processors_stopped_sender: mpsc::Sender<()>,
     store_factory: ObjectStoreFactory,
     public_blob_store: Option<Box<dyn ObjectStore>>,
     pool: ConnectionPool,
@@ -193,13 +225,18 @@ async fn get_prover_tasks(
         circuit_ids_for_round_to_be_proven,
         vk_commitments,
     );
-    Ok(vec![tokio::spawn(prover.run(stop_receiver, None))])
+    Ok(vec![spawn_job_processor(
+        prover,
+        stop_receiver,
+        processors_stopped_sender,
+    )])
 }
 
 #[cfg(feature = "gpu")]
 async fn get_prover_tasks(
     prover_config: FriProverConfig,
     stop_receiver: Receiver<bool>,
+    processors_stopped_sender: mpsc::Sender<()>,
     store_factory: ObjectStoreFactory,
     public_blob_store: Option<Box<dyn ObjectStore>>,
     pool: ConnectionPool,
@@ -253,6 +290,6 @@ async fn get_prover_tasks(
     );
     Ok(vec![
         tokio::spawn(socket_listener.listen_incoming_connections(stop_receiver.clone())),
-        tokio::spawn(prover.run(stop_receiver, None)),
+        spawn_job_processor(prover, stop_receiver, processors_stopped_sender),
     ])
 }
@@ -1,6 +1,11 @@
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
+use once_cell::sync::OnceCell;
 use tokio::task::JoinHandle;
 use zkevm_test_harness::prover_utils::{prove_base_layer_circuit, prove_recursion_layer_circuit};
 use zksync_config::configs::{fri_prover_group::FriProverGroupConfig, FriProverConfig};
@@ -20,12 +25,13 @@ use zksync_prover_fri_types::{
         },
         recursion_layer_proof_config, ZkSyncDefaultRoundFunction,
     },
-    CircuitWrapper, FriProofWrapper, ProverJob, ProverServiceDataKey,
+    get_current_pod_name, CircuitWrapper, FriProofWrapper, ProverJob, ProverServiceDataKey,
 };
 use zksync_prover_fri_utils::fetch_next_circuit;
 use zksync_queued_job_processor::{async_trait, JobProcessor};
 use zksync_types::{basic_fri_types::CircuitIdRoundTuple, protocol_version::L1VerifierConfig};
 use zksync_vk_setup_data_server_fri::{
+    generate_cpu_base_layer_setup_data, generate_cpu_recursive_layer_setup_data,
     get_cpu_setup_data_for_circuit_type, GoldilocksProverSetupData,
 };
 
@@ -37,9 +43,19 @@ use crate::{
     },
 };
 
+/// Setup data generated by provers, keyed by circuit. Each entry is initialized at most once;
+/// the map lock is only held to look up or insert an entry, so that setup data for different
+/// circuits can be generated concurrently.
+pub type GeneratedSetupDataCache =
+    Arc<Mutex<HashMap<ProverServiceDataKey, Arc<OnceCell<Arc<GoldilocksProverSetupData>>>>>>;
+
 pub enum SetupLoadMode {
     FromMemory(HashMap<ProverServiceDataKey, Arc<GoldilocksProverSetupData>>),
     FromDisk,
+    /// Setup data is generated from the circuits of proven jobs and cached in memory; the cache
+    /// can be shared among provers. Doesn't require setup data keys on disk, but is slow,
+    /// so it's only intended for tests with tiny batches.
+    Generated(GeneratedSetupDataCache),
 }
 
 pub struct Prover {
@@ -76,11 +92,8 @@ impl Prover {
         }
     }
 
-    fn get_setup_data(
-        &self,
-        key: ProverServiceDataKey,
-    ) -> anyhow::Result<Arc<GoldilocksProverSetupData>> {
-        let key = get_setup_data_key(key);
+    fn get_setup_data(&self, job: &ProverJob) -> anyhow::Result<Arc<GoldilocksProverSetupData>> {
+        let key = get_setup_data_key(job.setup_data_key.clone());
         Ok(match &self.setup_load_mode {
             SetupLoadMode::FromMemory(cache) => cache
                 .get(&key)
@@ -96,6 +109,35 @@ impl Prover {
 
                 Arc::new(artifact)
             }
+            SetupLoadMode::Generated(cache) => {
+                let cell = cache
+                    .lock()
+                    .unwrap()
+                    .entry(key.clone())
+                    .or_default()
+                    .clone();
+                // Concurrent callers for the same circuit block until the data is generated,
+                // while callers for other circuits are not affected.
+                let setup_data = cell.get_or_try_init(|| {
+                    let started_at = Instant::now();
+                    let setup_data = match &job.circuit_wrapper {
+                        CircuitWrapper::Base(circuit) => {
+                            generate_cpu_base_layer_setup_data(circuit.clone())
+                                .context("generate_cpu_base_layer_setup_data()")?
+                        }
+                        CircuitWrapper::Recursive(circuit) => {
+                            generate_cpu_recursive_layer_setup_data(circuit.clone())
+                                .context("generate_cpu_recursive_layer_setup_data()")?
+                        }
+                    };
+                    tracing::info!(
+                        "Generated setup data for {key:?} in {:?}",
+                        started_at.elapsed()
+                    );
+                    anyhow::Ok(Arc::new(setup_data))
+                })?;
+                setup_data.clone()
+            }
         })
     }
 
@@ -221,13 +263,43 @@ impl JobProcessor for Prover {
             .await;
     }
 
+    fn lease_duration(&self) -> Option<Duration> {
+        self.config.job_lease_duration()
+    }
+
+    async fn extend_lease(&self, job_id: &u32, lease_duration: Duration) -> anyhow::Result<bool> {
+        let mut storage = self
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection for Prover")?;
+        storage
+            .fri_prover_jobs_dal()
+            .extend_lease(*job_id, lease_duration, &get_current_pod_name())
+            .await
+            .context("extend_lease()")
+    }
+
+    async fn release_job(&self, job_id: u32) -> anyhow::Result<()> {
+        let mut storage = self
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection for Prover")?;
+        storage
+            .fri_prover_jobs_dal()
+            .release_job(job_id, &get_current_pod_name())
+            .await
+            .context("release_job()")
+    }
+
     async fn process_job(
         &self,
         job: Self::Job,
         _started_at: Instant,
     ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
         let config = Arc::clone(&self.config);
-        let setup_data = self.get_setup_data(job.setup_data_key.clone());
+        let setup_data = self.get_setup_data(&job);
         tokio::task::spawn_blocking(move || {
             Ok(Self::prove(
                 job,
@@ -1,7 +1,12 @@
 #![cfg_attr(not(feature = "gpu"), allow(unused_imports))]
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use anyhow::Context as _;
 use tokio::sync::Mutex;
 use zkevm_test_harness::prover_utils::{verify_base_layer_proof, verify_recursion_layer_proof};
 use zksync_dal::StorageProcessor;
@@ -60,6 +65,63 @@ pub struct GpuProverJob {
     pub assembly: ProvingAssembly,
 }
 
+/// Keeps track of the pods that picked the jobs proven by a GPU prover. GPU jobs are picked
+/// by a witness vector generator, so their leases must be extended and released on its behalf.
+#[derive(Debug, Default)]
+pub struct GpuJobLeases {
+    picked_by: std::sync::Mutex<HashMap<u32, String>>,
+}
+
+impl GpuJobLeases {
+    pub fn insert(&self, job_id: u32, picked_by: String) {
+        self.picked_by.lock().unwrap().insert(job_id, picked_by);
+    }
+
+    pub fn remove(&self, job_id: u32) {
+        self.picked_by.lock().unwrap().remove(&job_id);
+    }
+
+    fn picked_by(&self, job_id: u32) -> Option<String> {
+        self.picked_by.lock().unwrap().get(&job_id).cloned()
+    }
+
+    /// Extends the lease for the job. Returns `false` if the job is unknown or was picked
+    /// by another pod in the meantime.
+    pub async fn extend(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        job_id: u32,
+        lease_duration: Duration,
+    ) -> anyhow::Result<bool> {
+        let Some(picked_by) = self.picked_by(job_id) else {
+            return Ok(false);
+        };
+        storage
+            .fri_prover_jobs_dal()
+            .extend_lease(job_id, lease_duration, &picked_by)
+            .await
+            .context("extend_lease()")
+    }
+
+    /// Re-queues the job and stops tracking it.
+    pub async fn release(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        job_id: u32,
+    ) -> anyhow::Result<()> {
+        let Some(picked_by) = self.picked_by(job_id) else {
+            return Ok(());
+        };
+        storage
+            .fri_prover_jobs_dal()
+            .release_job(job_id, &picked_by)
+            .await
+            .context("release_job()")?;
+        self.remove(job_id);
+        Ok(())
+    }
+}
+
 pub async fn save_proof(
     job_id: u32,
     started_at: Instant,
@@ -177,6 +239,9 @@ pub fn get_setup_data_key(key: ProverServiceDataKey) -> ProverServiceDataKey {
 
 #[cfg(test)]
 mod tests {
+    use zksync_dal::ConnectionPool;
+    use zksync_types::protocol_version::{FriProtocolVersionId, L1VerifierConfig};
+
     use super::*;
 
     #[test]
@@ -208,4 +273,77 @@ mod tests {
         // Check if the key has remained same
         assert_eq!(key, result);
     }
+
+    async fn job_status(storage: &mut StorageProcessor<'_>) -> String {
+        let jobs = storage
+            .fri_prover_jobs_dal()
+            .get_prover_jobs_for_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(jobs.len(), 1);
+        jobs[0].1.status.clone()
+    }
+
+    #[tokio::test]
+    async fn extending_and_releasing_gpu_job_leases() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let protocol_version = FriProtocolVersionId::latest();
+        storage
+            .fri_protocol_versions_dal()
+            .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+            .await;
+        storage
+            .fri_prover_jobs_dal()
+            .insert_prover_job(
+                L1BatchNumber(1),
+                1,
+                0,
+                0,
+                AggregationRound::BasicCircuits,
+                "circuit.bin",
+                false,
+                protocol_version,
+            )
+            .await;
+        let job = storage
+            .fri_prover_jobs_dal()
+            .get_next_job(&[protocol_version], "wvg-pod")
+            .await
+            .unwrap();
+        storage
+            .fri_prover_jobs_dal()
+            .update_status(job.id, "in_gpu_proof")
+            .await;
+
+        let leases = GpuJobLeases::default();
+        let lease_duration = Duration::from_secs(60);
+        // Leases for unknown jobs are never extended.
+        assert!(!leases
+            .extend(&mut storage, job.id, lease_duration)
+            .await
+            .unwrap());
+
+        leases.insert(job.id, "wvg-pod".to_owned());
+        assert!(leases
+            .extend(&mut storage, job.id, lease_duration)
+            .await
+            .unwrap());
+
+        let other_leases = GpuJobLeases::default();
+        other_leases.insert(job.id, "other-pod".to_owned());
+        assert!(!other_leases
+            .extend(&mut storage, job.id, lease_duration)
+            .await
+            .unwrap());
+        other_leases.release(&mut storage, job.id).await.unwrap();
+        assert_eq!(job_status(&mut storage).await, "in_gpu_proof");
+
+        leases.release(&mut storage, job.id).await.unwrap();
+        assert_eq!(job_status(&mut storage).await, "queued");
+        assert!(!leases
+            .extend(&mut storage, job.id, lease_duration)
+            .await
+            .unwrap());
+    }
 }
@@ -21,6 +21,7 @@ pub(crate) struct PeriodicApiStruct {
     pub(crate) api_url: String,
     pub(crate) poll_duration: Duration,
     pub(crate) client: Client,
+    pub(crate) api_key: Option<String>,
 }
 
 impl PeriodicApiStruct {
@@ -35,9 +36,11 @@ impl PeriodicApiStruct {
     {
         tracing::info!("Sending request to {}", endpoint);
 
-        self.client
-            .post(endpoint)
-            .json(&request)
+        let mut request_builder = self.client.post(endpoint).json(&request);
+        if let Some(api_key) = &self.api_key {
+            request_builder = request_builder.bearer_auth(api_key);
+        }
+        request_builder
             .send()
             .await?
             .error_for_status()?
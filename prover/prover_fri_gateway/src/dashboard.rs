@@ -0,0 +1,154 @@
+//! Read-only HTTP API reporting proving progress for L1 batches.
+
+use std::net::SocketAddr;
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
+use zksync_types::{
+    proofs::AggregationRound,
+    prover_dashboard::{FriBatchProvingInfo, FriJobInfo, FriProvingStage},
+    L1BatchNumber,
+};
+
+use crate::operator_api::OperatorApiError;
+
+const DEFAULT_BATCHES_LIMIT: u32 = 10;
+const MAX_BATCHES_LIMIT: u32 = 20;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct BatchesQuery {
+    from: u32,
+    limit: Option<u32>,
+}
+
+async fn load_batch_info(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+) -> Result<FriBatchProvingInfo, SqlxError> {
+    let mut witness_dal = storage.fri_witness_generator_dal();
+    let basic_witness_jobs = witness_dal
+        .get_witness_jobs_for_batch(AggregationRound::BasicCircuits, l1_batch_number)
+        .await?;
+    let leaf_witness_jobs = witness_dal
+        .get_witness_jobs_for_batch(AggregationRound::LeafAggregation, l1_batch_number)
+        .await?;
+    let node_witness_jobs = witness_dal
+        .get_witness_jobs_for_batch(AggregationRound::NodeAggregation, l1_batch_number)
+        .await?;
+    let scheduler_witness_jobs = witness_dal
+        .get_witness_jobs_for_batch(AggregationRound::Scheduler, l1_batch_number)
+        .await?;
+
+    let prover_jobs = storage
+        .fri_prover_jobs_dal()
+        .get_prover_jobs_for_batch(l1_batch_number)
+        .await?;
+    let prover_jobs_for_round = |round: AggregationRound| -> Vec<FriJobInfo> {
+        prover_jobs
+            .iter()
+            .filter(|(job_round, _)| *job_round == round)
+            .map(|(_, job)| job.clone())
+            .collect()
+    };
+
+    let compression_job = storage
+        .fri_proof_compressor_dal()
+        .get_proof_compression_job_for_batch(l1_batch_number)
+        .await?;
+
+    let stage_jobs = vec![
+        (FriProvingStage::BasicWitnessGeneration, basic_witness_jobs),
+        (
+            FriProvingStage::BasicCircuitsProving,
+            prover_jobs_for_round(AggregationRound::BasicCircuits),
+        ),
+        (FriProvingStage::LeafWitnessGeneration, leaf_witness_jobs),
+        (
+            FriProvingStage::LeafAggregationProving,
+            prover_jobs_for_round(AggregationRound::LeafAggregation),
+        ),
+        (FriProvingStage::NodeWitnessGeneration, node_witness_jobs),
+        (
+            FriProvingStage::NodeAggregationProving,
+            prover_jobs_for_round(AggregationRound::NodeAggregation),
+        ),
+        (
+            FriProvingStage::SchedulerWitnessGeneration,
+            scheduler_witness_jobs,
+        ),
+        (
+            FriProvingStage::SchedulerProving,
+            prover_jobs_for_round(AggregationRound::Scheduler),
+        ),
+        (
+            FriProvingStage::Compression,
+            compression_job.into_iter().collect(),
+        ),
+    ];
+    Ok(FriBatchProvingInfo::new(l1_batch_number, stage_jobs))
+}
+
+async fn get_batch(
+    State(pool): State<ConnectionPool>,
+    Path(l1_batch_number): Path<u32>,
+) -> Result<Json<FriBatchProvingInfo>, OperatorApiError> {
+    let mut storage = pool.access_storage().await.unwrap();
+    let info = load_batch_info(&mut storage, L1BatchNumber(l1_batch_number)).await?;
+    Ok(Json(info))
+}
+
+/// Returns proving progress for up to `limit` consecutive L1 batches starting from `from`.
+async fn get_batches(
+    State(pool): State<ConnectionPool>,
+    Query(query): Query<BatchesQuery>,
+) -> Result<Json<Vec<FriBatchProvingInfo>>, OperatorApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_BATCHES_LIMIT)
+        .min(MAX_BATCHES_LIMIT);
+    let mut storage = pool.access_storage().await.unwrap();
+    let mut batches = Vec::with_capacity(limit as usize);
+    for l1_batch_number in query.from..query.from.saturating_add(limit) {
+        let info = load_batch_info(&mut storage, L1BatchNumber(l1_batch_number)).await?;
+        batches.push(info);
+    }
+    Ok(Json(batches))
+}
+
+fn router(pool: ConnectionPool) -> Router {
+    Router::new()
+        .route("/batches", get(get_batches))
+        .route("/batches/:l1_batch_number", get(get_batch))
+        .with_state(pool)
+}
+
+/// Runs the dashboard API. Unlike the operator API, it doesn't change any state, so it's
+/// served on a separate port that can be exposed outside the host.
+pub(crate) async fn run_server(
+    port: u16,
+    pool: ConnectionPool,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let bind_address = SocketAddr::from(([0, 0, 0, 0], port));
+    tracing::debug!("Starting prover dashboard API server on {bind_address}");
+
+    axum::Server::bind(&bind_address)
+        .serve(router(pool).into_make_service())
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!("Stop signal sender for prover dashboard API server was dropped without sending a signal");
+            }
+            tracing::info!("Stop signal received, prover dashboard API server is shutting down");
+        })
+        .await
+        .context("Prover dashboard API server failed")?;
+    tracing::info!("Prover dashboard API server shut down");
+    Ok(())
+}
@@ -12,7 +12,9 @@ use zksync_utils::wait_for_tasks::wait_for_tasks;
 use crate::api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH};
 
 mod api_data_fetcher;
+mod dashboard;
 mod metrics;
+mod operator_api;
 mod proof_gen_data_fetcher;
 mod proof_submitter;
 
@@ -54,13 +56,15 @@ async fn main() -> anyhow::Result<()> {
         api_url: format!("{}{SUBMIT_PROOF_PATH}", config.api_url),
         poll_duration: config.api_poll_duration(),
         client: Client::new(),
+        api_key: config.api_key.clone(),
     };
     let proof_gen_data_fetcher = PeriodicApiStruct {
         blob_store: store_factory.create_store().await,
-        pool,
+        pool: pool.clone(),
         api_url: format!("{}{PROOF_GENERATION_DATA_PATH}", config.api_url),
         poll_duration: config.api_poll_duration(),
         client: Client::new(),
+        api_key: config.api_key.clone(),
     };
 
     let (stop_sender, stop_receiver) = watch::channel(false);
@@ -76,7 +80,7 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Starting Fri Prover Gateway");
 
-    let tasks = vec![
+    let mut tasks = vec![
         tokio::spawn(
             PrometheusExporterConfig::pull(config.prometheus_listener_port)
                 .run(stop_receiver.clone()),
@@ -84,8 +88,22 @@ async fn main() -> anyhow::Result<()> {
         tokio::spawn(
             proof_gen_data_fetcher.run::<ProofGenerationDataRequest>(stop_receiver.clone()),
         ),
-        tokio::spawn(proof_submitter.run::<SubmitProofRequest>(stop_receiver)),
+        tokio::spawn(proof_submitter.run::<SubmitProofRequest>(stop_receiver.clone())),
     ];
+    if let Some(port) = config.operator_api_port {
+        tasks.push(tokio::spawn(operator_api::run_server(
+            port,
+            pool.clone(),
+            stop_receiver.clone(),
+        )));
+    }
+    if let Some(port) = config.dashboard_port {
+        tasks.push(tokio::spawn(dashboard::run_server(
+            port,
+            pool,
+            stop_receiver,
+        )));
+    }
 
     let graceful_shutdown = None::<futures::future::Ready<()>>;
     let tasks_allowed_to_finish = false;
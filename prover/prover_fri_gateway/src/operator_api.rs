@@ -0,0 +1,121 @@
+//! HTTP API for prover operators.
+
+use std::net::SocketAddr;
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, SqlxError};
+use zksync_types::{
+    proofs::{FriBatchPriority, FriProverJobPriority},
+    L1BatchNumber,
+};
+
+#[derive(Debug)]
+pub(crate) enum OperatorApiError {
+    Sqlx(SqlxError),
+}
+
+impl From<SqlxError> for OperatorApiError {
+    fn from(err: SqlxError) -> Self {
+        Self::Sqlx(err)
+    }
+}
+
+impl IntoResponse for OperatorApiError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Sqlx(err) => {
+                tracing::error!("Sqlx error: {err:?}");
+                (
+                    StatusCode::BAD_GATEWAY,
+                    "Failed fetching/saving from db".to_owned(),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SetBatchPriorityRequest {
+    priority: FriProverJobPriority,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SetBatchPriorityResponse {
+    l1_batch_number: L1BatchNumber,
+    priority: FriProverJobPriority,
+    /// Number of existing prover jobs for the batch which priority was changed.
+    updated_jobs: u64,
+}
+
+async fn get_batch_priorities(
+    State(pool): State<ConnectionPool>,
+) -> Result<Json<Vec<FriBatchPriority>>, OperatorApiError> {
+    let mut storage = pool.access_storage().await.unwrap();
+    let priorities = storage.fri_prover_jobs_dal().get_batch_priorities().await?;
+    Ok(Json(priorities))
+}
+
+/// Bumps or deprioritizes all prover jobs for the specified L1 batch. Setting the `normal` priority
+/// removes the override.
+async fn set_batch_priority(
+    State(pool): State<ConnectionPool>,
+    Path(l1_batch_number): Path<u32>,
+    Json(request): Json<SetBatchPriorityRequest>,
+) -> Result<Json<SetBatchPriorityResponse>, OperatorApiError> {
+    let l1_batch_number = L1BatchNumber(l1_batch_number);
+    let mut storage = pool.access_storage().await.unwrap();
+    let updated_jobs = storage
+        .fri_prover_jobs_dal()
+        .set_batch_priority(l1_batch_number, request.priority)
+        .await?;
+    tracing::info!(
+        "Set priority {:?} for L1 batch #{l1_batch_number}; updated {updated_jobs} prover jobs",
+        request.priority
+    );
+    Ok(Json(SetBatchPriorityResponse {
+        l1_batch_number,
+        priority: request.priority,
+        updated_jobs,
+    }))
+}
+
+pub(crate) fn router(pool: ConnectionPool) -> Router {
+    Router::new()
+        .route("/priorities", get(get_batch_priorities))
+        .route("/priorities/:l1_batch_number", put(set_batch_priority))
+        .with_state(pool)
+}
+
+pub(crate) async fn run_server(
+    port: u16,
+    pool: ConnectionPool,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    // The API has unauthenticated endpoints changing the proving order, so it's only reachable
+    // from the host running the gateway.
+    let bind_address = SocketAddr::from(([127, 0, 0, 1], port));
+    tracing::debug!("Starting prover operator API server on {bind_address}");
+
+    axum::Server::bind(&bind_address)
+        .serve(router(pool).into_make_service())
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!("Stop signal sender for prover operator API server was dropped without sending a signal");
+            }
+            tracing::info!("Stop signal received, prover operator API server is shutting down");
+        })
+        .await
+        .context("Prover operator API server failed")?;
+    tracing::info!("Prover operator API server shut down");
+    Ok(())
+}
@@ -70,6 +70,11 @@ impl PeriodicApi<SubmitProofRequest> for PeriodicApiStruct {
 
     async fn handle_response(&self, job_id: L1BatchNumber, response: Self::Response) {
         tracing::info!("Received response: {:?}", response);
-        self.save_successful_sent_proof(job_id).await;
+        match response {
+            SubmitProofResponse::Success => self.save_successful_sent_proof(job_id).await,
+            SubmitProofResponse::Error(err) => {
+                tracing::error!("Proof for L1 batch #{job_id} was not accepted: {err}");
+            }
+        }
     }
 }
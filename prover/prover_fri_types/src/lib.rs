@@ -68,13 +68,20 @@ impl StoredObject for FriProofWrapper {
 pub struct WitnessVectorArtifacts {
     pub witness_vector: WitnessVec<GoldilocksField>,
     pub prover_job: ProverJob,
+    /// Pod that picked the job from the queue; the GPU prover uses it to extend and release the job lease.
+    pub picked_by: String,
 }
 
 impl WitnessVectorArtifacts {
-    pub fn new(witness_vector: WitnessVec<GoldilocksField>, prover_job: ProverJob) -> Self {
+    pub fn new(
+        witness_vector: WitnessVec<GoldilocksField>,
+        prover_job: ProverJob,
+        picked_by: String,
+    ) -> Self {
         Self {
             witness_vector,
             prover_job,
+            picked_by,
         }
     }
 }
@@ -8,7 +8,9 @@ use circuit_definitions::circuit_definitions::aux_layer::{
     ZkSyncCompressionLayerStorageType, ZkSyncSnarkWrapperVK,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use zkevm_test_harness::prover_utils::create_base_layer_setup_data;
+use zkevm_test_harness::prover_utils::{
+    create_base_layer_setup_data, create_recursive_layer_setup_data,
+};
 use zksync_config::configs::FriProverConfig;
 use zksync_env_config::FromEnv;
 use zksync_prover_fri_types::{
@@ -38,6 +40,7 @@ use zksync_prover_fri_types::{
             base_layer::{ZkSyncBaseLayerCircuit, ZkSyncBaseLayerVerificationKey},
             recursion_layer::{
                 ZkSyncRecursionLayerStorageType, ZkSyncRecursionLayerVerificationKey,
+                ZkSyncRecursiveLayerCircuit,
             },
         },
         ZkSyncDefaultRoundFunction, BASE_LAYER_CAP_SIZE, BASE_LAYER_FRI_LDE_FACTOR,
@@ -357,6 +360,47 @@ pub fn generate_cpu_base_layer_setup_data(
     })
 }
 
+pub fn generate_cpu_recursive_layer_setup_data(
+    circuit: ZkSyncRecursiveLayerCircuit,
+) -> anyhow::Result<GoldilocksProverSetupData> {
+    let circuit_type = circuit.numeric_circuit_type();
+    tracing::info!(
+        "starting setup data generator for recursive layer circuit: {}.",
+        circuit_type
+    );
+    let worker = Worker::new();
+    let (setup_base, setup, vk, setup_tree, vars_hint, wits_hint, finalization_hint) =
+        create_recursive_layer_setup_data(
+            circuit.clone(),
+            &worker,
+            BASE_LAYER_FRI_LDE_FACTOR,
+            BASE_LAYER_CAP_SIZE,
+        );
+    let key = ProverServiceDataKey::new(
+        circuit_type,
+        get_round_for_recursive_circuit_type(circuit_type),
+    );
+    let existing_finalization_hint =
+        get_finalization_hints(key).context("get_finalization_hints()")?;
+    if existing_finalization_hint != finalization_hint {
+        anyhow::bail!("finalization hint mismatch for circuit: {circuit_type}");
+    }
+    let existing_vk = get_recursive_layer_vk_for_circuit_type(circuit_type)
+        .context("get_recursive_layer_vk_for_circuit_type()")?;
+    if existing_vk.into_inner() != vk {
+        anyhow::bail!("vk mismatch for circuit: {circuit_type}");
+    }
+    Ok(ProverSetupData {
+        setup_base,
+        setup,
+        vk: vk.clone(),
+        setup_tree,
+        vars_hint,
+        wits_hint,
+        finalization_hint,
+    })
+}
+
 pub fn save_finalization_hints(
     key: ProverServiceDataKey,
     hint: &FinalizationHintsForProver,
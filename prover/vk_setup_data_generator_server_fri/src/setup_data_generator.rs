@@ -1,31 +1,29 @@
 use anyhow::Context as _;
 use structopt::StructOpt;
-use zkevm_test_harness::{
-    geometry_config::get_geometry_config, prover_utils::create_recursive_layer_setup_data,
-};
+use zkevm_test_harness::geometry_config::get_geometry_config;
 use zksync_prover_fri_types::{
     circuit_definitions::{
         aux_definitions::witness_oracle::VmWitnessOracle,
-        boojum::{field::goldilocks::GoldilocksField, worker::Worker},
+        boojum::field::goldilocks::GoldilocksField,
         circuit_definitions::{
             base_layer::ZkSyncBaseLayerCircuit, recursion_layer::ZkSyncRecursiveLayerCircuit,
         },
-        ZkSyncDefaultRoundFunction, BASE_LAYER_CAP_SIZE, BASE_LAYER_FRI_LDE_FACTOR,
+        ZkSyncDefaultRoundFunction,
     },
     ProverServiceDataKey,
 };
 use zksync_types::proofs::AggregationRound;
 use zksync_vk_setup_data_server_fri::{
-    generate_cpu_base_layer_setup_data, get_finalization_hints,
-    get_recursive_layer_vk_for_circuit_type, get_round_for_recursive_circuit_type, save_setup_data,
+    generate_cpu_base_layer_setup_data, generate_cpu_recursive_layer_setup_data,
+    get_round_for_recursive_circuit_type, save_setup_data,
     utils::{
         get_basic_circuits, get_leaf_circuits, get_node_circuit, get_scheduler_circuit, CYCLE_LIMIT,
     },
-    GoldilocksProverSetupData, ProverSetupData,
 };
 #[cfg(feature = "gpu")]
 use {
     shivini::cs::setup::GpuSetup, shivini::ProverContext,
+    zksync_prover_fri_types::circuit_definitions::boojum::worker::Worker,
     zksync_vk_setup_data_server_fri::GpuProverSetupData,
 };
 
@@ -122,47 +120,6 @@ fn get_recursive_circuit(id: u8) -> anyhow::Result<ZkSyncRecursiveLayerCircuit>
         .with_context(|| format!("No recursive circuit found for id: {id}"))
 }
 
-fn generate_cpu_recursive_layer_setup_data(
-    circuit: ZkSyncRecursiveLayerCircuit,
-) -> anyhow::Result<GoldilocksProverSetupData> {
-    let circuit_type = circuit.numeric_circuit_type();
-    tracing::info!(
-        "starting setup data generator for recursive layer circuit: {}.",
-        circuit_type
-    );
-    let worker = Worker::new();
-    let (setup_base, setup, vk, setup_tree, vars_hint, wits_hint, finalization_hint) =
-        create_recursive_layer_setup_data(
-            circuit.clone(),
-            &worker,
-            BASE_LAYER_FRI_LDE_FACTOR,
-            BASE_LAYER_CAP_SIZE,
-        );
-    let key = ProverServiceDataKey::new(
-        circuit_type,
-        get_round_for_recursive_circuit_type(circuit_type),
-    );
-    let existing_finalization_hint =
-        get_finalization_hints(key).context("get_finalization_hints()")?;
-    if existing_finalization_hint != finalization_hint {
-        anyhow::bail!("finalization hint mismatch for circuit: {circuit_type}");
-    }
-    let existing_vk = get_recursive_layer_vk_for_circuit_type(circuit_type)
-        .context("get_recursive_layer_vk_for_circuit_type()")?;
-    if existing_vk.into_inner() != vk {
-        anyhow::bail!("vk mismatch for circuit: {circuit_type}");
-    }
-    Ok(ProverSetupData {
-        setup_base,
-        setup,
-        vk: vk.clone(),
-        setup_tree,
-        vars_hint,
-        wits_hint,
-        finalization_hint,
-    })
-}
-
 #[cfg(feature = "gpu")]
 fn generate_gpu_setup_data(is_base_layer: bool, numeric_circuit: u8) -> anyhow::Result<()> {
     let _context = ProverContext::create().context("failed initializing gpu prover context")?;
@@ -23,7 +23,7 @@ use zkevm_test_harness::{
 use zksync_config::configs::FriWitnessGeneratorConfig;
 use zksync_dal::{fri_witness_generator_dal::FriWitnessJobStatus, ConnectionPool};
 use zksync_object_store::{
-    Bucket, ClosedFormInputKey, ObjectStore, ObjectStoreFactory, StoredObject,
+    Bucket, ClosedFormInputKey, ObjectStore, ObjectStoreError, ObjectStoreFactory, StoredObject,
 };
 use zksync_prover_fri_types::{
     circuit_definitions::{
@@ -40,15 +40,17 @@ use zksync_prover_fri_types::{
 };
 use zksync_prover_fri_utils::get_recursive_layer_circuit_id_for_base_layer;
 use zksync_queued_job_processor::JobProcessor;
-use zksync_state::{PostgresStorage, StorageView};
+use zksync_state::{PostgresStorage, ReadStorage, StorageView, WitnessStorage};
 use zksync_types::{
     proofs::{AggregationRound, BasicCircuitWitnessGeneratorInput, PrepareBasicCircuitsJob},
     protocol_version::FriProtocolVersionId,
-    Address, L1BatchNumber, BOOTLOADER_ADDRESS, H256, U256,
+    witness_block_state::WitnessBlockState,
+    Address, L1BatchNumber, MiniblockNumber, BOOTLOADER_ADDRESS, H256, U256,
 };
 use zksync_utils::{bytes_to_chunks, h256_to_u256, u256_to_h256};
 
 use crate::{
+    input_cache::BasicWitnessInputCache,
     metrics::WITNESS_GENERATOR_METRICS,
     precalculated_merkle_paths_provider::PrecalculatedMerklePathsProvider,
     storage_oracle::StorageOracle,
@@ -81,7 +83,34 @@ struct BlobUrls {
 #[derive(Clone)]
 pub struct BasicWitnessGeneratorJob {
     block_number: L1BatchNumber,
+    protocol_version: FriProtocolVersionId,
     job: PrepareBasicCircuitsJob,
+    /// Storage reads recorded by the basic witness input producer, if available.
+    storage_reads: Option<WitnessBlockState>,
+}
+
+type WitnessGenerationOutput = (
+    BlockBasicCircuits<GoldilocksField, ZkSyncDefaultRoundFunction>,
+    BlockBasicCircuitsPublicInputs<GoldilocksField>,
+    BlockBasicCircuitsPublicCompactFormsWitnesses<GoldilocksField>,
+    SchedulerCircuitInstanceWitness<
+        GoldilocksField,
+        CircuitGoldilocksPoseidon2Sponge,
+        GoldilocksExt2,
+    >,
+    BlockAuxilaryOutputWitness<GoldilocksField>,
+);
+
+/// Data loaded from Postgres to generate the witness for an L1 batch.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct WitnessDbInputs {
+    bootloader_code: Vec<[u8; 32]>,
+    account_code_hash: U256,
+    used_bytecodes: HashMap<U256, Vec<[u8; 32]>>,
+    storage_refunds: Vec<u32>,
+    last_miniblock_number: MiniblockNumber,
+    previous_block_meta_hash: [u8; 32],
+    previous_block_aux_hash: [u8; 32],
 }
 
 #[derive(Debug)]
@@ -92,6 +121,7 @@ pub struct BasicWitnessGenerator {
     connection_pool: ConnectionPool,
     prover_connection_pool: ConnectionPool,
     protocol_versions: Vec<FriProtocolVersionId>,
+    input_cache: BasicWitnessInputCache,
 }
 
 impl BasicWitnessGenerator {
@@ -104,6 +134,7 @@ impl BasicWitnessGenerator {
         protocol_versions: Vec<FriProtocolVersionId>,
     ) -> Self {
         Self {
+            input_cache: BasicWitnessInputCache::new(config.input_cache_capacity()),
             config: Arc::new(config),
             object_store: store_factory.create_store().await.into(),
             public_blob_store,
@@ -117,11 +148,17 @@ impl BasicWitnessGenerator {
         object_store: Arc<dyn ObjectStore>,
         connection_pool: ConnectionPool,
         prover_connection_pool: ConnectionPool,
+        input_cache: BasicWitnessInputCache,
         basic_job: BasicWitnessGeneratorJob,
         started_at: Instant,
         config: Arc<FriWitnessGeneratorConfig>,
     ) -> Option<BasicCircuitArtifacts> {
-        let BasicWitnessGeneratorJob { block_number, job } = basic_job;
+        let BasicWitnessGeneratorJob {
+            block_number,
+            protocol_version,
+            job,
+            storage_reads,
+        } = basic_job;
         let shall_force_process_block = config
             .force_process_block
             .map_or(false, |block| block == block_number.0);
@@ -166,9 +203,12 @@ impl BasicWitnessGenerator {
                 &*object_store,
                 config,
                 connection_pool,
+                &input_cache,
                 started_at,
                 block_number,
+                protocol_version,
                 job,
+                storage_reads,
             )
             .await,
         )
@@ -202,8 +242,18 @@ impl JobProcessor for BasicWitnessGenerator {
                     "Processing FRI basic witness-gen for block {}",
                     block_number
                 );
+                let protocol_version = prover_connection
+                    .fri_witness_generator_dal()
+                    .protocol_version_for_l1_batch(block_number)
+                    .await;
                 let started_at = Instant::now();
-                let job = get_artifacts(block_number, &*self.object_store).await;
+                let job = get_artifacts(
+                    block_number,
+                    protocol_version,
+                    &*self.object_store,
+                    &self.input_cache,
+                )
+                .await;
 
                 WITNESS_GENERATOR_METRICS.blob_fetch_time[&AggregationRound::BasicCircuits.into()]
                     .observe(started_at.elapsed());
@@ -234,11 +284,13 @@ impl JobProcessor for BasicWitnessGenerator {
         let object_store = Arc::clone(&self.object_store);
         let connection_pool = self.connection_pool.clone();
         let prover_connection_pool = self.prover_connection_pool.clone();
+        let input_cache = self.input_cache.clone();
         tokio::spawn(async move {
             Ok(Self::process_job_impl(
                 object_store,
                 connection_pool,
                 prover_connection_pool,
+                input_cache,
                 job,
                 started_at,
                 config,
@@ -294,13 +346,17 @@ impl JobProcessor for BasicWitnessGenerator {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn process_basic_circuits_job(
     object_store: &dyn ObjectStore,
     config: Arc<FriWitnessGeneratorConfig>,
     connection_pool: ConnectionPool,
+    input_cache: &BasicWitnessInputCache,
     started_at: Instant,
     block_number: L1BatchNumber,
+    protocol_version: FriProtocolVersionId,
     job: PrepareBasicCircuitsJob,
+    storage_reads: Option<WitnessBlockState>,
 ) -> BasicCircuitArtifacts {
     let witness_gen_input =
         build_basic_circuits_witness_generator_input(&connection_pool, job, block_number).await;
@@ -310,7 +366,16 @@ async fn process_basic_circuits_job(
         per_circuit_closed_form_inputs,
         scheduler_witness,
         aux_output_witness,
-    ) = generate_witness(object_store, config, connection_pool, witness_gen_input).await;
+    ) = generate_witness(
+        object_store,
+        config,
+        connection_pool,
+        input_cache,
+        protocol_version,
+        witness_gen_input,
+        storage_reads,
+    )
+    .await;
     WITNESS_GENERATOR_METRICS.witness_generation_time[&AggregationRound::BasicCircuits.into()]
         .observe(started_at.elapsed());
 
@@ -368,10 +433,43 @@ async fn update_database(
 
 async fn get_artifacts(
     block_number: L1BatchNumber,
+    protocol_version: FriProtocolVersionId,
     object_store: &dyn ObjectStore,
+    input_cache: &BasicWitnessInputCache,
 ) -> BasicWitnessGeneratorJob {
-    let job = object_store.get(block_number).await.unwrap();
-    BasicWitnessGeneratorJob { block_number, job }
+    let cache_key = (block_number, protocol_version);
+    let job = if let Some(job) = input_cache.merkle_paths(cache_key) {
+        job
+    } else {
+        let job = object_store.get(block_number).await.unwrap();
+        input_cache.insert_merkle_paths(cache_key, &job);
+        job
+    };
+
+    let storage_reads = if let Some(storage_reads) = input_cache.storage_reads(cache_key) {
+        Some(storage_reads)
+    } else {
+        match object_store.get::<WitnessBlockState>(block_number).await {
+            Ok(storage_reads) => {
+                input_cache.insert_storage_reads(cache_key, &storage_reads);
+                Some(storage_reads)
+            }
+            // The basic witness input producer may be disabled; in this case, storage is read from Postgres.
+            Err(ObjectStoreError::KeyNotFound(_)) => None,
+            Err(err) => {
+                tracing::warn!(
+                    "Failed loading storage reads for L1 batch #{block_number}, falling back to Postgres: {err}"
+                );
+                None
+            }
+        }
+    };
+    BasicWitnessGeneratorJob {
+        block_number,
+        protocol_version,
+        job,
+        storage_reads,
+    }
 }
 
 async fn save_artifacts(
@@ -507,22 +605,10 @@ async fn build_basic_circuits_witness_generator_input(
     }
 }
 
-async fn generate_witness(
-    object_store: &dyn ObjectStore,
-    config: Arc<FriWitnessGeneratorConfig>,
-    connection_pool: ConnectionPool,
-    input: BasicCircuitWitnessGeneratorInput,
-) -> (
-    BlockBasicCircuits<GoldilocksField, ZkSyncDefaultRoundFunction>,
-    BlockBasicCircuitsPublicInputs<GoldilocksField>,
-    BlockBasicCircuitsPublicCompactFormsWitnesses<GoldilocksField>,
-    SchedulerCircuitInstanceWitness<
-        GoldilocksField,
-        CircuitGoldilocksPoseidon2Sponge,
-        GoldilocksExt2,
-    >,
-    BlockAuxilaryOutputWitness<GoldilocksField>,
-) {
+async fn load_witness_db_inputs(
+    connection_pool: &ConnectionPool,
+    input: &BasicCircuitWitnessGeneratorInput,
+) -> WitnessDbInputs {
     let mut connection = connection_pool.access_storage().await.unwrap();
     let header = connection
         .blocks_dal()
@@ -552,7 +638,6 @@ async fn generate_witness(
         .await
         .expect("Default aa bytecode should exist");
     let account_bytecode = bytes_to_chunks(&account_bytecode_bytes);
-    let bootloader_contents = expand_bootloader_contents(&input.initial_heap_content);
     let account_code_hash = h256_to_u256(header.base_system_contracts_hashes.default_aa);
 
     let hashes: HashSet<H256> = input
@@ -590,7 +675,39 @@ async fn generate_witness(
         .await
         .unwrap()
         .expect("L1 batch should contain at least one miniblock");
-    drop(connection);
+
+    WitnessDbInputs {
+        bootloader_code,
+        account_code_hash,
+        used_bytecodes,
+        storage_refunds,
+        last_miniblock_number,
+        previous_block_meta_hash: previous_batch_with_metadata.metadata.meta_parameters_hash.0,
+        previous_block_aux_hash: previous_batch_with_metadata.metadata.aux_data_hash.0,
+    }
+}
+
+async fn generate_witness(
+    object_store: &dyn ObjectStore,
+    config: Arc<FriWitnessGeneratorConfig>,
+    connection_pool: ConnectionPool,
+    input_cache: &BasicWitnessInputCache,
+    protocol_version: FriProtocolVersionId,
+    input: BasicCircuitWitnessGeneratorInput,
+    storage_reads: Option<WitnessBlockState>,
+) -> WitnessGenerationOutput {
+    let cache_key = (input.block_number, protocol_version);
+    let db_inputs = if let Some(db_inputs) = input_cache.db_inputs(cache_key) {
+        db_inputs
+    } else {
+        let db_inputs = load_witness_db_inputs(&connection_pool, &input).await;
+        input_cache.insert_db_inputs(cache_key, &db_inputs);
+        db_inputs
+    };
+    let last_miniblock_number = db_inputs.last_miniblock_number;
+    let previous_block_meta_hash = db_inputs.previous_block_meta_hash;
+    let previous_block_aux_hash = db_inputs.previous_block_aux_hash;
+    let bootloader_contents = expand_bootloader_contents(&input.initial_heap_content);
 
     let mut tree = PrecalculatedMerklePathsProvider::new(
         input.merkle_paths_input,
@@ -615,11 +732,11 @@ async fn generate_witness(
             last_miniblock_number.0,
             Address::zero(),
             BOOTLOADER_ADDRESS,
-            bootloader_code.clone(),
+            db_inputs.bootloader_code.clone(),
             bootloader_contents.clone(),
             false,
-            account_code_hash,
-            used_bytecodes.clone(),
+            db_inputs.account_code_hash,
+            db_inputs.used_bytecodes.clone(),
             Vec::default(),
             MAX_CYCLES_FOR_TX as usize,
             geometry_config,
@@ -628,6 +745,10 @@ async fn generate_witness(
         .await;
     }
 
+    // If storage reads are loaded with the job inputs, Postgres is not accessed. Otherwise, reads
+    // performed by the VM are cached after the run, so that retries don't need to access Postgres.
+    let input_cache = input_cache.clone();
+
     // The following part is CPU-heavy, so we move it to a separate thread.
     let rt_handle = tokio::runtime::Handle::current();
 
@@ -638,39 +759,37 @@ async fn generate_witness(
         mut scheduler_witness,
         block_aux_witness,
     ) = tokio::task::spawn_blocking(move || {
+        if let Some(storage_reads) = storage_reads {
+            let storage = WitnessStorage::new(storage_reads);
+            let (output, _) = run_witness_generation(
+                storage,
+                db_inputs,
+                bootloader_contents,
+                geometry_config,
+                &mut tree,
+            );
+            return output;
+        }
+
         let connection = rt_handle
             .block_on(connection_pool.access_storage())
             .unwrap();
-
         let storage = PostgresStorage::new(rt_handle, connection, last_miniblock_number, true);
-        let storage_view = StorageView::new(storage).to_rc_ptr();
-
-        let vm_storage_oracle: VmStorageOracle<StorageView<PostgresStorage<'_>>, HistoryDisabled> =
-            VmStorageOracle::new(storage_view.clone());
-        let storage_oracle = StorageOracle::new(vm_storage_oracle, storage_refunds);
-
-        zkevm_test_harness::external_calls::run_with_fixed_params(
-            Address::zero(),
-            BOOTLOADER_ADDRESS,
-            bootloader_code,
+        let (output, storage_reads) = run_witness_generation(
+            storage,
+            db_inputs,
             bootloader_contents,
-            false,
-            account_code_hash,
-            used_bytecodes,
-            Vec::default(),
-            MAX_CYCLES_FOR_TX as usize,
             geometry_config,
-            storage_oracle,
             &mut tree,
-        )
+        );
+        input_cache.insert_storage_reads(cache_key, &storage_reads);
+        output
     })
     .await
     .unwrap();
 
-    scheduler_witness.previous_block_meta_hash =
-        previous_batch_with_metadata.metadata.meta_parameters_hash.0;
-    scheduler_witness.previous_block_aux_hash =
-        previous_batch_with_metadata.metadata.aux_data_hash.0;
+    scheduler_witness.previous_block_meta_hash = previous_block_meta_hash;
+    scheduler_witness.previous_block_aux_hash = previous_block_aux_hash;
 
     (
         basic_circuits,
@@ -681,6 +800,38 @@ async fn generate_witness(
     )
 }
 
+/// Runs witness generation on top of the specified storage. Returns the storage reads
+/// performed by the VM along with the witness.
+fn run_witness_generation<S: ReadStorage>(
+    storage: S,
+    db_inputs: WitnessDbInputs,
+    bootloader_contents: Vec<u8>,
+    geometry_config: GeometryConfig,
+    tree: &mut PrecalculatedMerklePathsProvider,
+) -> (WitnessGenerationOutput, WitnessBlockState) {
+    let storage_view = StorageView::new(storage).to_rc_ptr();
+    let vm_storage_oracle: VmStorageOracle<StorageView<S>, HistoryDisabled> =
+        VmStorageOracle::new(storage_view.clone());
+    let storage_oracle = StorageOracle::new(vm_storage_oracle, db_inputs.storage_refunds);
+
+    let output = zkevm_test_harness::external_calls::run_with_fixed_params(
+        Address::zero(),
+        BOOTLOADER_ADDRESS,
+        db_inputs.bootloader_code,
+        bootloader_contents,
+        false,
+        db_inputs.account_code_hash,
+        db_inputs.used_bytecodes,
+        Vec::default(),
+        MAX_CYCLES_FOR_TX as usize,
+        geometry_config,
+        storage_oracle,
+        tree,
+    );
+    let storage_reads = storage_view.borrow().witness_block_state();
+    (output, storage_reads)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn save_run_with_fixed_params_args_to_gcs(
     object_store: &dyn ObjectStore,
@@ -746,3 +897,111 @@ impl StoredObject for RunWithFixedParamsInput {
 
     zksync_object_store::serialize_using_bincode!();
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{AccountTreeId, StorageKey};
+
+    use super::*;
+
+    const L1_BATCH_NUMBER: L1BatchNumber = L1BatchNumber(1);
+
+    fn storage_reads() -> WitnessBlockState {
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        WitnessBlockState {
+            read_storage_key: HashMap::from([(key, H256::repeat_byte(2))]),
+            is_write_initial: HashMap::from([(key, false)]),
+        }
+    }
+
+    async fn create_store(with_storage_reads: bool) -> Box<dyn ObjectStore> {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        store
+            .put(L1_BATCH_NUMBER, &PrepareBasicCircuitsJob::new(5))
+            .await
+            .unwrap();
+        if with_storage_reads {
+            store.put(L1_BATCH_NUMBER, &storage_reads()).await.unwrap();
+        }
+        store
+    }
+
+    #[tokio::test]
+    async fn loading_artifacts_populates_input_cache() {
+        let protocol_version = FriProtocolVersionId::latest();
+        let cache_key = (L1_BATCH_NUMBER, protocol_version);
+        let store = create_store(true).await;
+        let input_cache = BasicWitnessInputCache::new(1 << 20);
+
+        let job = get_artifacts(L1_BATCH_NUMBER, protocol_version, &*store, &input_cache).await;
+        assert_eq!(job.job.next_enumeration_index(), 5);
+        let job_storage_reads = job.storage_reads.unwrap();
+        assert_eq!(
+            job_storage_reads.read_storage_key,
+            storage_reads().read_storage_key
+        );
+
+        // Inputs must be cached once they are loaded, rather than after witness generation.
+        let cached_job = input_cache.merkle_paths(cache_key).unwrap();
+        assert_eq!(cached_job.next_enumeration_index(), 5);
+        let cached_storage_reads = input_cache.storage_reads(cache_key).unwrap();
+        assert_eq!(
+            cached_storage_reads.read_storage_key,
+            storage_reads().read_storage_key
+        );
+        assert_eq!(
+            cached_storage_reads.is_write_initial,
+            storage_reads().is_write_initial
+        );
+
+        // A retried job must be served from the cache.
+        let empty_store = ObjectStoreFactory::mock().create_store().await;
+        let job = get_artifacts(
+            L1_BATCH_NUMBER,
+            protocol_version,
+            &*empty_store,
+            &input_cache,
+        )
+        .await;
+        assert_eq!(job.job.next_enumeration_index(), 5);
+        assert!(job.storage_reads.is_some());
+
+        // Inputs are cached separately for each protocol version.
+        let other_cache_key = (L1_BATCH_NUMBER, FriProtocolVersionId::next());
+        assert!(input_cache.merkle_paths(other_cache_key).is_none());
+        assert!(input_cache.storage_reads(other_cache_key).is_none());
+    }
+
+    #[tokio::test]
+    async fn loading_artifacts_without_storage_reads() {
+        let protocol_version = FriProtocolVersionId::latest();
+        let cache_key = (L1_BATCH_NUMBER, protocol_version);
+        let store = create_store(false).await;
+        let input_cache = BasicWitnessInputCache::new(1 << 20);
+
+        let job = get_artifacts(L1_BATCH_NUMBER, protocol_version, &*store, &input_cache).await;
+        assert_eq!(job.job.next_enumeration_index(), 5);
+        assert!(job.storage_reads.is_none());
+        assert!(input_cache.merkle_paths(cache_key).is_some());
+        assert!(input_cache.storage_reads(cache_key).is_none());
+
+        // Storage reads recorded during witness generation are used for retries.
+        input_cache.insert_storage_reads(cache_key, &storage_reads());
+        let job = get_artifacts(L1_BATCH_NUMBER, protocol_version, &*store, &input_cache).await;
+        assert!(job.storage_reads.is_some());
+    }
+
+    #[tokio::test]
+    async fn loading_artifacts_with_disabled_input_cache() {
+        let protocol_version = FriProtocolVersionId::latest();
+        let cache_key = (L1_BATCH_NUMBER, protocol_version);
+        let store = create_store(true).await;
+        let input_cache = BasicWitnessInputCache::new(0);
+
+        let job = get_artifacts(L1_BATCH_NUMBER, protocol_version, &*store, &input_cache).await;
+        assert_eq!(job.job.next_enumeration_index(), 5);
+        assert!(job.storage_reads.is_some());
+        assert!(input_cache.merkle_paths(cache_key).is_none());
+        assert!(input_cache.storage_reads(cache_key).is_none());
+    }
+}
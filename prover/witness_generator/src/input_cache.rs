@@ -0,0 +1,85 @@
+//! In-memory cache of inputs for basic circuits witness generation.
+
+use serde::{de::DeserializeOwned, Serialize};
+use zksync_state::ContentAddressedCache;
+use zksync_types::{
+    proofs::PrepareBasicCircuitsJob, protocol_version::FriProtocolVersionId,
+    witness_block_state::WitnessBlockState, L1BatchNumber,
+};
+
+use crate::basic_circuits::WitnessDbInputs;
+
+type CacheKey = (L1BatchNumber, FriProtocolVersionId);
+
+/// Caches inputs loaded from the object store and Postgres for basic circuits witness generation,
+/// so that retried jobs and re-proven batches don't need to load them again.
+///
+/// Inputs are keyed by the L1 batch number and protocol version. Values are stored serialized
+/// and content-addressed, so equal inputs for different keys are stored once. Hits and misses
+/// are reported by the underlying caches.
+#[derive(Debug, Clone)]
+pub struct BasicWitnessInputCache {
+    merkle_paths: ContentAddressedCache<CacheKey>,
+    db_inputs: ContentAddressedCache<CacheKey>,
+    storage_reads: ContentAddressedCache<CacheKey>,
+}
+
+impl BasicWitnessInputCache {
+    /// Creates a cache with the specified total capacity in bytes. Merkle paths, which are
+    /// the largest input, get a half of it.
+    pub fn new(capacity: u64) -> Self {
+        Self {
+            merkle_paths: ContentAddressedCache::new("witness_merkle_paths", capacity / 2),
+            db_inputs: ContentAddressedCache::new("witness_db_inputs", capacity / 4),
+            storage_reads: ContentAddressedCache::new("witness_storage_reads", capacity / 4),
+        }
+    }
+
+    fn get<T: DeserializeOwned>(
+        cache: &ContentAddressedCache<CacheKey>,
+        key: CacheKey,
+    ) -> Option<T> {
+        let bytes = cache.get(&key)?;
+        match bincode::deserialize(&bytes) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                tracing::warn!("Failed deserializing cached witness input for {key:?}: {err}");
+                cache.remove(&key);
+                None
+            }
+        }
+    }
+
+    fn insert<T: Serialize>(cache: &ContentAddressedCache<CacheKey>, key: CacheKey, value: &T) {
+        match bincode::serialize(value) {
+            Ok(bytes) => cache.insert(key, bytes),
+            Err(err) => tracing::warn!("Failed serializing witness input for {key:?}: {err}"),
+        }
+    }
+
+    pub(crate) fn merkle_paths(&self, key: CacheKey) -> Option<PrepareBasicCircuitsJob> {
+        Self::get(&self.merkle_paths, key)
+    }
+
+    pub(crate) fn insert_merkle_paths(&self, key: CacheKey, value: &PrepareBasicCircuitsJob) {
+        Self::insert(&self.merkle_paths, key, value);
+    }
+
+    pub(crate) fn db_inputs(&self, key: CacheKey) -> Option<WitnessDbInputs> {
+        Self::get(&self.db_inputs, key)
+    }
+
+    pub(crate) fn insert_db_inputs(&self, key: CacheKey, value: &WitnessDbInputs) {
+        Self::insert(&self.db_inputs, key, value);
+    }
+
+    /// Returns storage reads for witness generation, either loaded together with other inputs or recorded
+    /// during a run on top of Postgres.
+    pub(crate) fn storage_reads(&self, key: CacheKey) -> Option<WitnessBlockState> {
+        Self::get(&self.storage_reads, key)
+    }
+
+    pub(crate) fn insert_storage_reads(&self, key: CacheKey, value: &WitnessBlockState) {
+        Self::insert(&self.storage_reads, key, value);
+    }
+}
@@ -1,6 +1,7 @@
 #![feature(generic_const_exprs)]
 
 pub mod basic_circuits;
+pub mod input_cache;
 pub mod leaf_aggregation;
 pub mod node_aggregation;
 pub mod precalculated_merkle_paths_provider;
@@ -26,6 +26,7 @@ use crate::{
 };
 
 mod basic_circuits;
+mod input_cache;
 mod leaf_aggregation;
 mod metrics;
 mod node_aggregation;
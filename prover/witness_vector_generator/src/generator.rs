@@ -7,8 +7,8 @@ use zksync_config::configs::FriWitnessVectorGeneratorConfig;
 use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStore;
 use zksync_prover_fri_types::{
-    circuit_definitions::boojum::field::goldilocks::GoldilocksField, CircuitWrapper, ProverJob,
-    WitnessVectorArtifacts,
+    circuit_definitions::boojum::field::goldilocks::GoldilocksField, get_current_pod_name,
+    CircuitWrapper, ProverJob, WitnessVectorArtifacts,
 };
 use zksync_prover_fri_utils::{
     fetch_next_circuit, get_numeric_circuit_id, socket_utils::send_assembly,
@@ -68,6 +68,7 @@ impl WitnessVectorGenerator {
         Ok(WitnessVectorArtifacts::new(
             cs.materialize_witness_vec(),
             job,
+            get_current_pod_name(),
         ))
     }
 }